@@ -0,0 +1,76 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, check_community_user_action},
+};
+use lemmy_db_schema::{
+  source::{comment::Comment, post::Post},
+  traits::Crud,
+};
+use lemmy_db_schema_file::enums::CommentDisplayMode;
+use lemmy_db_views_comment::{
+  api::{AcceptAnswer, CommentResponse},
+  CommentView,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn accept_answer(
+  data: Json<AcceptAnswer>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommentResponse>> {
+  let local_instance_id = local_user_view.person.instance_id;
+
+  let orig_comment = CommentView::read(
+    &mut context.pool(),
+    data.comment_id,
+    Some(&local_user_view.local_user),
+    local_instance_id,
+  )
+  .await?;
+
+  check_community_user_action(
+    &local_user_view,
+    &orig_comment.community,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // The question can be solved by its creator, or by mods
+  if local_user_view.person.id != orig_comment.post.creator_id {
+    check_community_mod_action(
+      &local_user_view,
+      &orig_comment.community,
+      false,
+      &mut context.pool(),
+    )
+    .await?;
+  }
+  if orig_comment.post.comment_display_mode != CommentDisplayMode::QuestionAnswer
+    && !orig_comment.community.question_answer
+  {
+    Err(LemmyErrorType::PostIsNotQuestion)?
+  }
+  if orig_comment.comment.parent_comment_id().is_some() {
+    Err(LemmyErrorType::OnlyTopLevelCommentCanBeAnswer)?
+  }
+
+  let comment = Comment::accept_answer(&mut context.pool(), data.comment_id, data.accepted).await?;
+
+  // Sent like other mod changes of the post, so that the community announces the accepted answer
+  let post = Post::read(&mut context.pool(), comment.post_id).await?;
+  ActivityChannel::submit_activity(SendActivityData::UpdatePost(post), &context)?;
+
+  let comment_view = CommentView::read(
+    &mut context.pool(),
+    data.comment_id,
+    Some(&local_user_view.local_user),
+    local_instance_id,
+  )
+  .await?;
+
+  Ok(Json(CommentResponse { comment_view }))
+}
@@ -0,0 +1,135 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  build_response::build_comment_response,
+  context::LemmyContext,
+  notify::{notify_content_rejected, NotifyData},
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::check_community_mod_action,
+};
+use lemmy_db_schema::{
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    mod_log::moderator::{ModRemoveComment, ModRemoveCommentForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views_comment::{
+  api::{ApprovePendingComment, CommentResponse},
+  CommentView,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn approve_pending_comment(
+  data: Json<ApprovePendingComment>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommentResponse>> {
+  let comment_id = data.comment_id;
+  let local_instance_id = local_user_view.person.instance_id;
+  let orig_comment = CommentView::read(
+    &mut context.pool(),
+    comment_id,
+    Some(&local_user_view.local_user),
+    local_instance_id,
+  )
+  .await?;
+
+  check_community_mod_action(
+    &local_user_view,
+    &orig_comment.community,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  if !orig_comment.comment.pending_approval {
+    Err(LemmyErrorType::NotFound)?
+  }
+
+  if data.approve {
+    let comment = Comment::update(
+      &mut context.pool(),
+      comment_id,
+      &CommentUpdateForm {
+        pending_approval: Some(false),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Local comments are held back until approval, remote comments were already sent to the
+    // community by their instance
+    let activity = if comment.local {
+      SendActivityData::CreateComment(comment.clone())
+    } else {
+      SendActivityData::AnnounceApprovedComment(comment.clone())
+    };
+    ActivityChannel::submit_activity(activity, &context)?;
+
+    let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+    NotifyData::new(
+      orig_comment.post,
+      Some(comment),
+      orig_comment.creator,
+      orig_comment.community,
+      !local_site.disable_email_notifications,
+    )
+    .send(&context);
+  } else {
+    let comment = Comment::update(
+      &mut context.pool(),
+      comment_id,
+      &CommentUpdateForm {
+        removed: Some(true),
+        pending_approval: Some(false),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Mod tables
+    let form = ModRemoveCommentForm {
+      mod_person_id: local_user_view.person.id,
+      comment_id,
+      removed: Some(true),
+      reason: data.reason.clone(),
+    };
+    ModRemoveComment::create(&mut context.pool(), &form).await?;
+
+    notify_content_rejected(
+      &local_user_view.person,
+      comment.creator_id,
+      comment.local_url(context.settings())?,
+      &orig_comment.community,
+      data.reason.as_deref(),
+      &context,
+    )
+    .await?;
+
+    // Local comments were never federated, so only remote comments need to be removed elsewhere
+    if !comment.local {
+      ActivityChannel::submit_activity(
+        SendActivityData::RemoveComment {
+          comment,
+          moderator: local_user_view.person.clone(),
+          community: orig_comment.community,
+          reason: data.reason.clone(),
+        },
+        &context,
+      )?;
+    }
+  }
+
+  Ok(Json(
+    build_comment_response(
+      &context,
+      comment_id,
+      Some(local_user_view),
+      local_instance_id,
+    )
+    .await?,
+  ))
+}
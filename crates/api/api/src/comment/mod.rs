@@ -1,4 +1,7 @@
+pub mod accept_answer;
+pub mod approve_pending;
 pub mod distinguish;
 pub mod like;
 pub mod list_comment_likes;
+pub mod list_pending;
 pub mod save;
@@ -0,0 +1,210 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use chrono::Utc;
+use lemmy_api_utils::{
+  automod::check_automod_regexes,
+  context::LemmyContext,
+  utils::{check_community_mod_action, slur_regex},
+};
+use lemmy_db_schema::{
+  newtypes::{CommunityId, TagId},
+  source::{
+    automod_rule::{AutomodRule, AutomodRuleInsertForm, AutomodRuleUpdateForm},
+    community::Community,
+    tag::Tag,
+  },
+  traits::Crud,
+  utils::{diesel_opt_number_update, diesel_required_string_update, diesel_string_update},
+};
+use lemmy_db_schema_file::enums::AutomodAction;
+use lemmy_db_views_community::api::{
+  CreateAutomodRule,
+  DeleteAutomodRule,
+  EditAutomodRule,
+  ListAutomodRules,
+  ListAutomodRulesResponse,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::{
+    slurs::check_slurs,
+    validation::{check_api_elements_count, is_valid_body_field, is_valid_display_name},
+  },
+};
+
+pub async fn create_automod_rule(
+  data: Json<CreateAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AutomodRule>> {
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+
+  // Verify that only mods can create rules
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+
+  let existing_rules = AutomodRule::list_for_community(&mut context.pool(), community.id).await?;
+  check_api_elements_count(existing_rules.len())?;
+
+  check_automod_regexes(&[
+    data.title_regex.as_deref(),
+    data.body_regex.as_deref(),
+    data.url_domain_regex.as_deref(),
+    data.instance_domain_regex.as_deref(),
+  ])?;
+  check_rule_fields(
+    &data.name,
+    data.action,
+    data.action_message.as_deref(),
+    data.tag_id,
+    community.id,
+    &context,
+  )
+  .await?;
+
+  let form = AutomodRuleInsertForm {
+    enabled: data.enabled,
+    apply_to_posts: data.apply_to_posts,
+    apply_to_comments: data.apply_to_comments,
+    title_regex: data.title_regex.clone(),
+    body_regex: data.body_regex.clone(),
+    url_domain_regex: data.url_domain_regex.clone(),
+    instance_domain_regex: data.instance_domain_regex.clone(),
+    max_account_age_days: data.max_account_age_days,
+    max_karma: data.max_karma,
+    language_id: data.language_id,
+    action_message: data.action_message.clone(),
+    tag_id: data.tag_id,
+    ..AutomodRuleInsertForm::new(
+      community.id,
+      local_user_view.person.id,
+      data.name.trim().to_string(),
+      data.action,
+    )
+  };
+  let rule = AutomodRule::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(rule))
+}
+
+pub async fn edit_automod_rule(
+  data: Json<EditAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AutomodRule>> {
+  let rule = AutomodRule::read(&mut context.pool(), data.automod_rule_id).await?;
+  let community = Community::read(&mut context.pool(), rule.community_id).await?;
+
+  // Verify that only mods can edit rules
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+
+  check_automod_regexes(
+    &[
+      &data.title_regex,
+      &data.body_regex,
+      &data.url_domain_regex,
+      &data.instance_domain_regex,
+    ]
+    .map(|r| r.as_deref().filter(|r| !r.is_empty())),
+  )?;
+
+  // Validate the rule as it will be after the edit
+  let name = data.name.as_deref().unwrap_or(&rule.name);
+  let action = data.action.unwrap_or(rule.action);
+  let action_message = match data.action_message.as_deref() {
+    Some("") => None,
+    Some(message) => Some(message),
+    None => rule.action_message.as_deref(),
+  };
+  let tag_id = data.tag_id.unwrap_or(rule.tag_id);
+  check_rule_fields(name, action, action_message, tag_id, community.id, &context).await?;
+
+  let form = AutomodRuleUpdateForm {
+    name: diesel_required_string_update(data.name.as_deref().map(str::trim)),
+    enabled: data.enabled,
+    apply_to_posts: data.apply_to_posts,
+    apply_to_comments: data.apply_to_comments,
+    title_regex: diesel_string_update(data.title_regex.as_deref()),
+    body_regex: diesel_string_update(data.body_regex.as_deref()),
+    url_domain_regex: diesel_string_update(data.url_domain_regex.as_deref()),
+    instance_domain_regex: diesel_string_update(data.instance_domain_regex.as_deref()),
+    max_account_age_days: diesel_opt_number_update(data.max_account_age_days),
+    max_karma: diesel_opt_number_update(data.max_karma),
+    language_id: data.language_id.map(Some),
+    action: data.action,
+    action_message: diesel_string_update(data.action_message.as_deref()),
+    tag_id: data.tag_id,
+    updated_at: Some(Some(Utc::now())),
+  };
+  let rule = AutomodRule::update(&mut context.pool(), rule.id, &form).await?;
+
+  Ok(Json(rule))
+}
+
+pub async fn delete_automod_rule(
+  data: Json<DeleteAutomodRule>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AutomodRule>> {
+  let rule = AutomodRule::read(&mut context.pool(), data.automod_rule_id).await?;
+  let community = Community::read(&mut context.pool(), rule.community_id).await?;
+
+  // Verify that only mods can delete rules
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+
+  // Modlog entries of the rule are kept, with an empty rule reference
+  AutomodRule::delete(&mut context.pool(), rule.id).await?;
+
+  Ok(Json(rule))
+}
+
+pub async fn list_automod_rules(
+  data: Query<ListAutomodRules>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListAutomodRulesResponse>> {
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+
+  // Rules are only visible to mods, so that they can't be evaded easily
+  check_community_mod_action(&local_user_view, &community, true, &mut context.pool()).await?;
+
+  let automod_rules = AutomodRule::list_for_community(&mut context.pool(), community.id).await?;
+
+  Ok(Json(ListAutomodRulesResponse { automod_rules }))
+}
+
+/// Checks the name and action of a rule.
+async fn check_rule_fields(
+  name: &str,
+  action: AutomodAction,
+  action_message: Option<&str>,
+  tag_id: Option<TagId>,
+  community_id: CommunityId,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+  let slur_regex = slur_regex(context).await?;
+  is_valid_display_name(name.trim(), local_site.actor_name_max_length)?;
+  check_slurs(name, &slur_regex)?;
+
+  if let Some(message) = action_message {
+    is_valid_body_field(message, false)?;
+    check_slurs(message, &slur_regex)?;
+  }
+
+  match action {
+    AutomodAction::Reply if action_message.unwrap_or_default().is_empty() => {
+      Err(LemmyErrorType::InvalidBodyField)?
+    }
+    AutomodAction::AddTag => {
+      let tag_id = tag_id.ok_or(LemmyErrorType::TagNotInCommunity)?;
+      let tag = Tag::read(&mut context.pool(), tag_id).await?;
+      if tag.deleted || tag.community_id != community_id {
+        Err(LemmyErrorType::TagNotInCommunity)?
+      }
+    }
+    _ => {}
+  }
+  Ok(())
+}
@@ -19,7 +19,7 @@ use lemmy_db_views_community::{
 };
 use lemmy_db_views_community_person_ban::CommunityPersonBanView;
 use lemmy_db_views_local_user::LocalUserView;
-use lemmy_utils::error::LemmyResult;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 
 pub async fn follow_community(
   data: Json<FollowCommunity>,
@@ -31,6 +31,10 @@ pub async fn follow_community(
   let person_id = local_user_view.person.id;
 
   if data.follow {
+    // Followers are expected to follow the new community instead
+    if community.moved_to_url.is_some() {
+      Err(LemmyErrorType::CommunityMoved)?
+    }
     // Only run these checks for local community, in case of remote community the local
     // state may be outdated. Can't use check_community_user_action() here as it only allows
     // actions from existing followers for private community (so following would be impossible).
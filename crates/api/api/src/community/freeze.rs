@@ -0,0 +1,69 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  build_response::build_community_response,
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, check_expire_time},
+};
+use lemmy_db_schema::{
+  source::{
+    community::{Community, CommunityUpdateForm},
+    mod_log::moderator::{ModFreezeCommunity, ModFreezeCommunityForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views_community::api::{CommunityResponse, FreezeCommunity};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_utils::{error::LemmyResult, utils::validation::is_valid_body_field};
+
+pub async fn freeze_community(
+  data: Json<FreezeCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityResponse>> {
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+
+  if let Some(reason) = &data.reason {
+    is_valid_body_field(reason, false)?;
+  }
+
+  // An expiry only makes sense while something is frozen
+  let frozen = data.posts_frozen || data.comments_frozen;
+  let expires_at = if frozen {
+    check_expire_time(data.expires_at)?
+  } else {
+    None
+  };
+
+  let community = Community::update(
+    &mut context.pool(),
+    community.id,
+    &CommunityUpdateForm {
+      posts_frozen: Some(data.posts_frozen),
+      comments_frozen: Some(data.comments_frozen),
+      freeze_expires_at: Some(expires_at),
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  // Mod tables
+  let form = ModFreezeCommunityForm {
+    mod_person_id: Some(local_user_view.person.id),
+    community_id: community.id,
+    posts_frozen: data.posts_frozen,
+    comments_frozen: data.comments_frozen,
+    expires_at,
+    reason: data.reason.clone(),
+  };
+  ModFreezeCommunity::create(&mut context.pool(), &form).await?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::UpdateCommunity(local_user_view.person.clone(), community.clone()),
+    &context,
+  )?;
+
+  build_community_response(&context, local_user_view, community.id).await
+}
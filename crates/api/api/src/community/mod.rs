@@ -1,7 +1,9 @@
 pub mod add_mod;
+pub mod automod;
 pub mod ban;
 pub mod block;
 pub mod follow;
+pub mod freeze;
 pub mod multi_community_follow;
 pub mod pending_follows;
 pub mod random;
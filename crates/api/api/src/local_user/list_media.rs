@@ -1,6 +1,6 @@
 use actix_web::web::{Data, Json, Query};
-use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_schema::traits::PaginationCursorBuilder;
+use lemmy_api_utils::{context::LemmyContext, media::quota::media_quota};
+use lemmy_db_schema::{source::images::LocalImage, traits::PaginationCursorBuilder};
 use lemmy_db_views_local_image::{
   api::{ListMedia, ListMediaResponse},
   LocalImageView,
@@ -31,9 +31,16 @@ pub async fn list_media(
   let next_page = images.last().map(PaginationCursorBuilder::to_cursor);
   let prev_page = images.first().map(PaginationCursorBuilder::to_cursor);
 
+  let total_size =
+    LocalImage::usage_for_person(&mut context.pool(), local_user_view.person.id).await?;
+  let quota = media_quota(&local_user_view, &context).await?;
+
   Ok(Json(ListMediaResponse {
     images,
     next_page,
     prev_page,
+    total_size: Some(total_size),
+    quota,
+    top_uploaders: None,
   }))
 }
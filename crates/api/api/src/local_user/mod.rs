@@ -5,6 +5,7 @@ pub mod change_password;
 pub mod change_password_after_reset;
 pub mod donation_dialog_shown;
 pub mod export_data;
+pub mod follow_person;
 pub mod generate_totp_secret;
 pub mod get_captcha;
 pub mod list_hidden;
@@ -21,6 +22,7 @@ pub mod report_count;
 pub mod resend_verification_email;
 pub mod reset_password;
 pub mod save_settings;
+pub mod update_person_notifications;
 pub mod update_totp;
 pub mod user_block_instance;
 pub mod validate_auth;
@@ -0,0 +1,23 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_db_schema::source::person::PersonActions;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_person::api::UpdatePersonNotifications;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn update_person_notifications(
+  data: Json<UpdatePersonNotifications>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  PersonActions::update_notification_state(
+    data.person_id,
+    local_user_view.person.id,
+    data.mode,
+    &mut context.pool(),
+  )
+  .await?;
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,119 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  build_response::build_post_response,
+  context::LemmyContext,
+  notify::{notify_content_rejected, NotifyData},
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, send_webmention},
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    mod_log::moderator::{ModRemovePost, ModRemovePostForm},
+    person::Person,
+    post::{Post, PostUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_post::api::{ApprovePendingPost, PostResponse};
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn approve_pending_post(
+  data: Json<ApprovePendingPost>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PostResponse>> {
+  let post_id = data.post_id;
+  let orig_post = Post::read(&mut context.pool(), post_id).await?;
+  let community = Community::read(&mut context.pool(), orig_post.community_id).await?;
+
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+
+  if !orig_post.pending_approval {
+    Err(LemmyErrorType::NotFound)?
+  }
+
+  if data.approve {
+    let post = Post::update(
+      &mut context.pool(),
+      post_id,
+      &PostUpdateForm {
+        pending_approval: Some(false),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Scheduled posts are sent out once they are published
+    if post.scheduled_publish_time_at.is_none() {
+      // Local posts are held back until approval, remote posts were already sent to the
+      // community by their instance
+      let activity = if post.local {
+        send_webmention(post.clone(), &community);
+        SendActivityData::CreatePost(post.clone())
+      } else {
+        SendActivityData::AnnounceApprovedPost(post.clone())
+      };
+      ActivityChannel::submit_activity(activity, &context)?;
+
+      let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+      let creator = Person::read(&mut context.pool(), post.creator_id).await?;
+      NotifyData::new(
+        post,
+        None,
+        creator,
+        community.clone(),
+        !local_site.disable_email_notifications,
+      )
+      .send(&context);
+    }
+  } else {
+    let post = Post::update(
+      &mut context.pool(),
+      post_id,
+      &PostUpdateForm {
+        removed: Some(true),
+        pending_approval: Some(false),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Mod tables
+    let form = ModRemovePostForm {
+      mod_person_id: local_user_view.person.id,
+      post_id,
+      removed: Some(true),
+      reason: data.reason.clone(),
+    };
+    ModRemovePost::create(&mut context.pool(), &form).await?;
+
+    notify_content_rejected(
+      &local_user_view.person,
+      post.creator_id,
+      post.local_url(context.settings())?,
+      &community,
+      data.reason.as_deref(),
+      &context,
+    )
+    .await?;
+
+    // Local posts were never federated, so only remote posts need to be removed elsewhere
+    if !post.local {
+      ActivityChannel::submit_activity(
+        SendActivityData::RemovePost {
+          post,
+          moderator: local_user_view.person.clone(),
+          reason: data.reason.clone(),
+          removed: true,
+        },
+        &context,
+      )?;
+    }
+  }
+
+  build_post_response(&context, community.id, local_user_view, post_id).await
+}
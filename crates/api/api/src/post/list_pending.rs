@@ -0,0 +1,75 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  utils::{check_community_mod_action, check_community_mod_of_any_or_admin_action},
+};
+use lemmy_db_schema::{
+  source::community::Community,
+  traits::{Crud, PaginationCursorBuilder},
+};
+use lemmy_db_schema_file::enums::{ListingType, PostSortType};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_post::{
+  api::{GetPostsResponse, ListPendingPosts},
+  impls::PostQuery,
+  PostView,
+};
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::LemmyResult;
+
+/// Lists the posts which are held for moderator approval
+pub async fn list_pending_posts(
+  data: Query<ListPendingPosts>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetPostsResponse>> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_user = &local_user_view.local_user;
+
+  if let Some(community_id) = data.community_id {
+    let community = Community::read(&mut context.pool(), community_id).await?;
+    check_community_mod_action(&local_user_view, &community, true, &mut context.pool()).await?;
+  } else {
+    check_community_mod_of_any_or_admin_action(&local_user_view, &mut context.pool()).await?;
+  }
+
+  // Admins see the queues of all communities, mods only those of their own communities
+  let listing_type = if local_user.admin {
+    ListingType::All
+  } else {
+    ListingType::ModeratorView
+  };
+
+  let cursor_data = if let Some(cursor) = &data.page_cursor {
+    Some(PostView::from_cursor(cursor, &mut context.pool()).await?)
+  } else {
+    None
+  };
+
+  let posts = PostQuery {
+    local_user: Some(local_user),
+    listing_type: Some(listing_type),
+    sort: Some(PostSortType::Old),
+    community_id: data.community_id,
+    show_hidden: Some(true),
+    show_read: Some(true),
+    show_nsfw: Some(true),
+    pending_approval_only: Some(true),
+    cursor_data,
+    page_back: data.page_back,
+    limit: data.limit,
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?;
+
+  let next_page = posts.last().map(PaginationCursorBuilder::to_cursor);
+  let prev_page = posts.first().map(PaginationCursorBuilder::to_cursor);
+
+  Ok(Json(GetPostsResponse {
+    posts,
+    next_page,
+    prev_page,
+  }))
+}
@@ -4,7 +4,7 @@ use lemmy_api_utils::{
   build_response::build_post_response,
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::check_community_mod_action,
+  utils::{check_community_mod_action, check_expire_time},
 };
 use lemmy_db_schema::{
   source::{
@@ -39,14 +39,26 @@ pub async fn lock_post(
   )
   .await?;
 
+  // Expiry and account age only apply to locks
+  let locked = data.locked;
+  let (expires_at, max_account_age_days) = if locked {
+    (
+      check_expire_time(data.expires_at)?,
+      data.max_account_age_days.filter(|days| *days > 0),
+    )
+  } else {
+    (None, None)
+  };
+
   // Update the post
   let post_id = data.post_id;
-  let locked = data.locked;
   let post = Post::update(
     &mut context.pool(),
     post_id,
     &PostUpdateForm {
       locked: Some(locked),
+      lock_expires_at: Some(expires_at),
+      lock_max_account_age_days: Some(max_account_age_days),
       ..Default::default()
     },
   )
@@ -58,6 +70,8 @@ pub async fn lock_post(
     post_id: data.post_id,
     locked: Some(locked),
     reason: data.reason.clone(),
+    expires_at,
+    max_account_age_days,
   };
   ModLockPost::create(&mut context.pool(), &form).await?;
 
@@ -1,7 +1,9 @@
+pub mod approve_pending;
 pub mod feature;
 pub mod get_link_metadata;
 pub mod hide;
 pub mod like;
+pub mod list_pending;
 pub mod list_post_likes;
 pub mod lock;
 pub mod mark_many_read;
@@ -16,6 +16,7 @@ use lemmy_api_utils::{
 use lemmy_db_schema::{
   source::post::{Post, PostUpdateForm},
   traits::Crud,
+  utils::diesel_opt_number_update,
 };
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_post::{
@@ -52,6 +53,8 @@ pub async fn mod_update_post(
 
   let mut post_form = PostUpdateForm {
     nsfw: data.nsfw,
+    comment_slow_mode_minutes: diesel_opt_number_update(data.comment_slow_mode_minutes),
+    comment_display_mode: data.comment_display_mode,
     updated_at: Some(Some(Utc::now())),
     ..Default::default()
   };
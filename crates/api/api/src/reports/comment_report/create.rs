@@ -58,6 +58,7 @@ pub async fn create_comment_report(
     original_comment_text: comment_view.comment.content,
     reason,
     violates_instance_rules: data.violates_instance_rules.unwrap_or_default(),
+    severity: data.severity.unwrap_or_default(),
   };
 
   let report = CommentReport::report(&mut context.pool(), &report_form).await?;
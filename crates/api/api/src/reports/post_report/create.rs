@@ -49,6 +49,7 @@ pub async fn create_post_report(
     original_post_body: post_view.post.body,
     reason,
     violates_instance_rules: data.violates_instance_rules.unwrap_or_default(),
+    severity: data.severity.unwrap_or_default(),
   };
 
   let report = PostReport::report(&mut context.pool(), &report_form).await?;
@@ -0,0 +1,45 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::check_community_mod_of_any_or_admin_action};
+use lemmy_db_schema::traits::PaginationCursorBuilder;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_report_combined::{
+  api::{ListReportCases, ListReportCasesResponse},
+  impls::ReportCaseQuery,
+  ReportCaseView,
+};
+use lemmy_utils::error::LemmyResult;
+
+/// Lists unresolved post and comment reports grouped by the reported item, for a community if an
+/// id is supplied or for all communities a user moderates
+pub async fn list_report_cases(
+  data: Query<ListReportCases>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListReportCasesResponse>> {
+  check_community_mod_of_any_or_admin_action(&local_user_view, &mut context.pool()).await?;
+
+  let cursor_data = if let Some(cursor) = &data.page_cursor {
+    Some(ReportCaseView::from_cursor(cursor, &mut context.pool()).await?)
+  } else {
+    None
+  };
+
+  let cases = ReportCaseQuery {
+    community_id: data.community_id,
+    show_community_rule_violations: data.show_community_rule_violations,
+    cursor_data,
+    page_back: data.page_back,
+    limit: data.limit,
+  }
+  .list(&mut context.pool(), &local_user_view)
+  .await?;
+
+  let next_page = cases.last().map(PaginationCursorBuilder::to_cursor);
+  let prev_page = cases.first().map(PaginationCursorBuilder::to_cursor);
+
+  Ok(Json(ListReportCasesResponse {
+    cases,
+    next_page,
+    prev_page,
+  }))
+}
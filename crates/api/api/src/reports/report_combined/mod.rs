@@ -1 +1,3 @@
 pub mod list;
+pub mod list_cases;
+pub mod resolve_case;
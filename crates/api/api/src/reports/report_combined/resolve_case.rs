@@ -0,0 +1,94 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use either::Either;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, is_admin},
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    comment_report::CommentReport,
+    community::Community,
+    post::Post,
+    post_report::PostReport,
+  },
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_report_combined::api::ResolveReportCase;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Resolves all unresolved reports in the case of a post or comment, and federates the resolution
+/// of each individual report. Reports which violate instance rules form a separate case, which
+/// can only be resolved by admins.
+pub async fn resolve_report_case(
+  data: Json<ResolveReportCase>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let person_id = local_user_view.person.id;
+  let violates_instance_rules = data.violates_instance_rules.unwrap_or_default();
+  if violates_instance_rules {
+    is_admin(&local_user_view)?;
+  }
+
+  let (object_id, community, report_creators) = match (data.post_id, data.comment_id) {
+    (Some(post_id), None) => {
+      let post = Post::read(&mut context.pool(), post_id).await?;
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      check_community_mod_action(&local_user_view, &community, true, &mut context.pool()).await?;
+
+      let report_creators =
+        PostReport::list_unresolved_creators(&mut context.pool(), post_id, violates_instance_rules)
+          .await?;
+      PostReport::resolve_case(
+        &mut context.pool(),
+        post_id,
+        violates_instance_rules,
+        person_id,
+      )
+      .await?;
+      (post.ap_id, community, report_creators)
+    }
+    (None, Some(comment_id)) => {
+      let comment = Comment::read(&mut context.pool(), comment_id).await?;
+      let post = Post::read(&mut context.pool(), comment.post_id).await?;
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      check_community_mod_action(&local_user_view, &community, true, &mut context.pool()).await?;
+
+      let report_creators = CommentReport::list_unresolved_creators(
+        &mut context.pool(),
+        comment_id,
+        violates_instance_rules,
+      )
+      .await?;
+      CommentReport::resolve_case(
+        &mut context.pool(),
+        comment_id,
+        violates_instance_rules,
+        person_id,
+      )
+      .await?;
+      (comment.ap_id, community, report_creators)
+    }
+    (None, None) => Err(LemmyErrorType::NoIdGiven)?,
+    (Some(_), Some(_)) => Err(LemmyErrorType::ContradictingFilters)?,
+  };
+
+  for report_creator in report_creators {
+    ActivityChannel::submit_activity(
+      SendActivityData::SendResolveReport {
+        object_id: object_id.inner().clone(),
+        actor: local_user_view.person.clone(),
+        report_creator,
+        receiver: Either::Right(community.clone()),
+      },
+      &context,
+    )?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
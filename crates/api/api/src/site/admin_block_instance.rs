@@ -1,5 +1,6 @@
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
+use chrono::Utc;
 use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
 use lemmy_db_schema::{
   source::{
@@ -31,7 +32,11 @@ pub async fn admin_block_instance(
   let form = FederationBlockListForm {
     instance_id,
     expires_at: data.expires_at,
-    updated_at: None,
+    updated_at: data.block.then(Utc::now),
+    severity: data.severity,
+    public_reason: data.reason.clone(),
+    private_comment: data.private_comment.clone(),
+    subscription_id: None,
   };
 
   if data.block {
@@ -45,6 +50,7 @@ pub async fn admin_block_instance(
     admin_person_id: local_user_view.person.id,
     blocked: data.block,
     reason: data.reason.clone(),
+    severity: data.block.then(|| data.severity.unwrap_or_default()),
   };
   AdminBlockInstance::create(&mut context.pool(), &mod_log_form).await?;
 
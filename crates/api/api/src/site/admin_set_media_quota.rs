@@ -0,0 +1,30 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, media::quota::media_quota_check, utils::is_admin};
+use lemmy_db_schema::{
+  source::local_user::{LocalUser, LocalUserUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views_local_image::api::AdminSetMediaQuota;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn admin_set_media_quota(
+  data: Json<AdminSetMediaQuota>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+  media_quota_check(data.quota)?;
+
+  // Only local users can upload
+  let target = LocalUserView::read_person(&mut context.pool(), data.person_id).await?;
+
+  let form = LocalUserUpdateForm {
+    media_quota: Some(data.quota),
+    ..Default::default()
+  };
+  LocalUser::update(&mut context.pool(), target.local_user.id, &form).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
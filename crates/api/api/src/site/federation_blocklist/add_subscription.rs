@@ -0,0 +1,46 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  federation_blocklist::refresh_blocklist_subscription,
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::federation_blocklist::{
+    FederationBlocklistPending,
+    FederationBlocklistSubscription,
+    FederationBlocklistSubscriptionInsertForm,
+  },
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{
+  AdminAddFederationBlocklistSubscription,
+  FederationBlocklistSubscriptionResponse,
+  FederationBlocklistSubscriptionView,
+};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn add_federation_blocklist_subscription(
+  data: Json<AdminAddFederationBlocklistSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<FederationBlocklistSubscriptionResponse>> {
+  is_admin(&local_user_view)?;
+
+  let form = FederationBlocklistSubscriptionInsertForm::new(data.url.to_string());
+  let subscription = FederationBlocklistSubscription::create(&mut context.pool(), &form).await?;
+
+  // Fetch the blocklist right away, so that its domains can be reviewed
+  refresh_blocklist_subscription(&subscription, &context).await?;
+  let subscription =
+    FederationBlocklistSubscription::read(&mut context.pool(), subscription.id).await?;
+  let pending =
+    FederationBlocklistPending::list(&mut context.pool(), Some(subscription.id)).await?;
+
+  Ok(Json(FederationBlocklistSubscriptionResponse {
+    subscription: FederationBlocklistSubscriptionView {
+      subscription,
+      pending,
+    },
+  }))
+}
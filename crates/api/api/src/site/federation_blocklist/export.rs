@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  federation_blocklist::blocklist_to_csv,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::federation_blocklist::FederationBlockList;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ExportFederationBlocklistResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn export_federation_blocklist(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ExportFederationBlocklistResponse>> {
+  is_admin(&local_user_view)?;
+
+  let blocklist = FederationBlockList::list(&mut context.pool()).await?;
+
+  Ok(Json(ExportFederationBlocklistResponse {
+    csv: blocklist_to_csv(&blocklist),
+  }))
+}
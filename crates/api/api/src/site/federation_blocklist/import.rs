@@ -0,0 +1,39 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  federation_blocklist::{block_instances, parse_blocklist_csv},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::instance::Instance;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{
+  AdminImportFederationBlocklist,
+  AdminImportFederationBlocklistResponse,
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn import_federation_blocklist(
+  data: Json<AdminImportFederationBlocklist>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AdminImportFederationBlocklistResponse>> {
+  is_admin(&local_user_view)?;
+
+  let allowlist = Instance::allowlist(&mut context.pool()).await?;
+  if !allowlist.is_empty() {
+    Err(LemmyErrorType::CannotCombineFederationBlocklistAndAllowlist)?;
+  }
+
+  let entries = parse_blocklist_csv(&data.csv);
+  let blocked = block_instances(
+    entries,
+    None,
+    local_user_view.person.id,
+    &mut context.pool(),
+  )
+  .await?;
+
+  Ok(Json(AdminImportFederationBlocklistResponse {
+    blocked: i32::try_from(blocked)?,
+  }))
+}
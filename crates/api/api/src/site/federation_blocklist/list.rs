@@ -0,0 +1,21 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::federation_blocklist::FederationBlockList;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{FederationBlocklistEntry, ListFederationBlocklistResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_federation_blocklist(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListFederationBlocklistResponse>> {
+  is_admin(&local_user_view)?;
+
+  let blocklist = FederationBlockList::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|(instance, block)| FederationBlocklistEntry { instance, block })
+    .collect();
+
+  Ok(Json(ListFederationBlocklistResponse { blocklist }))
+}
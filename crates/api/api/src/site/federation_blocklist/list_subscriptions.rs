@@ -0,0 +1,37 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::federation_blocklist::{
+  FederationBlocklistPending,
+  FederationBlocklistSubscription,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{
+  FederationBlocklistSubscriptionView,
+  ListFederationBlocklistSubscriptionsResponse,
+};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_federation_blocklist_subscriptions(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListFederationBlocklistSubscriptionsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let pending = FederationBlocklistPending::list(&mut context.pool(), None).await?;
+  let subscriptions = FederationBlocklistSubscription::list(&mut context.pool())
+    .await?
+    .into_iter()
+    .map(|subscription| FederationBlocklistSubscriptionView {
+      pending: pending
+        .iter()
+        .filter(|p| p.subscription_id == subscription.id)
+        .cloned()
+        .collect(),
+      subscription,
+    })
+    .collect();
+
+  Ok(Json(ListFederationBlocklistSubscriptionsResponse {
+    subscriptions,
+  }))
+}
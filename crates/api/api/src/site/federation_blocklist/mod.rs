@@ -0,0 +1,7 @@
+pub mod add_subscription;
+pub mod export;
+pub mod import;
+pub mod list;
+pub mod list_subscriptions;
+pub mod remove_subscription;
+pub mod review_subscription;
@@ -0,0 +1,21 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::{
+  source::federation_blocklist::FederationBlocklistSubscription,
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminRemoveFederationBlocklistSubscription, SuccessResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn remove_federation_blocklist_subscription(
+  data: Json<AdminRemoveFederationBlocklistSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  FederationBlocklistSubscription::delete(&mut context.pool(), data.id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
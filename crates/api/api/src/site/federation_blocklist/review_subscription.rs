@@ -0,0 +1,51 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  federation_blocklist::{block_instances, BlocklistEntry},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::{
+  federation_blocklist::FederationBlocklistPending,
+  instance::Instance,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminReviewFederationBlocklistSubscription, SuccessResponse};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn review_federation_blocklist_subscription(
+  data: Json<AdminReviewFederationBlocklistSubscription>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  if data.apply {
+    let allowlist = Instance::allowlist(&mut context.pool()).await?;
+    if !allowlist.is_empty() {
+      Err(LemmyErrorType::CannotCombineFederationBlocklistAndAllowlist)?;
+    }
+  }
+
+  if data.apply {
+    let entries = FederationBlocklistPending::take_all(&mut context.pool(), data.id)
+      .await?
+      .into_iter()
+      .map(|p| BlocklistEntry {
+        domain: p.domain,
+        severity: p.severity,
+        public_reason: p.public_reason,
+      })
+      .collect();
+    block_instances(
+      entries,
+      Some(data.id),
+      local_user_view.person.id,
+      &mut context.pool(),
+    )
+    .await?;
+  } else {
+    FederationBlocklistPending::discard_all(&mut context.pool(), data.id).await?;
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
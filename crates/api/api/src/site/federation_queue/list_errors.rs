@@ -0,0 +1,19 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::federation_send_error::FederationSendError;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{ListFederationSendErrors, ListFederationSendErrorsResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_federation_send_errors(
+  data: Query<ListFederationSendErrors>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<ListFederationSendErrorsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let errors = FederationSendError::list(&mut context.pool(), data.instance_id, data.limit).await?;
+
+  Ok(Json(ListFederationSendErrorsResponse { errors }))
+}
@@ -0,0 +1,21 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{federation_queue_state::FederationQueueState, instance::Instance};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminPauseFederation, SuccessResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn admin_pause_federation(
+  data: Json<AdminPauseFederation>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let instance = Instance::read(&mut context.pool(), data.instance_id).await?;
+  // The federation worker for the instance is stopped or started on its next check
+  FederationQueueState::set_paused(&mut context.pool(), instance.id, data.paused).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
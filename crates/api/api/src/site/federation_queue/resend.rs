@@ -0,0 +1,44 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{
+  federation_queue_command::{FederationQueueCommand, FederationQueueCommandForm},
+  instance::Instance,
+};
+use lemmy_db_schema_file::enums::FederationQueueCommandType;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminResendFederation, SuccessResponse};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Maximum number of activities which can be resent at once.
+const MAX_RESEND_ACTIVITIES: i64 = 1000;
+
+pub async fn admin_resend_federation(
+  data: Json<AdminResendFederation>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let difference = data
+    .last_activity_id
+    .0
+    .saturating_sub(data.first_activity_id.0);
+  if difference < 0 {
+    Err(LemmyErrorType::InvalidActivityRange)?
+  }
+  if difference >= MAX_RESEND_ACTIVITIES {
+    Err(LemmyErrorType::TooManyItems)?
+  }
+
+  let instance = Instance::read(&mut context.pool(), data.instance_id).await?;
+  let form = FederationQueueCommandForm {
+    instance_id: instance.id,
+    command: FederationQueueCommandType::Resend,
+    first_activity_id: Some(data.first_activity_id),
+    last_activity_id: Some(data.last_activity_id),
+  };
+  FederationQueueCommand::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
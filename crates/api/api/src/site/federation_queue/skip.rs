@@ -0,0 +1,30 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::{
+  federation_queue_command::{FederationQueueCommand, FederationQueueCommandForm},
+  instance::Instance,
+};
+use lemmy_db_schema_file::enums::FederationQueueCommandType;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminSkipFederation, SuccessResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn admin_skip_federation(
+  data: Json<AdminSkipFederation>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let instance = Instance::read(&mut context.pool(), data.instance_id).await?;
+  let form = FederationQueueCommandForm {
+    instance_id: instance.id,
+    command: FederationQueueCommandType::Skip,
+    first_activity_id: None,
+    last_activity_id: Some(data.activity_id),
+  };
+  FederationQueueCommand::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,42 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::{
+    image_hash::{image_hash, invalidate_image_hash_blocklist},
+    MediaStorage,
+  },
+  utils::is_admin,
+};
+use lemmy_db_schema::source::image_hash_block::{ImageHashBlock, ImageHashBlockForm};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{CreateImageHashBlock, ImageHashBlockResponse};
+use lemmy_utils::error::{LemmyErrorExt2, LemmyErrorType, LemmyResult};
+
+pub async fn create_image_hash_block(
+  data: Json<CreateImageHashBlock>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ImageHashBlockResponse>> {
+  is_admin(&local_user_view)?;
+
+  let hash = match (data.hash, &data.filename) {
+    (Some(hash), _) => hash,
+    (None, Some(filename)) => {
+      let image = context.media().read(filename, &context).await?;
+      image_hash(image)
+        .await
+        .with_lemmy_type(LemmyErrorType::InvalidImageHash)?
+    }
+    (None, None) => Err(LemmyErrorType::InvalidImageHash)?,
+  };
+
+  let form = ImageHashBlockForm {
+    hash,
+    reason: data.reason.clone(),
+    admin_person_id: Some(local_user_view.person.id),
+  };
+  let image_hash_block = ImageHashBlock::create(&mut context.pool(), &form).await?;
+  invalidate_image_hash_blocklist();
+
+  Ok(Json(ImageHashBlockResponse { image_hash_block }))
+}
@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::image_hash::invalidate_image_hash_blocklist,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::image_hash_block::ImageHashBlock;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{DeleteImageHashBlock, SuccessResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn delete_image_hash_block(
+  data: Json<DeleteImageHashBlock>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  ImageHashBlock::delete(&mut context.pool(), data.id).await?;
+  invalidate_image_hash_blocklist();
+
+  Ok(Json(SuccessResponse::default()))
+}
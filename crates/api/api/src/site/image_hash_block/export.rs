@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::image_hash::hash_list_to_string,
+  utils::is_admin,
+};
+use lemmy_db_schema::source::image_hash_block::ImageHashBlock;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ExportImageHashBlocklistResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn export_image_hash_blocklist(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ExportImageHashBlocklistResponse>> {
+  is_admin(&local_user_view)?;
+
+  let blocklist = ImageHashBlock::list(&mut context.pool()).await?;
+
+  Ok(Json(ExportImageHashBlocklistResponse {
+    list: hash_list_to_string(&blocklist),
+  }))
+}
@@ -0,0 +1,36 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  media::image_hash::{invalidate_image_hash_blocklist, parse_hash_list},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::image_hash_block::{ImageHashBlock, ImageHashBlockForm};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{
+  AdminImportImageHashBlocklist,
+  AdminImportImageHashBlocklistResponse,
+};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn import_image_hash_blocklist(
+  data: Json<AdminImportImageHashBlocklist>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<AdminImportImageHashBlocklistResponse>> {
+  is_admin(&local_user_view)?;
+
+  let forms = parse_hash_list(&data.list)?
+    .into_iter()
+    .map(|e| ImageHashBlockForm {
+      hash: e.hash,
+      reason: e.reason,
+      admin_person_id: Some(local_user_view.person.id),
+    })
+    .collect::<Vec<_>>();
+  let blocked = ImageHashBlock::create_many(&mut context.pool(), &forms).await?;
+  invalidate_image_hash_blocklist();
+
+  Ok(Json(AdminImportImageHashBlocklistResponse {
+    blocked: i32::try_from(blocked)?,
+  }))
+}
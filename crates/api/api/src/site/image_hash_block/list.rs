@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::image_hash_block::ImageHashBlock;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ListImageHashBlocksResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_image_hash_blocks(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListImageHashBlocksResponse>> {
+  is_admin(&local_user_view)?;
+
+  let image_hash_blocks = ImageHashBlock::list(&mut context.pool()).await?;
+
+  Ok(Json(ListImageHashBlocksResponse { image_hash_blocks }))
+}
@@ -0,0 +1,5 @@
+pub mod create;
+pub mod delete;
+pub mod export;
+pub mod import;
+pub mod list;
@@ -0,0 +1,41 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::inbound_activity::InboundActivity;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{ListInboundActivities, ListInboundActivitiesResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_inbound_activities(
+  data: Query<ListInboundActivities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListInboundActivitiesResponse>> {
+  is_admin(&local_user_view)?;
+
+  let cursor_data = if let Some(cursor) = &data.page_cursor {
+    Some(InboundActivity::from_cursor(cursor, &mut context.pool()).await?)
+  } else {
+    None
+  };
+
+  let inbound_activities = InboundActivity::list(
+    &mut context.pool(),
+    data.failed,
+    cursor_data,
+    data.page_back,
+    data.limit,
+  )
+  .await?;
+  let (pending_count, failed_count) = InboundActivity::counts(&mut context.pool()).await?;
+
+  let next_page = inbound_activities.last().map(InboundActivity::to_cursor);
+  let prev_page = inbound_activities.first().map(InboundActivity::to_cursor);
+
+  Ok(Json(ListInboundActivitiesResponse {
+    inbound_activities,
+    pending_count,
+    failed_count,
+    next_page,
+    prev_page,
+  }))
+}
@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::{source::inbound_activity::InboundActivity, traits::Crud};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{ReplayInboundActivity, SuccessResponse};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn replay_inbound_activity(
+  data: Json<ReplayInboundActivity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  // Only activities in the dead-letter list can be replayed, others are still being retried
+  let activity = InboundActivity::read(&mut context.pool(), data.id).await?;
+  if activity.failed_at.is_none() {
+    Err(LemmyErrorType::NotFound)?
+  }
+  InboundActivity::replay(&mut context.pool(), activity.id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
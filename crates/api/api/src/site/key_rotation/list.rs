@@ -0,0 +1,19 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::actor_key_rotation::ActorKeyRotation;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{ListKeyRotations, ListKeyRotationsResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_key_rotations(
+  data: Query<ListKeyRotations>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<ListKeyRotationsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let rotations = ActorKeyRotation::list(&mut context.pool(), data.limit).await?;
+
+  Ok(Json(ListKeyRotationsResponse { rotations }))
+}
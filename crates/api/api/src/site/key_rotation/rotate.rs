@@ -0,0 +1,42 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  key_rotation::rotate_actor_key,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::is_admin,
+};
+use lemmy_db_schema::source::actor_key_rotation::ActorKeyRotation;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminRotateKeys, SuccessResponse};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  spawn_try_task,
+};
+
+pub async fn admin_rotate_keys(
+  data: Json<AdminRotateKeys>,
+  local_user_view: LocalUserView,
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+  let admin_person_id = Some(local_user_view.person.id);
+
+  if data.all.unwrap_or_default() {
+    let context = context.reset_request_count();
+    spawn_try_task(async move {
+      for actor_ap_id in ActorKeyRotation::list_local_actors(&mut context.pool()).await? {
+        let rotation = rotate_actor_key(&mut context.pool(), &actor_ap_id, admin_person_id).await?;
+        ActivityChannel::submit_activity(SendActivityData::UpdateActorKey(rotation), &context)?;
+      }
+      Ok(())
+    });
+  } else if let Some(actor_ap_id) = &data.actor_ap_id {
+    let rotation = rotate_actor_key(&mut context.pool(), actor_ap_id, admin_person_id).await?;
+    ActivityChannel::submit_activity(SendActivityData::UpdateActorKey(rotation), &context)?;
+  } else {
+    Err(LemmyErrorType::NoIdGiven)?
+  }
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -4,6 +4,7 @@ use lemmy_db_schema::traits::PaginationCursorBuilder;
 use lemmy_db_views_local_image::{
   api::{ListMedia, ListMediaResponse},
   LocalImageView,
+  PersonMediaUsage,
 };
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_utils::error::LemmyResult;
@@ -29,9 +30,18 @@ pub async fn list_all_media(
   let next_page = images.last().map(PaginationCursorBuilder::to_cursor);
   let prev_page = images.first().map(PaginationCursorBuilder::to_cursor);
 
+  let top_uploaders = if data.page_cursor.is_none() {
+    Some(PersonMediaUsage::top_uploaders(&mut context.pool(), None).await?)
+  } else {
+    None
+  };
+
   Ok(Json(ListMediaResponse {
     images,
     next_page,
     prev_page,
+    total_size: None,
+    quota: None,
+    top_uploaders,
   }))
 }
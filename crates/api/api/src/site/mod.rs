@@ -1,9 +1,16 @@
 pub mod admin_allow_instance;
 pub mod admin_block_instance;
 pub mod admin_list_users;
+pub mod admin_set_media_quota;
 pub mod federated_instances;
+pub mod federation_blocklist;
+pub mod federation_queue;
+pub mod image_hash_block;
+pub mod inbound_activity;
+pub mod key_rotation;
 pub mod leave_admin;
 pub mod list_all_media;
 pub mod mod_log;
 pub mod purge;
 pub mod registration_applications;
+pub mod relay;
@@ -0,0 +1,51 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::{
+    federation_relay::{FederationRelay, FederationRelayInsertForm},
+    instance::Instance,
+  },
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminAddRelay, RelayResponse};
+use lemmy_utils::error::{FederationError, LemmyResult};
+
+pub async fn add_relay(
+  data: Json<AdminAddRelay>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RelayResponse>> {
+  is_admin(&local_user_view)?;
+
+  // Activities are only delivered to known instances
+  let domain = data.url.domain().ok_or(FederationError::UrlWithoutDomain)?;
+  Instance::read_or_create(&mut context.pool(), domain.to_string()).await?;
+
+  let url = data.url.clone().into();
+  let form = if FederationRelay::is_inbox_url(&data.url) {
+    FederationRelayInsertForm {
+      inbox_url: Some(data.url.clone().into()),
+      publish: data.publish,
+      max_objects_per_hour: data.max_objects_per_hour,
+      ..FederationRelayInsertForm::new(url)
+    }
+  } else {
+    FederationRelayInsertForm {
+      ap_id: Some(data.url.clone().into()),
+      publish: data.publish,
+      max_objects_per_hour: data.max_objects_per_hour,
+      ..FederationRelayInsertForm::new(url)
+    }
+  };
+  let relay = FederationRelay::create(&mut context.pool(), &form).await?;
+
+  ActivityChannel::submit_activity(SendActivityData::FollowRelay(relay.clone(), true), &context)?;
+
+  Ok(Json(RelayResponse { relay }))
+}
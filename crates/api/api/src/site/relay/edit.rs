@@ -0,0 +1,28 @@
+use actix_web::web::{Data, Json};
+use chrono::Utc;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::{
+  source::federation_relay::{FederationRelay, FederationRelayUpdateForm},
+  traits::Crud,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminEditRelay, RelayResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn edit_relay(
+  data: Json<AdminEditRelay>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<RelayResponse>> {
+  is_admin(&local_user_view)?;
+
+  let form = FederationRelayUpdateForm {
+    publish: data.publish,
+    max_objects_per_hour: data.max_objects_per_hour,
+    updated_at: Some(Some(Utc::now())),
+    ..Default::default()
+  };
+  let relay = FederationRelay::update(&mut context.pool(), data.id, &form).await?;
+
+  Ok(Json(RelayResponse { relay }))
+}
@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::source::federation_relay::FederationRelay;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::ListRelaysResponse;
+use lemmy_utils::error::LemmyResult;
+
+pub async fn list_relays(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ListRelaysResponse>> {
+  is_admin(&local_user_view)?;
+
+  let relays = FederationRelay::list(&mut context.pool()).await?;
+
+  Ok(Json(ListRelaysResponse { relays }))
+}
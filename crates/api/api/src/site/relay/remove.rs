@@ -0,0 +1,25 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::is_admin,
+};
+use lemmy_db_schema::{source::federation_relay::FederationRelay, traits::Crud};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::{AdminRemoveRelay, SuccessResponse};
+use lemmy_utils::error::LemmyResult;
+
+pub async fn remove_relay(
+  data: Json<AdminRemoveRelay>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let relay = FederationRelay::read(&mut context.pool(), data.id).await?;
+  FederationRelay::delete(&mut context.pool(), relay.id).await?;
+  ActivityChannel::submit_activity(SendActivityData::FollowRelay(relay, false), &context)?;
+
+  Ok(Json(SuccessResponse::default()))
+}
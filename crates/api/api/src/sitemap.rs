@@ -1,14 +1,55 @@
 use actix_web::{
   http::header::{self, CacheDirective},
-  web::Data,
+  web::{Data, Path},
   HttpResponse,
 };
-use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_schema::{newtypes::DbUrl, source::post::Post};
-use lemmy_utils::error::LemmyResult;
-use sitemap_rs::{url::Url, url_set::UrlSet};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use lemmy_api_utils::{context::LemmyContext, utils::check_private_instance};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{community::Community, local_site::LocalSite, person::Person, post::Post},
+  utils::SITEMAP_LIMIT,
+};
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  CACHE_DURATION_SITEMAP,
+};
+use moka::future::Cache;
+use sitemap_rs::{sitemap::Sitemap, sitemap_index::SitemapIndex, url::Url, url_set::UrlSet};
+use std::{
+  future::Future,
+  sync::{Arc, LazyLock},
+};
 use tracing::info;
 
+/// Generated sitemaps by path. They are regenerated when the cache expires, so that crawlers
+/// don't cause expensive queries.
+static SITEMAP_CACHE: LazyLock<Cache<String, Vec<u8>>> = LazyLock::new(|| {
+  Cache::builder()
+    .max_capacity(1000)
+    .time_to_live(CACHE_DURATION_SITEMAP)
+    .build()
+});
+
+/// What the sitemap index links to, so that requests for other sitemaps are rejected before
+/// anything is generated and cached.
+#[derive(Clone)]
+struct SitemapContents {
+  /// Months with posts, with the last modification of a post and the number of posts.
+  months: Vec<(DateTime<Utc>, DateTime<Utc>, i64)>,
+  communities: i64,
+  users: i64,
+}
+
+/// The contents by whether NSFW content is excluded.
+static SITEMAP_CONTENTS: LazyLock<Cache<bool, SitemapContents>> = LazyLock::new(|| {
+  Cache::builder()
+    .max_capacity(2)
+    .time_to_live(CACHE_DURATION_SITEMAP)
+    .build()
+});
+
 fn generate_urlset(posts: Vec<(DbUrl, chrono::DateTime<chrono::Utc>)>) -> LemmyResult<UrlSet> {
   let urls = posts
     .into_iter()
@@ -23,13 +64,174 @@ fn generate_urlset(posts: Vec<(DbUrl, chrono::DateTime<chrono::Utc>)>) -> LemmyR
   Ok(UrlSet::new(urls)?)
 }
 
+/// Child sitemaps with their url path, and the time of the last modification if known.
+fn generate_index(
+  base_url: &str,
+  sitemaps: Vec<(String, Option<DateTime<Utc>>)>,
+) -> LemmyResult<SitemapIndex> {
+  let sitemaps = sitemaps
+    .into_iter()
+    .map(|(path, last_modified)| {
+      Sitemap::new(
+        format!("{base_url}/sitemap/{path}.xml"),
+        last_modified.map(Into::into),
+      )
+    })
+    .collect();
+
+  Ok(SitemapIndex::new(sitemaps)?)
+}
+
+/// Numbers of the pages which are needed to list `count` items.
+fn pages(count: i64) -> impl Iterator<Item = i64> {
+  (1..).take_while(move |page| (page - 1) * SITEMAP_LIMIT < count)
+}
+
+/// The sitemap index, which links to the sitemaps of local posts by month, of local communities
+/// and of active local users. Months with many posts are split into several pages.
 pub async fn get_sitemap(context: Data<LemmyContext>) -> LemmyResult<HttpResponse> {
-  info!("Generating sitemap...",);
-  let posts = Post::list_for_sitemap(&mut context.pool()).await?;
-  info!("Loaded latest {} posts", posts.len());
+  let exclude_nsfw = check_sitemap_enabled(&context).await?.sitemap_exclude_nsfw;
+  cached_sitemap("index".to_string(), async {
+    let contents = sitemap_contents(&context, exclude_nsfw).await?;
+    info!(
+      "Generating sitemap index for {} months of posts, {} communities and {} users",
+      contents.months.len(),
+      contents.communities,
+      contents.users
+    );
 
+    let sitemaps = contents
+      .months
+      .iter()
+      .flat_map(|(month, last_modified, count)| {
+        pages(*count).map(move |page| {
+          (
+            format!("posts/{}/{page}", month.format("%Y-%m")),
+            Some(*last_modified),
+          )
+        })
+      })
+      .chain(pages(contents.communities).map(|page| (format!("communities/{page}"), None)))
+      .chain(pages(contents.users).map(|page| (format!("users/{page}"), None)))
+      .collect();
+
+    let mut buf = Vec::<u8>::new();
+    generate_index(&context.settings().get_protocol_and_hostname(), sitemaps)?.write(&mut buf)?;
+    Ok(buf)
+  })
+  .await
+}
+
+/// A page of the local posts which were published in the given month, formatted as `2025-08`.
+pub async fn get_posts_sitemap(
+  path: Path<(String, i64)>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  let (month, page) = path.into_inner();
+  let month_start = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+    .with_lemmy_type(LemmyErrorType::NotFound)?
+    .and_time(NaiveTime::MIN)
+    .and_utc();
+  let exclude_nsfw = check_sitemap_enabled(&context).await?.sitemap_exclude_nsfw;
+  let count = sitemap_contents(&context, exclude_nsfw)
+    .await?
+    .months
+    .iter()
+    .find(|(m, _, _)| *m == month_start)
+    .map(|(_, _, count)| *count)
+    .unwrap_or_default();
+  let page = check_page(page, count)?;
+  let month = month_start.format("%Y-%m");
+  cached_sitemap(format!("posts/{month}/{page}"), async {
+    let posts =
+      Post::list_for_sitemap(&mut context.pool(), month_start, page, exclude_nsfw).await?;
+    urlset_bytes(posts)
+  })
+  .await
+}
+
+pub async fn get_communities_sitemap(
+  page: Path<i64>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  let exclude_nsfw = check_sitemap_enabled(&context).await?.sitemap_exclude_nsfw;
+  let count = sitemap_contents(&context, exclude_nsfw).await?.communities;
+  let page = check_page(page.into_inner(), count)?;
+  cached_sitemap(format!("communities/{page}"), async {
+    let communities = Community::list_for_sitemap(&mut context.pool(), page, exclude_nsfw).await?;
+    urlset_bytes(communities)
+  })
+  .await
+}
+
+pub async fn get_users_sitemap(
+  page: Path<i64>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  let exclude_nsfw = check_sitemap_enabled(&context).await?.sitemap_exclude_nsfw;
+  let count = sitemap_contents(&context, exclude_nsfw).await?.users;
+  let page = check_page(page.into_inner(), count)?;
+  cached_sitemap(format!("users/{page}"), async {
+    let users = Person::list_for_sitemap(&mut context.pool(), page).await?;
+    urlset_bytes(users)
+  })
+  .await
+}
+
+/// Reads how many posts there are in each month, and how many communities and users there are.
+async fn sitemap_contents(
+  context: &LemmyContext,
+  exclude_nsfw: bool,
+) -> LemmyResult<SitemapContents> {
+  let contents = SITEMAP_CONTENTS
+    .try_get_with(exclude_nsfw, async {
+      let pool = &mut context.pool();
+      let mut months = vec![];
+      for (month, last_modified) in Post::list_sitemap_months(pool, exclude_nsfw).await? {
+        let count = Post::count_for_sitemap(pool, month, exclude_nsfw).await?;
+        months.push((month, last_modified, count));
+      }
+      Ok(SitemapContents {
+        months,
+        communities: Community::count_for_sitemap(pool, exclude_nsfw).await?,
+        users: Person::count_for_sitemap(pool).await?,
+      })
+    })
+    .await
+    .map_err(|e: Arc<LemmyError>| anyhow::anyhow!("err reading sitemap contents: {e:?}"))?;
+  Ok(contents)
+}
+
+/// Private instances have no sitemap.
+async fn check_sitemap_enabled(context: &LemmyContext) -> LemmyResult<LocalSite> {
+  let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+  check_private_instance(&None, &local_site)?;
+  Ok(local_site)
+}
+
+/// Only pages which are linked from the index exist, so that crawlers can't fill the cache with
+/// empty sitemaps.
+fn check_page(page: i64, count: i64) -> LemmyResult<i64> {
+  if !pages(count).any(|p| p == page) {
+    Err(LemmyErrorType::NotFound)?
+  }
+  Ok(page)
+}
+
+fn urlset_bytes(urls: Vec<(DbUrl, DateTime<Utc>)>) -> LemmyResult<Vec<u8>> {
   let mut buf = Vec::<u8>::new();
-  generate_urlset(posts)?.write(&mut buf)?;
+  generate_urlset(urls)?.write(&mut buf)?;
+  Ok(buf)
+}
+
+async fn cached_sitemap(
+  key: String,
+  generate: impl Future<Output = LemmyResult<Vec<u8>>>,
+) -> LemmyResult<HttpResponse> {
+  let buf = SITEMAP_CACHE
+    .try_get_with(key, generate)
+    .await
+    .map_err(|e: Arc<LemmyError>| anyhow::anyhow!("err generating sitemap: {e:?}"))?;
 
   Ok(
     HttpResponse::Ok()
@@ -42,7 +244,7 @@ pub async fn get_sitemap(context: Data<LemmyContext>) -> LemmyResult<HttpRespons
 #[cfg(test)]
 pub(crate) mod tests {
 
-  use crate::sitemap::generate_urlset;
+  use crate::sitemap::{check_page, generate_index, generate_urlset, pages};
   use chrono::{DateTime, NaiveDate, Utc};
   use elementtree::Element;
   use lemmy_db_schema::newtypes::DbUrl;
@@ -132,4 +334,64 @@ pub(crate) mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_generate_index() -> LemmyResult<()> {
+    let last_modified = NaiveDate::from_ymd_opt(2025, 8, 3)
+      .unwrap_or_default()
+      .and_hms_opt(4, 5, 6)
+      .unwrap_or_default()
+      .and_utc();
+    let sitemaps = vec![
+      ("posts/2025-08/1".to_string(), Some(last_modified)),
+      ("communities/1".to_string(), None),
+    ];
+
+    let mut buf = Vec::<u8>::new();
+    generate_index("https://lemmy.tld", sitemaps)?.write(&mut buf)?;
+    let root = Element::from_reader(buf.as_slice())?;
+
+    assert_eq!(root.tag().name(), "sitemapindex");
+    assert_eq!(root.child_count(), 2);
+    let locs = root
+      .children()
+      .filter_map(|n| n.children().find(|element| element.tag().name() == "loc"))
+      .map(Element::text)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      vec![
+        "https://lemmy.tld/sitemap/posts/2025-08/1.xml",
+        "https://lemmy.tld/sitemap/communities/1.xml"
+      ],
+      locs
+    );
+    assert_eq!(
+      root
+        .children()
+        .next()
+        .and_then(|n| n
+          .children()
+          .find(|element| element.tag().name() == "lastmod"))
+        .map(Element::text)
+        .unwrap_or_default(),
+      "2025-08-03T04:05:06+00:00"
+    );
+    assert_eq!(
+      None,
+      root.children().nth(1).and_then(|n| n
+        .children()
+        .find(|element| element.tag().name() == "lastmod"))
+    );
+
+    assert_eq!(0, pages(0).count());
+    assert_eq!(vec![1], pages(50_000).collect::<Vec<_>>());
+    assert_eq!(vec![1, 2], pages(50_001).collect::<Vec<_>>());
+
+    assert!(check_page(1, 1).is_ok());
+    assert!(check_page(2, 50_001).is_ok());
+    assert!(check_page(0, 1).is_err());
+    assert!(check_page(1, 0).is_err());
+    assert!(check_page(3, 50_001).is_err());
+    Ok(())
+  }
 }
@@ -2,14 +2,25 @@ pub use lemmy_db_schema::{
   newtypes::CommentId,
   source::comment::{Comment, CommentActions},
 };
+pub use lemmy_db_schema_file::enums::CommentDisplayMode;
 pub use lemmy_db_views_comment::{
-  api::{CommentResponse, GetComment, GetComments, GetCommentsResponse, GetCommentsSlimResponse},
+  api::{
+    CommentNode,
+    CommentResponse,
+    GetComment,
+    GetCommentTree,
+    GetCommentTreeResponse,
+    GetComments,
+    GetCommentsResponse,
+    GetCommentsSlimResponse,
+  },
   CommentSlimView,
   CommentView,
 };
 
 pub mod actions {
   pub use lemmy_db_views_comment::api::{
+    AcceptAnswer,
     CreateComment,
     CreateCommentLike,
     DeleteComment,
@@ -19,9 +30,11 @@ pub mod actions {
 
   pub mod moderation {
     pub use lemmy_db_views_comment::api::{
+      ApprovePendingComment,
       DistinguishComment,
       ListCommentLikes,
       ListCommentLikesResponse,
+      ListPendingComments,
       PurgeComment,
       RemoveComment,
     };
@@ -1,12 +1,13 @@
 pub use lemmy_db_schema::{
-  newtypes::{CommunityId, MultiCommunityId, TagId},
+  newtypes::{AutomodRuleId, CommunityId, MultiCommunityId, TagId},
   source::{
+    automod_rule::AutomodRule,
     community::{Community, CommunityActions},
     multi_community::{MultiCommunity, MultiCommunityFollow},
     tag::{Tag, TagsView},
   },
 };
-pub use lemmy_db_schema_file::enums::CommunityVisibility;
+pub use lemmy_db_schema_file::enums::{AutomodAction, CommunityVisibility};
 pub use lemmy_db_views_community::{
   api::{
     CommunityResponse,
@@ -46,13 +47,23 @@ pub mod actions {
       AddModToCommunity,
       AddModToCommunityResponse,
       ApproveCommunityPendingFollower,
+      BackfillCommunity,
       BanFromCommunity,
       BanFromCommunityResponse,
+      CommunityArchive,
+      CommunityArchiveTag,
       CommunityIdQuery,
+      CreateAutomodRule,
       CreateCommunityTag,
+      DeleteAutomodRule,
       DeleteCommunity,
       DeleteCommunityTag,
+      EditAutomodRule,
       EditCommunity,
+      ExportCommunity,
+      ListAutomodRules,
+      ListAutomodRulesResponse,
+      MoveCommunity,
       PurgeCommunity,
       RemoveCommunity,
       TransferCommunity,
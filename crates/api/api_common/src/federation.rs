@@ -7,7 +7,7 @@ pub use lemmy_db_schema::{
     instance::{Instance, InstanceActions},
   },
 };
-pub use lemmy_db_schema_file::enums::FederationMode;
+pub use lemmy_db_schema_file::enums::{ActorType, FederationBlockSeverity, FederationMode};
 pub use lemmy_db_views_readable_federation_state::ReadableFederationState;
 pub use lemmy_db_views_site::api::{
   FederatedInstances,
@@ -19,5 +19,46 @@ pub use lemmy_db_views_site::api::{
 };
 
 pub mod administration {
-  pub use lemmy_db_views_site::api::{AdminAllowInstanceParams, AdminBlockInstanceParams};
+  pub use lemmy_db_schema::{
+    newtypes::{FederationBlocklistSubscriptionId, FederationRelayId, InboundActivityId},
+    source::{
+      actor_key_rotation::ActorKeyRotation,
+      federation_blocklist::{FederationBlocklistPending, FederationBlocklistSubscription},
+      federation_relay::FederationRelay,
+      federation_send_error::FederationSendError,
+      inbound_activity::InboundActivity,
+    },
+  };
+  pub use lemmy_db_views_site::api::{
+    AdminAddFederationBlocklistSubscription,
+    AdminAddRelay,
+    AdminAllowInstanceParams,
+    AdminBlockInstanceParams,
+    AdminEditRelay,
+    AdminImportFederationBlocklist,
+    AdminImportFederationBlocklistResponse,
+    AdminPauseFederation,
+    AdminRemoveFederationBlocklistSubscription,
+    AdminRemoveRelay,
+    AdminResendFederation,
+    AdminRetryFederation,
+    AdminReviewFederationBlocklistSubscription,
+    AdminRotateKeys,
+    AdminSkipFederation,
+    ExportFederationBlocklistResponse,
+    FederationBlocklistEntry,
+    FederationBlocklistSubscriptionResponse,
+    FederationBlocklistSubscriptionView,
+    ListFederationBlocklistResponse,
+    ListFederationBlocklistSubscriptionsResponse,
+    ListFederationSendErrors,
+    ListFederationSendErrorsResponse,
+    ListInboundActivities,
+    ListInboundActivitiesResponse,
+    ListKeyRotations,
+    ListKeyRotationsResponse,
+    ListRelaysResponse,
+    RelayResponse,
+    ReplayInboundActivity,
+  };
 }
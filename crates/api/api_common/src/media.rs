@@ -1,4 +1,10 @@
-pub use lemmy_db_schema::source::images::{ImageDetails, LocalImage, RemoteImage};
+pub use lemmy_db_schema::{
+  newtypes::{ImageHash, ImageHashBlockId},
+  source::{
+    image_hash_block::ImageHashBlock,
+    images::{ImageDetails, LocalImage, RemoteImage},
+  },
+};
 pub use lemmy_db_views_local_image::{
   api::{
     DeleteImageParams,
@@ -9,4 +15,17 @@ pub use lemmy_db_views_local_image::{
     UploadImageResponse,
   },
   LocalImageView,
+  PersonMediaUsage,
 };
+pub use lemmy_db_views_site::api::{ImageHashBlockResponse, ListImageHashBlocksResponse};
+
+pub mod administration {
+  pub use lemmy_db_views_local_image::api::AdminSetMediaQuota;
+  pub use lemmy_db_views_site::api::{
+    AdminImportImageHashBlocklist,
+    AdminImportImageHashBlocklistResponse,
+    CreateImageHashBlock,
+    DeleteImageHashBlock,
+    ExportImageHashBlocklistResponse,
+  };
+}
@@ -10,9 +10,11 @@ pub use lemmy_db_schema::{
     AdminPurgePostId,
     AdminRemoveCommunityId,
     ModAddToCommunityId,
+    ModAutomodActionId,
     ModBanFromCommunityId,
     ModChangeCommunityVisibilityId,
     ModFeaturePostId,
+    ModFreezeCommunityId,
     ModLockPostId,
     ModRemoveCommentId,
     ModRemovePostId,
@@ -35,9 +37,11 @@ pub use lemmy_db_schema::{
       },
       moderator::{
         ModAddToCommunity,
+        ModAutomodAction,
         ModBanFromCommunity,
         ModChangeCommunityVisibility,
         ModFeaturePost,
+        ModFreezeCommunity,
         ModLockPost,
         ModRemoveComment,
         ModRemovePost,
@@ -59,9 +63,11 @@ pub use lemmy_db_views_modlog_combined::{
   AdminPurgePostView,
   AdminRemoveCommunityView,
   ModAddToCommunityView,
+  ModAutomodActionView,
   ModBanFromCommunityView,
   ModChangeCommunityVisibilityView,
   ModFeaturePostView,
+  ModFreezeCommunityView,
   ModLockPostView,
   ModRemoveCommentView,
   ModRemovePostView,
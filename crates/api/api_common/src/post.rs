@@ -1,6 +1,10 @@
 pub use lemmy_db_schema::{
   newtypes::PostId,
-  source::post::{Post, PostActions},
+  source::{
+    post::{Post, PostActions},
+    post_gallery::{PostGalleryItem, PostGalleryView},
+    video::{VideoDetails, VideoVariant, VideoVariantsView},
+  },
   PostFeatureType,
 };
 pub use lemmy_db_schema_file::enums::{PostListingMode, PostNotificationsMode};
@@ -25,6 +29,7 @@ pub mod actions {
     CreatePostLike,
     DeletePost,
     EditPost,
+    GalleryItem,
     HidePost,
     MarkManyPostsAsRead,
     MarkPostAsRead,
@@ -34,7 +39,9 @@ pub mod actions {
 
   pub mod moderation {
     pub use lemmy_db_views_post::api::{
+      ApprovePendingPost,
       FeaturePost,
+      ListPendingPosts,
       ListPostLikes,
       ListPostLikesResponse,
       LockPost,
@@ -18,6 +18,8 @@ pub use lemmy_db_views_report_combined::{
     CreatePrivateMessageReport,
     GetReportCount,
     GetReportCountResponse,
+    ListReportCases,
+    ListReportCasesResponse,
     ListReports,
     ListReportsResponse,
     PostReportResponse,
@@ -26,10 +28,12 @@ pub use lemmy_db_views_report_combined::{
     ResolveCommunityReport,
     ResolvePostReport,
     ResolvePrivateMessageReport,
+    ResolveReportCase,
   },
   CommentReportView,
   CommunityReportView,
   PostReportView,
   PrivateMessageReportView,
+  ReportCaseView,
   ReportCombinedView,
 };
@@ -2,6 +2,7 @@ use crate::community_use_pending;
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_utils::{
+  automod::automod_check_comment,
   build_response::build_comment_response,
   context::LemmyContext,
   notify::NotifyData,
@@ -10,7 +11,9 @@ use lemmy_api_utils::{
   utils::{
     check_comment_depth,
     check_community_user_action,
+    check_post_comment_allowed,
     check_post_deleted_or_removed,
+    content_requires_approval,
     get_url_blocklist,
     is_mod_or_admin,
     process_markdown,
@@ -67,13 +70,23 @@ pub async fn create_comment(
   check_community_user_action(&local_user_view, &post_view.community, &mut context.pool()).await?;
   check_post_deleted_or_removed(&post)?;
 
-  // Check if post is locked, no new comments
+  // Posts which are held for approval can't be commented on yet
+  if post.pending_approval {
+    Err(LemmyErrorType::NotFound)?
+  }
+
+  // Check if post is locked or in slow mode
   let is_mod_or_admin = is_mod_or_admin(&mut context.pool(), &local_user_view, community_id)
     .await
     .is_ok();
-  if post.locked && !is_mod_or_admin {
-    Err(LemmyErrorType::Locked)?
-  }
+  check_post_comment_allowed(
+    &post,
+    &post_view.community,
+    &local_user_view.person,
+    is_mod_or_admin,
+    &mut context.pool(),
+  )
+  .await?;
 
   // Fetch the parent, if it exists
   let parent_opt = if let Some(parent_id) = data.parent_id {
@@ -99,9 +112,17 @@ pub async fn create_comment(
   )
   .await?;
 
+  let pending_approval = content_requires_approval(
+    &post_view.community,
+    &local_user_view.person,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
   let mut comment_form = CommentInsertForm {
     language_id: Some(language_id),
     federation_pending: Some(community_use_pending(&post_view.community, &context).await),
+    pending_approval: Some(pending_approval),
     ..CommentInsertForm::new(local_user_view.person.id, data.post_id, content.clone())
   };
   comment_form = plugin_hook_before("before_create_local_comment", comment_form).await?;
@@ -112,24 +133,32 @@ pub async fn create_comment(
     Comment::create(&mut context.pool(), &comment_form, parent_path.as_ref()).await?;
   plugin_hook_after("after_create_local_comment", &inserted_comment)?;
 
-  NotifyData::new(
-    post.clone(),
-    Some(inserted_comment.clone()),
-    local_user_view.person.clone(),
-    post_view.community,
-    !local_site.disable_email_notifications,
-  )
-  .send(&context);
+  // Comments which are held for approval are sent out once a moderator approves them, and
+  // comments removed by automod aren't sent at all
+  let automod = automod_check_comment(&inserted_comment, &context).await?;
+  if automod.is_visible() {
+    NotifyData::new(
+      post.clone(),
+      Some(inserted_comment.clone()),
+      local_user_view.person.clone(),
+      post_view.community,
+      !local_site.disable_email_notifications,
+    )
+    .send(&context);
+  }
 
   // You like your own comment by default
   let like_form = CommentLikeForm::new(local_user_view.person.id, inserted_comment.id, 1);
 
   CommentActions::like(&mut context.pool(), &like_form).await?;
 
-  ActivityChannel::submit_activity(
-    SendActivityData::CreateComment(inserted_comment.clone()),
-    &context,
-  )?;
+  if automod.is_visible() {
+    ActivityChannel::submit_activity(
+      SendActivityData::CreateComment(inserted_comment.clone()),
+      &context,
+    )?;
+  }
+  automod.send_replies(&context)?;
 
   // Update the read comments, so your own new comment doesn't appear as a +1 unread
   update_read_comments(
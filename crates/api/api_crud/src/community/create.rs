@@ -94,7 +94,13 @@ pub async fn create_community(
     followers_url: Some(generate_followers_url(&community_ap_id)?),
     inbox_url: Some(generate_inbox_url()?),
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    cross_posting_restricted_to_mods: data.cross_posting_restricted_to_mods,
+    question_answer: data.question_answer,
     visibility: data.visibility,
+    posts_require_approval: data.posts_require_approval,
+    comments_require_approval: data.comments_require_approval,
+    approval_max_account_age_days: data.approval_max_account_age_days,
+    comment_slow_mode_minutes: data.comment_slow_mode_minutes,
     ..CommunityInsertForm::new(
       site_view.site.instance_id,
       data.name.clone(),
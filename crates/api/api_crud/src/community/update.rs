@@ -21,7 +21,7 @@ use lemmy_db_schema::{
     mod_log::moderator::{ModChangeCommunityVisibility, ModChangeCommunityVisibilityForm},
   },
   traits::Crud,
-  utils::diesel_string_update,
+  utils::{diesel_opt_number_update, diesel_string_update},
 };
 use lemmy_db_views_community::api::{CommunityResponse, EditCommunity};
 use lemmy_db_views_local_user::LocalUserView;
@@ -79,7 +79,13 @@ pub async fn update_community(
     description,
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
+    cross_posting_restricted_to_mods: data.cross_posting_restricted_to_mods,
+    question_answer: data.question_answer,
     visibility: data.visibility,
+    posts_require_approval: data.posts_require_approval,
+    comments_require_approval: data.comments_require_approval,
+    approval_max_account_age_days: diesel_opt_number_update(data.approval_max_account_age_days),
+    comment_slow_mode_minutes: diesel_opt_number_update(data.comment_slow_mode_minutes),
     updated_at: Some(Some(Utc::now())),
     ..Default::default()
   };
@@ -3,6 +3,7 @@ use crate::community_use_pending;
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use lemmy_api_utils::{
+  automod::{automod_check_post, AutomodOutcome},
   build_response::build_post_response,
   context::LemmyContext,
   notify::NotifyData,
@@ -10,14 +11,19 @@ use lemmy_api_utils::{
   request::generate_post_link_metadata,
   send_activity::SendActivityData,
   utils::{
+    check_community_cross_posting_allowed,
+    check_community_posts_not_frozen,
     check_community_user_action,
     check_nsfw_allowed,
+    content_requires_approval,
     get_url_blocklist,
     honeypot_check,
     process_markdown_opt,
     send_webmention,
     slur_regex,
+    update_post_gallery,
     update_post_tags,
+    validate_gallery,
   },
 };
 use lemmy_db_schema::{
@@ -26,13 +32,14 @@ use lemmy_db_schema::{
   traits::{Crud, Likeable},
   utils::diesel_url_create,
 };
+use lemmy_db_schema_file::enums::CommentDisplayMode;
 use lemmy_db_views_community::CommunityView;
 use lemmy_db_views_community_moderator::CommunityModeratorView;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_post::api::{CreatePost, PostResponse};
 use lemmy_db_views_site::SiteView;
 use lemmy_utils::{
-  error::LemmyResult,
+  error::{LemmyErrorType, LemmyResult},
   utils::{
     slurs::check_slurs,
     validation::{
@@ -58,7 +65,23 @@ pub async fn create_post(
   let url_blocklist = get_url_blocklist(&context).await?;
 
   let body = process_markdown_opt(&data.body, &slur_regex, &url_blocklist, &context).await?;
-  let url = diesel_url_create(data.url.as_deref())?;
+  let gallery = data.gallery.clone().unwrap_or_default();
+  validate_gallery(&gallery, local_user_view.person.id, &slur_regex, &context).await?;
+  // Gallery posts link to their first image
+  let (url, alt_text) = if let Some(first) = gallery.first() {
+    if data.url.is_some() {
+      Err(LemmyErrorType::GalleryPostCantHaveUrl)?
+    }
+    (
+      diesel_url_create(Some(&first.url))?,
+      data.alt_text.clone().or_else(|| first.alt_text.clone()),
+    )
+  } else {
+    (
+      diesel_url_create(data.url.as_deref())?,
+      data.alt_text.clone(),
+    )
+  };
   let custom_thumbnail = diesel_url_create(data.custom_thumbnail.as_deref())?;
   check_nsfw_allowed(data.nsfw, Some(&local_site))?;
 
@@ -73,7 +96,7 @@ pub async fn create_post(
     is_valid_url(custom_thumbnail)?;
   }
 
-  if let Some(alt_text) = &data.alt_text {
+  if let Some(alt_text) = &alt_text {
     is_valid_alt_text_field(alt_text)?;
   }
 
@@ -107,6 +130,25 @@ pub async fn create_post(
     )
     .await?;
   }
+  check_community_posts_not_frozen(community, local_user_view.person.id, &mut context.pool())
+    .await?;
+
+  let cross_post_of_id = if let Some(cross_post_of_id) = data.cross_post_of_id {
+    let original = Post::read(&mut context.pool(), cross_post_of_id).await?;
+    if original.deleted || original.removed {
+      Err(LemmyErrorType::NotFound)?
+    }
+    check_community_cross_posting_allowed(
+      community,
+      local_user_view.person.id,
+      &mut context.pool(),
+    )
+    .await?;
+    // Cross-posts of a cross-post are linked to the same original post
+    Some(original.cross_post_original_id())
+  } else {
+    None
+  };
 
   let language_id = validate_post_language(
     &mut context.pool(),
@@ -118,14 +160,29 @@ pub async fn create_post(
 
   let scheduled_publish_time_at =
     convert_published_time(data.scheduled_publish_time_at, &local_user_view, &context).await?;
+  let pending_approval = content_requires_approval(
+    community,
+    &local_user_view.person,
+    true,
+    &mut context.pool(),
+  )
+  .await?;
   let mut post_form = PostInsertForm {
     url,
     body,
-    alt_text: data.alt_text.clone(),
+    alt_text,
     nsfw,
     language_id: Some(language_id),
     federation_pending: Some(community_use_pending(community, &context).await),
     scheduled_publish_time_at,
+    pending_approval: Some(pending_approval),
+    cross_post_of_id,
+    // Posts in question communities are shown with accepted answers first by default
+    comment_display_mode: data.comment_display_mode.or(
+      community
+        .question_answer
+        .then_some(CommentDisplayMode::QuestionAnswer),
+    ),
     ..PostInsertForm::new(
       data.name.trim().to_string(),
       local_user_view.person.id,
@@ -135,16 +192,34 @@ pub async fn create_post(
 
   post_form = plugin_hook_before("before_create_local_post", post_form).await?;
 
-  let inserted_post = Post::create(&mut context.pool(), &post_form).await?;
+  let mut inserted_post = Post::create(&mut context.pool(), &post_form).await?;
 
   plugin_hook_after("after_create_local_post", &inserted_post)?;
 
   if let Some(tags) = &data.tags {
     update_post_tags(&inserted_post, tags, &context).await?;
   }
+  if !gallery.is_empty() {
+    update_post_gallery(&inserted_post, &gallery, &context).await?;
+  }
 
+  // Scheduled posts are checked once they are published
+  let automod = if scheduled_publish_time_at.is_none() {
+    automod_check_post(&inserted_post, &context).await?
+  } else {
+    AutomodOutcome {
+      pending_approval,
+      ..Default::default()
+    }
+  };
+  inserted_post.pending_approval = automod.pending_approval;
+  inserted_post.removed = automod.removed;
+  inserted_post.nsfw |= automod.nsfw;
+
+  // Posts which are held for approval are sent out once a moderator approves them, and posts
+  // removed by automod aren't sent at all
   let community_id = community.id;
-  let federate_post = if scheduled_publish_time_at.is_none() {
+  let federate_post = if scheduled_publish_time_at.is_none() && automod.is_visible() {
     send_webmention(inserted_post.clone(), community);
     |post| Some(SendActivityData::CreatePost(post))
   } else {
@@ -165,14 +240,17 @@ pub async fn create_post(
 
   PostActions::like(&mut context.pool(), &like_form).await?;
 
-  NotifyData::new(
-    inserted_post.clone(),
-    None,
-    local_user_view.person.clone(),
-    community.clone(),
-    !local_site.disable_email_notifications,
-  )
-  .send(&context);
+  if automod.is_visible() {
+    NotifyData::new(
+      inserted_post.clone(),
+      None,
+      local_user_view.person.clone(),
+      community.clone(),
+      !local_site.disable_email_notifications,
+    )
+    .send(&context);
+  }
+  automod.send_replies(&context)?;
 
   let read_form = PostReadForm::new(post_id, person_id);
   PostActions::mark_as_read(&mut context.pool(), &read_form).await?;
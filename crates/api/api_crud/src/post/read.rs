@@ -90,9 +90,30 @@ pub async fn get_post(
   )
   .await?;
 
-  // Fetch the cross_posts
-  let cross_posts = if let Some(url) = &post_view.post.url {
-    SearchCombinedQuery {
+  // Fetch the cross_posts, first those which are explicitly linked to this post
+  let mut cross_posts = Vec::new();
+  for cross_post_id in post_view
+    .post
+    .list_cross_post_ids(&mut context.pool())
+    .await?
+  {
+    // Posts which the user isn't allowed to see are skipped
+    if let Ok(cross_post) = PostView::read(
+      &mut context.pool(),
+      cross_post_id,
+      local_user.as_ref(),
+      local_instance_id,
+      false,
+    )
+    .await
+    {
+      cross_posts.push(cross_post);
+    }
+  }
+
+  // Then other posts with the same url
+  if let Some(url) = &post_view.post.url {
+    let url_cross_posts = SearchCombinedQuery {
       search_term: Some(url.inner().as_str().into()),
       post_url_only: Some(true),
       type_: Some(SearchType::Posts),
@@ -106,10 +127,13 @@ pub async fn get_post(
     // Don't return this post as one of the cross_posts
     .filter(|x| x.post.id != post_id)
     .cloned()
-    .collect::<Vec<PostView>>()
-  } else {
-    Vec::new()
-  };
+    .collect::<Vec<PostView>>();
+    for cross_post in url_cross_posts {
+      if !cross_posts.iter().any(|c| c.post.id == cross_post.post.id) {
+        cross_posts.push(cross_post);
+      }
+    }
+  }
 
   // Return the jwt
   Ok(Json(GetPostResponse {
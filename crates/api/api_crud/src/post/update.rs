@@ -16,7 +16,9 @@ use lemmy_api_utils::{
     process_markdown_opt,
     send_webmention,
     slur_regex,
+    update_post_gallery,
     update_post_tags,
+    validate_gallery,
   },
 };
 use lemmy_db_schema::{
@@ -107,6 +109,22 @@ pub async fn update_post(
     Err(LemmyErrorType::NoPostEditAllowed)?
   }
 
+  // Gallery posts link to their first image
+  let (url, alt_text) = match &data.gallery {
+    Some(gallery) => {
+      validate_gallery(gallery, local_user_view.person.id, &slur_regex, &context).await?;
+      match gallery.first() {
+        Some(_) if data.url.is_some() => Err(LemmyErrorType::GalleryPostCantHaveUrl)?,
+        Some(first) => (
+          diesel_url_update(Some(&first.url))?,
+          alt_text.or_else(|| first.alt_text.clone().map(Some)),
+        ),
+        None => (url, alt_text),
+      }
+    }
+    None => (url, alt_text),
+  };
+
   let language_id = validate_post_language(
     &mut context.pool(),
     data.language_id,
@@ -139,6 +157,7 @@ pub async fn update_post(
     language_id: Some(language_id),
     updated_at: Some(Some(Utc::now())),
     scheduled_publish_time_at,
+    comment_display_mode: data.comment_display_mode,
     ..Default::default()
   };
   post_form = plugin_hook_before("before_update_local_post", post_form).await?;
@@ -150,6 +169,9 @@ pub async fn update_post(
   if let Some(tags) = &data.tags {
     update_post_tags(&orig_post.post, tags, &context).await?;
   }
+  if let Some(gallery) = &data.gallery {
+    update_post_gallery(&orig_post.post, gallery, &context).await?;
+  }
 
   NotifyData::new(
     updated_post.clone(),
@@ -199,3 +221,161 @@ pub async fn update_post(
   )
   .await
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::post::create::create_post;
+  use lemmy_api_utils::media::image_url;
+  use lemmy_db_schema::{
+    newtypes::PersonId,
+    source::{
+      community::CommunityInsertForm,
+      images::{ImageDetailsInsertForm, LocalImage, LocalImageForm},
+      instance::Instance,
+    },
+    test_data::TestData,
+  };
+  use lemmy_db_views_post::api::{CreatePost, GalleryItem};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  async fn upload_image(
+    person_id: PersonId,
+    alias: &str,
+    context: &LemmyContext,
+  ) -> LemmyResult<String> {
+    let url = image_url(alias, &context.settings().get_protocol_and_hostname())?;
+    let form = LocalImageForm {
+      pictrs_alias: alias.to_string(),
+      person_id,
+      thumbnail_for_post_id: None,
+      phash: None,
+      file_size: None,
+    };
+    let details_form = ImageDetailsInsertForm {
+      link: url.clone().into(),
+      width: 100,
+      height: 100,
+      content_type: "image/png".to_string(),
+      blurhash: None,
+    };
+    LocalImage::create(&mut context.pool(), &form, &details_form).await?;
+    Ok(url.to_string())
+  }
+
+  fn gallery_item(url: &str, alt_text: Option<&str>) -> GalleryItem {
+    GalleryItem {
+      url: url.to_string(),
+      alt_text: alt_text.map(ToString::to_string),
+      caption: None,
+    }
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_gallery_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+    let instance = Instance::read_or_create(pool, "example.com".to_string()).await?;
+
+    let user = LocalUserView::create_test_user(pool, "gallery_user", "", false).await?;
+    let other = LocalUserView::create_test_user(pool, "gallery_other", "", false).await?;
+    let community_form = CommunityInsertForm::new(
+      instance.id,
+      "gallery".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+
+    let first = upload_image(user.person.id, "gallery_first.png", &context).await?;
+    let second = upload_image(user.person.id, "gallery_second.png", &context).await?;
+    let foreign = upload_image(other.person.id, "gallery_foreign.png", &context).await?;
+
+    // Only images which the user uploaded to this instance can be used
+    let slur_regex = slur_regex(&context).await?;
+    for url in [
+      foreign.as_str(),
+      "https://remote.tld/api/v4/image/gallery_first.png",
+      "https://remote.tld/gallery_first.png",
+    ] {
+      let res = validate_gallery(
+        &[gallery_item(url, None)],
+        user.person.id,
+        &slur_regex,
+        &context,
+      )
+      .await;
+      assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::InvalidGalleryImage));
+    }
+
+    // The post links to the first image and uses its alt text
+    let form = CreatePost {
+      name: "gallery post".to_string(),
+      community_id: community.id,
+      gallery: Some(vec![
+        gallery_item(&first, Some("first alt")),
+        gallery_item(&second, None),
+      ]),
+      ..Default::default()
+    };
+    let post = create_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    assert_eq!(Some(first.clone()), post.post.url.map(|u| u.to_string()));
+    assert_eq!(Some("first alt".to_string()), post.post.alt_text);
+    assert_eq!(2, post.gallery.0.len());
+
+    // Without alt text on the new first image, the existing alt text is kept
+    let form = EditPost {
+      post_id: post.post.id,
+      gallery: Some(vec![gallery_item(&second, None)]),
+      ..Default::default()
+    };
+    let edited = update_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    assert_eq!(Some(second.clone()), edited.post.url.map(|u| u.to_string()));
+    assert_eq!(Some("first alt".to_string()), edited.post.alt_text);
+    assert_eq!(1, edited.gallery.0.len());
+
+    // An explicitly cleared alt text isn't replaced by the one of the first image
+    let form = EditPost {
+      post_id: post.post.id,
+      alt_text: Some(String::new()),
+      gallery: Some(vec![gallery_item(&second, Some("second alt"))]),
+      ..Default::default()
+    };
+    let edited = update_post(Json(form), context.clone(), user.clone())
+      .await?
+      .0
+      .post_view;
+    assert_eq!(None, edited.post.alt_text);
+
+    // Gallery posts can't have another url, or use foreign images
+    let form = EditPost {
+      post_id: post.post.id,
+      url: Some(first.clone()),
+      gallery: Some(vec![gallery_item(&second, None)]),
+      ..Default::default()
+    };
+    let res = update_post(Json(form), context.clone(), user.clone()).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::GalleryPostCantHaveUrl));
+    let form = EditPost {
+      post_id: post.post.id,
+      gallery: Some(vec![gallery_item(&foreign, None)]),
+      ..Default::default()
+    };
+    let res = update_post(Json(form), context.clone(), user.clone()).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::InvalidGalleryImage));
+
+    Instance::delete(pool, instance.id).await?;
+    data.delete(pool).await?;
+
+    Ok(())
+  }
+}
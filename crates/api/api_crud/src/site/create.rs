@@ -1,10 +1,15 @@
 use super::not_zero;
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  image_hash_block_threshold_check,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
 use actix_web::web::Json;
 use chrono::Utc;
 use lemmy_api_utils::{
   context::LemmyContext,
+  media::quota::media_quota_check,
   utils::{
     generate_inbox_url,
     get_url_blocklist,
@@ -22,7 +27,7 @@ use lemmy_db_schema::{
     site::{Site, SiteUpdateForm},
   },
   traits::Crud,
-  utils::diesel_string_update,
+  utils::{diesel_opt_number_update, diesel_string_update},
 };
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_site::{
@@ -108,6 +113,10 @@ pub async fn create_site(
     disallow_nsfw_content: data.disallow_nsfw_content,
     disable_email_notifications: data.disable_email_notifications,
     suggested_communities: data.suggested_communities,
+    sitemap_exclude_nsfw: data.sitemap_exclude_nsfw,
+    image_hash_block_threshold: data.image_hash_block_threshold,
+    media_quota_user: diesel_opt_number_update(data.media_quota_user),
+    media_quota_moderator: diesel_opt_number_update(data.media_quota_moderator),
     ..Default::default()
   };
 
@@ -168,6 +177,9 @@ fn validate_create_payload(local_site: &LocalSite, create_site: &CreateSite) ->
   }
 
   site_default_post_listing_type_check(&create_site.default_post_listing_type)?;
+  image_hash_block_threshold_check(create_site.image_hash_block_threshold)?;
+  media_quota_check(create_site.media_quota_user)?;
+  media_quota_check(create_site.media_quota_moderator)?;
 
   // Ensure that the sidebar has fewer than the max num characters...
   if let Some(body) = &create_site.sidebar {
@@ -21,6 +21,15 @@ pub fn site_default_post_listing_type_check(
   }
 }
 
+/// Checks that the image hash block threshold is a valid number of bits. A threshold of 64 would
+/// match every image.
+pub fn image_hash_block_threshold_check(threshold: Option<i32>) -> LemmyResult<()> {
+  if threshold.is_some_and(|t| !(0..64).contains(&t)) {
+    Err(LemmyErrorType::InvalidImageHashBlockThreshold)?
+  }
+  Ok(())
+}
+
 /// Checks whether the application question and registration mode align.
 pub fn application_question_check(
   current_application_question: &Option<String>,
@@ -50,7 +59,12 @@ fn not_zero(val: Option<i32>) -> Option<i32> {
 #[cfg(test)]
 mod tests {
 
-  use crate::site::{application_question_check, not_zero, site_default_post_listing_type_check};
+  use crate::site::{
+    application_question_check,
+    image_hash_block_threshold_check,
+    not_zero,
+    site_default_post_listing_type_check,
+  };
   use lemmy_db_schema_file::enums::{ListingType, RegistrationMode};
 
   #[test]
@@ -61,6 +75,15 @@ mod tests {
     assert!(site_default_post_listing_type_check(&Some(ListingType::Subscribed)).is_err());
   }
 
+  #[test]
+  fn test_image_hash_block_threshold_check() {
+    assert!(image_hash_block_threshold_check(None).is_ok());
+    assert!(image_hash_block_threshold_check(Some(0)).is_ok());
+    assert!(image_hash_block_threshold_check(Some(10)).is_ok());
+    assert!(image_hash_block_threshold_check(Some(-1)).is_err());
+    assert!(image_hash_block_threshold_check(Some(64)).is_err());
+  }
+
   #[test]
   fn test_application_question_check() {
     assert!(
@@ -1,10 +1,15 @@
 use super::not_zero;
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  image_hash_block_threshold_check,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::config::Data;
 use actix_web::web::Json;
 use chrono::Utc;
 use lemmy_api_utils::{
   context::LemmyContext,
+  media::quota::media_quota_check,
   utils::{
     get_url_blocklist,
     is_admin,
@@ -119,6 +124,10 @@ pub async fn update_site(
     disallow_nsfw_content: data.disallow_nsfw_content,
     disable_email_notifications: data.disable_email_notifications,
     suggested_communities: data.suggested_communities,
+    sitemap_exclude_nsfw: data.sitemap_exclude_nsfw,
+    image_hash_block_threshold: data.image_hash_block_threshold,
+    media_quota_user: diesel_opt_number_update(data.media_quota_user),
+    media_quota_moderator: diesel_opt_number_update(data.media_quota_moderator),
     ..Default::default()
   };
 
@@ -212,6 +221,9 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   }
 
   site_default_post_listing_type_check(&edit_site.default_post_listing_type)?;
+  image_hash_block_threshold_check(edit_site.image_hash_block_threshold)?;
+  media_quota_check(edit_site.media_quota_user)?;
+  media_quota_check(edit_site.media_quota_moderator)?;
 
   // Ensure that the sidebar has fewer than the max num characters...
   if let Some(body) = &edit_site.sidebar {
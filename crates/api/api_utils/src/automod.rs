@@ -0,0 +1,571 @@
+use crate::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::check_is_mod_or_admin,
+};
+use chrono::{DateTime, Utc};
+use either::Either;
+use lemmy_db_schema::{
+  newtypes::{CommentId, DbUrl, LanguageId, PostId},
+  source::{
+    automod_rule::AutomodRule,
+    comment::{Comment, CommentInsertForm, CommentUpdateForm},
+    comment_report::{CommentReport, CommentReportForm},
+    community::Community,
+    instance::Instance,
+    mod_log::moderator::{ModAutomodAction, ModAutomodActionForm},
+    person::Person,
+    post::{Post, PostUpdateForm},
+    post_report::{PostReport, PostReportForm},
+    tag::{PostTag, Tag},
+  },
+  traits::{Crud, Reportable},
+};
+use lemmy_db_schema_file::enums::{AutomodAction, ReportSeverity};
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::validation::build_and_check_regex,
+};
+use tracing::warn;
+use url::Url;
+
+/// The default reason used in the modlog, when the rule doesn't have an action message.
+const DEFAULT_AUTOMOD_REASON: &str = "Automod";
+
+/// The parts of a post or comment which automod rules can match on.
+#[derive(Clone, Copy)]
+struct AutomodTarget<'a> {
+  title: Option<&'a str>,
+  body: Option<&'a str>,
+  url: Option<&'a Url>,
+  language_id: LanguageId,
+  published_at: DateTime<Utc>,
+  creator: &'a Person,
+  instance_domain: &'a str,
+}
+
+/// What the automod rules did to a new post or comment.
+///
+/// Automod doesn't federate any changes to the content itself, as other instances don't know
+/// about it yet. Instead the caller federates the content in its final state, or not at all.
+#[derive(Debug, Default)]
+pub struct AutomodOutcome {
+  /// The content is held for moderator approval, and is federated once it is approved.
+  pub pending_approval: bool,
+  /// The content was removed, so it must not be federated or notified about.
+  pub removed: bool,
+  /// The post was marked as NSFW.
+  pub nsfw: bool,
+  /// Replies by automod, which need to be federated after the content.
+  pub replies: Vec<Comment>,
+}
+
+impl AutomodOutcome {
+  fn new(pending_approval: bool) -> Self {
+    AutomodOutcome {
+      pending_approval,
+      ..Default::default()
+    }
+  }
+
+  /// Returns true if the content can be federated and notified about.
+  pub fn is_visible(&self) -> bool {
+    !self.pending_approval && !self.removed
+  }
+
+  /// Federates the replies by automod, after the content itself was federated or received.
+  pub fn send_replies(self, context: &LemmyContext) -> LemmyResult<()> {
+    if self.is_visible() {
+      for reply in self.replies {
+        ActivityChannel::submit_activity(SendActivityData::CreateComment(reply), context)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Checks a newly created or received post against the automod rules of its community, and
+/// applies the actions of all matching rules.
+///
+/// Each rule only acts once on a given post. Failures of single rules are logged, so that a
+/// broken rule can't prevent the post from being created.
+pub async fn automod_check_post(
+  post: &Post,
+  context: &LemmyContext,
+) -> LemmyResult<AutomodOutcome> {
+  let mut outcome = AutomodOutcome::new(post.pending_approval);
+  let rules = AutomodRule::list_enabled_for_community(&mut context.pool(), post.community_id)
+    .await?
+    .into_iter()
+    .filter(|r| r.apply_to_posts)
+    .collect::<Vec<_>>();
+  if rules.is_empty() {
+    return Ok(outcome);
+  }
+
+  let creator = Person::read(&mut context.pool(), post.creator_id).await?;
+  if is_exempt(&creator, post, context).await? {
+    return Ok(outcome);
+  }
+  let instance = Instance::read(&mut context.pool(), creator.instance_id).await?;
+  let target = AutomodTarget {
+    title: Some(&post.name),
+    body: post.body.as_deref(),
+    url: post.url.as_ref().map(DbUrl::inner),
+    language_id: post.language_id,
+    published_at: post.published_at,
+    creator: &creator,
+    instance_domain: &instance.domain,
+  };
+
+  for rule in rules {
+    if let Err(e) = apply_rule(&rule, &target, post, None, &mut outcome, context).await {
+      warn!(
+        "Automod rule {} failed for post {}: {e}",
+        rule.id.0, post.id.0
+      );
+    }
+    // Once the post is removed, there is no point in running the remaining rules
+    if outcome.removed {
+      break;
+    }
+  }
+  Ok(outcome)
+}
+
+/// Checks a newly created or received comment against the automod rules of its community, and
+/// applies the actions of all matching rules.
+///
+/// Each rule only acts once on a given comment. Failures of single rules are logged, so that a
+/// broken rule can't prevent the comment from being created.
+pub async fn automod_check_comment(
+  comment: &Comment,
+  context: &LemmyContext,
+) -> LemmyResult<AutomodOutcome> {
+  let mut outcome = AutomodOutcome::new(comment.pending_approval);
+  let post = Post::read(&mut context.pool(), comment.post_id).await?;
+  let rules = AutomodRule::list_enabled_for_community(&mut context.pool(), post.community_id)
+    .await?
+    .into_iter()
+    .filter(|r| r.apply_to_comments)
+    .collect::<Vec<_>>();
+  if rules.is_empty() {
+    return Ok(outcome);
+  }
+
+  let creator = Person::read(&mut context.pool(), comment.creator_id).await?;
+  if is_exempt(&creator, &post, context).await? {
+    return Ok(outcome);
+  }
+  let instance = Instance::read(&mut context.pool(), creator.instance_id).await?;
+  let target = AutomodTarget {
+    title: None,
+    body: Some(&comment.content),
+    url: None,
+    language_id: comment.language_id,
+    published_at: comment.published_at,
+    creator: &creator,
+    instance_domain: &instance.domain,
+  };
+
+  for rule in rules {
+    let res = apply_rule(&rule, &target, &post, Some(comment), &mut outcome, context).await;
+    if let Err(e) = res {
+      warn!(
+        "Automod rule {} failed for comment {}: {e}",
+        rule.id.0, comment.id.0
+      );
+    }
+    if outcome.removed {
+      break;
+    }
+  }
+  Ok(outcome)
+}
+
+/// Validates the regexes of an automod rule, before it is saved.
+pub fn check_automod_regexes(regexes: &[Option<&str>]) -> LemmyResult<()> {
+  for regex in regexes.iter().flatten() {
+    if regex.is_empty() {
+      Err(LemmyErrorType::InvalidRegex)?
+    }
+    build_and_check_regex(Some(regex))?;
+  }
+  Ok(())
+}
+
+/// Moderators and admins are never affected by automod.
+async fn is_exempt(creator: &Person, post: &Post, context: &LemmyContext) -> LemmyResult<bool> {
+  let local_instance_id = SiteView::read_local(&mut context.pool())
+    .await?
+    .site
+    .instance_id;
+  Ok(
+    check_is_mod_or_admin(
+      &mut context.pool(),
+      creator.id,
+      post.community_id,
+      local_instance_id,
+    )
+    .await
+    .is_ok(),
+  )
+}
+
+/// Applies the action of the rule if it matches, and records it in `outcome`.
+async fn apply_rule(
+  rule: &AutomodRule,
+  target: &AutomodTarget<'_>,
+  post: &Post,
+  comment: Option<&Comment>,
+  outcome: &mut AutomodOutcome,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let comment_id = comment.map(|c| c.id);
+  if !rule_matches(rule, target, Utc::now())?
+    || ModAutomodAction::exists_for_rule(&mut context.pool(), rule.id, post.id, comment_id).await?
+  {
+    return Ok(());
+  }
+
+  let moderator = Person::read(&mut context.pool(), rule.creator_id).await?;
+  let reason = rule
+    .action_message
+    .clone()
+    .unwrap_or_else(|| format!("{DEFAULT_AUTOMOD_REASON}: {}", rule.name));
+
+  match (rule.action, comment) {
+    (AutomodAction::Hold, _) if outcome.pending_approval => return Ok(()),
+    (AutomodAction::Hold, None) => {
+      let form = PostUpdateForm {
+        pending_approval: Some(true),
+        ..Default::default()
+      };
+      Post::update(&mut context.pool(), post.id, &form).await?;
+      outcome.pending_approval = true;
+    }
+    (AutomodAction::Hold, Some(comment)) => {
+      let form = CommentUpdateForm {
+        pending_approval: Some(true),
+        ..Default::default()
+      };
+      Comment::update(&mut context.pool(), comment.id, &form).await?;
+      outcome.pending_approval = true;
+    }
+    (AutomodAction::Remove, None) => {
+      let form = PostUpdateForm {
+        removed: Some(true),
+        ..Default::default()
+      };
+      Post::update(&mut context.pool(), post.id, &form).await?;
+      outcome.removed = true;
+    }
+    (AutomodAction::Remove, Some(comment)) => {
+      let form = CommentUpdateForm {
+        removed: Some(true),
+        ..Default::default()
+      };
+      Comment::update(&mut context.pool(), comment.id, &form).await?;
+      outcome.removed = true;
+    }
+    (AutomodAction::Report, None) => {
+      let report_form = PostReportForm {
+        creator_id: moderator.id,
+        post_id: post.id,
+        original_post_name: post.name.clone(),
+        original_post_url: post.url.clone(),
+        original_post_body: post.body.clone(),
+        reason: reason.clone(),
+        violates_instance_rules: false,
+        severity: ReportSeverity::default(),
+      };
+      PostReport::report(&mut context.pool(), &report_form).await?;
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      ActivityChannel::submit_activity(
+        SendActivityData::CreateReport {
+          object_id: post.ap_id.inner().clone(),
+          actor: moderator,
+          receiver: Either::Right(community),
+          reason: reason.clone(),
+        },
+        context,
+      )?;
+    }
+    (AutomodAction::Report, Some(comment)) => {
+      let report_form = CommentReportForm {
+        creator_id: moderator.id,
+        comment_id: comment.id,
+        original_comment_text: comment.content.clone(),
+        reason: reason.clone(),
+        violates_instance_rules: false,
+        severity: ReportSeverity::default(),
+      };
+      CommentReport::report(&mut context.pool(), &report_form).await?;
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      ActivityChannel::submit_activity(
+        SendActivityData::CreateReport {
+          object_id: comment.ap_id.inner().clone(),
+          actor: moderator,
+          receiver: Either::Right(community),
+          reason: reason.clone(),
+        },
+        context,
+      )?;
+    }
+    (AutomodAction::MarkNsfw, None) => {
+      if post.nsfw || outcome.nsfw {
+        return Ok(());
+      }
+      let form = PostUpdateForm {
+        nsfw: Some(true),
+        ..Default::default()
+      };
+      Post::update(&mut context.pool(), post.id, &form).await?;
+      outcome.nsfw = true;
+    }
+    (AutomodAction::AddTag, None) => {
+      let tag_id = rule.tag_id.ok_or(LemmyErrorType::NotFound)?;
+      let tag = Tag::read(&mut context.pool(), tag_id).await?;
+      if tag.deleted || tag.community_id != post.community_id {
+        Err(LemmyErrorType::TagNotInCommunity)?
+      }
+      let mut tag_ids = Tag::read_for_post(&mut context.pool(), post.id)
+        .await?
+        .into_iter()
+        .map(|t| t.id)
+        .collect::<Vec<_>>();
+      if tag_ids.contains(&tag_id) {
+        return Ok(());
+      }
+      tag_ids.push(tag_id);
+      PostTag::update(&mut context.pool(), post, &tag_ids).await?;
+    }
+    // Comments can't be marked as NSFW or tagged
+    (AutomodAction::MarkNsfw | AutomodAction::AddTag, Some(_)) => return Ok(()),
+    (AutomodAction::Reply, _) => {
+      let content = rule
+        .action_message
+        .clone()
+        .ok_or(LemmyErrorType::InvalidBodyField)?;
+      let reply_form = CommentInsertForm {
+        distinguished: Some(true),
+        ..CommentInsertForm::new(moderator.id, post.id, content)
+      };
+      let parent_path = comment.map(|c| c.path.clone());
+      let reply = Comment::create(&mut context.pool(), &reply_form, parent_path.as_ref()).await?;
+      outcome.replies.push(reply);
+    }
+  }
+
+  log_automod_action(rule, post.id, comment_id, reason, context).await?;
+  Ok(())
+}
+
+async fn log_automod_action(
+  rule: &AutomodRule,
+  post_id: PostId,
+  comment_id: Option<CommentId>,
+  reason: String,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let form = ModAutomodActionForm {
+    mod_person_id: rule.creator_id,
+    automod_rule_id: Some(rule.id),
+    community_id: rule.community_id,
+    post_id,
+    comment_id,
+    action: rule.action,
+    reason: Some(reason),
+  };
+  ModAutomodAction::create(&mut context.pool(), &form).await?;
+  Ok(())
+}
+
+/// Checks whether all the conditions of a rule match. A rule without any conditions matches
+/// everything.
+///
+/// Rules never match content which was published before the rule was created, so that fetching
+/// old content over federation doesn't trigger them.
+fn rule_matches(
+  rule: &AutomodRule,
+  target: &AutomodTarget<'_>,
+  now: DateTime<Utc>,
+) -> LemmyResult<bool> {
+  if target.published_at < rule.published_at {
+    return Ok(false);
+  }
+
+  let regex_matches = |regex: &Option<String>, text: Option<&str>| -> LemmyResult<bool> {
+    match (regex, text) {
+      (None, _) => Ok(true),
+      (Some(_), None) => Ok(false),
+      (Some(regex), Some(text)) => Ok(build_and_check_regex(Some(regex))?.is_match(text)),
+    }
+  };
+
+  let url_domain = target.url.and_then(Url::domain);
+  let account_age_days = (now - target.creator.published_at).num_days();
+  let karma = i64::from(target.creator.post_score) + i64::from(target.creator.comment_score);
+
+  Ok(
+    regex_matches(&rule.title_regex, target.title)?
+      && regex_matches(&rule.body_regex, target.body)?
+      && regex_matches(&rule.url_domain_regex, url_domain)?
+      && regex_matches(&rule.instance_domain_regex, Some(target.instance_domain))?
+      && !rule
+        .max_account_age_days
+        .is_some_and(|max| account_age_days >= i64::from(max))
+      && !rule.max_karma.is_some_and(|max| karma >= i64::from(max))
+      && !rule.language_id.is_some_and(|l| l != target.language_id),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{Days, TimeZone};
+  use lemmy_db_schema::{
+    newtypes::{AutomodRuleId, CommunityId, InstanceId, PersonId},
+    source::person::PersonInsertForm,
+  };
+
+  fn test_rule() -> AutomodRule {
+    AutomodRule {
+      id: AutomodRuleId(1),
+      community_id: CommunityId(1),
+      creator_id: PersonId(1),
+      name: "test".to_string(),
+      enabled: true,
+      apply_to_posts: true,
+      apply_to_comments: true,
+      title_regex: None,
+      body_regex: None,
+      url_domain_regex: None,
+      instance_domain_regex: None,
+      max_account_age_days: None,
+      max_karma: None,
+      language_id: None,
+      action: AutomodAction::Report,
+      action_message: None,
+      tag_id: None,
+      published_at: Utc.timestamp_nanos(0),
+      updated_at: None,
+    }
+  }
+
+  fn test_person(published_at: DateTime<Utc>, score: i32) -> LemmyResult<Person> {
+    let form = PersonInsertForm::test_form(InstanceId(1), "automod_person");
+    Ok(Person {
+      id: PersonId(2),
+      name: form.name,
+      display_name: None,
+      avatar: None,
+      published_at,
+      updated_at: None,
+      ap_id: Url::parse("https://example.com/u/automod_person")?.into(),
+      bio: None,
+      local: true,
+      private_key: None,
+      public_key: form.public_key,
+      last_refreshed_at: published_at,
+      banner: None,
+      deleted: false,
+      inbox_url: Url::parse("https://example.com/inbox")?.into(),
+      matrix_user_id: None,
+      bot_account: false,
+      instance_id: form.instance_id,
+      post_count: 0,
+      post_score: score,
+      comment_count: 0,
+      comment_score: score,
+      previous_public_key: None,
+      key_rotated_at: None,
+    })
+  }
+
+  #[test]
+  fn test_rule_matches() -> LemmyResult<()> {
+    let now = Utc::now();
+    let new_account = now.checked_sub_days(Days::new(2)).unwrap_or(now);
+    let person = test_person(new_account, 5)?;
+    let url = Url::parse("https://spam.example.com/buy-now")?;
+    let target = AutomodTarget {
+      title: Some("Buy cheap watches"),
+      body: Some("Visit my shop"),
+      url: Some(&url),
+      language_id: LanguageId(37),
+      published_at: now,
+      creator: &person,
+      instance_domain: "lemmy.example.com",
+    };
+
+    // A rule without conditions matches everything
+    assert!(rule_matches(&test_rule(), &target, now)?);
+
+    let title_rule = AutomodRule {
+      title_regex: Some("cheap (watches|bags)".to_string()),
+      ..test_rule()
+    };
+    assert!(rule_matches(&title_rule, &target, now)?);
+
+    // Title conditions never match comments
+    let comment_target = AutomodTarget {
+      title: None,
+      ..target
+    };
+    assert!(!rule_matches(&title_rule, &comment_target, now)?);
+
+    let domain_rule = AutomodRule {
+      url_domain_regex: Some(r"spam\.example\.com$".to_string()),
+      instance_domain_regex: Some(r"^lemmy\.".to_string()),
+      ..test_rule()
+    };
+    assert!(rule_matches(&domain_rule, &target, now)?);
+
+    // All conditions have to match
+    let mixed_rule = AutomodRule {
+      body_regex: Some("my shop".to_string()),
+      language_id: Some(LanguageId(38)),
+      ..test_rule()
+    };
+    assert!(!rule_matches(&mixed_rule, &target, now)?);
+
+    let account_age_rule = AutomodRule {
+      max_account_age_days: Some(7),
+      max_karma: Some(20),
+      ..test_rule()
+    };
+    assert!(rule_matches(&account_age_rule, &target, now)?);
+    let old_person = test_person(Utc.timestamp_nanos(0), 5)?;
+    let old_target = AutomodTarget {
+      creator: &old_person,
+      ..target
+    };
+    assert!(!rule_matches(&account_age_rule, &old_target, now)?);
+
+    let karma_rule = AutomodRule {
+      max_karma: Some(10),
+      ..test_rule()
+    };
+    assert!(!rule_matches(&karma_rule, &target, now)?);
+
+    // Rules don't apply to content older than the rule
+    let future_rule = AutomodRule {
+      published_at: now.checked_add_days(Days::new(1)).unwrap_or(now),
+      ..test_rule()
+    };
+    assert!(!rule_matches(&future_rule, &target, now)?);
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_check_automod_regexes() {
+    assert!(check_automod_regexes(&[None, Some("spam")]).is_ok());
+    assert!(check_automod_regexes(&[Some("(unclosed")]).is_err());
+    assert!(check_automod_regexes(&[Some("")]).is_err());
+    // Regexes which match everything are rejected
+    assert!(check_automod_regexes(&[Some(".*")]).is_err());
+  }
+}
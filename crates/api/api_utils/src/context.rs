@@ -1,4 +1,4 @@
-use crate::request::client_builder;
+use crate::{media::MediaBackend, request::client_builder};
 use activitypub_federation::config::{Data, FederationConfig};
 use lemmy_db_schema::{
   source::secret::Secret,
@@ -18,6 +18,7 @@ pub struct LemmyContext {
   /// Pictrs requests must bypass proxy. Unfortunately no_proxy can only be set on ClientBuilder
   /// and not on RequestBuilder, so we need a separate client here.
   pictrs_client: Arc<ClientWithMiddleware>,
+  media: Arc<MediaBackend>,
   secret: Arc<Secret>,
   rate_limit_cell: RateLimit,
 }
@@ -27,6 +28,7 @@ impl LemmyContext {
     pool: ActualDbPool,
     client: ClientWithMiddleware,
     pictrs_client: ClientWithMiddleware,
+    media: MediaBackend,
     secret: Secret,
     rate_limit_cell: RateLimit,
   ) -> LemmyContext {
@@ -34,6 +36,7 @@ impl LemmyContext {
       pool,
       client: Arc::new(client),
       pictrs_client: Arc::new(pictrs_client),
+      media: Arc::new(media),
       secret: Arc::new(secret),
       rate_limit_cell,
     }
@@ -50,6 +53,10 @@ impl LemmyContext {
   pub fn pictrs_client(&self) -> &ClientWithMiddleware {
     &self.pictrs_client
   }
+  /// Storage for uploaded images and thumbnails.
+  pub fn media(&self) -> &MediaBackend {
+    &self.media
+  }
   pub fn settings(&self) -> &'static Settings {
     &SETTINGS
   }
@@ -77,11 +84,13 @@ impl LemmyContext {
     };
 
     let rate_limit_cell = RateLimit::with_test_config();
+    let media = MediaBackend::new(&SETTINGS).expect("build media storage");
 
     let context = LemmyContext::create(
       pool,
       client.clone(),
       client,
+      media,
       secret,
       rate_limit_cell.clone(),
     );
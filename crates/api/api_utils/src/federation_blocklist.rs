@@ -0,0 +1,309 @@
+//! Import, export and subscriptions for the federation blocklist. Blocklists are exchanged in the
+//! domain block CSV format used by Mastodon:
+//!
+//! ```csv
+//! #domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate
+//! example.com,suspend,false,false,Spam,false
+//! ```
+use crate::context::LemmyContext;
+use chrono::Utc;
+use futures::StreamExt;
+use lemmy_db_schema::{
+  newtypes::{FederationBlocklistSubscriptionId, PersonId},
+  source::{
+    federation_blocklist::{
+      FederationBlockList,
+      FederationBlockListForm,
+      FederationBlocklistPending,
+      FederationBlocklistPendingForm,
+      FederationBlocklistSubscription,
+      FederationBlocklistSubscriptionUpdateForm,
+    },
+    instance::Instance,
+    mod_log::admin::{AdminBlockInstance, AdminBlockInstanceForm},
+  },
+  traits::Crud,
+  utils::DbPool,
+};
+use lemmy_db_schema_file::enums::FederationBlockSeverity;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use tracing::warn;
+
+/// Blocklists larger than this are rejected.
+const MAX_BLOCKLIST_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlocklistEntry {
+  pub domain: String,
+  pub severity: FederationBlockSeverity,
+  pub public_reason: Option<String>,
+}
+
+/// Parses a blocklist in Mastodon's domain block CSV format. The header line is optional, without
+/// it the first column is read as domain and the second as severity. Entries with severity `noop`
+/// and obfuscated domains are skipped.
+pub fn parse_blocklist_csv(csv: &str) -> Vec<BlocklistEntry> {
+  let mut lines = csv.lines().filter(|l| !l.trim().is_empty()).peekable();
+
+  let mut domain_col = 0;
+  let mut severity_col = Some(1);
+  let mut reason_col = None;
+  if let Some(header) = lines.peek() {
+    let header = split_csv_line(header)
+      .into_iter()
+      .map(|h| h.trim_start_matches('#').to_lowercase())
+      .collect::<Vec<_>>();
+    if header.iter().any(|h| h == "domain") {
+      domain_col = header
+        .iter()
+        .position(|h| h == "domain")
+        .unwrap_or_default();
+      severity_col = header.iter().position(|h| h == "severity");
+      reason_col = header
+        .iter()
+        .position(|h| h == "public_comment" || h == "comment");
+      lines.next();
+    }
+  }
+
+  lines
+    .filter_map(|line| {
+      let fields = split_csv_line(line);
+      let domain = fields.get(domain_col)?.trim().to_lowercase();
+      if domain.is_empty() || domain.contains(['*', ' ', '/']) {
+        return None;
+      }
+      let severity = match severity_col
+        .and_then(|c| fields.get(c))
+        .map(|s| s.trim().to_lowercase())
+        .as_deref()
+      {
+        Some("silence") => FederationBlockSeverity::Silence,
+        Some("noop") => return None,
+        _ => FederationBlockSeverity::Suspend,
+      };
+      let public_reason = reason_col
+        .and_then(|c| fields.get(c))
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+      Some(BlocklistEntry {
+        domain,
+        severity,
+        public_reason,
+      })
+    })
+    .collect()
+}
+
+/// Serializes the blocklist in Mastodon's domain block CSV format. Private comments are not
+/// included.
+pub fn blocklist_to_csv(blocklist: &[(Instance, FederationBlockList)]) -> String {
+  let mut csv =
+    "#domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate\n".to_string();
+  for (instance, block) in blocklist {
+    let severity = match block.severity {
+      FederationBlockSeverity::Silence => "silence",
+      FederationBlockSeverity::Suspend => "suspend",
+    };
+    let reason = block.public_reason.as_deref().unwrap_or_default();
+    csv.push_str(&format!(
+      "{},{severity},false,false,{},false\n",
+      escape_csv_field(&instance.domain),
+      escape_csv_field(reason)
+    ));
+  }
+  csv
+}
+
+/// Splits a single CSV line into its fields, handling quoted fields with escaped quotes.
+fn split_csv_line(line: &str) -> Vec<String> {
+  let mut fields = vec![];
+  let mut field = String::new();
+  let mut in_quotes = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if in_quotes && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' => in_quotes = !in_quotes,
+      ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+      c => field.push(c),
+    }
+  }
+  fields.push(field);
+  fields
+}
+
+fn escape_csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+/// Blocks all the given domains, and writes a modlog entry for each of them. Returns the number of
+/// blocked instances.
+pub async fn block_instances(
+  entries: Vec<BlocklistEntry>,
+  subscription_id: Option<FederationBlocklistSubscriptionId>,
+  admin_person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<usize> {
+  let local_instance_id = SiteView::read_local(pool).await?.site.instance_id;
+  let local_domain = Instance::read(pool, local_instance_id).await?.domain;
+  if entries
+    .iter()
+    .any(|e| e.domain.eq_ignore_ascii_case(&local_domain))
+  {
+    Err(LemmyErrorType::CantBlockLocalInstance)?
+  }
+
+  let mut count = 0;
+  for entry in entries {
+    let instance_id = Instance::read_or_create(pool, entry.domain).await?.id;
+    let form = FederationBlockListForm {
+      instance_id,
+      severity: Some(entry.severity),
+      public_reason: entry.public_reason.clone(),
+      subscription_id,
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await?;
+
+    let mod_log_form = AdminBlockInstanceForm {
+      instance_id,
+      admin_person_id,
+      blocked: true,
+      reason: entry.public_reason,
+      severity: Some(entry.severity),
+    };
+    AdminBlockInstance::create(pool, &mod_log_form).await?;
+    count += 1;
+  }
+  Ok(count)
+}
+
+/// Fetches the subscribed blocklist, and stores all newly added domains for review by an admin.
+pub async fn refresh_blocklist_subscription(
+  subscription: &FederationBlocklistSubscription,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let res = fetch_blocklist(&subscription.url, context).await;
+  let last_error = match res {
+    Ok(entries) => {
+      let local_domain = &context.settings().hostname;
+      let forms = entries
+        .into_iter()
+        // The local instance can't be blocked
+        .filter(|e| !e.domain.eq_ignore_ascii_case(local_domain))
+        .map(|e| FederationBlocklistPendingForm {
+          subscription_id: subscription.id,
+          domain: e.domain,
+          severity: e.severity,
+          public_reason: e.public_reason,
+        })
+        .collect::<Vec<_>>();
+      FederationBlocklistPending::create_many(&mut context.pool(), &forms).await?;
+      None
+    }
+    Err(e) => {
+      warn!("Failed to refresh blocklist {}: {e}", subscription.url);
+      Some(e.to_string())
+    }
+  };
+  let form = FederationBlocklistSubscriptionUpdateForm {
+    last_refreshed_at: Some(Some(Utc::now())),
+    last_error: Some(last_error),
+  };
+  FederationBlocklistSubscription::update(&mut context.pool(), subscription.id, &form).await?;
+  Ok(())
+}
+
+/// Refreshes all subscribed blocklists.
+pub async fn refresh_blocklist_subscriptions(context: &LemmyContext) -> LemmyResult<()> {
+  for subscription in FederationBlocklistSubscription::list(&mut context.pool()).await? {
+    refresh_blocklist_subscription(&subscription, context).await?;
+  }
+  Ok(())
+}
+
+async fn fetch_blocklist(url: &str, context: &LemmyContext) -> LemmyResult<Vec<BlocklistEntry>> {
+  let mut stream = context
+    .client()
+    .get(url)
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes_stream();
+  let mut data = Vec::new();
+  while let Some(chunk) = stream.next().await {
+    let chunk = chunk?;
+    if data.len() + chunk.len() > MAX_BLOCKLIST_SIZE {
+      Err(anyhow::anyhow!("Blocklist is too large"))?
+    }
+    data.extend_from_slice(&chunk);
+  }
+  Ok(parse_blocklist_csv(&String::from_utf8(data)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_mastodon_csv() {
+    let csv = r#"#domain,#severity,#reject_media,#reject_reports,#public_comment,#obfuscate
+spam.example,suspend,false,false,"Spam, harassment",false
+noisy.example,silence,true,false,,false
+ignored.example,noop,true,false,,false
+obfuscated.*ample,suspend,false,false,,true
+"#;
+    assert_eq!(
+      vec![
+        BlocklistEntry {
+          domain: "spam.example".to_string(),
+          severity: FederationBlockSeverity::Suspend,
+          public_reason: Some("Spam, harassment".to_string()),
+        },
+        BlocklistEntry {
+          domain: "noisy.example".to_string(),
+          severity: FederationBlockSeverity::Silence,
+          public_reason: None,
+        },
+      ],
+      parse_blocklist_csv(csv)
+    );
+  }
+
+  #[test]
+  fn test_parse_csv_without_header() {
+    let csv = "Spam.Example\nnoisy.example,silence\n";
+    assert_eq!(
+      vec![
+        BlocklistEntry {
+          domain: "spam.example".to_string(),
+          severity: FederationBlockSeverity::Suspend,
+          public_reason: None,
+        },
+        BlocklistEntry {
+          domain: "noisy.example".to_string(),
+          severity: FederationBlockSeverity::Silence,
+          public_reason: None,
+        },
+      ],
+      parse_blocklist_csv(csv)
+    );
+  }
+
+  #[test]
+  fn test_csv_escape_roundtrip() {
+    let field = r#"Spam, "ads""#;
+    let line = format!("a.example,suspend,{}", escape_csv_field(field));
+    assert_eq!(vec!["a.example", "suspend", field], split_csv_line(&line));
+  }
+}
@@ -0,0 +1,73 @@
+//! Rotation of the signing keys of local actors. The previous public key is kept by the database,
+//! so that activities which were signed before the rotation can still be verified during
+//! [[KEY_ROTATION_GRACE_PERIOD]]. Other instances learn about the new key from an `Update`
+//! activity, or by refetching the actor when a signature doesn't match the key they know.
+use activitypub_federation::http_signatures::generate_actor_keypair;
+use lemmy_db_schema::{
+  newtypes::{DbUrl, PersonId},
+  source::{
+    actor_key_rotation::{ActorKeyRotation, ActorKeyRotationForm},
+    community::{Community, CommunityUpdateForm},
+    person::{Person, PersonUpdateForm},
+    site::{Site, SiteUpdateForm},
+  },
+  traits::{ApubActor, Crud},
+  utils::DbPool,
+};
+use lemmy_db_schema_file::enums::ActorType;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use tokio::task::spawn_blocking;
+
+/// Replaces the keypair of a local site, person or community, and records the rotation.
+pub async fn rotate_actor_key(
+  pool: &mut DbPool<'_>,
+  actor_ap_id: &DbUrl,
+  admin_person_id: Option<PersonId>,
+) -> LemmyResult<ActorKeyRotation> {
+  // Generating the key takes a while, so it shouldn't block other tasks
+  let keypair = spawn_blocking(generate_actor_keypair).await??;
+  let public_key = Some(keypair.public_key);
+  let private_key = Some(Some(keypair.private_key));
+
+  let site = Site::read_local(pool).await?;
+  let actor_type = if &site.ap_id == actor_ap_id {
+    let form = SiteUpdateForm {
+      public_key,
+      private_key,
+      ..Default::default()
+    };
+    Site::update(pool, site.id, &form).await?;
+    ActorType::Site
+  } else if let Some(person) = Person::read_from_apub_id(pool, actor_ap_id)
+    .await?
+    .filter(|p| p.local)
+  {
+    let form = PersonUpdateForm {
+      public_key,
+      private_key,
+      ..Default::default()
+    };
+    Person::update(pool, person.id, &form).await?;
+    ActorType::Person
+  } else if let Some(community) = Community::read_from_apub_id(pool, actor_ap_id)
+    .await?
+    .filter(|c| c.local)
+  {
+    let form = CommunityUpdateForm {
+      public_key,
+      private_key,
+      ..Default::default()
+    };
+    Community::update(pool, community.id, &form).await?;
+    ActorType::Community
+  } else {
+    Err(LemmyErrorType::NotFound)?
+  };
+
+  let form = ActorKeyRotationForm {
+    actor_type,
+    actor_ap_id: actor_ap_id.clone(),
+    admin_person_id,
+  };
+  ActorKeyRotation::create(pool, &form).await
+}
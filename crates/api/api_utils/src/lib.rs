@@ -1,6 +1,10 @@
+pub mod automod;
 pub mod build_response;
 pub mod claims;
 pub mod context;
+pub mod federation_blocklist;
+pub mod key_rotation;
+pub mod media;
 pub mod notify;
 pub mod plugins;
 pub mod request;
@@ -0,0 +1,605 @@
+//! Media backend which stores files with Lemmy itself, on the local filesystem or in an
+//! S3-compatible bucket. Images are decoded and encoded again in-process, which downscales them and
+//! leaves out EXIF and other metadata. Videos are only accepted if video processing is configured,
+//! and are stored unchanged.
+use super::{video::probe_video, MediaStorage, UploadType};
+use crate::{
+  context::LemmyContext,
+  request::{PictrsFile, PictrsFileDetails},
+};
+use actix_multipart::Multipart;
+use actix_web::{
+  http::header::{CacheControl, CacheDirective},
+  web::Payload,
+  HttpRequest,
+  HttpResponse,
+  HttpResponseBuilder,
+};
+use anyhow::anyhow;
+use chrono::Utc;
+use futures::{StreamExt, TryStreamExt};
+use image::{imageops::FilterType, DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use lemmy_db_views_local_image::api::ImageGetParams;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  settings::structs::{PictrsConfig, StorageBackend, VideoConfig},
+};
+use object_store::{
+  aws::AmazonS3Builder,
+  local::LocalFileSystem,
+  path::Path,
+  ObjectStore,
+  PutPayload,
+};
+use std::{io::Cursor, sync::Arc};
+use tokio::task::spawn_blocking;
+use url::Url;
+use uuid::Uuid;
+
+/// Larger uploads and remote images are rejected.
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Limit for video uploads, whose length is also limited by `video.max_duration`.
+const MAX_VIDEO_BYTES: usize = 500 * 1024 * 1024;
+
+/// Stored files never change, so clients can cache them for a long time.
+const CACHE_MAX_AGE: u32 = 365 * 24 * 60 * 60;
+
+#[derive(Clone)]
+pub struct BuiltinStorage {
+  store: Arc<dyn ObjectStore>,
+}
+
+impl BuiltinStorage {
+  pub fn new(config: &PictrsConfig) -> LemmyResult<Self> {
+    let store: Arc<dyn ObjectStore> = match config.storage {
+      StorageBackend::S3 => {
+        let s3 = config
+          .s3
+          .clone()
+          .ok_or_else(|| anyhow!("pictrs.s3 is required for S3 storage"))?;
+        let mut builder = AmazonS3Builder::new()
+          .with_bucket_name(s3.bucket)
+          .with_region(s3.region)
+          .with_access_key_id(s3.access_key_id)
+          .with_secret_access_key(s3.secret_access_key);
+        if let Some(endpoint) = s3.endpoint {
+          builder = builder.with_endpoint(endpoint).with_allow_http(true);
+        }
+        Arc::new(builder.build()?)
+      }
+      StorageBackend::Filesystem | StorageBackend::Pictrs => {
+        let path = config
+          .storage_path
+          .clone()
+          .ok_or_else(|| anyhow!("pictrs.storage_path is required for Filesystem storage"))?;
+        std::fs::create_dir_all(&path)?;
+        Arc::new(LocalFileSystem::new_with_prefix(path)?)
+      }
+    };
+    Ok(Self { store })
+  }
+
+  /// Processes the image and stores it under a new random alias.
+  async fn store(&self, data: Vec<u8>, max_size: Option<u32>) -> LemmyResult<PictrsFile> {
+    let image = spawn_blocking(move || process_image(&data, None, max_size)).await??;
+    let file = format!("{}.{}", Uuid::new_v4(), image.extension());
+    let file_size = i64::try_from(image.data.len())?;
+    self
+      .store
+      .put(&Path::from(file.as_str()), PutPayload::from(image.data))
+      .await?;
+
+    Ok(PictrsFile {
+      file,
+      details: PictrsFileDetails {
+        width: image.width.try_into()?,
+        height: image.height.try_into()?,
+        content_type: image.format.to_mime_type().to_string(),
+        created_at: Utc::now(),
+        blurhash: None,
+      },
+      file_size,
+    })
+  }
+
+  /// Stores an uploaded video without processing it, under a new random alias.
+  async fn store_video(
+    &self,
+    data: Vec<u8>,
+    kind: infer::Type,
+    config: &VideoConfig,
+  ) -> LemmyResult<PictrsFile> {
+    let probe = probe_video(&data, config).await?;
+    let file = format!("{}.{}", Uuid::new_v4(), kind.extension());
+    let file_size = i64::try_from(data.len())?;
+    self
+      .store
+      .put(&Path::from(file.as_str()), PutPayload::from(data))
+      .await?;
+
+    Ok(PictrsFile {
+      file,
+      details: PictrsFileDetails {
+        width: probe.width.try_into()?,
+        height: probe.height.try_into()?,
+        content_type: kind.mime_type().to_string(),
+        created_at: Utc::now(),
+        blurhash: None,
+      },
+      file_size,
+    })
+  }
+}
+
+impl MediaStorage for BuiltinStorage {
+  async fn upload(
+    &self,
+    req: &HttpRequest,
+    body: Payload,
+    upload_type: UploadType,
+    context: &LemmyContext,
+  ) -> LemmyResult<Vec<PictrsFile>> {
+    let pictrs = context.settings().pictrs()?;
+    let max_size = match upload_type {
+      UploadType::Avatar => Some(pictrs.max_avatar_size),
+      UploadType::Banner => Some(pictrs.max_banner_size),
+      UploadType::Other => pictrs.max_upload_size,
+    };
+
+    let video = pictrs.video.filter(|_| upload_type == UploadType::Other);
+    let max_bytes = if video.is_some() {
+      MAX_VIDEO_BYTES
+    } else {
+      MAX_IMAGE_BYTES
+    };
+
+    let mut multipart = Multipart::new(req.headers(), body);
+    let mut files = vec![];
+    while let Some(mut field) = multipart.try_next().await.map_err(|e| anyhow!("{e}"))? {
+      let mut data = Vec::new();
+      while let Some(chunk) = field.try_next().await.map_err(|e| anyhow!("{e}"))? {
+        if data.len() + chunk.len() > max_bytes {
+          Err(LemmyErrorType::InvalidImageUpload)?
+        }
+        data.extend_from_slice(&chunk);
+      }
+      let file = match (&video, video_type(&data)) {
+        (Some(config), Some(kind)) => self.store_video(data, kind, config).await?,
+        _ if data.len() > MAX_IMAGE_BYTES => Err(LemmyErrorType::InvalidImageUpload)?,
+        _ => self.store(data, max_size).await?,
+      };
+      files.push(file);
+    }
+    Ok(files)
+  }
+
+  async fn get(
+    &self,
+    alias: &str,
+    params: &ImageGetParams,
+    _req: &HttpRequest,
+    _context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    let data = match self.store.get(&Path::from(alias)).await {
+      Ok(res) => res.bytes().await?.to_vec(),
+      Err(object_store::Error::NotFound { .. }) => return Ok(HttpResponse::NotFound().finish()),
+      Err(e) => Err(e)?,
+    };
+    if let Some(kind) = video_type(&data) {
+      return Ok(cached_response(kind.mime_type()).body(data));
+    }
+    image_response(data, params).await
+  }
+
+  async fn proxy(
+    &self,
+    url: &Url,
+    params: &ImageGetParams,
+    _req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    let data = fetch_image(url, context).await?;
+    image_response(data, params).await
+  }
+
+  async fn download(
+    &self,
+    url: &Url,
+    max_size: u32,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFile> {
+    let data = fetch_image(url, context).await?;
+    self.store(data, Some(max_size)).await
+  }
+
+  async fn proxied_image_details(
+    &self,
+    url: &Url,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFileDetails> {
+    let data = fetch_image(url, context).await?;
+    let format = sniff_format(&data)?;
+    let (width, height) = ImageReader::with_format(Cursor::new(&data), format).into_dimensions()?;
+    Ok(PictrsFileDetails {
+      width: width.try_into()?,
+      height: height.try_into()?,
+      content_type: format.to_mime_type().to_string(),
+      created_at: Utc::now(),
+      blurhash: None,
+    })
+  }
+
+  async fn store_file(&self, data: Vec<u8>, _context: &LemmyContext) -> LemmyResult<String> {
+    let extension = infer::get(&data).map_or("bin", |kind| kind.extension());
+    let file = format!("{}.{}", Uuid::new_v4(), extension);
+    self
+      .store
+      .put(&Path::from(file.as_str()), PutPayload::from(data))
+      .await?;
+    Ok(file)
+  }
+
+  async fn read(&self, alias: &str, _context: &LemmyContext) -> LemmyResult<Vec<u8>> {
+    Ok(
+      self
+        .store
+        .get(&Path::from(alias))
+        .await?
+        .bytes()
+        .await?
+        .to_vec(),
+    )
+  }
+
+  async fn delete(&self, alias: &str, _context: &LemmyContext) -> LemmyResult<()> {
+    self.store.delete(&Path::from(alias)).await?;
+    Ok(())
+  }
+
+  /// There is only a single alias for each stored file.
+  async fn purge(&self, alias: &str, context: &LemmyContext) -> LemmyResult<Vec<String>> {
+    self.delete(alias, context).await?;
+    Ok(vec![alias.to_string()])
+  }
+
+  async fn health(&self, _context: &LemmyContext) -> LemmyResult<()> {
+    self.store.list_with_delimiter(None).await?;
+    Ok(())
+  }
+}
+
+/// Fetches a remote image, but only up to `MAX_IMAGE_BYTES`.
+pub(super) async fn fetch_image(url: &Url, context: &LemmyContext) -> LemmyResult<Vec<u8>> {
+  let mut stream = context
+    .client()
+    .get(url.as_str())
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes_stream();
+  let mut data = Vec::new();
+  while let Some(chunk) = stream.next().await {
+    let chunk = chunk?;
+    if data.len() + chunk.len() > MAX_IMAGE_BYTES {
+      Err(LemmyErrorType::NotAnImageType)?
+    }
+    data.extend_from_slice(&chunk);
+  }
+  Ok(data)
+}
+
+/// Responds with the image, converted to the requested file type and downscaled if the params ask
+/// for it.
+async fn image_response(data: Vec<u8>, params: &ImageGetParams) -> LemmyResult<HttpResponse> {
+  let (data, format) = if params.file_type.is_none() && params.max_size.is_none() {
+    let format = sniff_format(&data)?;
+    (data, format)
+  } else {
+    let output = params
+      .file_type
+      .as_deref()
+      .map(|f| ImageFormat::from_extension(f).ok_or(LemmyErrorType::NotAnImageType))
+      .transpose()?;
+    let max_size = params.max_size.map(u32::try_from).transpose()?;
+    let image = spawn_blocking(move || process_image(&data, output, max_size)).await??;
+    (image.data, image.format)
+  };
+
+  Ok(cached_response(format.to_mime_type()).body(data))
+}
+
+fn cached_response(content_type: &str) -> HttpResponseBuilder {
+  let mut res = HttpResponse::Ok();
+  res
+    .content_type(content_type)
+    .insert_header(CacheControl(vec![
+      CacheDirective::Public,
+      CacheDirective::MaxAge(CACHE_MAX_AGE),
+    ]));
+  res
+}
+
+fn video_type(data: &[u8]) -> Option<infer::Type> {
+  infer::get(data).filter(|kind| kind.matcher_type() == infer::MatcherType::Video)
+}
+
+struct ProcessedImage {
+  data: Vec<u8>,
+  format: ImageFormat,
+  width: u32,
+  height: u32,
+}
+
+impl ProcessedImage {
+  fn extension(&self) -> &'static str {
+    self
+      .format
+      .extensions_str()
+      .first()
+      .copied()
+      .unwrap_or("jpg")
+  }
+}
+
+/// Determines the image format from the content, ignoring file names or headers. Only formats
+/// which can be encoded again are supported.
+fn sniff_format(data: &[u8]) -> LemmyResult<ImageFormat> {
+  let mime_type = infer::get(data)
+    .map(|t| t.mime_type())
+    .ok_or(LemmyErrorType::NotAnImageType)?;
+  match mime_type {
+    "image/jpeg" | "image/png" | "image/gif" | "image/webp" => {
+      ImageFormat::from_mime_type(mime_type).ok_or(LemmyErrorType::NotAnImageType.into())
+    }
+    _ => Err(LemmyErrorType::NotAnImageType.into()),
+  }
+}
+
+/// Decodes the image, applies the EXIF orientation and downscales it to fit into `max_size`. It is
+/// then encoded again as `output`, or in the original format.
+///
+/// GIFs which are small enough keep their frames to preserve the animation, and only their metadata
+/// is removed.
+fn process_image(
+  data: &[u8],
+  output: Option<ImageFormat>,
+  max_size: Option<u32>,
+) -> LemmyResult<ProcessedImage> {
+  let format = sniff_format(data)?;
+  let output = output.unwrap_or(format);
+  let (width, height) = ImageReader::with_format(Cursor::new(data), format).into_dimensions()?;
+  let fits = max_size.map_or(true, |max| width <= max && height <= max);
+  if format == ImageFormat::Gif && output == ImageFormat::Gif && fits {
+    return Ok(ProcessedImage {
+      data: strip_gif_metadata(data)?,
+      format,
+      width,
+      height,
+    });
+  }
+
+  let mut decoder = ImageReader::with_format(Cursor::new(data), format).into_decoder()?;
+  let orientation = decoder.orientation()?;
+  let mut image = DynamicImage::from_decoder(decoder)?;
+  image.apply_orientation(orientation);
+  if let Some(max_size) = max_size.filter(|_| !fits) {
+    image = image.resize(max_size, max_size, FilterType::Lanczos3);
+  }
+  // JPEG has no alpha channel
+  if output == ImageFormat::Jpeg {
+    image = DynamicImage::ImageRgb8(image.to_rgb8());
+  }
+
+  let mut buf = Cursor::new(Vec::new());
+  image.write_to(&mut buf, output)?;
+  Ok(ProcessedImage {
+    data: buf.into_inner(),
+    format: output,
+    width: image.width(),
+    height: image.height(),
+  })
+}
+
+/// Copies a GIF without comments, plain text and application extensions, where metadata like XMP
+/// is stored. Only the application extension which makes the animation loop is kept. See
+/// https://www.w3.org/Graphics/GIF/spec-gif89a.txt for the format.
+fn strip_gif_metadata(data: &[u8]) -> LemmyResult<Vec<u8>> {
+  let mut reader = GifReader { data, pos: 0 };
+  let mut out = Vec::with_capacity(data.len());
+  // Header and logical screen descriptor, followed by the global color table
+  let screen = reader.take(13)?;
+  out.extend_from_slice(screen);
+  let packed = screen.get(10).copied().unwrap_or_default();
+  out.extend_from_slice(reader.take(color_table_len(packed))?);
+  loop {
+    match reader.byte()? {
+      // Image descriptor, optional local color table, LZW code size and image data
+      0x2C => {
+        let descriptor = reader.take(9)?;
+        let packed = descriptor.last().copied().unwrap_or_default();
+        out.push(0x2C);
+        out.extend_from_slice(descriptor);
+        out.extend_from_slice(reader.take(color_table_len(packed))?);
+        out.extend_from_slice(reader.take(1)?);
+        out.extend_from_slice(reader.sub_blocks()?);
+      }
+      0x21 => {
+        let label = reader.byte()?;
+        let blocks = reader.sub_blocks()?;
+        let keep = match label {
+          // Graphic control extension with the frame delay and transparency
+          0xF9 => true,
+          0xFF => matches!(
+            blocks.get(1..12),
+            Some(b"NETSCAPE2.0") | Some(b"ANIMEXTS1.0")
+          ),
+          _ => false,
+        };
+        if keep {
+          out.extend_from_slice(&[0x21, label]);
+          out.extend_from_slice(blocks);
+        }
+      }
+      0x3B => {
+        out.push(0x3B);
+        return Ok(out);
+      }
+      _ => Err(LemmyErrorType::NotAnImageType)?,
+    }
+  }
+}
+
+/// Size of the color table which follows a descriptor with the given packed fields.
+fn color_table_len(packed: u8) -> usize {
+  if packed & 0x80 == 0 {
+    0
+  } else {
+    3 << (usize::from(packed & 0x07) + 1)
+  }
+}
+
+struct GifReader<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> GifReader<'a> {
+  fn take(&mut self, len: usize) -> LemmyResult<&'a [u8]> {
+    let end = self
+      .pos
+      .checked_add(len)
+      .ok_or(LemmyErrorType::NotAnImageType)?;
+    let bytes = self
+      .data
+      .get(self.pos..end)
+      .ok_or(LemmyErrorType::NotAnImageType)?;
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  fn byte(&mut self) -> LemmyResult<u8> {
+    Ok(self.take(1)?.first().copied().unwrap_or_default())
+  }
+
+  /// Data sub-blocks, including their sizes and the terminating empty block.
+  fn sub_blocks(&mut self) -> LemmyResult<&'a [u8]> {
+    let start = self.pos;
+    loop {
+      let size = self.byte()?;
+      if size == 0 {
+        break;
+      }
+      self.take(usize::from(size))?;
+    }
+    Ok(self.data.get(start..self.pos).unwrap_or_default())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::RgbImage;
+  use object_store::memory::InMemory;
+  use pretty_assertions::assert_eq;
+
+  fn test_image(format: ImageFormat, width: u32, height: u32) -> LemmyResult<Vec<u8>> {
+    let image = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+    let mut buf = Cursor::new(Vec::new());
+    image.write_to(&mut buf, format)?;
+    Ok(buf.into_inner())
+  }
+
+  #[test]
+  fn test_process_image() -> LemmyResult<()> {
+    let png = test_image(ImageFormat::Png, 800, 400)?;
+
+    let resized = process_image(&png, None, Some(200))?;
+    assert_eq!(ImageFormat::Png, resized.format);
+    assert_eq!((200, 100), (resized.width, resized.height));
+    assert_eq!(ImageFormat::Png, sniff_format(&resized.data)?);
+
+    let converted = process_image(&png, Some(ImageFormat::Jpeg), None)?;
+    assert_eq!(ImageFormat::Jpeg, sniff_format(&converted.data)?);
+    assert_eq!((800, 400), (converted.width, converted.height));
+
+    // Videos and other files can't be uploaded
+    assert!(process_image(b"not an image", None, None).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_exif() -> LemmyResult<()> {
+    let jpeg = test_image(ImageFormat::Jpeg, 16, 16)?;
+    // Insert an APP1 segment with minimal EXIF data after the start of image marker
+    let exif = [
+      &[0xFF, 0xE1, 0x00, 0x16][..],
+      b"Exif\0\0",
+      b"MM\0\x2A\0\0\0\x08",
+      b"\0\0\0\0\0\0",
+    ]
+    .concat();
+    let (start, rest) = jpeg.split_at(2);
+    let with_exif = [start, &exif, rest].concat();
+    assert!(with_exif.windows(4).any(|w| w == b"Exif"));
+
+    let processed = process_image(&with_exif, None, None)?;
+    assert!(!processed.data.windows(4).any(|w| w == b"Exif"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_strip_gif_metadata() -> LemmyResult<()> {
+    let gif = test_image(ImageFormat::Gif, 16, 16)?;
+    // Add a comment, XMP metadata and the animation loop before the trailer
+    let extensions = [
+      &[0x21, 0xFE, 0x07][..],
+      b"comment",
+      &[0x00, 0x21, 0xFF, 0x0B],
+      b"XMP DataXMP",
+      &[0x04],
+      b"<x/>",
+      &[0x00, 0x21, 0xFF, 0x0B],
+      b"NETSCAPE2.0",
+      &[0x03, 0x01, 0x00, 0x00, 0x00],
+    ]
+    .concat();
+    let (rest, trailer) = gif.split_at(gif.len() - 1);
+    let with_metadata = [rest, &extensions, trailer].concat();
+
+    let processed = process_image(&with_metadata, None, None)?;
+    assert_eq!(ImageFormat::Gif, processed.format);
+    assert_eq!((16, 16), (processed.width, processed.height));
+    assert!(!processed.data.windows(7).any(|w| w == b"comment"));
+    assert!(!processed.data.windows(8).any(|w| w == b"XMP Data"));
+    assert!(processed.data.windows(11).any(|w| w == b"NETSCAPE2.0"));
+    assert_eq!(gif.len() + 19, processed.data.len());
+    process_image(&processed.data, Some(ImageFormat::Png), None)?;
+
+    // Truncated files are rejected
+    assert!(strip_gif_metadata(rest).is_err());
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_store() -> LemmyResult<()> {
+    let storage = BuiltinStorage {
+      store: Arc::new(InMemory::new()),
+    };
+    let file = storage
+      .store(test_image(ImageFormat::Png, 1000, 10)?, Some(500))
+      .await?;
+    assert!(file.file.ends_with(".png"));
+    assert_eq!(500, file.details.width);
+    assert_eq!("image/png", file.details.content_type);
+
+    let stored = storage
+      .store
+      .get(&Path::from(file.file.as_str()))
+      .await?
+      .bytes()
+      .await?;
+    assert_eq!(ImageFormat::Png, sniff_format(&stored)?);
+    Ok(())
+  }
+}
@@ -0,0 +1,226 @@
+//! Perceptual hashes of images, to block known unwanted images even after they were resized or
+//! encoded again. The hash is a difference hash (dHash): the image is reduced to 9x8 grayscale
+//! pixels, and each bit tells if a pixel is brighter than its right neighbour. Similar images have
+//! hashes which differ in only a few bits.
+//!
+//! Hash lists are exchanged as text files with one hash in hex per line, optionally followed by a
+//! comma and the reason. Lines starting with `#` are comments:
+//!
+//! ```text
+//! # Spam wave from August
+//! f0e1d2c3b4a59687,spam
+//! 0123456789abcdef
+//! ```
+use super::builtin::fetch_image;
+use crate::context::LemmyContext;
+use image::{imageops::FilterType, Luma};
+use lemmy_db_schema::{newtypes::ImageHash, source::image_hash_block::ImageHashBlock};
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  CacheLock,
+  CACHE_DURATION_FEDERATION,
+};
+use moka::future::Cache;
+use std::sync::{Arc, LazyLock};
+use tokio::task::spawn_blocking;
+use tracing::warn;
+use url::Url;
+
+static BLOCKLIST: CacheLock<Arc<Vec<ImageHash>>> = LazyLock::new(|| {
+  Cache::builder()
+    .max_capacity(1)
+    .time_to_live(CACHE_DURATION_FEDERATION)
+    .build()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashListEntry {
+  pub hash: ImageHash,
+  pub reason: Option<String>,
+}
+
+/// Computes the perceptual hash of an image. Fails for videos and other files which can't be
+/// decoded.
+pub async fn image_hash(data: Vec<u8>) -> LemmyResult<ImageHash> {
+  spawn_blocking(move || dhash(&data)).await?
+}
+
+fn dhash(data: &[u8]) -> LemmyResult<ImageHash> {
+  let pixels = image::load_from_memory(data)?
+    .grayscale()
+    .resize_exact(9, 8, FilterType::Triangle)
+    .to_luma8();
+  let mut hash = 0u64;
+  for y in 0..8 {
+    for x in 0..8 {
+      let Luma([left]) = *pixels.get_pixel(x, y);
+      let Luma([right]) = *pixels.get_pixel(x + 1, y);
+      hash = (hash << 1) | u64::from(left > right);
+    }
+  }
+  Ok(ImageHash(i64::from_be_bytes(hash.to_be_bytes())))
+}
+
+async fn blocklist(context: &LemmyContext) -> LemmyResult<Arc<Vec<ImageHash>>> {
+  Ok(
+    BLOCKLIST
+      .try_get_with::<_, LemmyError>((), async {
+        Ok(Arc::new(
+          ImageHashBlock::list_hashes(&mut context.pool()).await?,
+        ))
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to load image hash blocklist due to `{e}`"))?,
+  )
+}
+
+/// Needs to be called after changing the blocklist, so that changes take effect immediately.
+pub fn invalidate_image_hash_blocklist() {
+  BLOCKLIST.invalidate_all();
+}
+
+/// Fails with `ImageBlocked` if the hash is similar to a blocked hash.
+pub async fn check_image_hash(hash: ImageHash, context: &LemmyContext) -> LemmyResult<()> {
+  let blocklist = blocklist(context).await?;
+  if blocklist.is_empty() {
+    return Ok(());
+  }
+  let threshold = SiteView::read_local(&mut context.pool())
+    .await?
+    .local_site
+    .image_hash_block_threshold;
+  let threshold = u32::try_from(threshold).unwrap_or_default();
+  if blocklist.iter().any(|b| b.distance(hash) <= threshold) {
+    Err(LemmyErrorType::ImageBlocked)?
+  }
+  Ok(())
+}
+
+/// Checks if a remote image is similar to a blocked image. The image is only fetched if there are
+/// any blocked hashes. Images which can't be fetched or decoded are not considered blocked.
+pub async fn remote_image_blocked(url: &Url, context: &LemmyContext) -> bool {
+  let res = async {
+    if blocklist(context).await?.is_empty() {
+      return Ok(());
+    }
+    let hash = image_hash(fetch_image(url, context).await?).await?;
+    check_image_hash(hash, context).await
+  }
+  .await;
+  match res {
+    Err(e) if e.error_type == LemmyErrorType::ImageBlocked => true,
+    Err(e) => {
+      warn!("Failed to check image hash of {url}: {e}");
+      false
+    }
+    Ok(()) => false,
+  }
+}
+
+/// Parses a hash list, failing with `InvalidImageHash` for lines which don't start with a valid
+/// hash.
+pub fn parse_hash_list(list: &str) -> LemmyResult<Vec<HashListEntry>> {
+  list
+    .lines()
+    .map(str::trim)
+    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    .map(|line| {
+      let (hash, reason) = line.split_once(',').unwrap_or((line, ""));
+      let hash =
+        ImageHash::try_from(hash.to_string()).map_err(|_| LemmyErrorType::InvalidImageHash)?;
+      let reason = Some(reason.trim().to_string()).filter(|r| !r.is_empty());
+      Ok(HashListEntry { hash, reason })
+    })
+    .collect()
+}
+
+pub fn hash_list_to_string(blocklist: &[ImageHashBlock]) -> String {
+  let mut list = "# Lemmy image hash blocklist\n".to_string();
+  for block in blocklist {
+    list.push_str(&block.hash.to_string());
+    if let Some(reason) = &block.reason {
+      list.push(',');
+      list.push_str(&reason.replace('\n', " "));
+    }
+    list.push('\n');
+  }
+  list
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+  use image::{DynamicImage, ImageFormat, RgbImage};
+  use lemmy_db_schema::newtypes::ImageHashBlockId;
+  use pretty_assertions::assert_eq;
+  use std::io::Cursor;
+
+  fn gradient(width: u32, height: u32, format: ImageFormat) -> LemmyResult<Vec<u8>> {
+    // Gets brighter from left to right
+    let image = RgbImage::from_fn(width, height, |x, _| {
+      let v = u8::try_from(x * 255 / width).unwrap_or_default();
+      image::Rgb([v, v, v])
+    });
+    let mut buf = Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(image).write_to(&mut buf, format)?;
+    Ok(buf.into_inner())
+  }
+
+  #[test]
+  fn test_dhash_similar_images() -> LemmyResult<()> {
+    let original = dhash(&gradient(400, 300, ImageFormat::Png)?)?;
+    // Resized and converted to a lossy format
+    let resized = dhash(&gradient(200, 150, ImageFormat::Jpeg)?)?;
+    assert!(original.distance(resized) <= 6);
+
+    let mirrored = {
+      let image = image::load_from_memory(&gradient(400, 300, ImageFormat::Png)?)?.fliph();
+      let mut buf = Cursor::new(Vec::new());
+      image.write_to(&mut buf, ImageFormat::Png)?;
+      dhash(&buf.into_inner())?
+    };
+    assert!(original.distance(mirrored) > 6);
+
+    assert!(dhash(b"not an image").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_hash_list_roundtrip() -> LemmyResult<()> {
+    let list = "# comment\n\nf0e1d2c3b4a59687, spam\n0000000000000001\n";
+    let entries = parse_hash_list(list)?;
+    assert_eq!(
+      vec![
+        HashListEntry {
+          hash: ImageHash::try_from("f0e1d2c3b4a59687".to_string())?,
+          reason: Some("spam".to_string()),
+        },
+        HashListEntry {
+          hash: ImageHash(1),
+          reason: None,
+        },
+      ],
+      entries
+    );
+
+    let blocks = entries
+      .into_iter()
+      .map(|e| ImageHashBlock {
+        id: ImageHashBlockId(1),
+        hash: e.hash,
+        reason: e.reason,
+        admin_person_id: None,
+        published_at: Utc::now(),
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      "# Lemmy image hash blocklist\nf0e1d2c3b4a59687,spam\n0000000000000001\n",
+      hash_list_to_string(&blocks)
+    );
+
+    assert!(parse_hash_list("not a hash").is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,234 @@
+//! Storage for uploaded images and post thumbnails, and proxying of remote images.
+//!
+//! By default all media is handled by pict-rs. Small instances can use the builtin storage
+//! instead, which keeps files on the local filesystem or in an S3-compatible bucket.
+use crate::{
+  context::LemmyContext,
+  request::{PictrsFile, PictrsFileDetails},
+};
+use actix_web::{web::Payload, HttpRequest, HttpResponse};
+use builtin::BuiltinStorage;
+use lemmy_db_views_local_image::api::ImageGetParams;
+use lemmy_utils::{
+  error::LemmyResult,
+  settings::structs::{Settings, StorageBackend},
+};
+use pictrs::PictrsStorage;
+use std::future::Future;
+use url::Url;
+
+pub mod builtin;
+pub mod image_hash;
+pub mod pictrs;
+pub mod quota;
+pub mod video;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UploadType {
+  Avatar,
+  Banner,
+  Other,
+}
+
+/// Operations which every media backend provides. Files are identified by their alias, which is
+/// the last path segment of the image url.
+pub trait MediaStorage {
+  /// Stores the files of a multipart upload from a local user. Images are downscaled depending on
+  /// the upload type.
+  fn upload(
+    &self,
+    req: &HttpRequest,
+    body: Payload,
+    upload_type: UploadType,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<Vec<PictrsFile>>>;
+
+  /// Responds with a stored image, optionally converted to another file type and downscaled.
+  fn get(
+    &self,
+    alias: &str,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<HttpResponse>>;
+
+  /// Responds with a remote image, so that the client doesn't connect to the remote server.
+  fn proxy(
+    &self,
+    url: &Url,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<HttpResponse>>;
+
+  /// Downloads a remote image and stores it persistently, downscaled to `max_size`.
+  fn download(
+    &self,
+    url: &Url,
+    max_size: u32,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<PictrsFile>> + Send;
+
+  fn proxied_image_details(
+    &self,
+    url: &Url,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<PictrsFileDetails>> + Send;
+
+  /// Stores a file which was generated by Lemmy, like a transcoded video, unchanged. Returns the
+  /// new alias.
+  fn store_file(
+    &self,
+    data: Vec<u8>,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<String>> + Send;
+
+  /// Returns the original content of a stored file.
+  fn read(
+    &self,
+    alias: &str,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<Vec<u8>>> + Send;
+
+  /// Deletes a single alias. The file itself remains if it has other aliases.
+  fn delete(
+    &self,
+    alias: &str,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<()>> + Send;
+
+  /// Deletes the file with all of its aliases, and returns these aliases.
+  fn purge(
+    &self,
+    alias: &str,
+    context: &LemmyContext,
+  ) -> impl Future<Output = LemmyResult<Vec<String>>> + Send;
+
+  fn health(&self, context: &LemmyContext) -> impl Future<Output = LemmyResult<()>> + Send;
+}
+
+/// The backend which is selected with `pictrs.storage` in the config.
+#[derive(Clone)]
+pub enum MediaBackend {
+  Pictrs(PictrsStorage),
+  Builtin(BuiltinStorage),
+}
+
+impl MediaBackend {
+  pub fn new(settings: &Settings) -> LemmyResult<Self> {
+    // Without pictrs config images are disabled, and pict-rs requests fail accordingly.
+    let Ok(config) = settings.pictrs() else {
+      return Ok(MediaBackend::Pictrs(PictrsStorage));
+    };
+    Ok(match config.storage {
+      StorageBackend::Pictrs => MediaBackend::Pictrs(PictrsStorage),
+      StorageBackend::Filesystem | StorageBackend::S3 => {
+        MediaBackend::Builtin(BuiltinStorage::new(&config)?)
+      }
+    })
+  }
+}
+
+impl MediaStorage for MediaBackend {
+  async fn upload(
+    &self,
+    req: &HttpRequest,
+    body: Payload,
+    upload_type: UploadType,
+    context: &LemmyContext,
+  ) -> LemmyResult<Vec<PictrsFile>> {
+    match self {
+      MediaBackend::Pictrs(s) => s.upload(req, body, upload_type, context).await,
+      MediaBackend::Builtin(s) => s.upload(req, body, upload_type, context).await,
+    }
+  }
+
+  async fn get(
+    &self,
+    alias: &str,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    match self {
+      MediaBackend::Pictrs(s) => s.get(alias, params, req, context).await,
+      MediaBackend::Builtin(s) => s.get(alias, params, req, context).await,
+    }
+  }
+
+  async fn proxy(
+    &self,
+    url: &Url,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    match self {
+      MediaBackend::Pictrs(s) => s.proxy(url, params, req, context).await,
+      MediaBackend::Builtin(s) => s.proxy(url, params, req, context).await,
+    }
+  }
+
+  async fn download(
+    &self,
+    url: &Url,
+    max_size: u32,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFile> {
+    match self {
+      MediaBackend::Pictrs(s) => s.download(url, max_size, context).await,
+      MediaBackend::Builtin(s) => s.download(url, max_size, context).await,
+    }
+  }
+
+  async fn proxied_image_details(
+    &self,
+    url: &Url,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFileDetails> {
+    match self {
+      MediaBackend::Pictrs(s) => s.proxied_image_details(url, context).await,
+      MediaBackend::Builtin(s) => s.proxied_image_details(url, context).await,
+    }
+  }
+
+  async fn store_file(&self, data: Vec<u8>, context: &LemmyContext) -> LemmyResult<String> {
+    match self {
+      MediaBackend::Pictrs(s) => s.store_file(data, context).await,
+      MediaBackend::Builtin(s) => s.store_file(data, context).await,
+    }
+  }
+
+  async fn read(&self, alias: &str, context: &LemmyContext) -> LemmyResult<Vec<u8>> {
+    match self {
+      MediaBackend::Pictrs(s) => s.read(alias, context).await,
+      MediaBackend::Builtin(s) => s.read(alias, context).await,
+    }
+  }
+
+  async fn delete(&self, alias: &str, context: &LemmyContext) -> LemmyResult<()> {
+    match self {
+      MediaBackend::Pictrs(s) => s.delete(alias, context).await,
+      MediaBackend::Builtin(s) => s.delete(alias, context).await,
+    }
+  }
+
+  async fn purge(&self, alias: &str, context: &LemmyContext) -> LemmyResult<Vec<String>> {
+    match self {
+      MediaBackend::Pictrs(s) => s.purge(alias, context).await,
+      MediaBackend::Builtin(s) => s.purge(alias, context).await,
+    }
+  }
+
+  async fn health(&self, context: &LemmyContext) -> LemmyResult<()> {
+    match self {
+      MediaBackend::Pictrs(s) => s.health(context).await,
+      MediaBackend::Builtin(s) => s.health(context).await,
+    }
+  }
+}
+
+/// The url under which a local image is served.
+pub fn image_url(alias: &str, protocol_and_hostname: &str) -> Result<Url, url::ParseError> {
+  Url::parse(&format!("{protocol_and_hostname}/api/v4/image/{alias}"))
+}
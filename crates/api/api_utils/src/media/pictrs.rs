@@ -0,0 +1,446 @@
+//! Media backend which sends all requests to pict-rs, see https://git.asonix.dog/asonix/pict-rs/#api
+use super::{MediaStorage, UploadType};
+use crate::{
+  context::LemmyContext,
+  request::{PictrsFile, PictrsFileDetails, PictrsResponse},
+};
+use actix_web::{
+  body::BodyStream,
+  http::{
+    header::{HeaderName, ACCEPT_ENCODING, CONTENT_TYPE, HOST},
+    Method,
+    StatusCode,
+  },
+  web::Payload,
+  HttpRequest,
+  HttpResponse,
+};
+use futures::stream::{Stream, StreamExt};
+use http::HeaderValue;
+use lemmy_db_views_local_image::api::ImageGetParams;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  REQWEST_TIMEOUT,
+};
+use reqwest::Body;
+use reqwest_middleware::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+use urlencoding::encode;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct PictrsStorage;
+
+#[derive(Deserialize, Serialize, Debug)]
+struct PictrsPurgeResponse {
+  msg: String,
+  aliases: Vec<String>,
+}
+
+impl MediaStorage for PictrsStorage {
+  async fn upload(
+    &self,
+    req: &HttpRequest,
+    body: Payload,
+    upload_type: UploadType,
+    context: &LemmyContext,
+  ) -> LemmyResult<Vec<PictrsFile>> {
+    let pictrs = context.settings().pictrs()?;
+    let max_upload_size = pictrs.max_upload_size.map(|m| m.to_string());
+    let image_url = format!("{}image", pictrs.url);
+
+    let mut client_req = adapt_request(req, image_url, context);
+
+    // Set pictrs parameters to downscale images and restrict file types.
+    client_req = match upload_type {
+      UploadType::Avatar => {
+        let max_size = pictrs.max_avatar_size.to_string();
+        client_req.query(&[
+          ("resize", max_size.as_ref()),
+          ("allow_animation", "false"),
+          ("allow_video", "false"),
+        ])
+      }
+      UploadType::Banner => {
+        let max_size = pictrs.max_banner_size.to_string();
+        client_req.query(&[
+          ("resize", max_size.as_ref()),
+          ("allow_animation", "false"),
+          ("allow_video", "false"),
+        ])
+      }
+      UploadType::Other => {
+        let mut query = vec![("allow_video", pictrs.allow_video_uploads.to_string())];
+        if let Some(max_upload_size) = max_upload_size {
+          query.push(("resize", max_upload_size));
+        }
+        client_req.query(&query)
+      }
+    };
+    if let Some(addr) = req.head().peer_addr {
+      client_req = client_req.header("X-Forwarded-For", addr.to_string())
+    };
+    let res = client_req
+      .timeout(Duration::from_secs(pictrs.upload_timeout))
+      .body(Body::wrap_stream(make_send(body)))
+      .send()
+      .await?
+      .error_for_status()?;
+
+    let mut files = res.json::<PictrsResponse>().await?.files;
+    for file in &mut files {
+      file.file_size = original_file_size(&file.file, context).await?;
+    }
+    Ok(files)
+  }
+
+  async fn get(
+    &self,
+    alias: &str,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    // If there are no query params, the URL is original
+    let pictrs_url = context.settings().pictrs()?.url;
+    let processed_url = if params.file_type.is_none() && params.max_size.is_none() {
+      format!("{}image/original/{}", pictrs_url, alias)
+    } else {
+      let file_type = file_type(params.file_type.clone(), alias);
+      let mut url = format!("{}image/process.{}?src={}", pictrs_url, file_type, alias);
+
+      if let Some(size) = params.max_size {
+        url = format!("{url}&thumbnail={size}",);
+      }
+      url
+    };
+
+    do_get_image(processed_url, req, context).await
+  }
+
+  async fn proxy(
+    &self,
+    url: &Url,
+    params: &ImageGetParams,
+    req: &HttpRequest,
+    context: &LemmyContext,
+  ) -> LemmyResult<HttpResponse> {
+    let encoded_url = encode(url.as_str());
+    let pictrs_url = context.settings().pictrs()?.url;
+    let processed_url = if params.file_type.is_none() && params.max_size.is_none() {
+      format!("{}image/original?proxy={}", pictrs_url, encoded_url)
+    } else {
+      let file_type = file_type(params.file_type.clone(), url.path());
+      let mut url = format!(
+        "{}image/process.{}?proxy={}",
+        pictrs_url, file_type, encoded_url
+      );
+
+      if let Some(size) = params.max_size {
+        url = format!("{url}&thumbnail={size}",);
+      }
+      url
+    };
+
+    do_get_image(processed_url, req, context).await
+  }
+
+  async fn download(
+    &self,
+    url: &Url,
+    max_size: u32,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFile> {
+    let fetch_url = format!(
+      "{}image/download?url={}&resize={}",
+      context.settings().pictrs()?.url,
+      encode(url.as_str()),
+      max_size
+    );
+
+    let res = context
+      .pictrs_client()
+      .get(&fetch_url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<PictrsResponse>()
+      .await?;
+
+    res
+      .files
+      .into_iter()
+      .next()
+      .ok_or(LemmyErrorType::PictrsResponseError(res.msg).into())
+  }
+
+  async fn proxied_image_details(
+    &self,
+    url: &Url,
+    context: &LemmyContext,
+  ) -> LemmyResult<PictrsFileDetails> {
+    let pictrs_url = context.settings().pictrs()?.url;
+    let encoded_image_url = encode(url.as_str());
+
+    // Pictrs needs you to fetch the proxied image before you can fetch the details
+    let proxy_url = format!("{pictrs_url}image/original?proxy={encoded_image_url}");
+
+    context
+      .pictrs_client()
+      .get(&proxy_url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()
+      .with_lemmy_type(LemmyErrorType::NotAnImageType)?;
+
+    let details_url = format!("{pictrs_url}image/details/original?proxy={encoded_image_url}");
+
+    let res = context
+      .pictrs_client()
+      .get(&details_url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()?
+      .json()
+      .await?;
+
+    Ok(res)
+  }
+
+  async fn store_file(&self, data: Vec<u8>, context: &LemmyContext) -> LemmyResult<String> {
+    let pictrs = context.settings().pictrs()?;
+    let boundary = Uuid::new_v4().simple().to_string();
+    let mut body = format!(
+      "--{boundary}\r\nContent-Disposition: form-data; name=\"images[]\"; filename=\"file\"\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend(data);
+    body.extend(format!("\r\n--{boundary}--\r\n").into_bytes());
+
+    let res = context
+      .pictrs_client()
+      .post(format!("{}image", pictrs.url))
+      .query(&[("allow_video", "true")])
+      .header(
+        CONTENT_TYPE.as_str(),
+        format!("multipart/form-data; boundary={boundary}"),
+      )
+      .timeout(Duration::from_secs(pictrs.upload_timeout))
+      .body(body)
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<PictrsResponse>()
+      .await?;
+
+    res
+      .files
+      .into_iter()
+      .next()
+      .map(|f| f.file)
+      .ok_or(LemmyErrorType::PictrsResponseError(res.msg).into())
+  }
+
+  async fn read(&self, alias: &str, context: &LemmyContext) -> LemmyResult<Vec<u8>> {
+    let url = format!(
+      "{}image/original/{}",
+      context.settings().pictrs()?.url,
+      alias
+    );
+
+    let res = context
+      .pictrs_client()
+      .get(&url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()?;
+    Ok(res.bytes().await?.to_vec())
+  }
+
+  async fn delete(&self, alias: &str, context: &LemmyContext) -> LemmyResult<()> {
+    let pictrs_config = context.settings().pictrs()?;
+    let url = format!("{}internal/delete?alias={}", pictrs_config.url, &alias);
+
+    context
+      .pictrs_client()
+      .post(&url)
+      .header("X-Api-Token", pictrs_config.api_key.unwrap_or_default())
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()?;
+    Ok(())
+  }
+
+  async fn purge(&self, alias: &str, context: &LemmyContext) -> LemmyResult<Vec<String>> {
+    let pictrs_config = context.settings().pictrs()?;
+    let purge_url = format!("{}internal/purge?alias={}", pictrs_config.url, alias);
+
+    let pictrs_api_key = pictrs_config
+      .api_key
+      .ok_or(LemmyErrorType::PictrsApiKeyNotProvided)?;
+    let response = context
+      .pictrs_client()
+      .post(&purge_url)
+      .timeout(REQWEST_TIMEOUT)
+      .header("x-api-token", pictrs_api_key)
+      .send()
+      .await?
+      .error_for_status()?;
+
+    let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
+
+    match response.msg.as_str() {
+      // Pictrs purges return all aliases.
+      "ok" => Ok(response.aliases),
+      _ => Err(LemmyErrorType::PictrsPurgeResponseError(response.msg))?,
+    }
+  }
+
+  async fn health(&self, context: &LemmyContext) -> LemmyResult<()> {
+    let url = format!("{}healthz", context.settings().pictrs()?.url);
+
+    context
+      .pictrs_client()
+      .get(url)
+      .send()
+      .await?
+      .error_for_status()?;
+    Ok(())
+  }
+}
+
+/// Reads the size of a stored file from the headers of the original, without downloading it.
+async fn original_file_size(alias: &str, context: &LemmyContext) -> LemmyResult<i64> {
+  let url = format!(
+    "{}image/original/{}",
+    context.settings().pictrs()?.url,
+    alias
+  );
+  let res = context
+    .pictrs_client()
+    .head(&url)
+    .timeout(REQWEST_TIMEOUT)
+    .send()
+    .await?
+    .error_for_status()?;
+  res
+    .headers()
+    .get(http::header::CONTENT_LENGTH)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|v| v.parse().ok())
+    .ok_or(LemmyErrorType::InvalidImageUpload.into())
+}
+
+async fn do_get_image(
+  url: String,
+  req: &HttpRequest,
+  context: &LemmyContext,
+) -> LemmyResult<HttpResponse> {
+  let mut client_req = adapt_request(req, url, context);
+
+  if let Some(addr) = req.head().peer_addr {
+    client_req = client_req.header("X-Forwarded-For", addr.to_string());
+  }
+
+  let res = client_req.send().await?;
+
+  if res.status() == http::StatusCode::NOT_FOUND {
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let mut client_res = HttpResponse::build(StatusCode::from_u16(res.status().as_u16())?);
+
+  for (name, value) in res.headers().iter().filter(|(h, _)| *h != "connection") {
+    client_res.insert_header(convert_header(name, value));
+  }
+
+  Ok(client_res.body(BodyStream::new(res.bytes_stream())))
+}
+
+/// Take file type from param, name, or use jpg if nothing is given
+fn file_type(file_type: Option<String>, name: &str) -> String {
+  file_type
+    .clone()
+    .unwrap_or_else(|| name.split('.').next_back().unwrap_or("jpg").to_string())
+}
+
+fn adapt_request(request: &HttpRequest, url: String, context: &LemmyContext) -> RequestBuilder {
+  // remove accept-encoding header so that pictrs doesn't compress the response
+  const INVALID_HEADERS: &[HeaderName] = &[ACCEPT_ENCODING, HOST];
+
+  let client_request = context
+    .pictrs_client()
+    .request(convert_method(request.method()), url)
+    .timeout(REQWEST_TIMEOUT);
+
+  request
+    .headers()
+    .iter()
+    .fold(client_request, |client_req, (key, value)| {
+      if INVALID_HEADERS.contains(key) {
+        client_req
+      } else {
+        // TODO: remove as_str and as_bytes conversions after actix-web upgrades to http 1.0
+        client_req.header(key.as_str(), value.as_bytes())
+      }
+    })
+}
+
+fn make_send<S>(mut stream: S) -> impl Stream<Item = S::Item> + Send + Unpin + 'static
+where
+  S: Stream + Unpin + 'static,
+  S::Item: Send,
+{
+  // NOTE: the 8 here is arbitrary
+  let (tx, rx) = tokio::sync::mpsc::channel(8);
+
+  // NOTE: spawning stream into a new task can potentially hit this bug:
+  // - https://github.com/actix/actix-web/issues/1679
+  //
+  // Since 4.0.0-beta.2 this issue is incredibly less frequent. I have not personally reproduced it.
+  // That said, it is still technically possible to encounter.
+  actix_web::rt::spawn(async move {
+    while let Some(res) = stream.next().await {
+      if tx.send(res).await.is_err() {
+        break;
+      }
+    }
+  });
+
+  SendStream { rx }
+}
+
+struct SendStream<T> {
+  rx: tokio::sync::mpsc::Receiver<T>,
+}
+
+impl<T> Stream for SendStream<T>
+where
+  T: Send,
+{
+  type Item = T;
+
+  fn poll_next(
+    mut self: std::pin::Pin<&mut Self>,
+    cx: &mut std::task::Context<'_>,
+  ) -> std::task::Poll<Option<Self::Item>> {
+    std::pin::Pin::new(&mut self.rx).poll_recv(cx)
+  }
+}
+
+// TODO: remove these conversions after actix-web upgrades to http 1.0
+#[allow(clippy::expect_used)]
+fn convert_method(method: &Method) -> http::Method {
+  http::Method::from_bytes(method.as_str().as_bytes()).expect("method can be converted")
+}
+
+fn convert_header<'a>(name: &'a http::HeaderName, value: &'a HeaderValue) -> (&'a str, &'a [u8]) {
+  (name.as_str(), value.as_bytes())
+}
@@ -0,0 +1,89 @@
+//! Upload quotas limit how much storage each user can take up. Admins can set a quota for regular
+//! users and one for community moderators, and override it for single users. Admins themselves
+//! have no quota.
+use crate::context::LemmyContext;
+use lemmy_db_schema::source::{images::LocalImage, local_site::LocalSite};
+use lemmy_db_views_community_moderator::CommunityModeratorView;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+/// Quotas are given in bytes, so they can't be negative.
+pub fn media_quota_check(quota: Option<i64>) -> LemmyResult<()> {
+  if quota.is_some_and(|q| q < 0) {
+    Err(LemmyErrorType::InvalidMediaQuota)?
+  }
+  Ok(())
+}
+
+/// The upload quota of the user in bytes, or `None` if uploads are unlimited.
+pub async fn media_quota(
+  local_user_view: &LocalUserView,
+  context: &LemmyContext,
+) -> LemmyResult<Option<i64>> {
+  let local_user = &local_user_view.local_user;
+  if local_user.admin {
+    return Ok(None);
+  }
+  if local_user.media_quota.is_some() {
+    return Ok(local_user.media_quota);
+  }
+  let local_site = SiteView::read_local(&mut context.pool()).await?.local_site;
+  let is_mod = CommunityModeratorView::is_community_moderator_of_any(
+    &mut context.pool(),
+    local_user_view.person.id,
+  )
+  .await
+  .is_ok();
+  Ok(class_quota(&local_site, is_mod))
+}
+
+fn class_quota(local_site: &LocalSite, is_mod: bool) -> Option<i64> {
+  if is_mod {
+    local_site.media_quota_moderator
+  } else {
+    local_site.media_quota_user
+  }
+}
+
+/// Fails with `MediaQuotaExceeded` if uploading files of the given size would exceed the user's
+/// quota.
+pub async fn check_media_quota(
+  local_user_view: &LocalUserView,
+  new_size: i64,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if let Some(quota) = media_quota(local_user_view, context).await? {
+    let usage =
+      LocalImage::usage_for_person(&mut context.pool(), local_user_view.person.id).await?;
+    if usage.saturating_add(new_size) > quota {
+      Err(LemmyErrorType::MediaQuotaExceeded)?
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_media_quota_check() {
+    assert!(media_quota_check(None).is_ok());
+    assert!(media_quota_check(Some(0)).is_ok());
+    assert!(media_quota_check(Some(1_000_000)).is_ok());
+    assert!(media_quota_check(Some(-1)).is_err());
+  }
+
+  #[test]
+  fn test_class_quota() {
+    let local_site = LocalSite {
+      media_quota_user: Some(100),
+      media_quota_moderator: None,
+      ..Default::default()
+    };
+    assert_eq!(Some(100), class_quota(&local_site, false));
+    assert_eq!(None, class_quota(&local_site, true));
+  }
+}
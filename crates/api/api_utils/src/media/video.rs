@@ -0,0 +1,506 @@
+//! Processing of uploaded videos with ffmpeg. Uploads are probed right away, so that overly long
+//! videos are rejected. Transcoding to H.264 in several resolutions and extracting a poster frame
+//! happens in the background. The results are stored as additional files in the media backend.
+use super::{image_url, MediaStorage};
+use crate::context::LemmyContext;
+use anyhow::anyhow;
+use chrono::{TimeDelta, Utc};
+use lemmy_db_schema::{
+  newtypes::{DbUrl, PersonId},
+  source::{
+    images::{ImageDetails, ImageDetailsInsertForm, LocalImage, LocalImageForm},
+    post::Post,
+    video::{
+      VideoDetails,
+      VideoDetailsInsertForm,
+      VideoDetailsUpdateForm,
+      VideoTranscodeJob,
+      VideoTranscodeJobInsertForm,
+      VideoTranscodeJobUpdateForm,
+      VideoVariant,
+      VideoVariantForm,
+    },
+  },
+};
+use lemmy_utils::{
+  error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
+  settings::structs::VideoConfig,
+};
+use serde::Deserialize;
+use std::{path::PathBuf, sync::OnceLock, time::Duration};
+use tokio::{
+  process::Command,
+  sync::Semaphore,
+  time::{sleep, timeout},
+};
+use tracing::{debug, warn};
+use url::Url;
+use uuid::Uuid;
+
+/// Transcoded videos are always stored in this format, which every browser can play.
+const VARIANT_CONTENT_TYPE: &str = "video/mp4";
+const POSTER_CONTENT_TYPE: &str = "image/jpeg";
+/// How long to wait before checking the queue again if it is empty.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Failed jobs are retried with increasing delays, and given up after this many attempts.
+const MAX_ATTEMPTS: i32 = 3;
+const RETRY_DELAY: TimeDelta = TimeDelta::minutes(10);
+
+/// Shared by all ffmpeg and ffprobe processes, see [[run]].
+static PERMITS: OnceLock<Semaphore> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VideoProbe {
+  pub width: u32,
+  pub height: u32,
+  pub duration_ms: u32,
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+  #[serde(default)]
+  streams: Vec<FfprobeStream>,
+  format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+  codec_type: Option<String>,
+  width: Option<u32>,
+  height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+  duration: Option<String>,
+}
+
+/// A file in the temp dir which is removed when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+  fn new(extension: &str) -> Self {
+    Self(std::env::temp_dir().join(format!("lemmy-{}.{extension}", Uuid::new_v4())))
+  }
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    std::fs::remove_file(&self.0).ok();
+  }
+}
+
+/// Reads the dimensions and duration of a video, and rejects it if it is longer than allowed.
+pub async fn probe_video(data: &[u8], config: &VideoConfig) -> LemmyResult<VideoProbe> {
+  let input = TempFile::new("video");
+  tokio::fs::write(&input.0, data).await?;
+  let probe = probe(&input, config).await?;
+  if probe.duration_ms > config.max_duration.saturating_mul(1000) {
+    Err(LemmyErrorType::VideoTooLong)?
+  }
+  Ok(probe)
+}
+
+/// Stores the details of an uploaded video, and queues it for transcoding in the background.
+/// Once done, the poster frame becomes the thumbnail of posts linking to the video. The generated
+/// files belong to the uploader, so they count towards their quota.
+pub async fn process_uploaded_video(
+  alias: &str,
+  url: &Url,
+  probe: VideoProbe,
+  person_id: PersonId,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if context.settings().pictrs()?.video.is_none() {
+    return Ok(());
+  }
+  let link: DbUrl = url.clone().into();
+  let form = VideoDetailsInsertForm {
+    link: link.clone(),
+    width: Some(probe.width.try_into()?),
+    height: Some(probe.height.try_into()?),
+    duration_ms: Some(probe.duration_ms.try_into()?),
+    poster_url: None,
+  };
+  VideoDetails::upsert(&mut context.pool(), &form).await?;
+
+  let form = VideoTranscodeJobInsertForm {
+    link,
+    alias: alias.to_string(),
+    person_id,
+  };
+  VideoTranscodeJob::enqueue(&mut context.pool(), &form).await?;
+  Ok(())
+}
+
+/// Starts the workers which transcode uploaded videos. Jobs are stored in the database, so they
+/// are resumed after a restart.
+pub fn start_video_workers(context: LemmyContext) {
+  let Some(config) = context.settings().pictrs().ok().and_then(|p| p.video) else {
+    return;
+  };
+  for _ in 0..config.max_concurrent_jobs.max(1) {
+    tokio::task::spawn(video_worker(context.clone(), config.clone()));
+  }
+}
+
+async fn video_worker(context: LemmyContext, config: VideoConfig) {
+  // Each job runs ffmpeg once per variant and once for the poster. Claimed jobs are only handed
+  // to another worker once all of these could have timed out.
+  let runs = u32::try_from(config.resolutions.len())
+    .unwrap_or(u32::MAX)
+    .saturating_add(1);
+  let lease = Duration::from_secs(config.timeout)
+    .saturating_mul(runs)
+    .saturating_add(POLL_INTERVAL);
+  let lease = TimeDelta::from_std(lease).unwrap_or(TimeDelta::days(1));
+  loop {
+    match VideoTranscodeJob::claim_due(&mut context.pool(), 1, lease).await {
+      Ok(jobs) if !jobs.is_empty() => {
+        for job in jobs {
+          transcode_and_record(job, &config, &context).await;
+        }
+      }
+      Ok(_) => sleep(POLL_INTERVAL).await,
+      Err(e) => {
+        warn!("Failed to read queue of video transcoding jobs: {e}");
+        sleep(POLL_INTERVAL).await;
+      }
+    }
+  }
+}
+
+async fn transcode_and_record(
+  job: VideoTranscodeJob,
+  config: &VideoConfig,
+  context: &LemmyContext,
+) {
+  let attempts = job.attempts.saturating_add(1);
+  let recorded = match transcode_video(&job, config, context).await {
+    Ok(()) => VideoTranscodeJob::delete(&mut context.pool(), &job.link)
+      .await
+      .map(|_| ()),
+    Err(e) if attempts >= MAX_ATTEMPTS => {
+      warn!("Failed to transcode video {}: {e}", job.link);
+      VideoTranscodeJob::delete(&mut context.pool(), &job.link)
+        .await
+        .map(|_| ())
+    }
+    Err(e) => {
+      debug!(
+        "Failed to transcode video {}, retrying later: {e}",
+        job.link
+      );
+      let form = VideoTranscodeJobUpdateForm {
+        attempts: Some(attempts),
+        next_attempt_at: Some(Utc::now() + RETRY_DELAY * attempts),
+      };
+      VideoTranscodeJob::update(&mut context.pool(), &job.link, &form)
+        .await
+        .map(|_| ())
+    }
+  };
+  recorded
+    .inspect_err(|e| warn!("Failed to update video transcoding job {}: {e}", job.link))
+    .ok();
+}
+
+/// Generates the missing variants and the poster frame. Variants are stored one by one, so that
+/// a retried job continues where the previous attempt stopped.
+async fn transcode_video(
+  job: &VideoTranscodeJob,
+  config: &VideoConfig,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let link = &job.link;
+  let person_id = job.person_id;
+  let details = VideoDetails::read(&mut context.pool(), link).await?;
+  let probe = VideoProbe {
+    width: details
+      .width
+      .ok_or(LemmyErrorType::InvalidVideo)?
+      .try_into()?,
+    height: details
+      .height
+      .ok_or(LemmyErrorType::InvalidVideo)?
+      .try_into()?,
+    duration_ms: details
+      .duration_ms
+      .ok_or(LemmyErrorType::InvalidVideo)?
+      .try_into()?,
+  };
+  let existing = VideoVariant::list(&mut context.pool(), link)
+    .await?
+    .into_iter()
+    .map(|v| v.height)
+    .collect::<Vec<_>>();
+
+  let input = TempFile::new("video");
+  tokio::fs::write(&input.0, context.media().read(&job.alias, context).await?).await?;
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+
+  for height in ladder(&config.resolutions, probe.height) {
+    let variant_height: i32 = height.try_into()?;
+    if existing.contains(&variant_height) {
+      continue;
+    }
+    let output = TempFile::new("mp4");
+    run(
+      Command::new(&config.ffmpeg_path)
+        .args(["-v", "error", "-y", "-protocol_whitelist", "file", "-i"])
+        .arg(&input.0)
+        .arg("-vf")
+        .arg(format!("scale=-2:{height}"))
+        .args([
+          "-c:v", "libx264", "-preset", "veryfast", "-pix_fmt", "yuv420p",
+        ])
+        .args(["-c:a", "aac", "-movflags", "+faststart"])
+        .arg(&output.0),
+      config,
+    )
+    .await?;
+    let data = tokio::fs::read(&output.0).await?;
+    let file_size = i64::try_from(data.len())?;
+    let variant_alias = context.media().store_file(data, context).await?;
+    let variant_url: DbUrl = image_url(&variant_alias, &protocol_and_hostname)?.into();
+    let details_form = ImageDetailsInsertForm {
+      link: variant_url.clone(),
+      width: variant_width(&probe, height).try_into()?,
+      height: variant_height,
+      content_type: VARIANT_CONTENT_TYPE.to_string(),
+      blurhash: None,
+    };
+    create_local_file(&variant_alias, file_size, person_id, &details_form, context).await?;
+    let variant = VideoVariantForm {
+      link: link.clone(),
+      height: variant_height,
+      url: variant_url,
+      content_type: VARIANT_CONTENT_TYPE.to_string(),
+    };
+    VideoVariant::upsert_many(&mut context.pool(), &[variant]).await?;
+  }
+
+  // Skip the first second, which is often black
+  let seek = if probe.duration_ms > 2000 { "1" } else { "0" };
+  let poster = TempFile::new("jpg");
+  run(
+    Command::new(&config.ffmpeg_path)
+      .args(["-v", "error", "-y", "-protocol_whitelist", "file"])
+      .args(["-ss", seek, "-i"])
+      .arg(&input.0)
+      .args(["-frames:v", "1"])
+      .arg(&poster.0),
+    config,
+  )
+  .await?;
+  let data = tokio::fs::read(&poster.0).await?;
+  let file_size = i64::try_from(data.len())?;
+  let poster_alias = context.media().store_file(data, context).await?;
+  let poster_url: DbUrl = image_url(&poster_alias, &protocol_and_hostname)?.into();
+  let details_form = ImageDetailsInsertForm {
+    link: poster_url.clone(),
+    width: probe.width.try_into()?,
+    height: probe.height.try_into()?,
+    content_type: POSTER_CONTENT_TYPE.to_string(),
+    blurhash: None,
+  };
+  create_local_file(&poster_alias, file_size, person_id, &details_form, context).await?;
+
+  let form = VideoDetailsUpdateForm {
+    poster_url: Some(Some(poster_url.clone())),
+    transcoded_at: Some(Some(Utc::now())),
+  };
+  VideoDetails::update(&mut context.pool(), link, &form).await?;
+  Post::set_missing_thumbnail(&mut context.pool(), link, &poster_url).await?;
+  Ok(())
+}
+
+/// Stores a generated file like an upload of the person, so that it counts towards their quota.
+async fn create_local_file(
+  alias: &str,
+  file_size: i64,
+  person_id: PersonId,
+  details_form: &ImageDetailsInsertForm,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let form = LocalImageForm {
+    pictrs_alias: alias.to_string(),
+    person_id,
+    thumbnail_for_post_id: None,
+    phash: None,
+    file_size: Some(file_size),
+  };
+  LocalImage::create(&mut context.pool(), &form, details_form).await?;
+  Ok(())
+}
+
+/// Deletes the transcoded variants and poster frame of a local video.
+pub async fn delete_video_files(link: &DbUrl, context: &LemmyContext) -> LemmyResult<()> {
+  let Ok(details) = VideoDetails::read(&mut context.pool(), link).await else {
+    return Ok(());
+  };
+  let variants = VideoVariant::list(&mut context.pool(), link).await?;
+  let prefix = image_url("", &context.settings().get_protocol_and_hostname())?;
+  let urls = variants
+    .into_iter()
+    .map(|v| v.url)
+    .chain(details.poster_url)
+    .collect::<Vec<_>>();
+  let mut aliases = vec![];
+  for url in &urls {
+    if let Some(alias) = url.as_str().strip_prefix(prefix.as_str()) {
+      if let Err(e) = context.media().delete(alias, context).await {
+        warn!("Failed to delete video file {alias}: {e}");
+      }
+      aliases.push(alias.to_string());
+    }
+  }
+  LocalImage::delete_by_aliases(&mut context.pool(), &aliases).await?;
+  ImageDetails::delete_by_links(&mut context.pool(), &urls).await?;
+  VideoDetails::delete(&mut context.pool(), link).await?;
+  Ok(())
+}
+
+async fn probe(input: &TempFile, config: &VideoConfig) -> LemmyResult<VideoProbe> {
+  let output = run(
+    Command::new(&config.ffprobe_path)
+      .args([
+        "-v",
+        "error",
+        "-protocol_whitelist",
+        "file",
+        "-print_format",
+        "json",
+        "-show_streams",
+        "-show_format",
+      ])
+      .arg(&input.0),
+    config,
+  )
+  .await?;
+  parse_probe(&output)
+}
+
+fn parse_probe(json: &[u8]) -> LemmyResult<VideoProbe> {
+  let output: FfprobeOutput =
+    serde_json::from_slice(json).with_lemmy_type(LemmyErrorType::InvalidVideo)?;
+  let stream = output
+    .streams
+    .into_iter()
+    .find(|s| s.codec_type.as_deref() == Some("video"))
+    .ok_or(LemmyErrorType::InvalidVideo)?;
+  let duration_ms = output
+    .format
+    .and_then(|f| f.duration)
+    .and_then(|d| parse_duration_ms(&d))
+    .ok_or(LemmyErrorType::InvalidVideo)?;
+  Ok(VideoProbe {
+    width: stream.width.ok_or(LemmyErrorType::InvalidVideo)?,
+    height: stream.height.ok_or(LemmyErrorType::InvalidVideo)?,
+    duration_ms,
+  })
+}
+
+/// Parses a duration in seconds like `12.345000`.
+fn parse_duration_ms(duration: &str) -> Option<u32> {
+  let (secs, fraction) = duration.split_once('.').unwrap_or((duration, ""));
+  let secs: u32 = secs.parse().ok()?;
+  let millis: u32 = format!("{fraction:0<3}").get(..3)?.parse().ok()?;
+  secs.checked_mul(1000)?.checked_add(millis)
+}
+
+/// The heights to transcode to. Larger resolutions than the original are left out, and H.264
+/// needs even dimensions.
+fn ladder(resolutions: &[u32], source_height: u32) -> Vec<u32> {
+  let mut heights = resolutions
+    .iter()
+    .copied()
+    .filter(|h| *h <= source_height)
+    .collect::<Vec<_>>();
+  if heights.is_empty() {
+    heights.push(source_height);
+  }
+  for h in &mut heights {
+    *h -= *h % 2;
+  }
+  heights.retain(|h| *h > 0);
+  heights.sort_unstable_by(|a, b| b.cmp(a));
+  heights.dedup();
+  heights
+}
+
+/// Width of a variant with the given height. Ffmpeg keeps the aspect ratio with `scale=-2`, and
+/// rounds to an even number.
+fn variant_width(probe: &VideoProbe, height: u32) -> u32 {
+  let width = u64::from(probe.width) * u64::from(height) / u64::from(probe.height.max(1));
+  let width = u32::try_from(width).unwrap_or(u32::MAX);
+  width - width % 2
+}
+
+/// Runs ffmpeg or ffprobe. Only `max_concurrent_jobs` processes run at the same time, and they
+/// are killed after `timeout`.
+async fn run(command: &mut Command, config: &VideoConfig) -> LemmyResult<Vec<u8>> {
+  let permits = PERMITS.get_or_init(|| Semaphore::new(config.max_concurrent_jobs.max(1).into()));
+  let _permit = permits.acquire().await?;
+  let output = timeout(
+    Duration::from_secs(config.timeout),
+    command.kill_on_drop(true).output(),
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::InvalidVideo)??;
+  if !output.status.success() {
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    Err(anyhow!(stderr)).with_lemmy_type(LemmyErrorType::InvalidVideo)?
+  }
+  Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_parse_probe() -> LemmyResult<()> {
+    let json = br#"{
+      "streams": [
+        { "codec_type": "audio" },
+        { "codec_type": "video", "width": 1280, "height": 720 }
+      ],
+      "format": { "duration": "61.2" }
+    }"#;
+    assert_eq!(
+      VideoProbe {
+        width: 1280,
+        height: 720,
+        duration_ms: 61_200
+      },
+      parse_probe(json)?
+    );
+    // Audio files have no video stream
+    assert!(parse_probe(br#"{ "streams": [{ "codec_type": "audio" }] }"#).is_err());
+
+    assert_eq!(Some(5), parse_duration_ms("0.005000"));
+    assert_eq!(Some(3000), parse_duration_ms("3"));
+    assert_eq!(None, parse_duration_ms("N/A"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_ladder() {
+    assert_eq!(vec![720, 480], ladder(&[1080, 720, 480], 720));
+    assert_eq!(vec![360], ladder(&[1080, 720, 480], 361));
+    assert_eq!(vec![1080, 720], ladder(&[720, 1080, 720], 2160));
+  }
+
+  #[test]
+  fn test_variant_width() {
+    let probe = VideoProbe {
+      width: 1920,
+      height: 1080,
+      duration_ms: 0,
+    };
+    assert_eq!(1280, variant_width(&probe, 720));
+    assert_eq!(852, variant_width(&probe, 480));
+  }
+}
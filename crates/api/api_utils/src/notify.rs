@@ -1,13 +1,19 @@
-use crate::context::LemmyContext;
+use crate::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+};
 use lemmy_db_schema::{
   newtypes::{DbUrl, PersonId},
   source::{
     comment::Comment,
     community::{Community, CommunityActions},
+    federation_blocklist::FederationBlockList,
     instance::InstanceActions,
     notification::{Notification, NotificationInsertForm},
     person::{Person, PersonActions},
     post::{Post, PostActions},
+    private_message::{PrivateMessage, PrivateMessageInsertForm},
+    websub_subscription::WebsubSubscription,
   },
   traits::{ApubActor, Blockable, Crud},
 };
@@ -25,8 +31,14 @@ use lemmy_utils::{
   spawn_try_task,
   utils::mention::scrape_text_for_mentions,
 };
+use std::{collections::HashSet, sync::LazyLock};
+use tokio::sync::Notify;
 use url::Url;
 
+/// Wakes the WebSub hub in lemmy_routes after new content was published in feeds which have
+/// subscribers.
+pub static FEEDS_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
 #[derive(derive_new::new, Debug, Clone)]
 pub struct NotifyData {
   post: Post,
@@ -53,6 +65,8 @@ impl NotifyData {
 
   /// Logic for send(), in separate function so it can run serially in tests.
   pub async fn send_internal(self, context: LemmyContext) -> LemmyResult<()> {
+    notify_feed_subscribers(&self.post, self.comment_opt.is_some(), &context).await?;
+
     let mut collected = self.notify_parent_creator(&context).await?;
 
     collected.append(&mut self.notify_mentions(&context).await?);
@@ -189,6 +203,16 @@ impl NotifyData {
         // Ignore error if user is remote
         continue;
       };
+      if FederationBlockList::is_silenced(
+        &mut context.pool(),
+        self.creator.id,
+        self.creator.instance_id,
+        person.id,
+      )
+      .await?
+      {
+        continue;
+      }
 
       res.push(CollectedNotifyData {
         person_id: person.id,
@@ -208,14 +232,16 @@ impl NotifyData {
     context: &LemmyContext,
   ) -> LemmyResult<Vec<CollectedNotifyData<'a>>> {
     let is_post = self.comment_opt.is_none();
+    // Someone may be subscribed in multiple ways, but should only get one notification
     let subscribers = vec![
       PostActions::list_subscribers(self.post.id, &mut context.pool()).await?,
       CommunityActions::list_subscribers(self.post.community_id, is_post, &mut context.pool())
         .await?,
+      PersonActions::list_subscribers(self.creator.id, is_post, &mut context.pool()).await?,
     ]
     .into_iter()
     .flatten()
-    .collect::<Vec<_>>();
+    .collect::<HashSet<_>>();
 
     let mut res = vec![];
     for person_id in subscribers {
@@ -242,6 +268,20 @@ impl NotifyData {
   }
 }
 
+/// Marks WebSub subscriptions to the feeds which contain the new post or comment as changed, so
+/// that the hub pushes the feeds to their subscribers.
+pub async fn notify_feed_subscribers(
+  post: &Post,
+  new_comment: bool,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let changed = WebsubSubscription::mark_changed(&mut context.pool(), post, new_comment).await?;
+  if changed > 0 {
+    FEEDS_CHANGED.notify_one();
+  }
+  Ok(())
+}
+
 pub async fn notify_private_message(
   view: &PrivateMessageView,
   is_create: bool,
@@ -275,6 +315,36 @@ pub async fn notify_private_message(
   Ok(())
 }
 
+/// Tells the creator of a post or comment that a moderator rejected it from the approval queue.
+/// This is done with a private message from the moderator, so that it also reaches remote users.
+pub async fn notify_content_rejected(
+  moderator: &Person,
+  creator_id: PersonId,
+  content_url: Url,
+  community: &Community,
+  reason: Option<&str>,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if moderator.id == creator_id {
+    return Ok(());
+  }
+
+  let mut content = format!(
+    "Your submission {content_url} in {} was rejected by the moderators.",
+    community.ap_id
+  );
+  if let Some(reason) = reason {
+    content.push_str(&format!("\n\nReason: {reason}"));
+  }
+  let form = PrivateMessageInsertForm::new(moderator.id, creator_id, content);
+  let private_message = PrivateMessage::create(&mut context.pool(), &form).await?;
+  let view = PrivateMessageView::read(&mut context.pool(), private_message.id).await?;
+
+  notify_private_message(&view, true, context).await?;
+  ActivityChannel::submit_activity(SendActivityData::CreatePrivateMessage(view), context)?;
+  Ok(())
+}
+
 #[cfg(test)]
 #[expect(clippy::indexing_slicing)]
 mod tests {
@@ -1,5 +1,11 @@
 use crate::{
   context::LemmyContext,
+  media::{
+    image_hash::{check_image_hash, image_hash, remote_image_blocked},
+    image_url,
+    video::delete_video_files,
+    MediaStorage,
+  },
   send_activity::{ActivityChannel, SendActivityData},
   utils::proxy_image_link,
 };
@@ -9,15 +15,16 @@ use encoding_rs::{Encoding, UTF_8};
 use futures::StreamExt;
 use lemmy_db_schema::{
   source::{
-    images::{ImageDetailsInsertForm, LocalImage, LocalImageForm},
+    images::{ImageDetails, ImageDetailsInsertForm, LocalImage, LocalImageForm},
     post::{Post, PostUpdateForm},
     site::Site,
+    video::VideoDetails,
   },
   traits::Crud,
 };
 use lemmy_db_views_post::api::{LinkMetadata, OpenGraphData};
 use lemmy_utils::{
-  error::{FederationError, LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  error::{FederationError, LemmyError, LemmyErrorType, LemmyResult},
   settings::structs::{PictrsImageMode, Settings},
   REQWEST_TIMEOUT,
   VERSION,
@@ -36,7 +43,6 @@ use std::net::IpAddr;
 use tokio::net::lookup_host;
 use tracing::{info, warn};
 use url::Url;
-use urlencoding::encode;
 use webpage::HTML;
 
 pub fn client_builder(settings: &Settings) -> ClientBuilder {
@@ -50,18 +56,10 @@ pub fn client_builder(settings: &Settings) -> ClientBuilder {
     .use_rustls_tls()
 }
 
-/// Fetches metadata for the given link and optionally generates thumbnail.
-pub async fn fetch_link_metadata(
-  url: &Url,
-  context: &LemmyContext,
-  recursion: bool,
-) -> LemmyResult<LinkMetadata> {
-  if url.scheme() != "http" && url.scheme() != "https" {
-    return Err(LemmyErrorType::InvalidUrl.into());
-  }
-
-  // Resolve the domain and throw an error if it points to any internal IP,
-  // using logic from nightly IpAddr::is_global.
+/// Resolves the domain of the url and throws an error if it points to any internal IP, so that
+/// requests to user supplied urls can't reach services on the local network.
+pub async fn check_public_host(url: &Url) -> LemmyResult<()> {
+  // Using logic from nightly IpAddr::is_global.
   if !cfg!(debug_assertions) {
     // TODO: Replace with IpAddr::is_global() once stabilized
     //       https://doc.rust-lang.org/std/net/enum.IpAddr.html#method.is_global
@@ -83,6 +81,20 @@ pub async fn fetch_link_metadata(
       return Err(LemmyErrorType::InvalidUrl.into());
     }
   }
+  Ok(())
+}
+
+/// Fetches metadata for the given link and optionally generates thumbnail.
+pub async fn fetch_link_metadata(
+  url: &Url,
+  context: &LemmyContext,
+  recursion: bool,
+) -> LemmyResult<LinkMetadata> {
+  if url.scheme() != "http" && url.scheme() != "https" {
+    return Err(LemmyErrorType::InvalidUrl.into());
+  }
+
+  check_public_host(url).await?;
 
   info!("Fetching site metadata for url: {}", url);
   // We only fetch the first MB of data in order to not waste bandwidth especially for large
@@ -226,23 +238,47 @@ pub async fn generate_post_link_metadata(
     metadata.opengraph_data.image.clone()
   };
 
+  // Uploaded videos use their poster frame, if it was already extracted
+  let video_poster = match &post.url {
+    Some(url) => VideoDetails::read(&mut context.pool(), url)
+      .await
+      .ok()
+      .and_then(|v| v.poster_url),
+    None => None,
+  };
+
   // Attempt to generate a thumbnail depending on the instance settings. Either by proxying,
   // storing image persistently in pict-rs or returning the remote url directly as thumbnail.
+  // Thumbnails which are similar to a blocked image are left out.
   let thumbnail_url = if let (false, Some(url)) = (is_image_post, custom_thumbnail) {
-    proxy_image_link(url.clone(), true, &context)
-      .await
-      .map_err(|e| warn!("Failed to proxy thumbnail: {e}"))
-      .ok()
-      .or(Some(url.into()))
+    if remote_image_blocked(&url, &context).await {
+      None
+    } else {
+      proxy_image_link(url.clone(), true, &context)
+        .await
+        .map_err(|e| warn!("Failed to proxy thumbnail: {e}"))
+        .ok()
+        .or(Some(url.into()))
+    }
+  } else if let Some(poster) = video_poster {
+    Some(poster)
   } else if let (true, Some(url)) = (allow_generate_thumbnail, image_url.clone()) {
-    generate_pictrs_thumbnail(&post, &url, &context)
-      .await
-      .map_err(|e| warn!("Failed to generate thumbnail: {e}"))
-      .ok()
-      .map(Into::into)
-      .or(image_url)
+    match generate_pictrs_thumbnail(&post, &url, &context).await {
+      Ok(thumbnail_url) => Some(thumbnail_url.into()),
+      Err(e) if e.error_type == LemmyErrorType::ImageBlocked => None,
+      Err(e) => {
+        warn!("Failed to generate thumbnail: {e}");
+        image_url
+      }
+    }
+  } else if let Some(url) = image_url {
+    if remote_image_blocked(&url, &context).await {
+      None
+    } else {
+      Some(url)
+    }
   } else {
-    image_url.clone()
+    None
   };
 
   let form = PostUpdateForm {
@@ -323,14 +359,15 @@ pub struct PictrsResponse {
 pub struct PictrsFile {
   pub file: String,
   pub details: PictrsFileDetails,
+  /// Size of the stored file in bytes. Pict-rs doesn't include it in its responses, so it is
+  /// filled in by the media backend.
+  #[serde(default)]
+  pub file_size: i64,
 }
 
 impl PictrsFile {
   pub fn image_url(&self, protocol_and_hostname: &str) -> Result<Url, url::ParseError> {
-    Url::parse(&format!(
-      "{protocol_and_hostname}/api/v4/image/{}",
-      self.file
-    ))
+    image_url(&self.file, protocol_and_hostname)
   }
 }
 
@@ -360,12 +397,6 @@ impl PictrsFileDetails {
   }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-struct PictrsPurgeResponse {
-  msg: String,
-  aliases: Vec<String>,
-}
-
 /// Purges an image from pictrs
 /// Note: This should often be coerced from a Result to .ok() in order to fail softly, because:
 /// - It might fail due to image being not local
@@ -387,35 +418,11 @@ pub async fn purge_image_from_pictrs_url(
 }
 
 pub async fn purge_image_from_pictrs(alias: &str, context: &LemmyContext) -> LemmyResult<()> {
-  let pictrs_config = context.settings().pictrs()?;
-  let purge_url = format!("{}internal/purge?alias={}", pictrs_config.url, alias);
-
-  let pictrs_api_key = pictrs_config
-    .api_key
-    .ok_or(LemmyErrorType::PictrsApiKeyNotProvided)?;
-  let response = context
-    .pictrs_client()
-    .post(&purge_url)
-    .timeout(REQWEST_TIMEOUT)
-    .header("x-api-token", pictrs_api_key)
-    .send()
-    .await?
-    .error_for_status()?;
-
-  let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
-
-  // Pictrs purges return all aliases.
-  let aliases = response.aliases;
+  let aliases = context.media().purge(alias, context).await?;
 
   // Delete db rows of aliases.
-  LocalImage::delete_by_aliases(&mut context.pool(), &aliases)
-    .await
-    .ok();
-
-  match response.msg.as_str() {
-    "ok" => Ok(()),
-    _ => Err(LemmyErrorType::PictrsPurgeResponseError(response.msg))?,
-  }
+  delete_image_rows(&aliases, context).await;
+  Ok(())
 }
 
 /// Deletes an alias for an image from the local db and pictrs. If it's not the last / only alias,
@@ -426,27 +433,36 @@ pub async fn purge_image_from_pictrs(alias: &str, context: &LemmyContext) -> Lem
 /// alias. Callers MUST check if the user has permission to delete the alias
 /// before calling this function (the user is an admin or the image belongs to the user).
 pub async fn delete_image_alias(alias: &str, context: &LemmyContext) -> LemmyResult<()> {
-  let pictrs_config = context.settings().pictrs()?;
-  let url = format!("{}internal/delete?alias={}", pictrs_config.url, &alias);
-
-  // Send the delete request to pictrs.
-  context
-    .pictrs_client()
-    .post(&url)
-    .header("X-Api-Token", pictrs_config.api_key.unwrap_or_default())
-    .timeout(REQWEST_TIMEOUT)
-    .send()
-    .await?
-    .error_for_status()?;
+  context.media().delete(alias, context).await?;
 
   // Delete db row if any (old Lemmy versions didn't generate this).
-  LocalImage::delete_by_alias(&mut context.pool(), alias)
+  delete_image_rows(&[alias.to_string()], context).await;
+  Ok(())
+}
+
+/// Removes the `local_image` and `image_details` rows of deleted aliases. For videos, the
+/// transcoded files are deleted as well.
+async fn delete_image_rows(aliases: &[String], context: &LemmyContext) {
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let links = aliases
+    .iter()
+    .filter_map(|alias| image_url(alias, &protocol_and_hostname).ok())
+    .map(Into::into)
+    .collect::<Vec<_>>();
+  for link in &links {
+    delete_video_files(link, context).await.ok();
+  }
+  LocalImage::delete_by_aliases(&mut context.pool(), aliases)
+    .await
+    .ok();
+  ImageDetails::delete_by_links(&mut context.pool(), &links)
     .await
     .ok();
-  Ok(())
 }
 
 /// Retrieves the image with local pict-rs and generates a thumbnail. Returns the thumbnail url.
+///
+/// Fails with `ImageBlocked` if the image is similar to a blocked image.
 async fn generate_pictrs_thumbnail(
   post: &Post,
   image_url: &Url,
@@ -454,6 +470,13 @@ async fn generate_pictrs_thumbnail(
 ) -> LemmyResult<Url> {
   let pictrs_config = context.settings().pictrs()?;
 
+  if matches!(
+    pictrs_config.image_mode,
+    PictrsImageMode::None | PictrsImageMode::ProxyAllImages
+  ) && remote_image_blocked(image_url, context).await
+  {
+    Err(LemmyErrorType::ImageBlocked)?
+  }
   match pictrs_config.image_mode {
     PictrsImageMode::None => return Ok(image_url.clone()),
     PictrsImageMode::ProxyAllImages => {
@@ -467,33 +490,27 @@ async fn generate_pictrs_thumbnail(
   };
 
   // fetch remote non-pictrs images for persistent thumbnail link
-  let fetch_url = format!(
-    "{}image/download?url={}&resize={}",
-    pictrs_config.url,
-    encode(image_url.as_str()),
-    context.settings().pictrs()?.max_thumbnail_size
-  );
-
-  let res = context
-    .pictrs_client()
-    .get(&fetch_url)
-    .timeout(REQWEST_TIMEOUT)
-    .send()
-    .await?
-    .error_for_status()?
-    .json::<PictrsResponse>()
+  let image = context
+    .media()
+    .download(image_url, pictrs_config.max_thumbnail_size, context)
     .await?;
-
-  let image = res
-    .files
-    .first()
-    .ok_or(LemmyErrorType::PictrsResponseError(res.msg))?;
+  let data = context.media().read(&image.file, context).await?;
+  let file_size = i64::try_from(data.len()).ok();
+  let phash = image_hash(data).await.ok();
+  if let Some(phash) = phash {
+    if let Err(e) = check_image_hash(phash, context).await {
+      context.media().delete(&image.file, context).await?;
+      return Err(e);
+    }
+  }
 
   let form = LocalImageForm {
     pictrs_alias: image.file.clone(),
     // For thumbnails, the person_id is the post creator
     person_id: post.creator_id,
     thumbnail_for_post_id: Some(Some(post.id)),
+    phash,
+    file_size,
   };
   let protocol_and_hostname = context.settings().get_protocol_and_hostname();
   let thumbnail_url = image.image_url(&protocol_and_hostname)?;
@@ -512,34 +529,10 @@ pub async fn fetch_pictrs_proxied_image_details(
   image_url: &Url,
   context: &LemmyContext,
 ) -> LemmyResult<PictrsFileDetails> {
-  let pictrs_url = context.settings().pictrs()?.url;
-  let encoded_image_url = encode(image_url.as_str());
-
-  // Pictrs needs you to fetch the proxied image before you can fetch the details
-  let proxy_url = format!("{pictrs_url}image/original?proxy={encoded_image_url}");
-
   context
-    .pictrs_client()
-    .get(&proxy_url)
-    .timeout(REQWEST_TIMEOUT)
-    .send()
-    .await?
-    .error_for_status()
-    .with_lemmy_type(LemmyErrorType::NotAnImageType)?;
-
-  let details_url = format!("{pictrs_url}image/details/original?proxy={encoded_image_url}");
-
-  let res = context
-    .pictrs_client()
-    .get(&details_url)
-    .timeout(REQWEST_TIMEOUT)
-    .send()
-    .await?
-    .error_for_status()?
-    .json()
-    .await?;
-
-  Ok(res)
+    .media()
+    .proxied_image_details(image_url, context)
+    .await
 }
 
 // TODO: get rid of this by reading content type from db
@@ -5,8 +5,10 @@ use futures::future::BoxFuture;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, PersonId},
   source::{
+    actor_key_rotation::ActorKeyRotation,
     comment::Comment,
     community::Community,
+    federation_relay::FederationRelay,
     multi_community::MultiCommunity,
     person::Person,
     post::Post,
@@ -48,8 +50,12 @@ pub enum SendActivityData {
   },
   LockPost(Post, Person, bool, Option<String>),
   FeaturePost(Post, Person, bool),
+  /// Remote post which a moderator approved in a local community
+  AnnounceApprovedPost(Post),
   CreateComment(Comment),
   UpdateComment(Comment),
+  /// Remote comment which a moderator approved in a local community
+  AnnounceApprovedComment(Comment),
   DeleteComment(Comment, Person, Community),
   RemoveComment {
     comment: Comment,
@@ -66,9 +72,12 @@ pub enum SendActivityData {
   },
   FollowCommunity(Community, Person, bool),
   FollowMultiCommunity(MultiCommunity, Person, bool),
+  FollowPerson(Person, Person, bool),
   AcceptFollower(CommunityId, PersonId),
   RejectFollower(CommunityId, PersonId),
   UpdateCommunity(Person, Community),
+  /// Old and new community
+  MoveCommunity(Community, Community),
   DeleteCommunity(Person, Community, bool),
   RemoveCommunity {
     moderator: Person,
@@ -113,6 +122,8 @@ pub enum SendActivityData {
     receiver: Either<Site, Community>,
   },
   UpdateMultiCommunity(MultiCommunity, Person),
+  FollowRelay(FederationRelay, bool),
+  UpdateActorKey(ActorKeyRotation),
 }
 
 // TODO: instead of static, move this into LemmyContext. make sure that stopping the process with
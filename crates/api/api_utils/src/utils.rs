@@ -1,18 +1,19 @@
 use crate::{
   claims::Claims,
   context::LemmyContext,
+  media::image_url,
   request::{delete_image_alias, fetch_pictrs_proxied_image_details, purge_image_from_pictrs_url},
 };
 use actix_web::{http::header::Header, HttpRequest};
 use actix_web_httpauth::headers::authorization::{Authorization, Bearer};
-use chrono::{DateTime, Days, Local, TimeZone, Utc};
+use chrono::{DateTime, Days, Local, TimeDelta, TimeZone, Utc};
 use enum_map::{enum_map, EnumMap};
 use lemmy_db_schema::{
   newtypes::{CommentId, CommunityId, DbUrl, InstanceId, PersonId, PostId, PostOrCommentId, TagId},
   source::{
     comment::{Comment, CommentActions},
     community::{Community, CommunityActions, CommunityUpdateForm},
-    images::{ImageDetails, RemoteImage},
+    images::{ImageDetails, LocalImage, RemoteImage},
     instance::{Instance, InstanceActions},
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
@@ -26,6 +27,7 @@ use lemmy_db_schema::{
     oauth_account::OAuthAccount,
     person::{Person, PersonUpdateForm},
     post::{Post, PostActions, PostReadCommentsForm},
+    post_gallery::{PostGalleryItem, PostGalleryItemForm},
     private_message::PrivateMessage,
     registration_application::RegistrationApplication,
     site::Site,
@@ -34,13 +36,14 @@ use lemmy_db_schema::{
   traits::{Crud, Likeable},
   utils::DbPool,
 };
-use lemmy_db_schema_file::enums::{FederationMode, RegistrationMode};
+use lemmy_db_schema_file::enums::{FederationBlockSeverity, FederationMode, RegistrationMode};
 use lemmy_db_views_community_follower::CommunityFollowerView;
 use lemmy_db_views_community_moderator::CommunityModeratorView;
 use lemmy_db_views_community_person_ban::CommunityPersonBanView;
 use lemmy_db_views_local_image::LocalImageView;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_db_views_person::PersonView;
+use lemmy_db_views_post::api::GalleryItem;
 use lemmy_db_views_site::{
   api::{FederatedInstances, InstanceWithFederationState},
   SiteView,
@@ -52,8 +55,8 @@ use lemmy_utils::{
   spawn_try_task,
   utils::{
     markdown::{image_links::markdown_rewrite_image_links, markdown_check_for_blocked_urls},
-    slurs::remove_slurs,
-    validation::{build_and_check_regex, clean_urls_in_text},
+    slurs::{check_slurs_opt, remove_slurs},
+    validation::{build_and_check_regex, clean_urls_in_text, is_valid_alt_text_field},
   },
   CacheLock,
   CACHE_DURATION_FEDERATION,
@@ -265,6 +268,138 @@ pub async fn check_community_user_action(
   Ok(())
 }
 
+/// Checks if new content by the given person has to be approved by a moderator, before it becomes
+/// visible and gets federated.
+///
+/// This only applies to local communities, because remote ones announce content regardless of our
+/// settings. Moderators and admins can always post directly.
+pub async fn content_requires_approval(
+  community: &Community,
+  creator: &Person,
+  is_post: bool,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<bool> {
+  let enabled = if is_post {
+    community.posts_require_approval
+  } else {
+    community.comments_require_approval
+  };
+  if !community.local || !enabled {
+    return Ok(false);
+  }
+
+  // Established accounts are trusted
+  let account_age_days = (Utc::now() - creator.published_at).num_days();
+  if community
+    .approval_max_account_age_days
+    .is_some_and(|max| account_age_days >= i64::from(max))
+  {
+    return Ok(false);
+  }
+
+  let local_instance_id = SiteView::read_local(pool).await?.site.instance_id;
+  let is_mod_or_admin = check_is_mod_or_admin(pool, creator.id, community.id, local_instance_id)
+    .await
+    .is_ok();
+  Ok(!is_mod_or_admin)
+}
+
+/// Whether a freeze of the community is currently in effect. Expired freezes are only lifted
+/// periodically, so the expiry is checked here as well.
+fn community_freeze_active(community: &Community, frozen: bool) -> bool {
+  frozen
+    && !community
+      .freeze_expires_at
+      .is_some_and(|expires| expires <= Utc::now())
+}
+
+/// Checks that the person is allowed to create a new post in the community, based on its freeze.
+/// Moderators and admins are exempt.
+pub async fn check_community_posts_not_frozen(
+  community: &Community,
+  person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if !community_freeze_active(community, community.posts_frozen) {
+    return Ok(());
+  }
+  let local_instance_id = SiteView::read_local(pool).await?.site.instance_id;
+  if check_is_mod_or_admin(pool, person_id, community.id, local_instance_id)
+    .await
+    .is_err()
+  {
+    Err(LemmyErrorType::CommunityFrozen)?
+  }
+  Ok(())
+}
+
+/// Checks that the person is allowed to cross-post into the community. Moderators and admins are
+/// exempt.
+pub async fn check_community_cross_posting_allowed(
+  community: &Community,
+  person_id: PersonId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if !community.cross_posting_restricted_to_mods {
+    return Ok(());
+  }
+  let local_instance_id = SiteView::read_local(pool).await?.site.instance_id;
+  if check_is_mod_or_admin(pool, person_id, community.id, local_instance_id)
+    .await
+    .is_err()
+  {
+    Err(LemmyErrorType::CrossPostingRestricted)?
+  }
+  Ok(())
+}
+
+/// Checks that the person is allowed to write a new comment in the post, based on the freeze of
+/// the community and the lock and slow mode of the post. Slow mode counts comments in the whole
+/// community, so that it can't be avoided by switching posts. Moderators and admins are exempt.
+pub async fn check_post_comment_allowed(
+  post: &Post,
+  community: &Community,
+  creator: &Person,
+  is_mod_or_admin: bool,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if is_mod_or_admin {
+    return Ok(());
+  }
+  if community_freeze_active(community, community.comments_frozen) {
+    Err(LemmyErrorType::CommunityFrozen)?
+  }
+  let now = Utc::now();
+  if post_lock_applies(post, creator, now) {
+    Err(LemmyErrorType::Locked)?
+  }
+
+  let slow_mode_minutes = post
+    .comment_slow_mode_minutes
+    .filter(|m| *m > 0)
+    .or(community.comment_slow_mode_minutes)
+    .filter(|m| *m > 0);
+  if let Some(minutes) = slow_mode_minutes {
+    let latest = Comment::latest_published_by_creator(pool, community.id, creator.id).await?;
+    if latest.is_some_and(|latest| now - latest < TimeDelta::minutes(minutes.into())) {
+      Err(LemmyErrorType::SlowModeActive)?
+    }
+  }
+  Ok(())
+}
+
+/// Returns true if the post is locked for the given person, ignoring their mod or admin status.
+pub fn post_lock_applies(post: &Post, creator: &Person, now: DateTime<Utc>) -> bool {
+  // Expired locks are only lifted periodically, so check the expiry here as well
+  let lock_active = post.locked && !post.lock_expires_at.is_some_and(|expires| expires <= now);
+  // Locks for new accounts don't apply to established accounts
+  let account_age_days = (now - creator.published_at).num_days();
+  let lock_applies = !post
+    .lock_max_account_age_days
+    .is_some_and(|max| account_age_days >= i64::from(max));
+  lock_active && lock_applies
+}
+
 pub fn check_community_deleted_removed(community: &Community) -> LemmyResult<()> {
   if community.deleted || community.removed {
     Err(LemmyErrorType::Deleted)?
@@ -373,21 +508,27 @@ pub async fn build_federated_instances(
     let mut linked = Vec::new();
     let mut allowed = Vec::new();
     let mut blocked = Vec::new();
+    let mut silenced = Vec::new();
 
     let all = Instance::read_all_with_fed_state(pool).await?;
-    for (instance, federation_state, is_blocked, is_allowed) in all {
+    for (instance, federation_state, block, is_allowed) in all {
+      let severity = block.as_ref().map(|b| b.severity);
       let i = InstanceWithFederationState {
         instance,
         federation_state: federation_state.map(std::convert::Into::into),
+        block_reason: block.and_then(|b| b.public_reason),
       };
-      if is_blocked {
+      if severity == Some(FederationBlockSeverity::Suspend) {
         // blocked instances will only have an entry here if they had been federated with in the
         // past.
         blocked.push(i);
-      } else if is_allowed {
-        allowed.push(i.clone());
-        linked.push(i);
       } else {
+        if severity == Some(FederationBlockSeverity::Silence) {
+          silenced.push(i.clone());
+        }
+        if is_allowed {
+          allowed.push(i.clone());
+        }
         // not explicitly allowed but implicitly linked
         linked.push(i);
       }
@@ -397,6 +538,7 @@ pub async fn build_federated_instances(
       linked,
       allowed,
       blocked,
+      silenced,
     }))
   } else {
     Ok(None)
@@ -783,6 +925,11 @@ pub fn generate_featured_url(ap_id: &DbUrl) -> Result<DbUrl, ParseError> {
   Ok(Url::parse(&format!("{ap_id}/featured"))?.into())
 }
 
+/// Collection of all objects in the thread of a post, see FEP-7888.
+pub fn generate_context_url(post_ap_id: &DbUrl) -> Result<DbUrl, ParseError> {
+  Ok(Url::parse(&format!("{post_ap_id}/context"))?.into())
+}
+
 pub fn generate_moderators_url(community_id: &DbUrl) -> LemmyResult<DbUrl> {
   Ok(Url::parse(&format!("{community_id}/moderators"))?.into())
 }
@@ -1028,11 +1175,85 @@ pub async fn update_post_tags(
   Ok(())
 }
 
+/// Maximum number of images in a gallery post.
+pub const MAX_GALLERY_ITEMS: usize = 20;
+
+/// Checks that all gallery images were uploaded to this instance by the given user.
+pub async fn validate_gallery(
+  items: &[GalleryItem],
+  person_id: PersonId,
+  slur_regex: &Regex,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if items.len() > MAX_GALLERY_ITEMS {
+    Err(LemmyErrorType::TooManyItems)?
+  }
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  for item in items {
+    let url = Url::parse(&item.url).with_lemmy_type(LemmyErrorType::InvalidGalleryImage)?;
+    let alias = url
+      .path_segments()
+      .and_then(|mut s| s.next_back())
+      .unwrap_or_default();
+    if image_url(alias, &protocol_and_hostname).ok() != Some(url.clone()) {
+      Err(LemmyErrorType::InvalidGalleryImage)?
+    }
+    LocalImage::validate_by_alias_and_user(&mut context.pool(), alias, person_id)
+      .await
+      .with_lemmy_type(LemmyErrorType::InvalidGalleryImage)?;
+
+    if let Some(alt_text) = &item.alt_text {
+      is_valid_alt_text_field(alt_text)?;
+    }
+    if let Some(caption) = &item.caption {
+      is_valid_alt_text_field(caption)?;
+    }
+    check_slurs_opt(&item.alt_text, slur_regex)?;
+    check_slurs_opt(&item.caption, slur_regex)?;
+  }
+  Ok(())
+}
+
+/// Stores the images of a gallery post, which need to be checked with [validate_gallery] first.
+pub async fn update_post_gallery(
+  post: &Post,
+  items: &[GalleryItem],
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let mut forms = Vec::with_capacity(items.len());
+  for (position, item) in (0..).zip(items) {
+    let url: DbUrl = Url::parse(&item.url)?.into();
+    let url_content_type = ImageDetails::read(&mut context.pool(), &url)
+      .await
+      .ok()
+      .map(|d| d.content_type);
+    forms.push(PostGalleryItemForm {
+      post_id: post.id,
+      position,
+      url,
+      url_content_type,
+      alt_text: item.alt_text.clone(),
+      caption: item.caption.clone(),
+    });
+  }
+  PostGalleryItem::replace(&mut context.pool(), post.id, &forms).await?;
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use diesel_ltree::Ltree;
-  use lemmy_db_schema::newtypes::LanguageId;
+  use lemmy_db_schema::{
+    newtypes::LanguageId,
+    source::{
+      comment::CommentInsertForm,
+      community::CommunityInsertForm,
+      person::PersonInsertForm,
+      post::{PostInsertForm, PostUpdateForm},
+    },
+    utils::build_db_pool_for_tests,
+  };
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -1111,6 +1332,154 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_post_comment_allowed() -> LemmyResult<()> {
+    let pool = &mut build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+
+    let old_person_form = PersonInsertForm {
+      published_at: Some(Utc::now() - Days::new(30)),
+      ..PersonInsertForm::test_form(instance.id, "old_person")
+    };
+    let old_person = Person::create(pool, &old_person_form).await?;
+    let new_person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "new_person"),
+    )
+    .await?;
+
+    let community_form = CommunityInsertForm::new(
+      instance.id,
+      "test community lock".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::new("locked post".into(), old_person.id, community.id);
+    let post = Post::create(pool, &post_form).await?;
+    let other_post_form = PostInsertForm::new("other post".into(), old_person.id, community.id);
+    let other_post = Post::create(pool, &other_post_form).await?;
+
+    // Expired locks don't apply, even if they weren't lifted yet
+    let mut post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        locked: Some(true),
+        lock_expires_at: Some(Some(Utc::now() - Days::new(1))),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_ok()
+    );
+
+    post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        lock_expires_at: Some(Some(Utc::now() + Days::new(1))),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_err()
+    );
+    // Mods and admins are exempt
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, true, pool)
+        .await
+        .is_ok()
+    );
+
+    // Locks for new accounts only apply to accounts younger than the limit
+    post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        lock_max_account_age_days: Some(Some(7)),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_err()
+    );
+    assert!(
+      check_post_comment_allowed(&post, &community, &old_person, false, pool)
+        .await
+        .is_ok()
+    );
+
+    // Slow mode of the community counts comments in all of its posts
+    post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        locked: Some(false),
+        lock_expires_at: Some(None),
+        lock_max_account_age_days: Some(None),
+        ..Default::default()
+      },
+    )
+    .await?;
+    let community = Community::update(
+      pool,
+      community.id,
+      &CommunityUpdateForm {
+        comment_slow_mode_minutes: Some(Some(10)),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_ok()
+    );
+    let comment_form = CommentInsertForm::new(new_person.id, other_post.id, "first".into());
+    Comment::create(pool, &comment_form, None).await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_err()
+    );
+    assert!(
+      check_post_comment_allowed(&post, &community, &old_person, false, pool)
+        .await
+        .is_ok()
+    );
+
+    // A slow mode of zero on the post falls back to the community setting
+    post = Post::update(
+      pool,
+      post.id,
+      &PostUpdateForm {
+        comment_slow_mode_minutes: Some(Some(0)),
+        ..Default::default()
+      },
+    )
+    .await?;
+    assert!(
+      check_post_comment_allowed(&post, &community, &new_person, false, pool)
+        .await
+        .is_err()
+    );
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
   #[test]
   fn test_comment_depth() -> LemmyResult<()> {
     let mut comment = Comment {
@@ -1136,6 +1505,8 @@ mod tests {
       report_count: 0,
       unresolved_report_count: 0,
       federation_pending: false,
+      pending_approval: false,
+      accepted_answer: false,
     };
     assert!(check_comment_depth(&comment).is_ok());
     comment.path = Ltree("0.123.456".to_string());
@@ -19,7 +19,12 @@ use lemmy_apub_objects::{
     protocol::{Id, InCommunity},
   },
 };
-use lemmy_db_schema::source::{activity::ActivitySendTargets, community::CommunityActions};
+use lemmy_db_schema::source::{
+  activity::ActivitySendTargets,
+  community::CommunityActions,
+  federation_relay::FederationRelay,
+};
+use lemmy_db_schema_file::enums::CommunityVisibility;
 use lemmy_utils::error::{FederationError, LemmyError, LemmyErrorType, LemmyResult};
 use serde_json::Value;
 use url::Url;
@@ -56,11 +61,12 @@ impl Activity for RawAnnouncableActivities {
     // verify and receive activity
     activity.verify(context).await?;
     let ap_id = activity.actor().clone().into();
-    activity.receive(context).await?;
+    activity.clone().receive(context).await?;
 
-    // if community is local, send activity to followers
+    // if community is local, send activity to followers. Content which is held for approval is
+    // only sent once a moderator approves it, and content removed by automod isn't sent.
     if let Some(community) = community {
-      if community.local {
+      if community.local && !activity.is_held_back(context).await {
         verify_person_in_community(&ap_id, &community, context).await?;
         AnnounceActivity::send(self, &community, context).await?;
       }
@@ -128,6 +134,11 @@ impl AnnounceActivity {
           .clone(),
       };
       let announce_compat = AnnounceActivity::new(announcable_page, community, context)?;
+      let mut inboxes = inboxes;
+      if community.visibility == CommunityVisibility::Public {
+        // Relays use the same format as Mastodon, and only forward public content
+        inboxes.add_inboxes(FederationRelay::list_publish_inboxes(&mut context.pool()).await?);
+      }
       send_lemmy_activity(context, announce_compat, community, inboxes, false).await?;
     }
     Ok(())
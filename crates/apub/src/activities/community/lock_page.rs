@@ -61,6 +61,8 @@ impl Activity for LockPage {
     let reason = self.summary;
     let form = PostUpdateForm {
       locked,
+      lock_expires_at: Some(self.end_time),
+      lock_max_account_age_days: Some(self.max_account_age_days),
       ..Default::default()
     };
     let post = self.object.dereference(context).await?;
@@ -71,6 +73,8 @@ impl Activity for LockPage {
       post_id: post.id,
       locked,
       reason,
+      expires_at: self.end_time,
+      max_account_age_days: self.max_account_age_days,
     };
     ModLockPost::create(&mut context.pool(), &form).await?;
 
@@ -105,6 +109,8 @@ impl Activity for UndoLockPage {
     let reason = self.summary;
     let form = PostUpdateForm {
       locked,
+      lock_expires_at: Some(None),
+      lock_max_account_age_days: Some(None),
       ..Default::default()
     };
     let post = self.object.object.dereference(context).await?;
@@ -115,6 +121,8 @@ impl Activity for UndoLockPage {
       post_id: post.id,
       locked,
       reason,
+      expires_at: None,
+      max_account_age_days: None,
     };
     ModLockPost::create(&mut context.pool(), &form).await?;
 
@@ -143,6 +151,8 @@ pub(crate) async fn send_lock_post(
     kind: LockType::Lock,
     id,
     summary: reason.clone(),
+    end_time: post.lock_expires_at,
+    max_account_age_days: post.lock_max_account_age_days,
   };
   let activity = if locked {
     AnnouncableActivities::LockPost(lock)
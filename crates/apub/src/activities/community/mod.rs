@@ -32,6 +32,7 @@ pub mod announce;
 pub mod collection_add;
 pub mod collection_remove;
 pub mod lock_page;
+pub mod move_community;
 pub mod report;
 pub mod resolve_report;
 pub mod update;
@@ -0,0 +1,187 @@
+use crate::{
+  activities::{following::send_follow, generate_activity_id, send_lemmy_activity},
+  protocol::activities::community::move_community::MoveCommunity,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::MoveType, public},
+  protocol::verification::verify_urls_match,
+  traits::{Activity, Object},
+};
+use either::Either::*;
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::objects::community::ApubCommunity;
+use lemmy_db_schema::{
+  source::{
+    activity::ActivitySendTargets,
+    community::{Community, CommunityActions, CommunityFollowerForm, CommunityUpdateForm},
+  },
+  traits::{Crud, Followable},
+};
+use lemmy_db_schema_file::enums::{CommunityFollowerState, CommunityVisibility};
+use lemmy_utils::error::{FederationError, LemmyError, LemmyResult};
+use url::Url;
+
+pub(crate) async fn send_move_community(
+  old_community: Community,
+  new_community: Community,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let old_community: ApubCommunity = old_community.into();
+  let move_ = MoveCommunity {
+    actor: old_community.id().clone().into(),
+    to: vec![public()],
+    object: old_community.id().clone().into(),
+    target: new_community.ap_id.into(),
+    kind: MoveType::Move,
+    id: generate_activity_id(MoveType::Move, &context)?,
+  };
+  let inboxes = ActivitySendTargets::to_local_community_followers(old_community.id);
+  send_lemmy_activity(&context, move_, &old_community, inboxes, false).await
+}
+
+/// Marks the old community as moved, and makes its local followers follow the new community
+/// instead. The new community must confirm that it was moved from the old one.
+pub async fn move_community_followers(
+  old_community: &Community,
+  new_community: &Community,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if new_community.moved_from_url.as_ref() != Some(&old_community.ap_id) {
+    Err(FederationError::InvalidCommunityMove)?
+  }
+  let form = CommunityUpdateForm {
+    moved_to_url: Some(Some(new_community.ap_id.clone())),
+    ..Default::default()
+  };
+  Community::update(&mut context.pool(), old_community.id, &form).await?;
+
+  let follow_state = if new_community.visibility == CommunityVisibility::Private {
+    CommunityFollowerState::ApprovalRequired
+  } else if new_community.local {
+    CommunityFollowerState::Accepted
+  } else {
+    CommunityFollowerState::Pending
+  };
+  let followers =
+    CommunityActions::list_local_followers(&mut context.pool(), old_community.id).await?;
+  for person in followers {
+    let form = CommunityFollowerForm::new(new_community.id, person.id, follow_state);
+    CommunityActions::follow(&mut context.pool(), &form).await?;
+    CommunityActions::unfollow(&mut context.pool(), person.id, old_community.id).await?;
+
+    if !new_community.local {
+      let target = Right(Left(new_community.clone().into()));
+      send_follow(target, person.clone(), true, context).await?;
+    }
+    if !old_community.local {
+      let target = Right(Left(old_community.clone().into()));
+      send_follow(target, person, false, context).await?;
+    }
+  }
+  Ok(())
+}
+
+#[async_trait::async_trait]
+impl Activity for MoveCommunity {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<Self::DataType>) -> LemmyResult<()> {
+    // A community can only move itself
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    Ok(())
+  }
+
+  async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
+    let old_community = self.object.dereference(context).await?;
+    // Always refetch, in case the move was prepared after the new community was last fetched
+    let new_community = self.target.dereference_forced(context).await?;
+    move_community_followers(&old_community, &new_community, context).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::{
+    source::{
+      community::CommunityInsertForm,
+      person::{Person, PersonInsertForm},
+    },
+    test_data::TestData,
+  };
+  use lemmy_utils::error::LemmyErrorType;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_move_community_followers() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+
+    let form = PersonInsertForm::test_form(data.instance.id, "move_follower");
+    let person = Person::create(pool, &form).await?;
+    let community_form = |name: &str| {
+      CommunityInsertForm::new(
+        data.instance.id,
+        name.to_string(),
+        name.to_string(),
+        "pubkey".to_string(),
+      )
+    };
+    let old_community = Community::create(pool, &community_form("movefrom")).await?;
+    let new_community = Community::create(pool, &community_form("moveto")).await?;
+    let form = CommunityFollowerForm::new(
+      old_community.id,
+      person.id,
+      CommunityFollowerState::Accepted,
+    );
+    CommunityActions::follow(pool, &form).await?;
+
+    // The new community didn't confirm the move, so it is rejected
+    let res = move_community_followers(&old_community, &new_community, &context).await;
+    assert!(res.is_err_and(|e| e.error_type
+      == LemmyErrorType::FederationError {
+        error: Some(FederationError::InvalidCommunityMove)
+      }));
+    let old_followers = CommunityActions::list_local_followers(pool, old_community.id).await?;
+    assert_eq!(
+      vec![person.id],
+      old_followers.iter().map(|p| p.id).collect::<Vec<_>>()
+    );
+
+    let form = CommunityUpdateForm {
+      moved_from_url: Some(Some(old_community.ap_id.clone())),
+      ..Default::default()
+    };
+    let new_community = Community::update(pool, new_community.id, &form).await?;
+    move_community_followers(&old_community, &new_community, &context).await?;
+
+    let old_community = Community::read(pool, old_community.id).await?;
+    assert_eq!(
+      Some(new_community.ap_id.clone()),
+      old_community.moved_to_url
+    );
+    let old_followers = CommunityActions::list_local_followers(pool, old_community.id).await?;
+    assert!(old_followers.is_empty());
+    let new_followers = CommunityActions::list_local_followers(pool, new_community.id).await?;
+    assert_eq!(
+      vec![person.id],
+      new_followers.iter().map(|p| p.id).collect::<Vec<_>>()
+    );
+
+    data.delete(pool).await?;
+    Ok(())
+  }
+}
@@ -40,6 +40,7 @@ use lemmy_db_schema::{
   },
   traits::Reportable,
 };
+use lemmy_db_schema_file::enums::ReportSeverity;
 use lemmy_utils::error::{LemmyError, LemmyResult};
 use url::Url;
 
@@ -112,6 +113,7 @@ impl Activity for Report {
           reason,
           original_post_body: post.body.clone(),
           violates_instance_rules: false,
+          severity: ReportSeverity::default(),
         };
         PostReport::report(&mut context.pool(), &report_form).await?;
       }
@@ -124,6 +126,7 @@ impl Activity for Report {
           original_comment_text: comment.content.clone(),
           reason,
           violates_instance_rules: false,
+          severity: ReportSeverity::default(),
         };
         CommentReport::report(&mut context.pool(), &report_form).await?;
       }
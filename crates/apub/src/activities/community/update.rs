@@ -21,7 +21,12 @@ use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
     community::Community,
-    mod_log::moderator::{ModChangeCommunityVisibility, ModChangeCommunityVisibilityForm},
+    mod_log::moderator::{
+      ModChangeCommunityVisibility,
+      ModChangeCommunityVisibilityForm,
+      ModFreezeCommunity,
+      ModFreezeCommunityForm,
+    },
     multi_community::MultiCommunity,
     person::Person,
   },
@@ -125,6 +130,22 @@ impl Activity for Update {
           };
           ModChangeCommunityVisibility::create(&mut context.pool(), &form).await?;
         }
+
+        if old_community.posts_frozen != community.posts_frozen
+          || old_community.comments_frozen != community.comments_frozen
+          || old_community.freeze_expires_at != community.freeze_expires_at
+        {
+          let actor = self.actor.dereference(context).await?;
+          let form = ModFreezeCommunityForm {
+            mod_person_id: Some(actor.id),
+            community_id: old_community.id,
+            posts_frozen: community.posts_frozen,
+            comments_frozen: community.comments_frozen,
+            expires_at: community.freeze_expires_at,
+            reason: None,
+          };
+          ModFreezeCommunity::create(&mut context.pool(), &form).await?;
+        }
       }
       Either::Right(m) => {
         ApubMultiCommunity::from_json(m, context).await?;
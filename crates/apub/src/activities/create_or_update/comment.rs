@@ -5,7 +5,11 @@ use crate::{
     generate_activity_id,
   },
   activity_lists::AnnouncableActivities,
-  protocol::activities::{create_or_update::note::CreateOrUpdateNote, CreateOrUpdateType},
+  protocol::activities::{
+    community::announce::AnnounceActivity,
+    create_or_update::note::CreateOrUpdateNote,
+    CreateOrUpdateType,
+  },
 };
 use activitypub_federation::{
   config::Data,
@@ -43,6 +47,27 @@ use serde_json::{from_value, to_value};
 use url::Url;
 
 impl CreateOrUpdateNote {
+  async fn new(
+    comment: Comment,
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    kind: CreateOrUpdateType,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<CreateOrUpdateNote> {
+    let id = generate_activity_id(kind.clone(), context)?;
+    let note = ApubComment(comment).into_json(context).await?;
+
+    Ok(CreateOrUpdateNote {
+      actor: actor.id().clone().into(),
+      to: generate_to(community)?,
+      cc: note.cc.clone(),
+      tag: note.tag.clone(),
+      object: note,
+      kind,
+      id,
+    })
+  }
+
   pub(crate) async fn send(
     comment: Comment,
     person_id: PersonId,
@@ -58,18 +83,8 @@ impl CreateOrUpdateNote {
       .await?
       .into();
 
-    let id = generate_activity_id(kind.clone(), &context)?;
-    let note = ApubComment(comment).into_json(&context).await?;
-
-    let create_or_update = CreateOrUpdateNote {
-      actor: person.id().clone().into(),
-      to: generate_to(&community)?,
-      cc: note.cc.clone(),
-      tag: note.tag.clone(),
-      object: note,
-      kind,
-      id: id.clone(),
-    };
+    let create_or_update =
+      CreateOrUpdateNote::new(comment, &person, &community, kind, &context).await?;
 
     let tagged_users: Vec<ObjectId<ApubPerson>> = create_or_update
       .tag
@@ -97,6 +112,36 @@ impl CreateOrUpdateNote {
     let activity = AnnouncableActivities::CreateOrUpdateNoteWrapper(converted);
     send_activity_in_community(activity, &person, &community, inboxes, false, &context).await
   }
+
+  /// Approving a remote comment from the approval queue of a local community, see
+  /// [[CreateOrUpdatePage::announce_approved]].
+  pub(crate) async fn announce_approved(
+    comment: Comment,
+    context: Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let post = Post::read(&mut context.pool(), comment.post_id).await?;
+    let community: ApubCommunity = Community::read(&mut context.pool(), post.community_id)
+      .await?
+      .into();
+    if !community.local || !community.visibility.can_federate() {
+      return Ok(());
+    }
+    let creator: ApubPerson = Person::read(&mut context.pool(), comment.creator_id)
+      .await?
+      .into();
+
+    let create = CreateOrUpdateNote::new(
+      comment,
+      &creator,
+      &community,
+      CreateOrUpdateType::Create,
+      &context,
+    )
+    .await?;
+    let converted = from_value(to_value(create)?)?;
+    let activity = AnnouncableActivities::CreateOrUpdateNoteWrapper(converted);
+    AnnounceActivity::send(activity.try_into()?, &community, &context).await
+  }
 }
 
 #[async_trait::async_trait]
@@ -169,8 +214,12 @@ impl Activity for CreateOrUpdateNote {
     // anyway.
     // TODO: for compatibility with other projects, it would be much better to read this from cc or
     // tags
-    let community = Community::read(&mut context.pool(), post.community_id).await?;
-    NotifyData::new(post.0, Some(comment.0), actor.0, community, do_send_email).send(context);
+    // Comments which are held for approval notify once a moderator approves them, and removed
+    // comments never notify
+    if !comment.pending_approval && !comment.removed {
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      NotifyData::new(post.0, Some(comment.0), actor.0, community, do_send_email).send(context);
+    }
     Ok(())
   }
 }
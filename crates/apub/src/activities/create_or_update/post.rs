@@ -5,7 +5,11 @@ use crate::{
     generate_activity_id,
   },
   activity_lists::AnnouncableActivities,
-  protocol::activities::{create_or_update::page::CreateOrUpdatePage, CreateOrUpdateType},
+  protocol::activities::{
+    community::announce::AnnounceActivity,
+    create_or_update::page::CreateOrUpdatePage,
+    CreateOrUpdateType,
+  },
 };
 use activitypub_federation::{
   config::Data,
@@ -18,7 +22,7 @@ use lemmy_apub_objects::{
   objects::{
     community::ApubCommunity,
     person::ApubPerson,
-    post::{post_nsfw, update_apub_post_tags, ApubPost},
+    post::{post_nsfw, update_apub_accepted_answer, update_apub_post_tags, ApubPost},
   },
   utils::{
     functions::{generate_to, verify_mod_action, verify_person_in_community, verify_visibility},
@@ -84,6 +88,36 @@ impl CreateOrUpdatePage {
     .await?;
     Ok(())
   }
+
+  /// Approving a remote post from the approval queue of a local community. Its instance already
+  /// sent the Create to the community, so it only needs to be announced to community followers.
+  /// For remote communities the approval is local only, as the community instance has its own
+  /// approval queue.
+  pub(crate) async fn announce_approved(
+    post: Post,
+    context: Data<LemmyContext>,
+  ) -> LemmyResult<()> {
+    let community: ApubCommunity = Community::read(&mut context.pool(), post.community_id)
+      .await?
+      .into();
+    if !community.local || !community.visibility.can_federate() {
+      return Ok(());
+    }
+    let creator: ApubPerson = Person::read(&mut context.pool(), post.creator_id)
+      .await?
+      .into();
+
+    let create = CreateOrUpdatePage::new(
+      post.into(),
+      &creator,
+      &community,
+      CreateOrUpdateType::Create,
+      &context,
+    )
+    .await?;
+    let activity = AnnouncableActivities::CreateOrUpdatePost(create);
+    AnnounceActivity::send(activity.try_into()?, &community, &context).await
+  }
 }
 
 #[async_trait::async_trait]
@@ -130,10 +164,14 @@ impl Activity for CreateOrUpdatePage {
           let form = PostUpdateForm {
             updated_at: Some(Some(Utc::now())),
             nsfw: post_nsfw(&self.object, &community, Some(&local_site), context).await?,
+            comment_slow_mode_minutes: self.object.comment_slow_mode_minutes.map(Some),
+            comment_display_mode: self.object.comment_display_mode,
+            solved: self.object.solved,
             ..Default::default()
           };
           Post::update(&mut context.pool(), post.id, &form).await?;
           update_apub_post_tags(&self.object, &post, context).await?;
+          update_apub_accepted_answer(&self.object, &post, context).await?;
         }
       }
 
@@ -152,12 +190,16 @@ impl Activity for CreateOrUpdatePage {
     // Calculate initial hot_rank for post
     Post::update_ranks(&mut context.pool(), post.id).await?;
 
-    let do_send_email =
-      self.kind == CreateOrUpdateType::Create && !site_view.local_site.disable_email_notifications;
-    let actor = self.actor.dereference(context).await?;
+    // Posts which are held for approval notify once a moderator approves them, and removed posts
+    // never notify
+    if !post.pending_approval && !post.removed {
+      let do_send_email = self.kind == CreateOrUpdateType::Create
+        && !site_view.local_site.disable_email_notifications;
+      let actor = self.actor.dereference(context).await?;
 
-    let community = Community::read(&mut context.pool(), post.community_id).await?;
-    NotifyData::new(post.0, None, actor.0, community, do_send_email).send(context);
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      NotifyData::new(post.0, None, actor.0, community, do_send_email).send(context);
+    }
 
     Ok(())
   }
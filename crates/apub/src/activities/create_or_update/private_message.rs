@@ -12,9 +12,13 @@ use activitypub_federation::{
 };
 use lemmy_api_utils::context::LemmyContext;
 use lemmy_apub_objects::objects::{person::ApubPerson, private_message::ApubPrivateMessage};
-use lemmy_db_schema::source::activity::ActivitySendTargets;
+use lemmy_db_schema::source::{
+  activity::ActivitySendTargets,
+  federation_blocklist::FederationBlockList,
+};
 use lemmy_db_views_private_message::PrivateMessageView;
 use lemmy_utils::error::{LemmyError, LemmyResult};
+use tracing::debug;
 use url::Url;
 
 pub(crate) async fn send_create_or_update_pm(
@@ -62,6 +66,23 @@ impl Activity for CreateOrUpdatePrivateMessage {
   }
 
   async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
+    // Unsolicited messages from silenced instances are dropped. This is not an error, as the
+    // activity would be retried otherwise.
+    let sender = self.actor.dereference(context).await?;
+    let [recipient] = self.to;
+    let recipient = recipient.dereference(context).await?;
+    if FederationBlockList::is_silenced(
+      &mut context.pool(),
+      sender.id,
+      sender.instance_id,
+      recipient.id,
+    )
+    .await?
+    {
+      debug!("Dropping activity {} from silenced instance", self.id);
+      return Ok(());
+    }
+
     ApubPrivateMessage::from_json(self.object, context).await?;
     Ok(())
   }
@@ -1,5 +1,6 @@
 use crate::{
   activities::{generate_activity_id, send_lemmy_activity},
+  fetcher::backfill::spawn_backfill_community,
   protocol::activities::following::{accept::AcceptFollow, follow::Follow},
 };
 use activitypub_federation::{
@@ -8,9 +9,10 @@ use activitypub_federation::{
   protocol::verification::verify_urls_match,
   traits::{Activity, Actor, Object},
 };
+use either::Either::*;
 use lemmy_api_utils::context::LemmyContext;
 use lemmy_db_schema::{
-  source::{activity::ActivitySendTargets, community::CommunityActions},
+  source::{activity::ActivitySendTargets, community::CommunityActions, person::PersonActions},
   traits::Followable,
 };
 use lemmy_utils::error::{FederationError, LemmyError, LemmyResult};
@@ -56,13 +58,22 @@ impl Activity for AcceptFollow {
   }
 
   async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
-    let community = self.actor.dereference(context).await?;
+    let target = self.actor.dereference(context).await?;
     let actor = self.object.actor.dereference(context).await?;
     let person = actor.left().ok_or(FederationError::Unreachable)?;
     // This will throw an error if no follow was requested
-    let community_id = community.id;
-    let person_id = person.id;
-    CommunityActions::follow_accepted(&mut context.pool(), community_id, person_id).await?;
+    match target {
+      Left(u) => {
+        PersonActions::follow_accepted(&mut context.pool(), u.id, person.id).await?;
+      }
+      Right(c) => {
+        CommunityActions::follow_accepted(&mut context.pool(), c.id, person.id).await?;
+        // Fetch older content when the community gets its first local follower
+        if c.subscribers_local <= 1 {
+          spawn_backfill_community(c, context.reset_request_count());
+        }
+      }
+    }
 
     Ok(())
   }
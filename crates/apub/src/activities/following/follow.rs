@@ -10,7 +10,7 @@ use activitypub_federation::{
 };
 use either::Either::*;
 use lemmy_api_utils::context::LemmyContext;
-use lemmy_apub_objects::objects::{person::ApubPerson, CommunityOrMulti};
+use lemmy_apub_objects::objects::{person::ApubPerson, UserOrCommunityOrMulti};
 use lemmy_db_schema::{
   source::{
     activity::ActivitySendTargets,
@@ -30,7 +30,7 @@ use url::Url;
 impl Follow {
   pub(in crate::activities::following) fn new(
     actor: &ApubPerson,
-    target: &CommunityOrMulti,
+    target: &UserOrCommunityOrMulti,
     context: &Data<LemmyContext>,
   ) -> LemmyResult<Follow> {
     Ok(Follow {
@@ -44,7 +44,7 @@ impl Follow {
 
   pub async fn send(
     actor: &ApubPerson,
-    target: &CommunityOrMulti,
+    target: &UserOrCommunityOrMulti,
     context: &Data<LemmyContext>,
   ) -> LemmyResult<()> {
     let follow = Follow::new(actor, target, context)?;
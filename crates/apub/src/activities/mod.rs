@@ -4,6 +4,7 @@ use crate::{
     community::{
       collection_add::{send_add_mod_to_community, send_feature_post},
       lock_page::send_lock_post,
+      move_community::send_move_community,
       update::{send_update_community, send_update_multi_community},
     },
     create_or_update::private_message::send_create_or_update_pm,
@@ -14,6 +15,8 @@ use crate::{
       DeletableObjects,
     },
     following::send_follow,
+    relay::send_follow_relay,
+    update_actor::send_update_actor,
     voting::send_like_activity,
   },
   protocol::activities::{
@@ -54,6 +57,8 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod relay;
+pub mod update_actor;
 pub mod voting;
 
 /// Checks that the specified Url actually identifies a Person (by fetching it), and that the person
@@ -194,6 +199,7 @@ pub async fn match_outgoing_activities(
         send_lock_post(post, actor, locked, reason, context).await
       }
       FeaturePost(post, actor, featured) => send_feature_post(post, actor, featured, context).await,
+      AnnounceApprovedPost(post) => CreateOrUpdatePage::announce_approved(post, context).await,
       CreateComment(comment) => {
         let creator_id = comment.creator_id;
         CreateOrUpdateNote::send(comment, creator_id, CreateOrUpdateType::Create, context).await
@@ -202,6 +208,9 @@ pub async fn match_outgoing_activities(
         let creator_id = comment.creator_id;
         CreateOrUpdateNote::send(comment, creator_id, CreateOrUpdateType::Update, context).await
       }
+      AnnounceApprovedComment(comment) => {
+        CreateOrUpdateNote::announce_approved(comment, context).await
+      }
       DeleteComment(comment, actor, community) => {
         let is_deleted = comment.deleted;
         let deletable = DeletableObjects::Comment(comment.into());
@@ -238,12 +247,18 @@ pub async fn match_outgoing_activities(
         .await
       }
       FollowCommunity(community, person, follow) => {
-        send_follow(Either::Left(community.into()), person, follow, &context).await
+        let target = Either::Right(Either::Left(community.into()));
+        send_follow(target, person, follow, &context).await
       }
       FollowMultiCommunity(multi, person, follow) => {
-        send_follow(Either::Right(multi.into()), person, follow, &context).await
+        let target = Either::Right(Either::Right(multi.into()));
+        send_follow(target, person, follow, &context).await
+      }
+      FollowPerson(target, person, follow) => {
+        send_follow(Either::Left(target.into()), person, follow, &context).await
       }
       UpdateCommunity(actor, community) => send_update_community(community, actor, context).await,
+      MoveCommunity(old, new) => send_move_community(old, new, context).await,
       DeleteCommunity(actor, community, removed) => {
         let deletable = DeletableObjects::Community(community.clone().into());
         send_apub_delete_in_community(actor, community, deletable, None, removed, &context).await
@@ -345,6 +360,8 @@ pub async fn match_outgoing_activities(
       UpdateMultiCommunity(multi, actor) => {
         send_update_multi_community(multi, actor, context).await
       }
+      FollowRelay(relay, follow) => send_follow_relay(relay, follow, &context).await,
+      UpdateActorKey(rotation) => send_update_actor(rotation, &context).await,
     }
   };
   fed_task.await?;
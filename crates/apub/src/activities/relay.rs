@@ -0,0 +1,309 @@
+//! Activities exchanged with ActivityPub relays. Only relays which forward the id of public
+//! content are supported (LitePub style, also offered by most Mastodon compatible relays).
+//! Forwarded activities which are signed by the original author with LD signatures are ignored.
+use super::{generate_activity_id, send_lemmy_activity};
+use crate::{
+  fetcher::activity_object_id,
+  protocol::activities::relay::{
+    AcceptRelay,
+    AnnounceRelay,
+    FollowRelay,
+    RejectRelay,
+    UndoFollowRelay,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::object_id::ObjectId,
+  kinds::{
+    activity::{FollowType, UndoType},
+    public,
+  },
+  protocol::verification::verify_domains_match,
+  traits::{Activity, Actor},
+};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::objects::{instance::ApubSite, relay::ApubRelay, PostOrComment};
+use lemmy_db_schema::{
+  newtypes::FederationRelayId,
+  source::{
+    activity::ActivitySendTargets,
+    federation_relay::{FederationRelay, FederationRelayUpdateForm},
+    site::Site,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{FederationError, LemmyError, LemmyResult};
+use moka::future::Cache;
+use serde::Deserialize;
+use serde_json::Value;
+use std::{
+  sync::{
+    atomic::{AtomicI32, Ordering},
+    Arc,
+    LazyLock,
+  },
+  time::Duration,
+};
+use tracing::{debug, warn};
+use url::Url;
+
+pub async fn send_follow_relay(
+  relay: FederationRelay,
+  follow: bool,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  // The relay was never reached, so there is nothing to undo
+  if !follow && relay.inbox_url.is_none() {
+    return Ok(());
+  }
+  let actor: ApubSite = Site::read_local(&mut context.pool()).await?.into();
+  // LitePub style relays are followed by their actor, so it needs to be fetched to get the inbox
+  let relay: ApubRelay = match relay.ap_id.clone() {
+    Some(ap_id) if relay.inbox_url.is_none() => {
+      ObjectId::<ApubRelay>::from(ap_id)
+        .dereference_forced(context)
+        .await?
+    }
+    _ => relay.into(),
+  };
+  let object = if FederationRelay::is_inbox_url(&relay.url) {
+    public()
+  } else {
+    relay.id().clone()
+  };
+  let follow_activity = FollowRelay {
+    actor: actor.id().clone().into(),
+    object,
+    kind: FollowType::Follow,
+    id: generate_activity_id(FollowType::Follow, context)?,
+  };
+  let inbox = ActivitySendTargets::to_inbox(relay.inbox());
+  if follow {
+    let form = FederationRelayUpdateForm {
+      follow_id: Some(Some(follow_activity.id.clone().into())),
+      ..Default::default()
+    };
+    FederationRelay::update(&mut context.pool(), relay.id, &form).await?;
+    send_lemmy_activity(context, follow_activity, &actor, inbox, false).await
+  } else {
+    let undo = UndoFollowRelay {
+      actor: actor.id().clone().into(),
+      object: follow_activity,
+      kind: UndoType::Undo,
+      id: generate_activity_id(UndoType::Undo, context)?,
+    };
+    send_lemmy_activity(context, undo, &actor, inbox, false).await
+  }
+}
+
+#[async_trait::async_trait]
+impl Activity for FollowRelay {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(FederationError::Unreachable.into())
+  }
+
+  async fn receive(self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(FederationError::Unreachable.into())
+  }
+}
+
+#[async_trait::async_trait]
+impl Activity for UndoFollowRelay {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(FederationError::Unreachable.into())
+  }
+
+  async fn receive(self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    Err(FederationError::Unreachable.into())
+  }
+}
+
+#[async_trait::async_trait]
+impl Activity for AcceptRelay {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    Ok(())
+  }
+
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let relay = self.actor.dereference(context).await?;
+    let form = FederationRelayUpdateForm {
+      follow_pending: Some(false),
+      ..Default::default()
+    };
+    FederationRelay::update(&mut context.pool(), relay.id, &form).await?;
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait]
+impl Activity for RejectRelay {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    Ok(())
+  }
+
+  /// The relay stays pending, so that admins can see that it was not accepted.
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let relay = self.actor.dereference(context).await?;
+    warn!("Relay {} rejected the follow", relay.url);
+    let form = FederationRelayUpdateForm {
+      follow_pending: Some(true),
+      ..Default::default()
+    };
+    FederationRelay::update(&mut context.pool(), relay.id, &form).await?;
+    Ok(())
+  }
+}
+
+#[async_trait::async_trait]
+impl Activity for AnnounceRelay {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<LemmyContext>) -> LemmyResult<()> {
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    Ok(())
+  }
+
+  async fn receive(self, context: &Data<LemmyContext>) -> LemmyResult<()> {
+    let relay = self.actor.dereference(context).await?;
+    if relay.follow_pending {
+      return Ok(());
+    }
+    let Some(object_id) = activity_object_id(&self.object) else {
+      return Ok(());
+    };
+    let object = ObjectId::<PostOrComment>::from(object_id.clone());
+    if object.dereference_local(context).await.is_ok() {
+      return Ok(());
+    }
+    if !take_relay_quota(&relay).await {
+      debug!(
+        "Skipping {object_id} announced by relay {}, hourly limit reached",
+        relay.url
+      );
+      return Ok(());
+    }
+    // Relays forward all kinds of content, so objects which Lemmy can't store (eg microblog
+    // posts without community) are skipped.
+    if let Err(e) = object.dereference(context).await {
+      debug!("Failed to fetch {object_id} announced by relay: {e}");
+    }
+    Ok(())
+  }
+}
+
+/// Counts a new object forwarded by the relay, and returns false if the relay already reached its
+/// hourly limit. New content is stored even if no local user follows its community, which also
+/// fetches the community if it is unknown. So the limit is what keeps a relay from making this
+/// instance fetch and store arbitrary amounts of content.
+async fn take_relay_quota(relay: &FederationRelay) -> bool {
+  // Counters start when the first object of the relay arrives, and reset an hour later
+  static COUNTERS: LazyLock<Cache<FederationRelayId, Arc<AtomicI32>>> = LazyLock::new(|| {
+    Cache::builder()
+      .max_capacity(1000)
+      .time_to_live(Duration::from_secs(60 * 60))
+      .build()
+  });
+  let counter = COUNTERS
+    .get_with(relay.id, async { Arc::new(AtomicI32::new(0)) })
+    .await;
+  counter.fetch_add(1, Ordering::Relaxed) < relay.max_objects_per_hour
+}
+
+/// Finds the relay which sent an activity. Mastodon style relays are subscribed by their inbox,
+/// so their actor is only learned from the Accept, which refers to the follow that was sent to
+/// the relay. Other actors on the relay domain are never treated as the relay.
+pub(crate) async fn read_relay_for_actor(
+  actor: &Url,
+  body: &[u8],
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Option<FederationRelay>> {
+  if let Some(relay) = FederationRelay::read_for_actor(&mut context.pool(), actor).await? {
+    return Ok(Some(relay));
+  }
+  let Ok(activity) = serde_json::from_slice::<FollowResponse>(body) else {
+    return Ok(None);
+  };
+  if !matches!(activity.kind.as_str(), "Accept" | "Reject") {
+    return Ok(None);
+  }
+  let Some(follow_id) = activity_object_id(&activity.object) else {
+    return Ok(None);
+  };
+  let Some(relay) =
+    FederationRelay::read_from_follow_id(&mut context.pool(), &follow_id.into()).await?
+  else {
+    return Ok(None);
+  };
+  verify_domains_match(relay.url.inner(), actor)?;
+  let form = FederationRelayUpdateForm {
+    ap_id: Some(Some(actor.clone().into())),
+    ..Default::default()
+  };
+  Ok(Some(
+    FederationRelay::update(&mut context.pool(), relay.id, &form).await?,
+  ))
+}
+
+/// Accept or Reject of a relay follow, with the follow as object.
+#[derive(Deserialize)]
+struct FollowResponse {
+  #[serde(rename = "type")]
+  kind: String,
+  object: Value,
+}
@@ -0,0 +1,65 @@
+use super::{generate_activity_id, send_lemmy_activity};
+use crate::protocol::activities::update_actor::UpdateActor;
+use activitypub_federation::{
+  config::Data,
+  fetch::object_id::ObjectId,
+  kinds::{activity::UpdateType, public},
+  protocol::verification::verify_urls_match,
+  traits::{Activity, Object},
+};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::objects::UserOrCommunity;
+use lemmy_db_schema::source::{
+  activity::ActivitySendTargets,
+  actor_key_rotation::ActorKeyRotation,
+};
+use lemmy_db_schema_file::enums::ActorType;
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use url::Url;
+
+pub(crate) async fn send_update_actor(
+  rotation: ActorKeyRotation,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  // The site doesn't send activities to the shared inbox, its key is only used for signed fetches
+  if rotation.actor_type == ActorType::Site {
+    return Ok(());
+  }
+  let actor: UserOrCommunity = ObjectId::from(rotation.actor_ap_id)
+    .dereference_local(context)
+    .await?;
+  let update = UpdateActor {
+    actor: actor.id().clone().into(),
+    to: vec![public()],
+    object: actor.id().clone().into(),
+    kind: UpdateType::Update,
+    id: generate_activity_id(UpdateType::Update, context)?,
+  };
+  let inboxes = ActivitySendTargets::to_all_instances();
+  send_lemmy_activity(context, update, &actor, inboxes, false).await
+}
+
+#[async_trait::async_trait]
+impl Activity for UpdateActor {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  async fn verify(&self, _context: &Data<Self::DataType>) -> LemmyResult<()> {
+    // An actor can only announce its own key
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    Ok(())
+  }
+
+  async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
+    self.object.dereference_forced(context).await?;
+    Ok(())
+  }
+}
@@ -5,11 +5,16 @@ use crate::protocol::activities::{
     collection_add::CollectionAdd,
     collection_remove::CollectionRemove,
     lock_page::{LockPage, UndoLockPage},
+    move_community::MoveCommunity,
     report::Report,
     resolve_report::ResolveReport,
     update::Update,
   },
-  create_or_update::{note_wrapper::CreateOrUpdateNoteWrapper, page::CreateOrUpdatePage},
+  create_or_update::{
+    note::CreateOrUpdateNote,
+    note_wrapper::CreateOrUpdateNoteWrapper,
+    page::CreateOrUpdatePage,
+  },
   deletion::{delete::Delete, undo_delete::UndoDelete},
   following::{
     accept::AcceptFollow,
@@ -17,6 +22,8 @@ use crate::protocol::activities::{
     reject::RejectFollow,
     undo_follow::UndoFollow,
   },
+  relay::{AcceptRelay, AnnounceRelay, RejectRelay},
+  update_actor::UpdateActor,
   voting::{undo_vote::UndoVote, vote::Vote},
 };
 use activitypub_federation::{config::Data, traits::Activity};
@@ -28,6 +35,7 @@ use lemmy_apub_objects::{
 };
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 use serde::{Deserialize, Serialize};
+use serde_json::{from_value, to_value};
 use url::Url;
 
 /// List of activities which the shared inbox can handle.
@@ -45,11 +53,24 @@ pub(crate) enum SharedInboxActivities {
   UndoFollow(UndoFollow),
   Report(Report),
   ResolveReport(ResolveReport),
+  MoveCommunity(MoveCommunity),
+  UpdateActor(UpdateActor),
   AnnounceActivity(AnnounceActivity),
   /// This is a catch-all and needs to be last
   RawAnnouncableActivities(RawAnnouncableActivities),
 }
 
+/// Activities which relays send to the shared inbox. They can't be distinguished from other
+/// activities by their json, so the inbox checks if the actor is a subscribed relay instead.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+#[enum_delegate::implement(Activity)]
+pub(crate) enum RelayInboxActivities {
+  AcceptRelay(AcceptRelay),
+  RejectRelay(RejectRelay),
+  AnnounceRelay(AnnounceRelay),
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 #[enum_delegate::implement(Activity)]
@@ -97,6 +118,35 @@ impl InCommunity for AnnouncableActivities {
   }
 }
 
+impl AnnouncableActivities {
+  /// Checks if the activity creates a post or comment which is held for moderator approval, or
+  /// which was removed by automod. Held content is only announced once a moderator approves it,
+  /// and removed content is never announced.
+  pub(crate) async fn is_held_back(&self, context: &Data<LemmyContext>) -> bool {
+    match self {
+      AnnouncableActivities::CreateOrUpdatePost(a) => a
+        .object
+        .id
+        .dereference_local(context)
+        .await
+        .is_ok_and(|p| p.pending_approval || p.removed),
+      AnnouncableActivities::CreateOrUpdateNoteWrapper(a) => {
+        // Private messages fail to convert, and are never held
+        let Ok(note) = to_value(a).and_then(from_value::<CreateOrUpdateNote>) else {
+          return false;
+        };
+        note
+          .object
+          .id
+          .dereference_local(context)
+          .await
+          .is_ok_and(|c| c.pending_approval || c.removed)
+      }
+      _ => false,
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -124,6 +174,7 @@ mod tests {
     test_parse_lemmy_item::<SharedInboxActivities>(
       "assets/lemmy/activities/create_or_update/create_comment.json",
     )?;
+    test_parse_lemmy_item::<SharedInboxActivities>("assets/lemmy/activities/update_actor.json")?;
     test_json::<SharedInboxActivities>("assets/mastodon/activities/follow.json")?;
     Ok(())
   }
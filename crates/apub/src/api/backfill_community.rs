@@ -0,0 +1,25 @@
+use crate::fetcher::backfill::spawn_backfill_community;
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_utils::{context::LemmyContext, utils::is_admin};
+use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_db_views_community::api::BackfillCommunity;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_utils::error::{FederationError, LemmyResult};
+
+pub async fn backfill_community(
+  data: Json<BackfillCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+  if community.local {
+    Err(FederationError::InvalidCommunity)?
+  }
+  spawn_backfill_community(community.into(), context.reset_request_count());
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,449 @@
+use activitypub_federation::{
+  config::Data,
+  fetch::object_id::ObjectId,
+  http_signatures::generate_actor_keypair,
+};
+use actix_web::web::{Json, Query};
+use lemmy_api_utils::{
+  build_response::build_community_response,
+  context::LemmyContext,
+  utils::{
+    check_community_mod_action,
+    check_nsfw_allowed,
+    generate_followers_url,
+    generate_inbox_url,
+    get_url_blocklist,
+    is_admin,
+    process_markdown_opt,
+    slur_regex,
+  },
+};
+use lemmy_apub_objects::objects::{
+  comment::ApubComment,
+  community::ApubCommunity,
+  person::ApubPerson,
+  post::ApubPost,
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    community::{
+      Community,
+      CommunityActions,
+      CommunityFollowerForm,
+      CommunityInsertForm,
+      CommunityModeratorForm,
+    },
+    post::Post,
+    tag::{Tag, TagInsertForm},
+  },
+  traits::{ApubActor, Crud, Followable},
+};
+use lemmy_db_schema_file::enums::CommunityFollowerState;
+use lemmy_db_views_community::api::{
+  CommunityArchive,
+  CommunityArchiveTag,
+  CommunityResponse,
+  ExportCommunity,
+};
+use lemmy_db_views_community_moderator::CommunityModeratorView;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::{
+  error::{FederationError, LemmyErrorType, LemmyResult},
+  spawn_try_task,
+  utils::{
+    slurs::check_slurs,
+    validation::{
+      check_api_elements_count,
+      description_length_check,
+      is_valid_actor_name,
+      is_valid_body_field,
+    },
+  },
+};
+use tracing::{debug, info};
+use url::Url;
+
+/// Maximum number of posts, and of comments, which are included in a community archive.
+const MAX_ARCHIVE_ITEMS: i64 = 10_000;
+
+pub async fn export_community(
+  data: Query<ExportCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityArchive>> {
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+  if !community.local {
+    Err(FederationError::InvalidCommunity)?
+  }
+  check_community_mod_action(&local_user_view, &community, true, &mut context.pool()).await?;
+
+  let moderators = CommunityModeratorView::for_community(&mut context.pool(), community.id)
+    .await?
+    .into_iter()
+    .map(|m| m.moderator.ap_id)
+    .collect();
+  let tags = Tag::read_for_community(&mut context.pool(), community.id)
+    .await?
+    .into_iter()
+    .map(|t| CommunityArchiveTag {
+      name: t.name,
+      display_name: t.display_name,
+      description: t.description,
+    })
+    .collect();
+  let posts =
+    Post::list_ap_ids_for_community(&mut context.pool(), community.id, MAX_ARCHIVE_ITEMS).await?;
+  let comments =
+    Comment::list_ap_ids_for_community(&mut context.pool(), community.id, MAX_ARCHIVE_ITEMS)
+      .await?;
+
+  Ok(Json(CommunityArchive {
+    ap_id: community.ap_id,
+    name: community.name,
+    title: community.title,
+    sidebar: community.sidebar,
+    description: community.description,
+    icon: community.icon,
+    banner: community.banner,
+    nsfw: community.nsfw,
+    posting_restricted_to_mods: community.posting_restricted_to_mods,
+    cross_posting_restricted_to_mods: community.cross_posting_restricted_to_mods,
+    question_answer: community.question_answer,
+    visibility: community.visibility,
+    moderators,
+    tags,
+    posts,
+    comments,
+  }))
+}
+
+/// Creates a new local community from the archive of a community on another instance. Posts and
+/// comments of the old community are fetched in the background and moved into the new community.
+///
+/// Only admins and moderators of the old community can import it.
+pub async fn import_community(
+  data: Json<CommunityArchive>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityResponse>> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_site = &site_view.local_site;
+  let admin = is_admin(&local_user_view).is_ok();
+
+  let old_community: ApubCommunity = ObjectId::from(data.ap_id.clone())
+    .dereference(&context)
+    .await?;
+  if old_community.local {
+    Err(FederationError::InvalidCommunity)?
+  }
+  if !admin {
+    CommunityModeratorView::check_is_community_moderator(
+      &mut context.pool(),
+      old_community.id,
+      local_user_view.person.id,
+    )
+    .await?;
+  }
+  if local_site.community_creation_admin_only && !admin {
+    Err(LemmyErrorType::OnlyAdminsCanCreateCommunities)?
+  }
+
+  check_api_elements_count(data.moderators.len() + data.tags.len())?;
+  check_api_elements_count(data.posts.len())?;
+  check_api_elements_count(data.comments.len())?;
+  check_nsfw_allowed(Some(data.nsfw), Some(local_site))?;
+  let slur_regex = slur_regex(&context).await?;
+  let url_blocklist = get_url_blocklist(&context).await?;
+  check_slurs(&data.name, &slur_regex)?;
+  check_slurs(&data.title, &slur_regex)?;
+  is_valid_actor_name(&data.name, local_site.actor_name_max_length)?;
+  let sidebar = process_markdown_opt(&data.sidebar, &slur_regex, &url_blocklist, &context).await?;
+  if let Some(sidebar) = &sidebar {
+    is_valid_body_field(sidebar, false)?;
+  }
+  if let Some(desc) = &data.description {
+    description_length_check(desc)?;
+    check_slurs(desc, &slur_regex)?;
+  }
+
+  let community_ap_id = Community::generate_local_actor_url(&data.name, context.settings())?;
+  let community_dupe = Community::read_from_apub_id(&mut context.pool(), &community_ap_id).await?;
+  if community_dupe.is_some() {
+    Err(LemmyErrorType::AlreadyExists)?
+  }
+
+  let keypair = generate_actor_keypair()?;
+  let community_form = CommunityInsertForm {
+    sidebar,
+    description: data.description.clone(),
+    icon: data.icon.clone(),
+    banner: data.banner.clone(),
+    nsfw: Some(data.nsfw),
+    ap_id: Some(community_ap_id.clone()),
+    private_key: Some(keypair.private_key),
+    followers_url: Some(generate_followers_url(&community_ap_id)?),
+    inbox_url: Some(generate_inbox_url()?),
+    posting_restricted_to_mods: Some(data.posting_restricted_to_mods),
+    cross_posting_restricted_to_mods: Some(data.cross_posting_restricted_to_mods),
+    question_answer: Some(data.question_answer),
+    visibility: Some(data.visibility),
+    moved_from_url: Some(data.ap_id.clone()),
+    ..CommunityInsertForm::new(
+      site_view.site.instance_id,
+      data.name.clone(),
+      data.title.clone(),
+      keypair.public_key,
+    )
+  };
+  let community = Community::create(&mut context.pool(), &community_form).await?;
+
+  // The importing user becomes a moderator and follows the community, same as on creation
+  let moderator_form = CommunityModeratorForm::new(community.id, local_user_view.person.id);
+  CommunityActions::join(&mut context.pool(), &moderator_form).await?;
+  let follower_form = CommunityFollowerForm::new(
+    community.id,
+    local_user_view.person.id,
+    CommunityFollowerState::Accepted,
+  );
+  CommunityActions::follow(&mut context.pool(), &follower_form).await?;
+
+  for tag in &data.tags {
+    let ap_id = Url::parse(&format!("{}/tag/{}", community.ap_id, &tag.name))?;
+    let form = TagInsertForm {
+      ap_id: ap_id.into(),
+      name: tag.name.clone(),
+      display_name: tag.display_name.clone(),
+      description: tag.description.clone(),
+      community_id: community.id,
+      deleted: Some(false),
+    };
+    Tag::create(&mut context.pool(), &form).await?;
+  }
+
+  let community_id = community.id;
+  let task_context = context.reset_request_count();
+  spawn_try_task(async move {
+    import_community_content(data.0, old_community, community, task_context).await
+  });
+
+  build_community_response(&context, local_user_view, community_id).await
+}
+
+async fn import_community_content(
+  archive: CommunityArchive,
+  old_community: ApubCommunity,
+  community: Community,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  info!("Starting import of community {}", archive.ap_id);
+  for moderator in archive.moderators {
+    let context = context.reset_request_count();
+    let person = ObjectId::<ApubPerson>::from(moderator)
+      .dereference(&context)
+      .await;
+    let Ok(person) = person else {
+      continue;
+    };
+    // Only actual moderators of the old community are taken over, so that the archive can't be
+    // used to add arbitrary users as moderators
+    if CommunityModeratorView::check_is_community_moderator(
+      &mut context.pool(),
+      old_community.id,
+      person.id,
+    )
+    .await
+    .is_err()
+    {
+      debug!("{} is not a moderator of {}", person.ap_id, archive.ap_id);
+      continue;
+    }
+    let form = CommunityModeratorForm::new(community.id, person.id);
+    CommunityActions::join(&mut context.pool(), &form).await?;
+  }
+
+  // Only posts from the old community are moved, so that the archive can't be used to take over
+  // content from other communities
+  for post_id in archive.posts {
+    let context = context.reset_request_count();
+    let post = ObjectId::<ApubPost>::from(post_id.clone())
+      .dereference(&context)
+      .await;
+    match post {
+      Ok(post) if post.community_id == old_community.id => {
+        Post::move_to_community(&mut context.pool(), post.id, community.id).await?;
+      }
+      Ok(_) => debug!("Post {post_id} is not in community {}", archive.ap_id),
+      Err(e) => debug!("Failed to import post {post_id}: {e}"),
+    }
+  }
+
+  // Comments belong to their post, so they only need to be fetched
+  for comment_id in archive.comments {
+    let context = context.reset_request_count();
+    if let Err(e) = ObjectId::<ApubComment>::from(comment_id.clone())
+      .dereference(&context)
+      .await
+    {
+      debug!("Failed to import comment {comment_id}: {e}");
+    }
+  }
+  info!("Finished import of community {}", archive.ap_id);
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lemmy_db_schema::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::PostInsertForm,
+    },
+    test_data::TestData,
+  };
+  use lemmy_db_views_community::api::ExportCommunity;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use std::time::Duration;
+  use tokio::time::sleep;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_export_import_community() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+    let admin = LocalUserView::create_test_user(pool, "archivist", "", true).await?;
+
+    // Export a local community
+    let form = CommunityInsertForm::new(
+      data.instance.id,
+      "exportcom".to_string(),
+      "Export".to_string(),
+      "pubkey".to_string(),
+    );
+    let local_community = Community::create(pool, &form).await?;
+    let form = CommunityModeratorForm::new(local_community.id, admin.person.id);
+    CommunityActions::join(pool, &form).await?;
+    let form = TagInsertForm {
+      ap_id: Url::parse(&format!("{}/tag/news", local_community.ap_id))?.into(),
+      name: "news".to_string(),
+      display_name: None,
+      description: None,
+      community_id: local_community.id,
+      deleted: Some(false),
+    };
+    Tag::create(pool, &form).await?;
+    let form = PostInsertForm::new(
+      "local post".to_string(),
+      admin.person.id,
+      local_community.id,
+    );
+    let local_post = Post::create(pool, &form).await?;
+
+    let query = Query(ExportCommunity {
+      community_id: local_community.id,
+    });
+    let archive = export_community(query, context.clone(), admin.clone())
+      .await?
+      .0;
+    assert_eq!(local_community.ap_id, archive.ap_id);
+    assert_eq!(vec![admin.person.ap_id.clone()], archive.moderators);
+    assert_eq!(
+      vec!["news".to_string()],
+      archive
+        .tags
+        .iter()
+        .map(|t| t.name.clone())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![local_post.ap_id.clone()], archive.posts);
+
+    // The same archive format is used to import a community from another instance
+    let remote_instance = Instance::read_or_create(pool, "remote.example".to_string()).await?;
+    let remote_person = |name: &str| -> LemmyResult<PersonInsertForm> {
+      Ok(PersonInsertForm {
+        ap_id: Some(Url::parse(&format!("https://remote.example/u/{name}"))?.into()),
+        local: Some(false),
+        ..PersonInsertForm::test_form(remote_instance.id, name)
+      })
+    };
+    let remote_mod = Person::create(pool, &remote_person("remote_mod")?).await?;
+    let remote_user = Person::create(pool, &remote_person("remote_user")?).await?;
+    let remote_community = |name: &str| -> LemmyResult<CommunityInsertForm> {
+      Ok(CommunityInsertForm {
+        ap_id: Some(Url::parse(&format!("https://remote.example/c/{name}"))?.into()),
+        local: Some(false),
+        ..CommunityInsertForm::new(
+          remote_instance.id,
+          name.to_string(),
+          name.to_string(),
+          "pubkey".to_string(),
+        )
+      })
+    };
+    let old_community = Community::create(pool, &remote_community("oldcom")?).await?;
+    let other_community = Community::create(pool, &remote_community("othercom")?).await?;
+    let form = CommunityModeratorForm::new(old_community.id, remote_mod.id);
+    CommunityActions::join(pool, &form).await?;
+    let remote_post = |name: &str, community_id| -> LemmyResult<PostInsertForm> {
+      Ok(PostInsertForm {
+        ap_id: Some(Url::parse(&format!("https://remote.example/post/{name}"))?.into()),
+        local: Some(false),
+        ..PostInsertForm::new(name.to_string(), remote_mod.id, community_id)
+      })
+    };
+    let old_post = Post::create(pool, &remote_post("old", old_community.id)?).await?;
+    let other_post = Post::create(pool, &remote_post("other", other_community.id)?).await?;
+
+    let archive: CommunityArchive = serde_json::from_str(&serde_json::to_string(&archive)?)?;
+    let archive = CommunityArchive {
+      ap_id: old_community.ap_id.clone(),
+      name: "importcom".to_string(),
+      moderators: vec![remote_mod.ap_id.clone(), remote_user.ap_id.clone()],
+      posts: vec![old_post.ap_id.clone(), other_post.ap_id.clone()],
+      ..archive
+    };
+    let imported = import_community(Json(archive), context.clone(), admin.clone())
+      .await?
+      .0
+      .community_view
+      .community;
+    assert_eq!(Some(old_community.ap_id.clone()), imported.moved_from_url);
+    let tags = Tag::read_for_community(pool, imported.id).await?;
+    assert_eq!(
+      vec!["news".to_string()],
+      tags.into_iter().map(|t| t.name).collect::<Vec<_>>()
+    );
+
+    // wait for background task to finish
+    sleep(Duration::from_millis(1000)).await;
+
+    // Only moderators of the old community are taken over, and only its own posts are moved
+    let mut moderators = CommunityModeratorView::for_community(pool, imported.id)
+      .await?
+      .into_iter()
+      .map(|m| m.moderator.id)
+      .collect::<Vec<_>>();
+    moderators.sort_by_key(|id| id.0);
+    let mut expected = vec![admin.person.id, remote_mod.id];
+    expected.sort_by_key(|id| id.0);
+    assert_eq!(expected, moderators);
+    assert_eq!(
+      imported.id,
+      Post::read(pool, old_post.id).await?.community_id
+    );
+    assert_eq!(
+      other_community.id,
+      Post::read(pool, other_post.id).await?.community_id
+    );
+
+    Instance::delete(pool, remote_instance.id).await?;
+    Person::delete(pool, admin.person.id).await?;
+    data.delete(pool).await?;
+    Ok(())
+  }
+}
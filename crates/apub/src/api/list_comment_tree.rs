@@ -0,0 +1,75 @@
+use super::comment_sort_type_with_default;
+use crate::api::fetch_limit_with_default;
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_utils::{context::LemmyContext, utils::check_private_instance};
+use lemmy_db_schema::{
+  source::{comment::Comment, post::Post},
+  traits::{Crud, PaginationCursorBuilder},
+};
+use lemmy_db_views_comment::{
+  api::{GetCommentTree, GetCommentTreeResponse},
+  impls::CommentTreeQuery,
+  CommentView,
+};
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+pub async fn list_comment_tree(
+  data: Query<GetCommentTree>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetCommentTreeResponse>> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let local_site = &site_view.local_site;
+
+  check_private_instance(&local_user_view, local_site)?;
+
+  // If a parent_id is given, the post is taken from the parent comment
+  let parent = if let Some(parent_id) = data.parent_id {
+    Some(Comment::read(&mut context.pool(), parent_id).await?)
+  } else {
+    None
+  };
+  let post_id = parent
+    .as_ref()
+    .map(|p| p.post_id)
+    .or(data.post_id)
+    .ok_or(LemmyErrorType::NoIdGiven)?;
+  let display_mode = Post::read(&mut context.pool(), post_id)
+    .await?
+    .comment_display_mode;
+
+  let local_user = local_user_view.as_ref().map(|u| &u.local_user);
+  let sort = Some(comment_sort_type_with_default(
+    data.sort, local_user, local_site,
+  ));
+  let limit = Some(fetch_limit_with_default(data.limit, local_user, local_site));
+
+  let cursor_data = if let Some(cursor) = &data.page_cursor {
+    Some(CommentView::from_cursor(cursor, &mut context.pool()).await?)
+  } else {
+    None
+  };
+
+  let (comments, next_page) = CommentTreeQuery {
+    post_id,
+    parent_path: parent.map(|p| p.path),
+    display_mode,
+    sort,
+    max_depth: data.max_depth,
+    children_limit: data.children_limit,
+    local_user,
+    cursor_data,
+    limit,
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?;
+
+  Ok(Json(GetCommentTreeResponse {
+    display_mode,
+    comments,
+    next_page,
+  }))
+}
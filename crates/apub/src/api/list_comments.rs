@@ -95,6 +95,10 @@ async fn list_comments_common(
     parent_path,
     post_id,
     local_user,
+    pending_approval_only: None,
+    top_level_only: None,
+    subtrees: None,
+    accepted_first: None,
     cursor_data,
     page_back,
     limit,
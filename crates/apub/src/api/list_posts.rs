@@ -87,12 +87,16 @@ pub async fn list_posts(
     time_range_seconds,
     community_id,
     multi_community_id,
+    tag_id: None,
     limit,
     show_hidden,
     show_read,
     show_nsfw,
     hide_media,
     no_comments_only,
+    solved: data.solved,
+    unsolved_first: data.unsolved_first,
+    pending_approval_only: None,
     keyword_blocks,
     cursor_data,
     page_back,
@@ -10,9 +10,13 @@ use lemmy_db_schema_file::enums::{CommentSortType, ListingType, PostSortType};
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 
+pub mod backfill_community;
+pub mod community_archive;
+pub mod list_comment_tree;
 pub mod list_comments;
 pub mod list_person_content;
 pub mod list_posts;
+pub mod move_community;
 pub mod read_community;
 pub mod read_person;
 pub mod resolve_object;
@@ -0,0 +1,49 @@
+use crate::activities::community::move_community::move_community_followers;
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::web::Json;
+use lemmy_api_utils::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::{check_community_mod_action, is_admin, is_top_mod},
+};
+use lemmy_apub_objects::objects::community::ApubCommunity;
+use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_db_views_community::api::MoveCommunity;
+use lemmy_db_views_community_moderator::CommunityModeratorView;
+use lemmy_db_views_local_user::LocalUserView;
+use lemmy_db_views_site::api::SuccessResponse;
+use lemmy_utils::error::{FederationError, LemmyErrorType, LemmyResult};
+
+pub async fn move_community(
+  data: Json<MoveCommunity>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let community = Community::read(&mut context.pool(), data.community_id).await?;
+  if !community.local {
+    Err(FederationError::InvalidCommunity)?
+  }
+  check_community_mod_action(&local_user_view, &community, false, &mut context.pool()).await?;
+  let community_mods =
+    CommunityModeratorView::for_community(&mut context.pool(), community.id).await?;
+  if !(is_top_mod(&local_user_view, &community_mods).is_ok() || is_admin(&local_user_view).is_ok())
+  {
+    Err(LemmyErrorType::NotAnAdmin)?
+  }
+  if community.moved_to_url.is_some() {
+    Err(LemmyErrorType::CommunityMoved)?
+  }
+
+  // Refetch the new community, as it may have been fetched before the import was finished
+  let new_community: ApubCommunity = ObjectId::parse(&data.new_community)?
+    .dereference_forced(&context)
+    .await?;
+  move_community_followers(&community, &new_community, &context).await?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::MoveCommunity(community, (*new_community).clone()),
+    &context,
+  )?;
+
+  Ok(Json(SuccessResponse::default()))
+}
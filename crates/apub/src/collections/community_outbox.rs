@@ -6,45 +6,77 @@ use crate::{
       create_or_update::page::CreateOrUpdatePage,
       CreateOrUpdateType,
     },
-    collections::group_outbox::GroupOutbox,
+    collections::group_outbox::{GroupOutbox, GroupOutboxPage},
   },
 };
 use activitypub_federation::{
   config::Data,
-  kinds::collection::OrderedCollectionType,
+  kinds::collection::{OrderedCollectionPageType, OrderedCollectionType},
   protocol::verification::verify_domains_match,
   traits::{Activity, Collection},
 };
 use futures::future::join_all;
 use lemmy_api_utils::{context::LemmyContext, utils::generate_outbox_url};
 use lemmy_apub_objects::objects::community::ApubCommunity;
-use lemmy_db_schema::{source::site::Site, utils::FETCH_LIMIT_MAX};
+use lemmy_db_schema::{
+  newtypes::PaginationCursor,
+  source::site::Site,
+  traits::PaginationCursorBuilder,
+  utils::FETCH_LIMIT_MAX,
+};
 use lemmy_db_schema_file::enums::PostSortType;
-use lemmy_db_views_post::impls::PostQuery;
+use lemmy_db_views_post::{impls::PostQuery, PostView};
 use lemmy_utils::error::{LemmyError, LemmyResult};
 use url::Url;
 
 #[derive(Clone, Debug)]
 pub(crate) struct ApubCommunityOutbox(());
 
-#[async_trait::async_trait]
-impl Collection for ApubCommunityOutbox {
-  type Owner = ApubCommunity;
-  type DataType = LemmyContext;
-  type Kind = GroupOutbox;
-  type Error = LemmyError;
+impl ApubCommunityOutbox {
+  /// A page of the outbox, so that remote instances can backfill all posts of the community.
+  pub(crate) async fn read_local_page(
+    owner: &ApubCommunity,
+    page_cursor: Option<&PaginationCursor>,
+    data: &Data<LemmyContext>,
+  ) -> LemmyResult<GroupOutboxPage> {
+    let outbox: Url = generate_outbox_url(&owner.ap_id)?.into();
+    let (ordered_items, next_page) = Self::read_announces(owner, page_cursor, data).await?;
+    Ok(GroupOutboxPage {
+      r#type: OrderedCollectionPageType::OrderedCollectionPage,
+      id: outbox_page_url(&outbox, page_cursor),
+      next: next_page.map(|c| outbox_page_url(&outbox, Some(&c))),
+      part_of: outbox,
+      ordered_items,
+    })
+  }
 
-  async fn read_local(owner: &Self::Owner, data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+  /// Announces for the newest posts in the community, starting after the cursor. Also returns the
+  /// cursor of the next page if there may be more posts.
+  async fn read_announces(
+    owner: &ApubCommunity,
+    page_cursor: Option<&PaginationCursor>,
+    data: &Data<LemmyContext>,
+  ) -> LemmyResult<(Vec<AnnounceActivity>, Option<PaginationCursor>)> {
     let site = Site::read_local(&mut data.pool()).await?;
+    let cursor_data = match page_cursor {
+      Some(cursor) => Some(PostView::from_cursor(cursor, &mut data.pool()).await?),
+      None => None,
+    };
 
     let post_views = PostQuery {
       community_id: Some(owner.id),
       sort: Some(PostSortType::New),
       limit: Some(FETCH_LIMIT_MAX.try_into()?),
+      cursor_data,
       ..Default::default()
     }
     .list(&site, &mut data.pool())
     .await?;
+    let next_page = if post_views.len() == FETCH_LIMIT_MAX {
+      post_views.last().map(PaginationCursorBuilder::to_cursor)
+    } else {
+      None
+    };
 
     let mut ordered_items = vec![];
     for post_view in post_views {
@@ -64,10 +96,34 @@ impl Collection for ApubCommunityOutbox {
         }
       }
     }
+    Ok((ordered_items, next_page))
+  }
+}
+
+/// Id of an outbox page, starting at the newest post if there is no cursor.
+fn outbox_page_url(outbox: &Url, page_cursor: Option<&PaginationCursor>) -> Url {
+  let mut url = outbox.clone();
+  url.query_pairs_mut().append_pair("page", "true");
+  if let Some(cursor) = page_cursor {
+    url.query_pairs_mut().append_pair("page_cursor", &cursor.0);
+  }
+  url
+}
+
+#[async_trait::async_trait]
+impl Collection for ApubCommunityOutbox {
+  type Owner = ApubCommunity;
+  type DataType = LemmyContext;
+  type Kind = GroupOutbox;
+  type Error = LemmyError;
 
+  async fn read_local(owner: &Self::Owner, data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    let id: Url = generate_outbox_url(&owner.ap_id)?.into();
+    let (ordered_items, _) = Self::read_announces(owner, None, data).await?;
     Ok(GroupOutbox {
       r#type: OrderedCollectionType::OrderedCollection,
-      id: generate_outbox_url(&owner.ap_id)?.into(),
+      first: Some(outbox_page_url(&id, None)),
+      id,
       total_items: owner.posts,
       ordered_items,
     })
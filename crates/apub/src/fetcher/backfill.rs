@@ -0,0 +1,165 @@
+//! Backfill of remote communities. When a community is first seen only the newest posts from its
+//! outbox are fetched. Backfill reads all pages of the outbox, and fetches the comments of each
+//! post from its thread context (FEP-7888). Parent comments which are missing from the context are
+//! fetched through `inReplyTo` as usual.
+//!
+//! Only one backfill runs per remote instance at a time, with a delay between fetches, so that the
+//! remote instance doesn't get overloaded.
+use super::activity_object_id;
+use crate::protocol::collections::remote_collection::RemoteCollection;
+use activitypub_federation::{
+  config::Data,
+  fetch::{fetch_object_http, object_id::ObjectId},
+  protocol::verification::verify_domains_match,
+  traits::Object,
+};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::{
+  objects::{comment::ApubComment, community::ApubCommunity, post::ApubPost},
+  protocol::{group::Group, page::Page},
+};
+use lemmy_db_schema::source::comment::Comment;
+use lemmy_utils::{
+  error::{FederationError, LemmyResult},
+  spawn_try_task,
+};
+use serde_json::Value;
+use std::{
+  collections::HashMap,
+  sync::{Arc, LazyLock, Mutex},
+  time::Duration,
+};
+use tokio::time::sleep;
+use tracing::{debug, info};
+use url::Url;
+
+/// Maximum number of posts which are fetched when backfilling a community.
+const MAX_BACKFILL_POSTS: usize = 1000;
+/// Maximum number of objects which are read from the thread context of a post.
+const MAX_THREAD_ITEMS: usize = 1000;
+/// Maximum number of collection pages which are read, in case a remote instance returns endless
+/// pages.
+const MAX_COLLECTION_PAGES: usize = 100;
+/// Delay between two fetches during backfill.
+const FETCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Locks which ensure that only one backfill runs per remote instance.
+static INSTANCE_LOCKS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> =
+  LazyLock::new(Default::default);
+
+/// Starts the backfill in the background, as it can take a long time.
+pub fn spawn_backfill_community(community: ApubCommunity, context: Data<LemmyContext>) {
+  spawn_try_task(async move { backfill_community(&community, &context).await });
+}
+
+async fn backfill_community(
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  if community.local {
+    return Ok(());
+  }
+  let domain = community
+    .ap_id
+    .inner()
+    .domain()
+    .ok_or(FederationError::UrlWithoutDomain)?
+    .to_string();
+  let lock = INSTANCE_LOCKS
+    .lock()
+    .map_err(|_| FederationError::Unreachable)?
+    .entry(domain)
+    .or_default()
+    .clone();
+  let _guard = lock.lock().await;
+  info!("Starting backfill of community {}", community.ap_id);
+
+  let group: Group = fetch_object_http(community.ap_id.inner(), context)
+    .await?
+    .object;
+  let post_ids = read_collection(&group.outbox, MAX_BACKFILL_POSTS, context).await?;
+  let mut count = 0;
+  for post_id in post_ids {
+    sleep(FETCH_INTERVAL).await;
+    let context = context.reset_request_count();
+    match backfill_post(&post_id, &context).await {
+      Ok(()) => count += 1,
+      Err(e) => debug!("Failed to backfill post {post_id}: {e}"),
+    }
+  }
+  info!(
+    "Finished backfill of community {}, fetched {count} posts",
+    community.ap_id
+  );
+  Ok(())
+}
+
+/// Fetches the post, and all comments from its thread context.
+async fn backfill_post(post_id: &Url, context: &Data<LemmyContext>) -> LemmyResult<()> {
+  let page: Page = fetch_object_http(post_id, context).await?.object;
+  let thread = page.context.clone();
+  ApubPost::verify(&page, post_id, context).await?;
+  ApubPost::from_json(page, context).await?;
+
+  let Some(thread) = thread else {
+    return Ok(());
+  };
+  let comment_ids = read_collection(&thread, MAX_THREAD_ITEMS, context).await?;
+  for comment_id in comment_ids.into_iter().filter(|c| c != post_id) {
+    let exists = Comment::read_from_apub_id(&mut context.pool(), comment_id.clone())
+      .await?
+      .is_some();
+    if exists {
+      continue;
+    }
+    sleep(FETCH_INTERVAL).await;
+    let context = context.reset_request_count();
+    if let Err(e) = ObjectId::<ApubComment>::from(comment_id.clone())
+      .dereference(&context)
+      .await
+    {
+      debug!("Failed to backfill comment {comment_id}: {e}");
+    }
+  }
+  Ok(())
+}
+
+/// Reads the ids of all items in a collection, following its pages if there are any.
+async fn read_collection(
+  id: &Url,
+  max_items: usize,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<Vec<Url>> {
+  let collection: RemoteCollection = fetch_object_http(id, context).await?.object;
+  // If there are pages they contain all items, including those embedded in the collection
+  let (mut items, mut next) = match collection.first {
+    Some(Value::String(first)) => (vec![], Some(Url::parse(&first)?)),
+    Some(first @ Value::Object(_)) => {
+      let page: RemoteCollection = serde_json::from_value(first)?;
+      (page.ordered_items, page.next)
+    }
+    _ => (collection.ordered_items, None),
+  };
+
+  let mut pages = 0;
+  while let Some(page_id) = next {
+    if items.len() >= max_items || pages >= MAX_COLLECTION_PAGES {
+      break;
+    }
+    sleep(FETCH_INTERVAL).await;
+    let context = context.reset_request_count();
+    let page: RemoteCollection = fetch_object_http(&page_id, &context).await?.object;
+    verify_domains_match(&page.id, id)?;
+    items.extend(page.ordered_items);
+    next = page.next;
+    pages += 1;
+  }
+
+  Ok(
+    items
+      .iter()
+      .filter_map(activity_object_id)
+      .take(max_items)
+      .collect(),
+  )
+}
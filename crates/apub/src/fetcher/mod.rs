@@ -11,7 +11,10 @@ use lemmy_apub_objects::objects::SiteOrMultiOrCommunityOrUser;
 use lemmy_db_schema::{newtypes::InstanceId, traits::ApubActor};
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use serde_json::Value;
+use url::Url;
 
+pub(crate) mod backfill;
 pub mod search;
 
 /// Resolve actor identifier like `!news@example.com` to user or community object.
@@ -74,3 +77,46 @@ pub(crate) fn get_instance_id(s: &SiteOrMultiOrCommunityOrUser) -> InstanceId {
     Right(Right(c)) => c.instance_id,
   }
 }
+
+/// Returns the id of the object which an activity is about, following nested `Announce`, `Create`
+/// and `Update` activities. For anything else the id of the item itself is returned.
+pub(crate) fn activity_object_id(item: &Value) -> Option<Url> {
+  match item {
+    Value::String(id) => Url::parse(id).ok(),
+    Value::Object(o) => match o.get("type").and_then(Value::as_str) {
+      Some("Announce" | "Create" | "Update") => o.get("object").and_then(activity_object_id),
+      _ => o
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(|id| Url::parse(id).ok()),
+    },
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::activity_object_id;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+  use url::Url;
+
+  #[test]
+  fn test_activity_object_id() -> LemmyResult<()> {
+    let post = Url::parse("https://ds9.lemmy.ml/post/1723")?;
+    assert_eq!(Some(post.clone()), activity_object_id(&json!(post)));
+    let announce = json!({
+      "type": "Announce",
+      "id": "https://ds9.lemmy.ml/activities/announce/1",
+      "object": {
+        "type": "Create",
+        "id": "https://ds9.lemmy.ml/activities/create/1",
+        "object": { "type": "Page", "id": post }
+      }
+    });
+    assert_eq!(Some(post), activity_object_id(&announce));
+    assert_eq!(None, activity_object_id(&json!(1)));
+    Ok(())
+  }
+}
@@ -8,7 +8,10 @@ use lemmy_db_schema::{
   source::{comment::Comment, community::Community, post::Post},
   traits::Crud,
 };
-use lemmy_utils::{error::LemmyResult, FEDERATION_CONTEXT};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  FEDERATION_CONTEXT,
+};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -25,6 +28,10 @@ pub(crate) async fn get_apub_comment(
   let id = CommentId(info.comment_id.parse::<i32>()?);
   // Can't use CommentView here because it excludes deleted/removed/local-only items
   let comment: ApubComment = Comment::read(&mut context.pool(), id).await?.into();
+  // Comments which are held for approval are not federated yet
+  if comment.pending_approval {
+    Err(LemmyErrorType::NotFound)?
+  }
   let post = Post::read(&mut context.pool(), comment.post_id).await?;
   let community = Community::read(&mut context.pool(), post.community_id).await?;
   check_community_content_fetchable(&community, &request, &context).await?;
@@ -31,6 +31,7 @@ use lemmy_apub_objects::{
   protocol::tags::CommunityTag,
 };
 use lemmy_db_schema::{
+  newtypes::PaginationCursor,
   source::{community::Community, multi_community::MultiCommunity, tag::Tag},
   traits::ApubActor,
 };
@@ -47,6 +48,12 @@ pub(crate) struct CommunityPath {
   community_name: String,
 }
 
+#[derive(Deserialize, Clone, Default)]
+pub(crate) struct CommunityOutboxQuery {
+  page: Option<bool>,
+  page_cursor: Option<PaginationCursor>,
+}
+
 #[derive(Deserialize, Clone)]
 pub(crate) struct CommunityIsFollowerQuery {
   is_follower: Option<ObjectId<SiteOrMultiOrCommunityOrUser>>,
@@ -121,9 +128,10 @@ async fn check_is_follower(
 }
 
 /// Returns the community outbox, which is populated by a maximum of 20 posts (but no other
-/// activities like votes or comments).
+/// activities like votes or comments). Older posts are available in pages of the outbox.
 pub(crate) async fn get_apub_community_outbox(
   info: Path<CommunityPath>,
+  query: Query<CommunityOutboxQuery>,
   context: Data<LemmyContext>,
   request: HttpRequest,
 ) -> LemmyResult<HttpResponse> {
@@ -133,6 +141,12 @@ pub(crate) async fn get_apub_community_outbox(
       .ok_or(LemmyErrorType::NotFound)?
       .into();
   check_community_content_fetchable(&community, &request, &context).await?;
+  if query.page.unwrap_or_default() || query.page_cursor.is_some() {
+    let page =
+      ApubCommunityOutbox::read_local_page(&community, query.page_cursor.as_ref(), &context)
+        .await?;
+    return Ok(create_http_response(page, &FEDERATION_CONTEXT)?);
+  }
   let outbox = ApubCommunityOutbox::read_local(&community, &context).await?;
   Ok(create_http_response(outbox, &FEDERATION_CONTEXT)?)
 }
@@ -309,7 +323,16 @@ pub(crate) mod tests {
     assert_eq!(200, res.status());
     let res = get_apub_community_moderators(path.clone().into(), context.clone()).await?;
     assert_eq!(200, res.status());
-    let res = get_apub_community_outbox(path, context.clone(), request).await?;
+    let query = Query(CommunityOutboxQuery::default());
+    let res =
+      get_apub_community_outbox(path.clone().into(), query, context.clone(), request.clone())
+        .await?;
+    assert_eq!(200, res.status());
+    let query = Query(CommunityOutboxQuery {
+      page: Some(true),
+      page_cursor: None,
+    });
+    let res = get_apub_community_outbox(path, query, context.clone(), request).await?;
     assert_eq!(200, res.status());
 
     data.delete(&mut context.pool()).await?;
@@ -339,7 +362,8 @@ pub(crate) mod tests {
     assert!(res.is_err());
     let res = get_apub_community_moderators(path.clone().into(), context.clone()).await;
     assert!(res.is_err());
-    let res = get_apub_community_outbox(path, context.clone(), request).await;
+    let query = Query(CommunityOutboxQuery::default());
+    let res = get_apub_community_outbox(path, query, context.clone(), request).await;
     assert!(res.is_err());
 
     data.delete(&mut context.pool()).await?;
@@ -365,7 +389,8 @@ pub(crate) mod tests {
     assert!(res.is_err());
     let res = get_apub_community_moderators(path.clone().into(), context.clone()).await;
     assert!(res.is_err());
-    let res = get_apub_community_outbox(path, context.clone(), request).await;
+    let query = Query(CommunityOutboxQuery::default());
+    let res = get_apub_community_outbox(path, query, context.clone(), request).await;
     assert!(res.is_err());
 
     data.delete(&mut context.pool()).await?;
@@ -387,7 +412,8 @@ pub(crate) mod tests {
     let form = PostInsertForm::new("title".to_string(), person.id, community.id);
     Post::create(&mut context.pool(), &form).await?;
 
-    let res = get_apub_community_outbox(path, context.clone(), request).await?;
+    let query = Query(CommunityOutboxQuery::default());
+    let res = get_apub_community_outbox(path, query, context.clone(), request).await?;
     assert_eq!(200, res.status());
 
     data.delete(&mut context.pool()).await?;
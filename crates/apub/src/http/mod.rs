@@ -1,11 +1,13 @@
-use crate::{activity_lists::SharedInboxActivities, fetcher::get_instance_id};
+use crate::{
+  activities::relay::read_relay_for_actor,
+  activity_lists::{RelayInboxActivities, SharedInboxActivities},
+  fetcher::get_instance_id,
+  inbox_queue::check_activity_allowed,
+};
 use activitypub_federation::{
-  actix_web::{
-    inbox::{receive_activity_with_hook, ReceiveActivityHook},
-    response::create_http_response,
-    signing_actor,
-  },
+  actix_web::{response::create_http_response, signing_actor},
   config::Data,
+  protocol::verification::verify_domains_match,
   traits::{Activity, Object},
 };
 use actix_web::{
@@ -14,20 +16,27 @@ use actix_web::{
   HttpResponse,
 };
 use lemmy_api_utils::{context::LemmyContext, plugins::plugin_hook_after};
-use lemmy_apub_objects::objects::{SiteOrMultiOrCommunityOrUser, UserOrCommunity};
-use lemmy_db_schema::source::{
-  activity::{ReceivedActivity, SentActivity},
-  community::Community,
+use lemmy_apub_objects::{
+  objects::{relay::ApubRelay, SiteOrMultiOrCommunityOrUser},
+  utils::functions::local_site_data_cached,
+};
+use lemmy_db_schema::{
+  source::{
+    activity::SentActivity,
+    community::Community,
+    federation_relay::FederationRelay,
+    inbound_activity::{InboundActivity, InboundActivityInsertForm},
+  },
+  traits::Crud,
 };
 use lemmy_db_schema_file::enums::CommunityVisibility;
 use lemmy_db_views_community_follower::CommunityFollowerView;
 use lemmy_utils::{
-  error::{FederationError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  error::{FederationError, LemmyErrorType, LemmyResult},
   FEDERATION_CONTEXT,
 };
 use serde::Deserialize;
-use std::time::Duration;
-use tokio::time::timeout;
+use signature::verify_signing_actor;
 use tracing::debug;
 use url::Url;
 
@@ -36,51 +45,65 @@ mod community;
 mod person;
 mod post;
 pub mod routes;
+mod signature;
 pub mod site;
 
-const INCOMING_ACTIVITY_TIMEOUT: Duration = Duration::from_secs(9);
-
+/// Verifies the signature of an incoming activity and stores it in the queue, so that it is
+/// processed in the background by `inbox_queue`. This way the sender gets a response right away,
+/// even if processing needs slow fetches from other instances.
 pub async fn shared_inbox(
   request: HttpRequest,
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  let receive_fut =
-    receive_activity_with_hook::<SharedInboxActivities, UserOrCommunity, LemmyContext>(
-      request, body, Dummy, &data,
-    );
-  // Set a timeout shorter than `REQWEST_TIMEOUT` for processing incoming activities. This is to
-  // avoid taking a long time to process an incoming activity when a required data fetch times out.
-  // In this case our own instance would timeout and be marked as dead by the sender. Better to
-  // consider the activity broken and move on.
-  timeout(INCOMING_ACTIVITY_TIMEOUT, receive_fut)
-    .await
-    .with_lemmy_type(FederationError::InboxTimeout.into())?
-}
-
-struct Dummy;
-
-impl ReceiveActivityHook<SharedInboxActivities, UserOrCommunity, LemmyContext> for Dummy {
-  async fn hook(
-    self,
-    activity: &SharedInboxActivities,
-    _actor: &UserOrCommunity,
-    context: &Data<LemmyContext>,
-  ) -> LemmyResult<()> {
-    // Store received activities in the database. This ensures that the same activity doesn't get
-    // received and processed more than once, which would be a waste of resources.
-    debug!("Received activity {}", activity.id().to_string());
-    ReceivedActivity::create(&mut context.pool(), &activity.id().clone().into()).await?;
+  // Activities from relays are handled separately, see [[RelayInboxActivities]]
+  let ActivityActor { actor } = serde_json::from_slice(&body)?;
+  let (id, actor) = if read_relay_for_actor(&actor, &body, &data).await?.is_some() {
+    let relay = signing_actor::<ApubRelay>(&request, Some(body.clone()), &data).await?;
+    let activity: RelayInboxActivities = serde_json::from_slice(&body)?;
+    if activity.actor() != relay.id() {
+      Err(FederationError::ActivitySignedByOtherActor)?
+    }
+    plugin_hook_after("activity_received", &activity)?;
+    (activity.id().clone(), activity.actor().clone())
+  } else {
+    let actor = verify_signing_actor(&request, &body, &actor, &data).await?;
+    let activity: SharedInboxActivities = serde_json::from_slice(&body)?;
+    if activity.actor() != actor.id() {
+      Err(FederationError::ActivitySignedByOtherActor)?
+    }
 
     // This could also take the actor as param, but lifetimes and serde derives are tricky.
     // It is really a before hook, but doesnt allow modifying the data. It could use a
     // separate method so that error in plugin causes activity to be rejected.
-    plugin_hook_after("activity_received", activity)?;
+    plugin_hook_after("activity_received", &activity)?;
+    (activity.id().clone(), activity.actor().clone())
+  };
+  verify_domains_match(&id, &actor)?;
+  check_activity_allowed(
+    &id,
+    &actor,
+    &local_site_data_cached(&mut data.pool()).await?,
+  )?;
 
-    // This method could also be used to check if actor is banned, instead of checking in each
-    // activity handler.
-    Ok(())
-  }
+  // Store received activities in the database. This ensures that the same activity doesn't get
+  // received and processed more than once, which would be a waste of resources.
+  debug!("Received activity {}", id.to_string());
+
+  // Keep the json as it was sent, including fields which Lemmy doesn't know about
+  let form = InboundActivityInsertForm {
+    ap_id: id.into(),
+    actor_apub_id: actor.into(),
+    data: String::from_utf8(body.to_vec())?,
+  };
+  InboundActivity::enqueue(&mut data.pool(), &form).await?;
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Deserialize)]
+struct ActivityActor {
+  actor: Url,
 }
 
 #[derive(Deserialize)]
@@ -1,13 +1,20 @@
-use crate::protocol::collections::empty_outbox::EmptyOutbox;
+use crate::protocol::collections::{empty_outbox::EmptyOutbox, group_followers::GroupFollowers};
 use activitypub_federation::{
   actix_web::response::create_http_response,
   config::Data,
+  kinds::collection::CollectionType,
   traits::Object,
 };
 use actix_web::{web::Path, HttpResponse};
-use lemmy_api_utils::{context::LemmyContext, utils::generate_outbox_url};
+use lemmy_api_utils::{
+  context::LemmyContext,
+  utils::{generate_followers_url, generate_outbox_url},
+};
 use lemmy_apub_objects::objects::person::ApubPerson;
-use lemmy_db_schema::{source::person::Person, traits::ApubActor};
+use lemmy_db_schema::{
+  source::person::{Person, PersonActions},
+  traits::ApubActor,
+};
 use lemmy_utils::{
   error::{LemmyErrorType, LemmyResult},
   FEDERATION_CONTEXT,
@@ -45,3 +52,20 @@ pub(crate) async fn get_apub_person_outbox(
   let outbox = EmptyOutbox::new(outbox_id)?;
   Ok(create_http_response(outbox, &FEDERATION_CONTEXT)?)
 }
+
+/// Returns an empty followers collection, only populating the size (for privacy).
+pub(crate) async fn get_apub_person_followers(
+  info: Path<PersonQuery>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  let person = Person::read_from_name(&mut context.pool(), &info.user_name, false)
+    .await?
+    .ok_or(LemmyErrorType::NotFound)?;
+  let followers = GroupFollowers {
+    id: generate_followers_url(&person.ap_id)?.into(),
+    r#type: CollectionType::Collection,
+    total_items: PersonActions::count_followers(&mut context.pool(), person.id).await?,
+    items: vec![],
+  };
+  Ok(create_http_response(followers, &FEDERATION_CONTEXT)?)
+}
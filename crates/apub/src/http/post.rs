@@ -1,16 +1,28 @@
 use super::check_community_content_fetchable;
-use activitypub_federation::{config::Data, traits::Object};
+use crate::protocol::collections::post_context::PostContext;
+use activitypub_federation::{
+  actix_web::response::create_http_response,
+  config::Data,
+  kinds::collection::OrderedCollectionType,
+  traits::Object,
+};
 use actix_web::{web, HttpRequest, HttpResponse};
-use lemmy_api_utils::context::LemmyContext;
+use lemmy_api_utils::{context::LemmyContext, utils::generate_context_url};
 use lemmy_apub_objects::objects::post::ApubPost;
 use lemmy_db_schema::{
   newtypes::PostId,
-  source::{community::Community, post::Post},
+  source::{comment::Comment, community::Community, post::Post},
   traits::Crud,
 };
-use lemmy_utils::{error::LemmyResult, FEDERATION_CONTEXT};
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  FEDERATION_CONTEXT,
+};
 use serde::Deserialize;
 
+/// Maximum number of comments in the thread context of a post.
+const MAX_CONTEXT_ITEMS: i64 = 10_000;
+
 #[derive(Deserialize)]
 pub(crate) struct PostQuery {
   post_id: String,
@@ -25,9 +37,41 @@ pub(crate) async fn get_apub_post(
   let id = PostId(info.post_id.parse::<i32>()?);
   // Can't use PostView here because it excludes deleted/removed/local-only items
   let post: ApubPost = Post::read(&mut context.pool(), id).await?.into();
+  // Posts which are held for approval are not federated yet
+  if post.pending_approval {
+    Err(LemmyErrorType::NotFound)?
+  }
   let community = Community::read(&mut context.pool(), post.community_id).await?;
 
   check_community_content_fetchable(&community, &request, &context).await?;
 
   post.http_response(&FEDERATION_CONTEXT, &context).await
 }
+
+/// Return the thread context of a local post, so that remote instances can fetch all comments.
+pub(crate) async fn get_apub_post_context(
+  info: web::Path<PostQuery>,
+  context: Data<LemmyContext>,
+  request: HttpRequest,
+) -> LemmyResult<HttpResponse> {
+  let id = PostId(info.post_id.parse::<i32>()?);
+  let post = Post::read(&mut context.pool(), id).await?;
+  if !post.local || post.pending_approval || post.deleted || post.removed {
+    Err(LemmyErrorType::NotFound)?
+  }
+  let community = Community::read(&mut context.pool(), post.community_id).await?;
+  check_community_content_fetchable(&community, &request, &context).await?;
+
+  let comments = Comment::list_ap_ids_for_post(&mut context.pool(), id, MAX_CONTEXT_ITEMS).await?;
+  let ordered_items: Vec<_> = std::iter::once(post.ap_id.clone())
+    .chain(comments)
+    .map(Into::into)
+    .collect();
+  let collection = PostContext {
+    r#type: OrderedCollectionType::OrderedCollection,
+    id: generate_context_url(&post.ap_id)?.into(),
+    total_items: ordered_items.len().try_into()?,
+    ordered_items,
+  };
+  Ok(create_http_response(collection, &FEDERATION_CONTEXT)?)
+}
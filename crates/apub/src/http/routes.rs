@@ -11,8 +11,8 @@ use crate::http::{
     get_apub_person_multi_community_follows,
   },
   get_activity,
-  person::{get_apub_person_http, get_apub_person_outbox},
-  post::get_apub_post,
+  person::{get_apub_person_followers, get_apub_person_http, get_apub_person_outbox},
+  post::{get_apub_post, get_apub_post_context},
   shared_inbox,
   site::{get_apub_site_http, get_apub_site_outbox},
 };
@@ -55,6 +55,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
       "/u/{user_name}/outbox",
       web::get().to(get_apub_person_outbox),
     )
+    .route(
+      "/u/{user_name}/followers",
+      web::get().to(get_apub_person_followers),
+    )
     .route(
       "/m/{multi_name}",
       web::get().to(get_apub_person_multi_community),
@@ -64,6 +68,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
       web::get().to(get_apub_person_multi_community_follows),
     )
     .route("/post/{post_id}", web::get().to(get_apub_post))
+    .route(
+      "/post/{post_id}/context",
+      web::get().to(get_apub_post_context),
+    )
     .route("/comment/{comment_id}", web::get().to(get_apub_comment))
     .route("/activities/{type_}/{id}", web::get().to(get_activity));
 
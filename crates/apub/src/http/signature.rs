@@ -0,0 +1,145 @@
+use activitypub_federation::{
+  actix_web::signing_actor,
+  config::Data,
+  fetch::object_id::ObjectId,
+  traits::{Actor, Object},
+};
+use actix_web::{web::Bytes, HttpRequest};
+use chrono::{DateTime, Utc};
+use either::Either;
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::objects::UserOrCommunity;
+use lemmy_utils::{
+  error::{FederationError, LemmyError, LemmyErrorType, LemmyResult},
+  KEY_ROTATION_GRACE_PERIOD,
+};
+use serde_json::Value;
+use std::time::Duration;
+use url::Url;
+
+/// A remote actor is refetched at most this often because of a signature which doesn't match its
+/// key.
+const REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Verifies the signature of an activity from a person or community. If it doesn't match, the
+/// actor may have rotated its key. Then the activity is accepted with the previous key during the
+/// grace period, or the actor is refetched to get the new key.
+pub(super) async fn verify_signing_actor(
+  request: &HttpRequest,
+  body: &Bytes,
+  actor_id: &Url,
+  data: &Data<LemmyContext>,
+) -> LemmyResult<UserOrCommunity> {
+  let error = match signing_actor::<UserOrCommunity>(request, Some(body.clone()), data).await {
+    Ok(actor) => return Ok(actor),
+    Err(e) => e,
+  };
+  let Ok(actor) = ObjectId::<UserOrCommunity>::from(actor_id.clone())
+    .dereference_local(data)
+    .await
+  else {
+    return Err(error);
+  };
+
+  if previous_key(&actor).is_some() {
+    if let Ok(previous) = signing_actor::<PreviousKeyActor>(request, Some(body.clone()), data).await
+    {
+      return ObjectId::<UserOrCommunity>::from(previous.id)
+        .dereference_local(data)
+        .await;
+    }
+  }
+
+  if elapsed(actor.last_refreshed_at().unwrap_or_default()) > REFETCH_INTERVAL {
+    ObjectId::<UserOrCommunity>::from(actor_id.clone())
+      .dereference_forced(data)
+      .await?;
+    return signing_actor::<UserOrCommunity>(request, Some(body.clone()), data).await;
+  }
+  Err(error)
+}
+
+fn elapsed(time: DateTime<Utc>) -> Duration {
+  Utc::now()
+    .signed_duration_since(time)
+    .to_std()
+    .unwrap_or_default()
+}
+
+/// The previous key of the actor, if it was rotated within the grace period.
+fn previous_key(actor: &UserOrCommunity) -> Option<String> {
+  let (previous_public_key, key_rotated_at) = match actor {
+    Either::Left(p) => (&p.previous_public_key, p.key_rotated_at),
+    Either::Right(c) => (&c.previous_public_key, c.key_rotated_at),
+  };
+  if elapsed(key_rotated_at?) < KEY_ROTATION_GRACE_PERIOD {
+    previous_public_key.clone()
+  } else {
+    None
+  }
+}
+
+/// A person or community with the key it used before its last key rotation. This is only read from
+/// the database, and never fetched.
+#[derive(Clone, Debug)]
+struct PreviousKeyActor {
+  id: Url,
+  public_key: String,
+  inbox: Url,
+}
+
+#[async_trait::async_trait]
+impl Object for PreviousKeyActor {
+  type DataType = LemmyContext;
+  type Kind = Value;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    let actor = UserOrCommunity::read_from_id(object_id, data).await?;
+    Ok(actor.and_then(|actor| {
+      previous_key(&actor).map(|public_key| PreviousKeyActor {
+        id: actor.id().clone(),
+        public_key,
+        inbox: actor.inbox(),
+      })
+    }))
+  }
+
+  async fn delete(self, _data: &Data<Self::DataType>) -> LemmyResult<()> {
+    Ok(())
+  }
+
+  async fn into_json(self, _data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    Err(FederationError::Unreachable.into())
+  }
+
+  async fn verify(
+    _json: &Self::Kind,
+    _expected_domain: &Url,
+    _data: &Data<Self::DataType>,
+  ) -> LemmyResult<()> {
+    Err(LemmyErrorType::NotFound.into())
+  }
+
+  async fn from_json(_json: Self::Kind, _data: &Data<Self::DataType>) -> LemmyResult<Self> {
+    Err(LemmyErrorType::NotFound.into())
+  }
+}
+
+impl Actor for PreviousKeyActor {
+  fn public_key_pem(&self) -> &str {
+    &self.public_key
+  }
+
+  fn private_key_pem(&self) -> Option<String> {
+    None
+  }
+
+  fn inbox(&self) -> Url {
+    self.inbox.clone()
+  }
+}
@@ -0,0 +1,216 @@
+//! Background processing of incoming activities. The inbox only verifies the signature and stores
+//! activities in the queue. Workers then process them, retrying with increasing delays when
+//! processing fails, for example because a required object can't be fetched. Activities which
+//! keep failing, or which can never succeed, are moved to the dead-letter list, where admins can
+//! inspect and replay them.
+
+use crate::activity_lists::{RelayInboxActivities, SharedInboxActivities};
+use activitypub_federation::{config::Data, traits::Activity};
+use chrono::{TimeDelta, Utc};
+use futures::future::join_all;
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_apub_objects::utils::functions::{
+  check_apub_id_valid,
+  local_site_data_cached,
+  LocalSiteData,
+};
+use lemmy_db_schema::{
+  source::{
+    federation_relay::FederationRelay,
+    inbound_activity::{InboundActivity, InboundActivityUpdateForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::{FederationError, LemmyError, LemmyErrorType, LemmyResult};
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use tracing::{debug, warn};
+use url::Url;
+
+/// Number of activities which a worker claims and processes at once.
+const BATCH_SIZE: i64 = 10;
+/// Maximum time for processing a single activity, including all fetches.
+const PROCESSING_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long to wait before checking the queue again if it is empty.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// After this many failed attempts, the activity is moved to the dead-letter list.
+const MAX_ATTEMPTS: i32 = 5;
+
+/// Starts the given number of workers which process the queue of incoming activities.
+pub fn start_inbox_workers(context: Data<LemmyContext>, count: u8) {
+  for _ in 0..count {
+    tokio::task::spawn(inbox_worker(context.clone()));
+  }
+}
+
+async fn inbox_worker(context: Data<LemmyContext>) {
+  // Claimed activities are only handed to another worker once this is over
+  let lease = TimeDelta::from_std(PROCESSING_TIMEOUT * 2).unwrap_or(TimeDelta::minutes(2));
+  loop {
+    match InboundActivity::claim_due(&mut context.pool(), BATCH_SIZE, lease).await {
+      Ok(batch) if !batch.is_empty() => {
+        // Activities of the same actor often depend on each other, for example a comment on a
+        // post which was created right before. So they are processed one by one in the order
+        // they were received, while different actors are processed concurrently.
+        let mut by_actor: Vec<Vec<InboundActivity>> = vec![];
+        for activity in batch {
+          match by_actor
+            .iter_mut()
+            .find(|other| other.first().map(|o| &o.actor_apub_id) == Some(&activity.actor_apub_id))
+          {
+            Some(activities) => activities.push(activity),
+            None => by_actor.push(vec![activity]),
+          }
+        }
+        join_all(by_actor.into_iter().map(|activities| async {
+          for activity in activities {
+            process_and_record(activity, &context).await;
+          }
+        }))
+        .await;
+      }
+      Ok(_) => sleep(POLL_INTERVAL).await,
+      Err(e) => {
+        warn!("Failed to read queue of incoming activities: {e}");
+        sleep(POLL_INTERVAL).await;
+      }
+    }
+  }
+}
+
+async fn process_and_record(activity: InboundActivity, context: &Data<LemmyContext>) {
+  // Each activity gets its own limit of outgoing fetches
+  let context = context.reset_request_count();
+  let res = timeout(PROCESSING_TIMEOUT, process(&activity, &context))
+    .await
+    .unwrap_or_else(|_| Err(Failure::Transient(FederationError::InboxTimeout.into())));
+
+  let recorded = match res {
+    Ok(()) => InboundActivity::delete(&mut context.pool(), activity.id)
+      .await
+      .map(|_| ()),
+    Err(failure) => {
+      let (e, permanent) = match failure {
+        Failure::Permanent(e) => (e, true),
+        Failure::Transient(e) => (e, false),
+      };
+      debug!("Failed to process activity {}: {e}", activity.ap_id);
+      let attempts = activity.attempts.saturating_add(1);
+      let now = Utc::now();
+      let form = if permanent || attempts >= MAX_ATTEMPTS {
+        InboundActivityUpdateForm {
+          attempts: Some(attempts),
+          last_error: Some(Some(e.to_string())),
+          failed_at: Some(Some(now)),
+          claimed_until: Some(None),
+          ..Default::default()
+        }
+      } else {
+        InboundActivityUpdateForm {
+          attempts: Some(attempts),
+          next_attempt_at: Some(now + retry_delay(attempts)),
+          last_error: Some(Some(e.to_string())),
+          claimed_until: Some(None),
+          ..Default::default()
+        }
+      };
+      InboundActivity::update(&mut context.pool(), activity.id, &form)
+        .await
+        .map(|_| ())
+    }
+  };
+  recorded
+    .inspect_err(|e| warn!("Failed to update queued activity {}: {e}", activity.ap_id))
+    .ok();
+}
+
+/// Why processing an activity failed.
+enum Failure {
+  /// The activity fails the same way on every attempt, so it goes straight to the dead-letter
+  /// list. For example it can't be parsed, or it was rejected because the actor is banned.
+  Permanent(LemmyError),
+  /// Retrying may help, for example if fetching a dependency timed out.
+  Transient(LemmyError),
+}
+
+impl From<LemmyError> for Failure {
+  fn from(e: LemmyError) -> Self {
+    // Network and database errors don't have a specific type, neither do objects which couldn't
+    // be fetched yet. Errors with a specific type are rejections of the activity.
+    let transient = matches!(
+      e.error_type,
+      LemmyErrorType::Unknown(_)
+        | LemmyErrorType::NotFound
+        | LemmyErrorType::CouldntCreate
+        | LemmyErrorType::CouldntUpdate
+        | LemmyErrorType::FederationError {
+          error: Some(FederationError::InboxTimeout)
+        }
+    );
+    if transient {
+      Failure::Transient(e)
+    } else {
+      Failure::Permanent(e)
+    }
+  }
+}
+
+async fn process(activity: &InboundActivity, context: &Data<LemmyContext>) -> Result<(), Failure> {
+  // The blocklist or allowlist may have changed while the activity was waiting in the queue, in
+  // which case it is dropped without retrying
+  let local_site_data = local_site_data_cached(&mut context.pool()).await?;
+  if let Err(e) = check_activity_allowed(
+    activity.ap_id.inner(),
+    activity.actor_apub_id.inner(),
+    &local_site_data,
+  ) {
+    debug!("Dropping activity {}: {e}", activity.ap_id);
+    return Ok(());
+  }
+
+  let relay =
+    FederationRelay::read_for_actor(&mut context.pool(), activity.actor_apub_id.inner()).await?;
+  if relay.is_some() {
+    let activity: RelayInboxActivities =
+      serde_json::from_str(&activity.data).map_err(|e| Failure::Permanent(e.into()))?;
+    activity.verify(context).await?;
+    Ok(activity.receive(context).await?)
+  } else {
+    let activity: SharedInboxActivities =
+      serde_json::from_str(&activity.data).map_err(|e| Failure::Permanent(e.into()))?;
+    activity.verify(context).await?;
+    Ok(activity.receive(context).await?)
+  }
+}
+
+/// Rejects activities from blocked instances, from instances which are not in the allowlist,
+/// and all activities while federation is disabled.
+pub(crate) fn check_activity_allowed(
+  id: &Url,
+  actor: &Url,
+  local_site_data: &LocalSiteData,
+) -> LemmyResult<()> {
+  check_apub_id_valid(id, local_site_data)?;
+  check_apub_id_valid(actor, local_site_data)
+}
+
+/// Delay before the next attempt, starting with 30 seconds and quadrupling every time. This gives
+/// remote instances time to recover if fetching a dependency of the activity failed.
+fn retry_delay(attempts: i32) -> TimeDelta {
+  let exponent = u32::try_from(attempts.saturating_sub(1)).unwrap_or_default();
+  TimeDelta::seconds(30_i64.saturating_mul(4_i64.saturating_pow(exponent)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_retry_delay() {
+    assert_eq!(TimeDelta::seconds(30), retry_delay(1));
+    assert_eq!(TimeDelta::minutes(2), retry_delay(2));
+    assert_eq!(TimeDelta::minutes(8), retry_delay(3));
+    assert_eq!(TimeDelta::minutes(32), retry_delay(4));
+  }
+}
@@ -11,6 +11,7 @@ pub mod api;
 pub mod collections;
 pub mod fetcher;
 pub mod http;
+pub mod inbox_queue;
 pub mod protocol;
 
 /// Maximum number of outgoing HTTP requests to fetch a single object. Needs to be high enough
@@ -4,6 +4,7 @@ use activitypub_federation::{
   kinds::activity::UndoType,
   protocol::helpers::deserialize_one_or_many,
 };
+use chrono::{DateTime, Utc};
 use lemmy_api_utils::context::LemmyContext;
 use lemmy_apub_objects::{
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
@@ -12,6 +13,7 @@ use lemmy_apub_objects::{
 use lemmy_db_schema::{source::community::Community, traits::Crud};
 use lemmy_utils::error::LemmyResult;
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use strum::Display;
 use url::Url;
 
@@ -20,6 +22,7 @@ pub enum LockType {
   Lock,
 }
 
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LockPage {
@@ -34,6 +37,10 @@ pub struct LockPage {
   pub(crate) id: Url,
   /// Summary is the reason for the lock.
   pub(crate) summary: Option<String>,
+  /// Time at which the lock is lifted.
+  pub(crate) end_time: Option<DateTime<Utc>>,
+  /// Lemmy extension, the lock only applies to accounts which are younger than this many days.
+  pub(crate) max_account_age_days: Option<i32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -2,6 +2,7 @@ pub mod announce;
 pub mod collection_add;
 pub mod collection_remove;
 pub mod lock_page;
+pub mod move_community;
 pub mod report;
 pub mod resolve_report;
 pub mod update;
@@ -14,6 +15,7 @@ mod tests {
     collection_add::CollectionAdd,
     collection_remove::CollectionRemove,
     lock_page::{LockPage, UndoLockPage},
+    move_community::MoveCommunity,
     report::Report,
     update::Update,
   };
@@ -40,6 +42,9 @@ mod tests {
     test_parse_lemmy_item::<UndoLockPage>("assets/lemmy/activities/community/undo_lock_page.json")?;
 
     test_parse_lemmy_item::<Update>("assets/lemmy/activities/community/update_community.json")?;
+    test_parse_lemmy_item::<MoveCommunity>(
+      "assets/lemmy/activities/community/move_community.json",
+    )?;
 
     test_parse_lemmy_item::<Report>("assets/lemmy/activities/community/report_page.json")?;
     test_parse_lemmy_item::<ResolveReport>(
@@ -0,0 +1,23 @@
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::MoveType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use lemmy_apub_objects::objects::community::ApubCommunity;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by a community which moved to another instance. Followers of the old community follow the
+/// new one instead. The new community needs to list the old one in `alsoKnownAs`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCommunity {
+  pub(crate) actor: ObjectId<ApubCommunity>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<ApubCommunity>,
+  pub(crate) target: ObjectId<ApubCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: MoveType,
+  pub(crate) id: Url,
+}
@@ -6,6 +6,8 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod relay;
+pub mod update_actor;
 pub mod voting;
 
 #[derive(Clone, Debug, Display, Deserialize, Serialize, PartialEq, Eq)]
@@ -21,11 +23,19 @@ mod tests {
     create_or_update::{note::CreateOrUpdateNote, page::CreateOrUpdatePage},
     deletion::delete::Delete,
     following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+    relay::{AcceptRelay, AnnounceRelay},
+    update_actor::UpdateActor,
     voting::{undo_vote::UndoVote, vote::Vote},
   };
-  use lemmy_apub_objects::utils::test::test_json;
+  use lemmy_apub_objects::utils::test::{test_json, test_parse_lemmy_item};
   use lemmy_utils::error::LemmyResult;
 
+  #[test]
+  fn test_parse_lemmy_update_actor() -> LemmyResult<()> {
+    test_parse_lemmy_item::<UpdateActor>("assets/lemmy/activities/update_actor.json")?;
+    Ok(())
+  }
+
   #[test]
   fn test_parse_smithereen_activities() -> LemmyResult<()> {
     test_json::<CreateOrUpdateNote>("assets/smithereen/activities/create_note.json")?;
@@ -100,4 +110,11 @@ mod tests {
     test_json::<AnnounceActivity>("assets/wordpress/activities/announce.json")?;
     Ok(())
   }
+
+  #[test]
+  fn test_parse_activity_relay_activities() -> LemmyResult<()> {
+    test_json::<AcceptRelay>("assets/activity_relay/activities/accept.json")?;
+    test_json::<AnnounceRelay>("assets/activity_relay/activities/announce.json")?;
+    Ok(())
+  }
 }
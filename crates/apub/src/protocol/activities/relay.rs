@@ -0,0 +1,63 @@
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::{AcceptType, AnnounceType, FollowType, RejectType, UndoType},
+};
+use lemmy_apub_objects::objects::{instance::ApubSite, relay::ApubRelay};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+/// Subscribes the local site to a relay. LitePub style relays are followed directly, Mastodon
+/// style relays expect the public collection as object.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowRelay {
+  pub(crate) actor: ObjectId<ApubSite>,
+  pub(crate) object: Url,
+  #[serde(rename = "type")]
+  pub(crate) kind: FollowType,
+  pub(crate) id: Url,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoFollowRelay {
+  pub(crate) actor: ObjectId<ApubSite>,
+  pub(crate) object: FollowRelay,
+  #[serde(rename = "type")]
+  pub(crate) kind: UndoType,
+  pub(crate) id: Url,
+}
+
+/// Relays differ in how they embed the follow, so the object is not parsed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptRelay {
+  pub(crate) actor: ObjectId<ApubRelay>,
+  pub(crate) object: Value,
+  #[serde(rename = "type")]
+  pub(crate) kind: AcceptType,
+  pub(crate) id: Url,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectRelay {
+  pub(crate) actor: ObjectId<ApubRelay>,
+  pub(crate) object: Value,
+  #[serde(rename = "type")]
+  pub(crate) kind: RejectType,
+  pub(crate) id: Url,
+}
+
+/// Public content which the relay received from one of its subscribers. The object is either
+/// the id of a post or comment, or a `Create`/`Update` activity which contains it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnounceRelay {
+  pub(crate) actor: ObjectId<ApubRelay>,
+  pub(crate) object: Value,
+  #[serde(rename = "type")]
+  pub(crate) kind: AnnounceType,
+  pub(crate) id: Url,
+}
@@ -0,0 +1,22 @@
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::UpdateType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use lemmy_apub_objects::objects::UserOrCommunity;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent by a local person or community after its signing key was rotated. Receivers refetch the
+/// actor to get the new public key.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateActor {
+  pub(crate) actor: ObjectId<UserOrCommunity>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: ObjectId<UserOrCommunity>,
+  #[serde(rename = "type")]
+  pub(crate) kind: UpdateType,
+  pub(crate) id: Url,
+}
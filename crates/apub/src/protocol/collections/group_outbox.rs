@@ -1,13 +1,29 @@
 use crate::protocol::activities::community::announce::AnnounceActivity;
-use activitypub_federation::kinds::collection::OrderedCollectionType;
+use activitypub_federation::kinds::collection::{OrderedCollectionPageType, OrderedCollectionType};
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use url::Url;
 
+/// Contains the newest posts directly, older versions of Lemmy only read these. All posts can be
+/// read by following the pages, starting with `first`.
+#[skip_serializing_none]
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupOutbox {
   pub(crate) r#type: OrderedCollectionType,
   pub(crate) id: Url,
   pub(crate) total_items: i32,
+  pub(crate) first: Option<Url>,
+  pub(crate) ordered_items: Vec<AnnounceActivity>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupOutboxPage {
+  pub(crate) r#type: OrderedCollectionPageType,
+  pub(crate) id: Url,
+  pub(crate) part_of: Url,
+  pub(crate) next: Option<Url>,
   pub(crate) ordered_items: Vec<AnnounceActivity>,
 }
@@ -3,6 +3,8 @@ pub(crate) mod group_featured;
 pub(crate) mod group_followers;
 pub(crate) mod group_moderators;
 pub(crate) mod group_outbox;
+pub(crate) mod post_context;
+pub(crate) mod remote_collection;
 
 #[cfg(test)]
 #[allow(clippy::as_conversions)]
@@ -12,7 +14,9 @@ mod tests {
     group_featured::GroupFeatured,
     group_followers::GroupFollowers,
     group_moderators::GroupModerators,
-    group_outbox::GroupOutbox,
+    group_outbox::{GroupOutbox, GroupOutboxPage},
+    post_context::PostContext,
+    remote_collection::RemoteCollection,
   };
   use lemmy_apub_objects::utils::test::{test_json, test_parse_lemmy_item};
   use lemmy_utils::error::LemmyResult;
@@ -24,6 +28,8 @@ mod tests {
     let outbox =
       test_parse_lemmy_item::<GroupOutbox>("assets/lemmy/collections/group_outbox.json")?;
     assert_eq!(outbox.ordered_items.len(), outbox.total_items as usize);
+    test_parse_lemmy_item::<GroupOutboxPage>("assets/lemmy/collections/group_outbox_page.json")?;
+    test_parse_lemmy_item::<PostContext>("assets/lemmy/collections/post_context.json")?;
     test_parse_lemmy_item::<GroupFeatured>("assets/lemmy/collections/group_featured_posts.json")?;
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json")?;
     test_parse_lemmy_item::<EmptyOutbox>("assets/lemmy/collections/person_outbox.json")?;
@@ -35,4 +41,15 @@ mod tests {
     test_json::<GroupFeatured>("assets/mastodon/collections/featured.json")?;
     Ok(())
   }
+
+  #[test]
+  fn test_parse_remote_collections() -> LemmyResult<()> {
+    let outbox = test_json::<RemoteCollection>("assets/lemmy/collections/group_outbox.json")?;
+    assert_eq!(2, outbox.inner().ordered_items.len());
+    assert!(outbox.inner().first.is_some());
+    let page = test_json::<RemoteCollection>("assets/lemmy/collections/group_outbox_page.json")?;
+    assert!(page.inner().next.is_some());
+    test_json::<RemoteCollection>("assets/lemmy/collections/post_context.json")?;
+    Ok(())
+  }
 }
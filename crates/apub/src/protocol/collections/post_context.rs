@@ -0,0 +1,14 @@
+use activitypub_federation::kinds::collection::OrderedCollectionType;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// All objects in the thread of a post, starting with the post itself and with parent comments
+/// before their children. See FEP-7888.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostContext {
+  pub(crate) r#type: OrderedCollectionType,
+  pub(crate) id: Url,
+  pub(crate) total_items: i64,
+  pub(crate) ordered_items: Vec<Url>,
+}
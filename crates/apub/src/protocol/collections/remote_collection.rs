@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+/// A collection or collection page in any of the formats used across the fediverse. Items are
+/// either ids, or embedded objects and activities. Only used for reading.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RemoteCollection {
+  pub(crate) id: Url,
+  #[serde(default, alias = "items")]
+  pub(crate) ordered_items: Vec<Value>,
+  /// Either the id of the first page, or the page itself
+  pub(crate) first: Option<Value>,
+  pub(crate) next: Option<Url>,
+}
@@ -25,12 +25,17 @@ use activitypub_federation::{
 };
 use chrono::{DateTime, Utc};
 use lemmy_api_utils::{
+  automod::automod_check_comment,
   context::LemmyContext,
   plugins::{plugin_hook_after, plugin_hook_before},
   utils::{
     check_comment_depth,
     check_is_mod_or_admin,
+    check_post_comment_allowed,
+    content_requires_approval,
+    generate_context_url,
     get_url_blocklist,
+    post_lock_applies,
     process_markdown,
     slur_regex,
   },
@@ -138,6 +143,11 @@ impl Object for ApubComment {
       distinguished: Some(self.distinguished),
       language,
       attachment: vec![],
+      context: post
+        .local
+        .then(|| generate_context_url(&post.ap_id))
+        .transpose()?
+        .map(Into::into),
     };
 
     Ok(note)
@@ -187,7 +197,20 @@ impl Object for ApubComment {
     )
     .await
     .is_ok();
-    if post.locked && !is_mod_or_admin {
+    // Slow mode only applies to new comments, edits are only checked for the lock
+    if Comment::read_from_apub_id(&mut context.pool(), note.id.clone().into_inner())
+      .await?
+      .is_none()
+    {
+      check_post_comment_allowed(
+        &post,
+        &community,
+        &creator,
+        is_mod_or_admin,
+        &mut context.pool(),
+      )
+      .await
+    } else if !is_mod_or_admin && post_lock_applies(&post, &creator, Utc::now()) {
       Err(FederationError::PostIsLocked)?
     } else {
       Ok(())
@@ -216,6 +239,17 @@ impl Object for ApubComment {
         .await?,
     );
 
+    // Only new comments are held for approval, edits must not hide comments which are already
+    // approved
+    let existing =
+      Comment::read_from_apub_id(&mut context.pool(), note.id.clone().into_inner()).await?;
+    let pending_approval = if existing.is_none() {
+      let community = Community::read(&mut context.pool(), post.community_id).await?;
+      Some(content_requires_approval(&community, &creator, false, &mut context.pool()).await?)
+    } else {
+      None
+    };
+
     let mut form = CommentInsertForm {
       creator_id: creator.id,
       post_id: post.id,
@@ -229,11 +263,12 @@ impl Object for ApubComment {
       local: Some(false),
       language_id,
       federation_pending: Some(false),
+      pending_approval,
     };
     form = plugin_hook_before("before_receive_federated_comment", form).await?;
     let parent_comment_path = parent_comment.map(|t| t.0.path);
     let timestamp: DateTime<Utc> = note.updated.or(note.published).unwrap_or_else(Utc::now);
-    let comment = Comment::insert_apub(
+    let mut comment = Comment::insert_apub(
       &mut context.pool(),
       Some(timestamp),
       &form,
@@ -241,6 +276,13 @@ impl Object for ApubComment {
     )
     .await?;
     plugin_hook_after("after_receive_federated_comment", &comment)?;
+    // Automod only runs once, so that refetches don't undo the actions of mods
+    if existing.is_none() {
+      let automod = automod_check_comment(&comment, context).await?;
+      comment.pending_approval = automod.pending_approval;
+      comment.removed = automod.removed;
+      automod.send_replies(context)?;
+    }
     Ok(comment.into())
   }
 }
@@ -141,12 +141,26 @@ impl Object for ApubCommunity {
       published: Some(self.published_at),
       updated: self.updated_at,
       posting_restricted_to_mods: Some(self.posting_restricted_to_mods),
+      cross_posting_restricted_to_mods: Some(self.cross_posting_restricted_to_mods),
+      question_answer: Some(self.question_answer),
+      // Zero is sent for no slow mode, so that removing it also federates
+      comment_slow_mode_minutes: Some(self.comment_slow_mode_minutes.unwrap_or_default()),
+      posts_frozen: Some(self.posts_frozen),
+      comments_frozen: Some(self.comments_frozen),
+      freeze_expires_at: self.freeze_expires_at,
       attributed_to: Some(AttributedTo::Lemmy(
         generate_moderators_url(&self.ap_id)?.into(),
       )),
       manually_approves_followers: Some(self.visibility == CommunityVisibility::Private),
       discoverable: Some(self.visibility != CommunityVisibility::Unlisted),
       tag: post_tags.into_iter().map(CommunityTag::to_json).collect(),
+      moved_to: self.moved_to_url.clone().map(Into::into),
+      also_known_as: self
+        .moved_from_url
+        .clone()
+        .map(Into::into)
+        .into_iter()
+        .collect(),
     };
     Ok(group)
   }
@@ -221,6 +235,14 @@ impl Object for ApubCommunity {
         .clone()
         .and_then(AttributedTo::url),
       posting_restricted_to_mods: group.posting_restricted_to_mods,
+      cross_posting_restricted_to_mods: group.cross_posting_restricted_to_mods,
+      question_answer: group.question_answer,
+      comment_slow_mode_minutes: group.comment_slow_mode_minutes,
+      posts_frozen: group.posts_frozen,
+      comments_frozen: group.comments_frozen,
+      freeze_expires_at: group.freeze_expires_at,
+      moved_to_url: group.moved_to.clone().map(Into::into),
+      moved_from_url: group.also_known_as.first().cloned().map(Into::into),
       featured_url: group.featured.clone().clone().map(Into::into),
       visibility,
       ..CommunityInsertForm::new(
@@ -237,7 +259,16 @@ impl Object for ApubCommunity {
       LanguageTag::to_language_id_multiple(group.language.clone(), &mut context.pool()).await?;
 
     let timestamp = group.updated.or(group.published).unwrap_or_else(Utc::now);
-    let community = Community::insert_apub(&mut context.pool(), timestamp, &form).await?;
+    let mut community = Community::insert_apub(&mut context.pool(), timestamp, &form).await?;
+    // Empty fields are skipped by the upsert, so a removed freeze expiry needs to be cleared
+    // separately
+    if community.freeze_expires_at.is_some() && group.freeze_expires_at.is_none() {
+      let form = CommunityUpdateForm {
+        freeze_expires_at: Some(None),
+        ..Default::default()
+      };
+      community = Community::update(&mut context.pool(), community.id, &form).await?;
+    }
     CommunityLanguage::update(&mut context.pool(), languages, community.id).await?;
 
     let new_tags = group
@@ -6,6 +6,7 @@ pub mod multi_community_collection;
 pub mod person;
 pub mod post;
 pub mod private_message;
+pub mod relay;
 
 use comment::ApubComment;
 use community::ApubCommunity;
@@ -20,6 +20,7 @@ use chrono::{DateTime, Utc};
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{
+    generate_followers_url,
     generate_outbox_url,
     get_url_blocklist,
     process_markdown_opt,
@@ -116,6 +117,7 @@ impl Object for ApubPerson {
       matrix_user_id: self.matrix_user_id.clone(),
       published: Some(self.published_at),
       outbox: generate_outbox_url(&self.ap_id)?.into(),
+      followers: Some(generate_followers_url(&self.ap_id)?.into()),
       endpoints: None,
       public_key: self.public_key(),
       updated: self.updated_at,
@@ -1,6 +1,7 @@
 use crate::{
   protocol::{
     page::{
+      parse_duration,
       Attachment,
       Hashtag,
       HashtagOrLemmyTag,
@@ -31,24 +32,33 @@ use anyhow::anyhow;
 use chrono::Utc;
 use html2text::{from_read_with_decorator, render::TrivialDecorator};
 use lemmy_api_utils::{
+  automod::automod_check_post,
   context::LemmyContext,
   plugins::{plugin_hook_after, plugin_hook_before},
   request::generate_post_link_metadata,
   utils::{
+    check_community_cross_posting_allowed,
+    check_community_posts_not_frozen,
     check_nsfw_allowed,
+    content_requires_approval,
+    generate_context_url,
     get_url_blocklist,
     process_markdown_opt,
     slur_regex,
     update_post_tags,
+    MAX_GALLERY_ITEMS,
   },
 };
 use lemmy_db_schema::{
   source::{
+    comment::Comment,
     community::Community,
     local_site::LocalSite,
     person::Person,
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_gallery::{PostGalleryItem, PostGalleryItemForm},
     tag::Tag,
+    video::{VideoDetails, VideoDetailsInsertForm, VideoVariant, VideoVariantForm},
   },
   traits::Crud,
 };
@@ -130,18 +140,21 @@ impl Object for ApubPost {
     let community = Community::read(&mut context.pool(), community_id).await?;
     let language = Some(LanguageTag::new_single(self.language_id, &mut context.pool()).await?);
 
-    let attachment = self
-      .url
-      .clone()
-      .map(|url| {
-        Attachment::new(
-          url.into(),
-          self.url_content_type.clone(),
-          self.alt_text.clone(),
-        )
-      })
-      .into_iter()
-      .collect();
+    let gallery = PostGalleryItem::list_for_post(&mut context.pool(), self.id).await?;
+    let attachment = if let (true, Some(url)) = (gallery.is_empty(), self.url.clone()) {
+      let media_type = self.url_content_type.clone();
+      let alt_text = self.alt_text.clone();
+      let attachment = match VideoDetails::read(&mut context.pool(), &url).await {
+        Ok(details) => Attachment::new_video(url.into(), media_type, alt_text, details),
+        Err(_) => Attachment::new(url.into(), media_type, alt_text),
+      };
+      vec![attachment]
+    } else {
+      gallery
+        .into_iter()
+        .map(Attachment::new_gallery_item)
+        .collect()
+    };
 
     // Add tags defined by community and applied to this post
     let mut tags: Vec<HashtagOrLemmyTag> = Tag::read_for_post(&mut context.pool(), self.id)
@@ -158,6 +171,21 @@ impl Object for ApubPost {
     };
     tags.push(HashtagOrLemmyTag::Hashtag(hashtag));
 
+    let quote_uri = if let Some(cross_post_of_id) = self.cross_post_of_id {
+      Some(
+        Post::read(&mut context.pool(), cross_post_of_id)
+          .await?
+          .ap_id
+          .into(),
+      )
+    } else {
+      None
+    };
+
+    let accepted_answer = Comment::read_accepted_answer(&mut context.pool(), self.id)
+      .await?
+      .map(|c| c.ap_id.into());
+
     let page = Page {
       kind: PageType::Page,
       id: self.ap_id.clone().into(),
@@ -176,6 +204,20 @@ impl Object for ApubPost {
       updated: self.updated_at,
       in_reply_to: None,
       tag: tags,
+      // Always set, with zero meaning that mods disabled slow mode for the post
+      comment_slow_mode_minutes: Some(self.comment_slow_mode_minutes.unwrap_or_default()),
+      comment_display_mode: Some(self.comment_display_mode),
+      solved: Some(self.solved),
+      accepted_answer,
+      quote_uri,
+      context: self
+        .local
+        .then(|| generate_context_url(&self.ap_id))
+        .transpose()?
+        .map(Into::into),
+      url: vec![],
+      icon: vec![],
+      duration: None,
     };
     Ok(page)
   }
@@ -221,6 +263,33 @@ impl Object for ApubPost {
       )
       .await?;
     }
+    // The original of a cross-post may not be available anymore, then the post is stored without
+    // the link
+    let quote_uri = page
+      .quote_uri
+      .as_ref()
+      .filter(|q| q.inner() != page.id.inner());
+    let cross_post_of_id = if let Some(quote_uri) = quote_uri {
+      quote_uri
+        .dereference(context)
+        .await
+        .ok()
+        .map(|original| original.cross_post_original_id())
+    } else {
+      None
+    };
+
+    // Only new posts are rejected while a local community is frozen, edits are still allowed
+    if community.local
+      && Post::read_from_apub_id(&mut context.pool(), page.id.clone().into_inner())
+        .await?
+        .is_none()
+    {
+      check_community_posts_not_frozen(&community, creator.id, &mut context.pool()).await?;
+      if cross_post_of_id.is_some() {
+        check_community_cross_posting_allowed(&community, creator.id, &mut context.pool()).await?;
+      }
+    }
     let mut name = page
       .name
       .clone()
@@ -285,6 +354,15 @@ impl Object for ApubPost {
       .await?,
     );
 
+    // Only new posts are held for approval, edits must not hide posts which are already approved
+    let existing =
+      Post::read_from_apub_id(&mut context.pool(), page.id.clone().into_inner()).await?;
+    let pending_approval = if existing.is_none() {
+      Some(content_requires_approval(&community, &creator, true, &mut context.pool()).await?)
+    } else {
+      None
+    };
+
     let mut form = PostInsertForm {
       url: url.map(Into::into),
       body,
@@ -297,24 +375,51 @@ impl Object for ApubPost {
       // May be a local post which is updated by remote mod.
       local: Some(page.id.is_local(context)),
       language_id,
+      pending_approval,
+      // Slow mode is set by mods, so only the instance of the community is trusted with it
+      comment_slow_mode_minutes: page.comment_slow_mode_minutes.filter(|_| !community.local),
+      comment_display_mode: page.comment_display_mode,
+      // In local communities, posts are only solved by accepting an answer which is known locally
+      solved: page.solved.filter(|_| !community.local),
+      cross_post_of_id,
       ..PostInsertForm::new(name, creator.id, community.id)
     };
     form = plugin_hook_before("before_receive_federated_post", form).await?;
 
     let timestamp = page.updated.or(page.published).unwrap_or_else(Utc::now);
-    let post = Post::insert_apub(&mut context.pool(), timestamp, &form).await?;
+    let mut post = Post::insert_apub(&mut context.pool(), timestamp, &form).await?;
     plugin_hook_after("after_receive_federated_post", &post)?;
 
     update_apub_post_tags(&page, &post, context).await?;
+    update_apub_post_gallery(&page, &post, context).await?;
+    update_apub_video_details(&page, &post, context).await?;
+    update_apub_accepted_answer(&page, &post, context).await?;
+    // Automod only runs once, so that refetches and remote edits don't re-add tags which were
+    // removed since. Content which automod holds or removes isn't announced to other instances.
+    if existing.is_none() {
+      let automod = automod_check_post(&post, context).await?;
+      post.pending_approval = automod.pending_approval;
+      post.removed = automod.removed;
+      post.nsfw |= automod.nsfw;
+      automod.send_replies(context)?;
+    }
 
     let post_ = post.clone();
     let context_ = context.clone();
+    // PeerTube thumbnails can't be found in the metadata of the video page
+    let custom_thumbnail = if page.kind == PageType::Video {
+      page
+        .largest_icon()
+        .or_else(|| page.image.clone().map(|i| i.url))
+    } else {
+      None
+    };
 
     // Generates a post thumbnail in background task, because some sites can be very slow to
     // respond.
-    spawn_try_task(
-      async move { generate_post_link_metadata(post_, None, |_| None, context_).await },
-    );
+    spawn_try_task(async move {
+      generate_post_link_metadata(post_, custom_thumbnail, |_| None, context_).await
+    });
 
     Ok(post.into())
   }
@@ -340,6 +445,121 @@ pub async fn update_apub_post_tags(
   Ok(())
 }
 
+/// Accepts the answer which is referenced by the page. Only top-level comments of the post which
+/// are already known can be accepted. The accepted answer is only cleared if the page explicitly
+/// marks the post as unsolved, as other software doesn't know about answers.
+pub async fn update_apub_accepted_answer(
+  page: &Page,
+  post: &Post,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let answer = match &page.accepted_answer {
+    Some(answer) => answer
+      .dereference_local(context)
+      .await
+      .ok()
+      .filter(|c| c.post_id == post.id && c.parent_comment_id().is_none()),
+    None => None,
+  };
+  if let Some(answer) = answer {
+    if !answer.accepted_answer {
+      Comment::accept_answer(&mut context.pool(), answer.id, true).await?;
+    }
+  } else if page.accepted_answer.is_none() && page.solved == Some(false) {
+    if let Some(answer) = Comment::read_accepted_answer(&mut context.pool(), post.id).await? {
+      Comment::accept_answer(&mut context.pool(), answer.id, false).await?;
+    }
+  }
+  Ok(())
+}
+
+/// Posts with multiple attachments are stored as gallery, others have their gallery removed.
+async fn update_apub_post_gallery(
+  page: &Page,
+  post: &Post,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let forms = if page.attachment.len() > 1 {
+    let url_blocklist = get_url_blocklist(context).await?;
+    page
+      .attachment
+      .iter()
+      .filter(|a| {
+        let url = a.clone().url();
+        is_url_blocked(&url, &url_blocklist).is_ok() && is_valid_url(&url).is_ok()
+      })
+      .take(MAX_GALLERY_ITEMS)
+      .zip(0..)
+      .map(|(a, position)| PostGalleryItemForm {
+        post_id: post.id,
+        position,
+        url_content_type: a.media_type(),
+        caption: a.caption(),
+        alt_text: a.clone().alt_text(),
+        url: a.clone().url().into(),
+      })
+      .collect()
+  } else {
+    vec![]
+  };
+  PostGalleryItem::replace(&mut context.pool(), post.id, &forms).await?;
+  Ok(())
+}
+
+/// Stores the details of video attachments and PeerTube videos, so that clients can play them
+/// directly.
+async fn update_apub_video_details(
+  page: &Page,
+  post: &Post,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  let Some(link) = post.url.clone() else {
+    return Ok(());
+  };
+  let (form, variants) = if let Some(Attachment::Video(video)) = page.attachment.first() {
+    let form = VideoDetailsInsertForm {
+      link,
+      width: video.width,
+      height: video.height,
+      duration_ms: video.duration.as_deref().and_then(parse_duration),
+      poster_url: None,
+    };
+    (form, vec![])
+  } else if page.kind == PageType::Video {
+    let url_blocklist = get_url_blocklist(context).await?;
+    let files = page
+      .video_files()
+      .into_iter()
+      .filter(|f| is_url_blocked(&f.href, &url_blocklist).is_ok() && is_valid_url(&f.href).is_ok())
+      .collect::<Vec<_>>();
+    let largest = files.iter().max_by_key(|f| f.height);
+    let variants = files
+      .iter()
+      .filter_map(|f| {
+        Some(VideoVariantForm {
+          link: link.clone(),
+          height: f.height?,
+          url: f.href.clone().into(),
+          content_type: "video/mp4".to_string(),
+        })
+      })
+      .collect();
+    let form = VideoDetailsInsertForm {
+      link,
+      width: largest.and_then(|f| f.width),
+      height: largest.and_then(|f| f.height),
+      duration_ms: page.duration.as_deref().and_then(parse_duration),
+      poster_url: page.largest_icon().map(Into::into),
+    };
+    (form, variants)
+  } else {
+    return Ok(());
+  };
+  VideoDetails::upsert(&mut context.pool(), &form).await?;
+  VideoVariant::upsert_many(&mut context.pool(), &variants).await?;
+  Ok(())
+}
+
 pub async fn post_nsfw(
   page: &Page,
   community: &Community,
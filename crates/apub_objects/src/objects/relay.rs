@@ -0,0 +1,105 @@
+use crate::{protocol::relay::Relay, utils::functions::check_apub_id_valid_with_strictness};
+use activitypub_federation::{
+  config::Data,
+  protocol::verification::{verify_domains_match, verify_is_remote_object},
+  traits::{Actor, Object},
+};
+use chrono::{DateTime, Utc};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_db_schema::{
+  source::federation_relay::{FederationRelay, FederationRelayUpdateForm},
+  traits::Crud,
+};
+use lemmy_utils::error::{FederationError, LemmyError, LemmyErrorType, LemmyResult};
+use std::ops::Deref;
+use url::Url;
+
+/// Actor of a relay which the local site is subscribed to. Only relays which were added by an
+/// admin can be fetched, all other relay actors are rejected.
+#[derive(Clone, Debug)]
+pub struct ApubRelay(pub FederationRelay);
+
+impl Deref for ApubRelay {
+  type Target = FederationRelay;
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl From<FederationRelay> for ApubRelay {
+  fn from(r: FederationRelay) -> Self {
+    ApubRelay(r)
+  }
+}
+
+#[async_trait::async_trait]
+impl Object for ApubRelay {
+  type DataType = LemmyContext;
+  type Kind = Relay;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    self.ap_id.as_ref().unwrap_or(&self.url).inner()
+  }
+
+  fn last_refreshed_at(&self) -> Option<DateTime<Utc>> {
+    Some(self.updated_at.unwrap_or(self.published_at))
+  }
+
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    Ok(
+      FederationRelay::read_from_apub_id(&mut data.pool(), &object_id.into())
+        .await?
+        .map(Into::into),
+    )
+  }
+
+  async fn delete(self, _data: &Data<Self::DataType>) -> LemmyResult<()> {
+    Ok(())
+  }
+
+  async fn into_json(self, _data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    Err(FederationError::Unreachable.into())
+  }
+
+  async fn verify(
+    apub: &Self::Kind,
+    expected_domain: &Url,
+    data: &Data<Self::DataType>,
+  ) -> LemmyResult<()> {
+    check_apub_id_valid_with_strictness(apub.id.inner(), false, data).await?;
+    verify_domains_match(expected_domain, apub.id.inner())?;
+    verify_is_remote_object(&apub.id, data)?;
+    Ok(())
+  }
+
+  async fn from_json(apub: Self::Kind, context: &Data<Self::DataType>) -> LemmyResult<Self> {
+    let relay = FederationRelay::read_for_actor(&mut context.pool(), apub.id.inner())
+      .await?
+      .ok_or(LemmyErrorType::NotFound)?;
+    let inbox_url = apub.endpoints.map(|e| e.shared_inbox).unwrap_or(apub.inbox);
+    let form = FederationRelayUpdateForm {
+      ap_id: Some(Some(apub.id.into())),
+      inbox_url: Some(Some(inbox_url.into())),
+      public_key: Some(Some(apub.public_key.public_key_pem)),
+      updated_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    let relay = FederationRelay::update(&mut context.pool(), relay.id, &form).await?;
+    Ok(relay.into())
+  }
+}
+
+impl Actor for ApubRelay {
+  fn public_key_pem(&self) -> &str {
+    self.public_key.as_deref().unwrap_or_default()
+  }
+
+  fn private_key_pem(&self) -> Option<String> {
+    None
+  }
+
+  fn inbox(&self) -> Url {
+    self.inbox_url.as_ref().unwrap_or(&self.url).clone().into()
+  }
+}
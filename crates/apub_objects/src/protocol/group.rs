@@ -51,6 +51,18 @@ pub struct Group {
   pub attributed_to: Option<AttributedTo>,
   // lemmy extension
   pub posting_restricted_to_mods: Option<bool>,
+  // lemmy extension
+  pub cross_posting_restricted_to_mods: Option<bool>,
+  // lemmy extension
+  pub question_answer: Option<bool>,
+  // lemmy extension
+  pub comment_slow_mode_minutes: Option<i32>,
+  // lemmy extension
+  pub posts_frozen: Option<bool>,
+  // lemmy extension
+  pub comments_frozen: Option<bool>,
+  // lemmy extension
+  pub freeze_expires_at: Option<DateTime<Utc>>,
   pub outbox: Url,
   pub endpoints: Option<Endpoints>,
   pub featured: Option<Url>,
@@ -64,4 +76,9 @@ pub struct Group {
   pub(crate) discoverable: Option<bool>,
   #[serde(default)]
   pub(crate) tag: Vec<CommunityTag>,
+  /// New address of the community, if it moved to another instance
+  pub moved_to: Option<Url>,
+  /// Previous addresses of the community, needed to verify a `Move`
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub also_known_as: Vec<Url>,
 }
@@ -61,6 +61,9 @@ pub struct Note {
   pub(crate) language: Option<LanguageTag>,
   #[serde(default)]
   pub(crate) attachment: Vec<Attachment>,
+  /// Collection with all objects in the thread, see FEP-7888
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) context: Option<Url>,
 }
 
 impl Note {
@@ -1,5 +1,5 @@
 use crate::{
-  objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::tags::CommunityTag,
   utils::protocol::{
     AttributedTo,
@@ -15,7 +15,7 @@ use activitypub_federation::{
   fetch::object_id::ObjectId,
   kinds::{
     link::LinkType,
-    object::{DocumentType, ImageType},
+    object::{DocumentType, ImageType, VideoType},
   },
   protocol::{
     helpers::{deserialize_one_or_many, deserialize_skip_error},
@@ -26,8 +26,15 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use lemmy_api_utils::{context::LemmyContext, utils::proxy_image_link};
+use lemmy_db_schema::source::{post_gallery::PostGalleryItem, video::VideoDetails};
+use lemmy_db_schema_file::enums::CommentDisplayMode;
 use lemmy_utils::error::{FederationError, LemmyError, LemmyErrorType, LemmyResult};
-use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde::{
+  de::{DeserializeOwned, Error},
+  Deserialize,
+  Deserializer,
+  Serialize,
+};
 use serde_with::skip_serializing_none;
 use url::Url;
 
@@ -61,8 +68,8 @@ pub struct Page {
   pub(crate) media_type: Option<MediaTypeMarkdownOrHtml>,
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) source: Option<Source>,
-  /// most software uses array type for attachment field, so we do the same. nevertheless, we only
-  /// use the first item
+  /// most software uses array type for attachment field, so we do the same. The first item is
+  /// used as post url. If there are multiple items, the post is a gallery of all of them.
   #[serde(default)]
   pub(crate) attachment: Vec<Attachment>,
   pub(crate) image: Option<ImageObject>,
@@ -74,6 +81,63 @@ pub struct Page {
   /// Contains hashtags and post tags.
   /// https://www.w3.org/TR/activitystreams-vocabulary/#dfn-tag
   pub(crate) tag: Vec<HashtagOrLemmyTag>,
+  // lemmy extension
+  pub comment_slow_mode_minutes: Option<i32>,
+  // lemmy extension, unknown modes from newer versions are ignored
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub comment_display_mode: Option<CommentDisplayMode>,
+  // lemmy extension
+  pub solved: Option<bool>,
+  /// The comment which was accepted as answer to the question. Named after the schema.org
+  /// property.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) accepted_answer: Option<ObjectId<ApubComment>>,
+  /// The original post if this is a cross-post. Uses the same field as quote posts in Misskey and
+  /// Fedibird.
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) quote_uri: Option<ObjectId<ApubPost>>,
+  /// Collection with all objects in the thread, see FEP-7888
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub context: Option<Url>,
+  /// Video files of PeerTube videos.
+  #[serde(
+    deserialize_with = "deserialize_valid_items",
+    default,
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub(crate) url: Vec<VideoLink>,
+  /// Thumbnails of PeerTube videos in different sizes.
+  #[serde(
+    deserialize_with = "deserialize_valid_items",
+    default,
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub(crate) icon: Vec<Icon>,
+  /// Length of PeerTube videos, as ISO 8601 duration.
+  pub(crate) duration: Option<String>,
+}
+
+/// Link to a PeerTube video file. Links to HLS playlists contain links to the individual files as
+/// tags.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoLink {
+  pub(crate) href: Url,
+  pub(crate) media_type: Option<String>,
+  pub(crate) width: Option<i32>,
+  pub(crate) height: Option<i32>,
+  #[serde(
+    deserialize_with = "deserialize_valid_items",
+    default,
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub(crate) tag: Vec<VideoLink>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Icon {
+  pub(crate) url: Url,
+  pub(crate) width: Option<i32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -92,6 +156,9 @@ pub struct Image {
   url: Url,
   /// Used for alt_text
   name: Option<String>,
+  /// Used for gallery captions
+  #[serde(skip_serializing_if = "Option::is_none")]
+  summary: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -103,6 +170,27 @@ pub struct Document {
   media_type: Option<String>,
   /// Used for alt_text
   name: Option<String>,
+  /// Used for gallery captions
+  #[serde(skip_serializing_if = "Option::is_none")]
+  summary: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Video {
+  #[serde(rename = "type")]
+  kind: VideoType,
+  url: Url,
+  media_type: Option<String>,
+  /// Used for alt_text
+  name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) width: Option<i32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) height: Option<i32>,
+  /// ISO 8601 duration, for example `PT1M30S`
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) duration: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -111,6 +199,7 @@ pub enum Attachment {
   Link(Link),
   Image(Image),
   Document(Document),
+  Video(Video),
 }
 
 impl Attachment {
@@ -122,6 +211,7 @@ impl Attachment {
       Attachment::Image(i) => i.url,
       // sent by mobilizon
       Attachment::Document(d) => d.url,
+      Attachment::Video(v) => v.url,
     }
   }
 
@@ -129,6 +219,24 @@ impl Attachment {
     match self {
       Attachment::Image(i) => i.name,
       Attachment::Document(d) => d.name,
+      Attachment::Video(v) => v.name,
+      _ => None,
+    }
+  }
+
+  pub(crate) fn caption(&self) -> Option<String> {
+    match self {
+      Attachment::Image(i) => i.summary.clone(),
+      Attachment::Document(d) => d.summary.clone(),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn media_type(&self) -> Option<String> {
+    match self {
+      Attachment::Document(d) => d.media_type.clone(),
+      Attachment::Link(l) => l.media_type.clone(),
+      Attachment::Video(v) => v.media_type.clone(),
       _ => None,
     }
   }
@@ -138,6 +246,7 @@ impl Attachment {
       Attachment::Image(i) => (i.url.clone(), i.name.clone(), Some(String::from("image"))),
       Attachment::Document(d) => (d.url.clone(), d.name.clone(), d.media_type.clone()),
       Attachment::Link(l) => (l.href.clone(), None, l.media_type.clone()),
+      Attachment::Video(v) => (v.url.clone(), v.name.clone(), v.media_type.clone()),
     };
 
     let is_image =
@@ -195,6 +304,25 @@ impl Page {
         .ok_or_else(|| FederationError::PageDoesNotSpecifyCreator.into()),
     }
   }
+
+  /// MP4 files of a PeerTube video with one file per height, including those from HLS playlists.
+  pub(crate) fn video_files(&self) -> Vec<&VideoLink> {
+    self
+      .url
+      .iter()
+      .flat_map(|l| std::iter::once(l).chain(l.tag.iter()))
+      .filter(|l| l.media_type.as_deref() == Some("video/mp4") && l.height.is_some())
+      .unique_by(|l| l.height)
+      .collect()
+  }
+
+  pub(crate) fn largest_icon(&self) -> Option<Url> {
+    self
+      .icon
+      .iter()
+      .max_by_key(|i| i.width.unwrap_or_default())
+      .map(|i| i.url.clone())
+  }
 }
 
 impl Attachment {
@@ -206,6 +334,7 @@ impl Attachment {
         kind: Default::default(),
         url,
         name: alt_text,
+        summary: None,
       })
     } else {
       Attachment::Link(Link {
@@ -215,6 +344,48 @@ impl Attachment {
       })
     }
   }
+
+  /// Creates an attachment for a video whose details are known.
+  pub(crate) fn new_video(
+    url: Url,
+    media_type: Option<String>,
+    alt_text: Option<String>,
+    details: VideoDetails,
+  ) -> Attachment {
+    Attachment::Video(Video {
+      kind: Default::default(),
+      url,
+      media_type,
+      name: alt_text,
+      width: details.width,
+      height: details.height,
+      duration: details.duration_ms.map(format_duration),
+    })
+  }
+
+  /// Creates an attachment for an image of a gallery post.
+  pub(crate) fn new_gallery_item(item: PostGalleryItem) -> Attachment {
+    let is_image = item
+      .url_content_type
+      .as_deref()
+      .map_or(true, |t| t.starts_with("image"));
+    if is_image {
+      Attachment::Image(Image {
+        kind: Default::default(),
+        url: item.url.into(),
+        name: item.alt_text,
+        summary: item.caption,
+      })
+    } else {
+      Attachment::Document(Document {
+        kind: Default::default(),
+        url: item.url.into(),
+        media_type: item.url_content_type,
+        name: item.alt_text,
+        summary: item.caption,
+      })
+    }
+  }
 }
 
 // Used for community outbox, so that it can be compatible with Pleroma/Mastodon.
@@ -281,12 +452,97 @@ where
   }
 }
 
+/// Deserializes a single item or an array, leaving out items which are invalid.
+fn deserialize_valid_items<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: DeserializeOwned,
+{
+  let values = match serde_json::Value::deserialize(deserializer)? {
+    serde_json::Value::Array(values) => values,
+    value => vec![value],
+  };
+  Ok(
+    values
+      .into_iter()
+      .filter_map(|v| serde_json::from_value(v).ok())
+      .collect(),
+  )
+}
+
+/// Parses an ISO 8601 duration like `PT1H2M3.5S` into milliseconds.
+pub(crate) fn parse_duration(duration: &str) -> Option<i32> {
+  let mut total_ms = 0i64;
+  let mut number = String::new();
+  for c in duration.strip_prefix("PT")?.chars() {
+    if c.is_ascii_digit() || c == '.' {
+      number.push(c);
+      continue;
+    }
+    let (int, fraction) = number.split_once('.').unwrap_or((&number, ""));
+    let unit_ms = match c {
+      'H' => 3_600_000,
+      'M' => 60_000,
+      'S' => 1000,
+      _ => return None,
+    };
+    let fraction_ms: i64 = match (c, fraction) {
+      (_, "") => 0,
+      ('S', f) => format!("{f:0<3}").get(..3)?.parse().ok()?,
+      _ => return None,
+    };
+    let part = int.parse::<i64>().ok()?.checked_mul(unit_ms)?;
+    total_ms = total_ms.checked_add(part)?.checked_add(fraction_ms)?;
+    number.clear();
+  }
+  if !number.is_empty() {
+    return None;
+  }
+  total_ms.try_into().ok()
+}
+
+fn format_duration(duration_ms: i32) -> String {
+  let (secs, millis) = (duration_ms / 1000, duration_ms % 1000);
+  if millis == 0 {
+    format!("PT{secs}S")
+  } else {
+    format!("PT{secs}.{millis:03}S")
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::{protocol::page::Page, utils::test::test_parse_lemmy_item};
+  use super::*;
+  use crate::utils::test::{test_json, test_parse_lemmy_item};
+  use pretty_assertions::assert_eq;
 
   #[test]
   fn test_not_parsing_note_as_page() {
     assert!(test_parse_lemmy_item::<Page>("assets/lemmy/objects/note.json").is_err());
   }
+
+  #[test]
+  fn test_duration() {
+    assert_eq!(Some(1_145_000), parse_duration("PT1145S"));
+    assert_eq!(Some(3_723_500), parse_duration("PT1H2M3.5S"));
+    assert_eq!(None, parse_duration("P1D"));
+    assert_eq!(None, parse_duration("PT1.5M"));
+    assert_eq!("PT61.200S", format_duration(61_200));
+    assert_eq!(Some(61_200), parse_duration(&format_duration(61_200)));
+  }
+
+  #[test]
+  fn test_peertube_video_files() -> LemmyResult<()> {
+    let json = test_json::<Page>("../apub/assets/peertube/objects/video.json")?;
+    let page = json.inner();
+    let files = page.video_files();
+    assert!(!files.is_empty());
+    assert_eq!(Some(1080), files.first().and_then(|f| f.height));
+    assert!(page.largest_icon().is_some());
+    assert_eq!(
+      Some(1_145_000),
+      page.duration.as_deref().and_then(parse_duration)
+    );
+    Ok(())
+  }
 }
@@ -33,6 +33,8 @@ pub struct Person {
   pub(crate) inbox: Url,
   /// mandatory field in activitypub, lemmy currently serves an empty outbox
   pub(crate) outbox: Url,
+  /// only contains the number of followers
+  pub(crate) followers: Option<Url>,
   pub(crate) public_key: PublicKey,
 
   /// displayname
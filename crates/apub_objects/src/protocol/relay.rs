@@ -0,0 +1,19 @@
+use crate::{objects::relay::ApubRelay, utils::protocol::Endpoints};
+use activitypub_federation::{fetch::object_id::ObjectId, protocol::public_key::PublicKey};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use url::Url;
+
+/// The actor of an ActivityPub relay. Relays use different actor types, usually `Application` or
+/// `Service`, so the type is not checked.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relay {
+  #[serde(rename = "type")]
+  pub(crate) kind: String,
+  pub(crate) id: ObjectId<ApubRelay>,
+  pub(crate) inbox: Url,
+  pub(crate) public_key: PublicKey,
+  pub(crate) endpoints: Option<Endpoints>,
+}
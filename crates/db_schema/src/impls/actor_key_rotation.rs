@@ -0,0 +1,123 @@
+use crate::{
+  newtypes::DbUrl,
+  source::actor_key_rotation::{ActorKeyRotation, ActorKeyRotationForm},
+  utils::{get_conn, limit_fetch, DbPool},
+};
+use diesel::{dsl::insert_into, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::{actor_key_rotation, community, local_site, person, site};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl ActorKeyRotation {
+  pub async fn create(pool: &mut DbPool<'_>, form: &ActorKeyRotationForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(actor_key_rotation::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// The latest key rotations, newest first.
+  pub async fn list(pool: &mut DbPool<'_>, limit: Option<i64>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    actor_key_rotation::table
+      .order_by(actor_key_rotation::published_at.desc())
+      .then_order_by(actor_key_rotation::id.desc())
+      .limit(limit_fetch(limit)?)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// All local actors whose keys can be rotated: the site, persons and communities. Deleted actors
+  /// are skipped.
+  pub async fn list_local_actors(pool: &mut DbPool<'_>) -> LemmyResult<Vec<DbUrl>> {
+    let conn = &mut get_conn(pool).await?;
+    let site: DbUrl = site::table
+      .inner_join(local_site::table)
+      .select(site::ap_id)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+    let persons: Vec<DbUrl> = person::table
+      .filter(person::local)
+      .filter(person::deleted.eq(false))
+      .select(person::ap_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+    let communities: Vec<DbUrl> = community::table
+      .filter(community::local)
+      .filter(community::deleted.eq(false))
+      .select(community::ap_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+
+    let mut actors = vec![site];
+    actors.extend(persons);
+    actors.extend(communities);
+    Ok(actors)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm, PersonUpdateForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_db_schema_file::enums::ActorType;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_key_rotation() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let form = PersonInsertForm::test_form(instance.id, "key_rotation_person");
+    let person = Person::create(pool, &form).await?;
+    assert_eq!(None, person.previous_public_key);
+
+    // The old key is kept when the key changes
+    let form = PersonUpdateForm {
+      public_key: Some("new_pubkey".to_string()),
+      ..Default::default()
+    };
+    let person = Person::update(pool, person.id, &form).await?;
+    assert_eq!(Some("pubkey".to_string()), person.previous_public_key);
+    assert!(person.key_rotated_at.is_some());
+
+    // and unchanged by other updates
+    let form = PersonUpdateForm {
+      public_key: Some("new_pubkey".to_string()),
+      bio: Some(Some("bio".to_string())),
+      ..Default::default()
+    };
+    let updated = Person::update(pool, person.id, &form).await?;
+    assert_eq!(person.previous_public_key, updated.previous_public_key);
+    assert_eq!(person.key_rotated_at, updated.key_rotated_at);
+
+    let form = ActorKeyRotationForm {
+      actor_type: ActorType::Person,
+      actor_ap_id: person.ap_id.clone(),
+      admin_person_id: None,
+    };
+    let rotation = ActorKeyRotation::create(pool, &form).await?;
+    let rotations = ActorKeyRotation::list(pool, None).await?;
+    assert_eq!(Some(&rotation), rotations.first());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
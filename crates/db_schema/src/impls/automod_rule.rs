@@ -0,0 +1,69 @@
+use crate::{
+  newtypes::{AutomodRuleId, CommunityId},
+  source::automod_rule::{AutomodRule, AutomodRuleInsertForm, AutomodRuleUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::automod_rule;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl Crud for AutomodRule {
+  type InsertForm = AutomodRuleInsertForm;
+  type UpdateForm = AutomodRuleUpdateForm;
+  type IdType = AutomodRuleId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(automod_rule::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: AutomodRuleId,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(automod_rule::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl AutomodRule {
+  /// All rules of the community, including disabled ones.
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    automod_rule::table
+      .filter(automod_rule::community_id.eq(community_id))
+      .order_by(automod_rule::id.asc())
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// The enabled rules of the community, in the order they were created.
+  pub async fn list_enabled_for_community(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    automod_rule::table
+      .filter(automod_rule::community_id.eq(community_id))
+      .filter(automod_rule::enabled.eq(true))
+      .order_by(automod_rule::id.asc())
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
@@ -1,6 +1,6 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommentId, CommunityId, DbUrl, InstanceId, PersonId},
+  newtypes::{CommentId, CommunityId, DbUrl, InstanceId, PersonId, PostId},
   source::comment::{
     Comment,
     CommentActions,
@@ -20,14 +20,14 @@ use crate::{
 };
 use chrono::{DateTime, Utc};
 use diesel::{
-  dsl::insert_into,
+  dsl::{exists, insert_into, max},
   expression::SelectableHelper,
   update,
   ExpressionMethods,
   JoinOnDsl,
   QueryDsl,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
 use diesel_ltree::Ltree;
 use diesel_uplete::{uplete, UpleteCount};
 use lemmy_db_schema_file::schema::{comment, comment_actions, community, post};
@@ -55,6 +55,69 @@ impl Comment {
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
 
+  /// Marks the comment as accepted answer, replacing the previously accepted answer of the post.
+  /// The post is solved as long as it has an accepted answer.
+  pub async fn accept_answer(
+    pool: &mut DbPool<'_>,
+    comment_id: CommentId,
+    accepted: bool,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let comment = update(comment::table.find(comment_id))
+            .set(comment::accepted_answer.eq(accepted))
+            .get_result::<Self>(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+
+          if accepted {
+            update(
+              comment::table
+                .filter(comment::post_id.eq(comment.post_id))
+                .filter(comment::id.ne(comment_id))
+                .filter(comment::accepted_answer),
+            )
+            .set(comment::accepted_answer.eq(false))
+            .execute(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+          }
+
+          let has_answer = exists(
+            comment::table
+              .filter(comment::post_id.eq(comment.post_id))
+              .filter(comment::accepted_answer),
+          );
+          update(post::table.find(comment.post_id))
+            .set(post::solved.eq(has_answer))
+            .execute(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+
+          Ok(comment)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// The currently accepted answer to the post, if any.
+  pub async fn read_accepted_answer(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+  ) -> LemmyResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .filter(comment::post_id.eq(post_id))
+      .filter(comment::accepted_answer)
+      .first(conn)
+      .await
+      .optional()
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   pub async fn update_removed_for_creator(
     pool: &mut DbPool<'_>,
     creator_id: PersonId,
@@ -236,6 +299,66 @@ impl Comment {
     }
     Ok(())
   }
+
+  /// Ids of all visible comments in the post, parents before their children.
+  pub async fn list_ap_ids_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    limit: i64,
+  ) -> LemmyResult<Vec<DbUrl>> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .filter(comment::post_id.eq(post_id))
+      .filter(comment::deleted.eq(false))
+      .filter(comment::removed.eq(false))
+      .filter(comment::pending_approval.eq(false))
+      .filter(comment::federation_pending.eq(false))
+      .order_by(comment::path)
+      .limit(limit)
+      .select(comment::ap_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Ids of all visible comments in the community, parents before their children.
+  pub async fn list_ap_ids_for_community(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    limit: i64,
+  ) -> LemmyResult<Vec<DbUrl>> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .inner_join(post::table)
+      .filter(post::community_id.eq(community_id))
+      .filter(comment::deleted.eq(false))
+      .filter(comment::removed.eq(false))
+      .filter(comment::pending_approval.eq(false))
+      .filter(comment::federation_pending.eq(false))
+      .order_by((comment::post_id, comment::path))
+      .limit(limit)
+      .select(comment::ap_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// The time of the newest comment which the person wrote in the community, used for slow mode.
+  pub async fn latest_published_by_creator(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+    creator_id: PersonId,
+  ) -> LemmyResult<Option<DateTime<Utc>>> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .inner_join(post::table)
+      .filter(post::community_id.eq(community_id))
+      .filter(comment::creator_id.eq(creator_id))
+      .select(max(comment::published_at))
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 impl Crud for Comment {
@@ -451,6 +574,8 @@ mod tests {
       report_count: 0,
       unresolved_report_count: 0,
       federation_pending: false,
+      pending_approval: false,
+      accepted_answer: false,
     };
 
     let child_comment_form = CommentInsertForm::new(
@@ -461,6 +586,24 @@ mod tests {
     let inserted_child_comment =
       Comment::create(pool, &child_comment_form, Some(&inserted_comment.path)).await?;
 
+    // The newest comment of the person is used for slow mode
+    let latest_published =
+      Comment::latest_published_by_creator(pool, inserted_community.id, inserted_person.id).await?;
+    assert_eq!(Some(inserted_child_comment.published_at), latest_published);
+
+    // The thread context lists parents before their children
+    let thread = Comment::list_ap_ids_for_post(pool, inserted_post.id, 10).await?;
+    assert_eq!(
+      vec![
+        inserted_comment.ap_id.clone(),
+        inserted_child_comment.ap_id.clone()
+      ],
+      thread
+    );
+    let community_comments =
+      Comment::list_ap_ids_for_community(pool, inserted_community.id, 10).await?;
+    assert_eq!(thread, community_comments);
+
     // Comment Like
     let comment_like_form = CommentLikeForm::new(inserted_person.id, inserted_comment.id, 1);
 
@@ -503,6 +646,56 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_accept_answer() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "asker");
+    let inserted_person = Person::create(pool, &new_person).await?;
+    let new_community = CommunityInsertForm::new(
+      inserted_instance.id,
+      "questions".into(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let inserted_community = Community::create(pool, &new_community).await?;
+    let new_post = PostInsertForm::new(
+      "A question".into(),
+      inserted_person.id,
+      inserted_community.id,
+    );
+    let inserted_post = Post::create(pool, &new_post).await?;
+    let answer_form = CommentInsertForm::new(inserted_person.id, inserted_post.id, "Yes".into());
+    let answer_1 = Comment::create(pool, &answer_form, None).await?;
+    let answer_2 = Comment::create(pool, &answer_form, None).await?;
+
+    // Accepting another answer replaces the first one
+    Comment::accept_answer(pool, answer_1.id, true).await?;
+    let accepted = Comment::accept_answer(pool, answer_2.id, true).await?;
+    assert!(accepted.accepted_answer);
+    assert!(!Comment::read(pool, answer_1.id).await?.accepted_answer);
+    assert!(Post::read(pool, inserted_post.id).await?.solved);
+    let read_accepted = Comment::read_accepted_answer(pool, inserted_post.id).await?;
+    assert_eq!(Some(answer_2.id), read_accepted.map(|c| c.id));
+
+    // Unaccepting a comment which isn't accepted keeps the post solved
+    Comment::accept_answer(pool, answer_1.id, false).await?;
+    assert!(Post::read(pool, inserted_post.id).await?.solved);
+    Comment::accept_answer(pool, answer_2.id, false).await?;
+    assert!(!Post::read(pool, inserted_post.id).await?.solved);
+    assert!(Comment::read_accepted_answer(pool, inserted_post.id)
+      .await?
+      .is_none());
+
+    Community::delete(pool, inserted_community.id).await?;
+    Person::delete(pool, inserted_person.id).await?;
+    Instance::delete(pool, inserted_instance.id).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_aggregates() -> LemmyResult<()> {
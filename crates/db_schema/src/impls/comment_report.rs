@@ -1,6 +1,9 @@
 use crate::{
   newtypes::{CommentId, CommentReportId, PersonId},
-  source::comment_report::{CommentReport, CommentReportForm},
+  source::{
+    comment_report::{CommentReport, CommentReportForm},
+    person::Person,
+  },
   traits::Reportable,
   utils::{get_conn, DbPool},
 };
@@ -9,10 +12,12 @@ use diesel::{
   dsl::{insert_into, update},
   BoolExpressionMethods,
   ExpressionMethods,
+  JoinOnDsl,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
-use lemmy_db_schema_file::schema::comment_report;
+use lemmy_db_schema_file::schema::{comment_report, person};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
 impl Reportable for CommentReport {
@@ -96,3 +101,48 @@ impl Reportable for CommentReport {
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
 }
+
+impl CommentReport {
+  /// Returns the creators of all unresolved reports in the case of the given comment, which is
+  /// either the reports violating instance rules or the other ones.
+  pub async fn list_unresolved_creators(
+    pool: &mut DbPool<'_>,
+    comment_id: CommentId,
+    violates_instance_rules: bool,
+  ) -> LemmyResult<Vec<Person>> {
+    let conn = &mut get_conn(pool).await?;
+    comment_report::table
+      .inner_join(person::table.on(comment_report::creator_id.eq(person::id)))
+      .filter(comment_report::comment_id.eq(comment_id))
+      .filter(comment_report::resolved.eq(false))
+      .filter(comment_report::violates_instance_rules.eq(violates_instance_rules))
+      .select(Person::as_select())
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Resolves the unresolved reports in the case of the given comment.
+  pub async fn resolve_case(
+    pool: &mut DbPool<'_>,
+    comment_id: CommentId,
+    violates_instance_rules: bool,
+    by_resolver_id: PersonId,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    update(
+      comment_report::table
+        .filter(comment_report::comment_id.eq(comment_id))
+        .filter(comment_report::resolved.eq(false))
+        .filter(comment_report::violates_instance_rules.eq(violates_instance_rules)),
+    )
+    .set((
+      comment_report::resolved.eq(true),
+      comment_report::resolver_id.eq(by_resolver_id),
+      comment_report::updated_at.eq(Utc::now()),
+    ))
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
@@ -13,6 +13,7 @@ use crate::{
       CommunityPersonBanForm,
       CommunityUpdateForm,
     },
+    person::Person,
     post::Post,
   },
   traits::{ApubActor, Bannable, Blockable, Crud, Followable},
@@ -21,12 +22,14 @@ use crate::{
     functions::{coalesce, coalesce_2_nullable, lower, random_smallint},
     get_conn,
     DbPool,
+    SITEMAP_LIMIT,
   },
 };
 use chrono::{DateTime, Utc};
 use diesel::{
   dsl::{exists, insert_into, not},
   expression::SelectableHelper,
+  pg::Pg,
   select,
   update,
   BoolExpressionMethods,
@@ -38,7 +41,7 @@ use diesel_async::RunQueryDsl;
 use diesel_uplete::{uplete, UpleteCount};
 use lemmy_db_schema_file::{
   enums::{CommunityFollowerState, CommunityNotificationsMode, CommunityVisibility, ListingType},
-  schema::{comment, community, community_actions, instance, local_user, post},
+  schema::{comment, community, community_actions, instance, local_user, person, post},
 };
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
@@ -259,6 +262,52 @@ impl Community {
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
 
+  /// Local communities which are listed in the sitemap, with the time of their last change.
+  /// Private and unlisted communities are left out, and NSFW communities if configured. Pages
+  /// start at 1.
+  pub async fn list_for_sitemap(
+    pool: &mut DbPool<'_>,
+    page: i64,
+    exclude_nsfw: bool,
+  ) -> LemmyResult<Vec<(DbUrl, DateTime<Utc>)>> {
+    let conn = &mut get_conn(pool).await?;
+    Self::sitemap_query(exclude_nsfw)
+      .select((
+        community::ap_id,
+        coalesce(community::updated_at, community::published_at),
+      ))
+      .order_by(community::id)
+      .offset(page.saturating_sub(1).saturating_mul(SITEMAP_LIMIT))
+      .limit(SITEMAP_LIMIT)
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn count_for_sitemap(pool: &mut DbPool<'_>, exclude_nsfw: bool) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    Self::sitemap_query(exclude_nsfw)
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  fn sitemap_query(exclude_nsfw: bool) -> community::BoxedQuery<'static, Pg> {
+    let mut query = community::table
+      .filter(community::local.eq(true))
+      .filter(Self::hide_removed_and_deleted())
+      .filter(community::visibility.eq_any([
+        CommunityVisibility::Public,
+        CommunityVisibility::LocalOnlyPublic,
+      ]))
+      .into_boxed();
+    if exclude_nsfw {
+      query = query.filter(community::nsfw.eq(false));
+    }
+    query
+  }
+
   #[diesel::dsl::auto_type(no_type_alias)]
   pub fn hide_removed_and_deleted() -> _ {
     community::removed
@@ -463,6 +512,23 @@ impl CommunityActions {
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
+
+  /// Local users which follow the community, including pending follows.
+  pub async fn list_local_followers(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> LemmyResult<Vec<Person>> {
+    let conn = &mut get_conn(pool).await?;
+    community_actions::table
+      .inner_join(person::table.on(community_actions::person_id.eq(person::id)))
+      .filter(community_actions::community_id.eq(community_id))
+      .filter(community_actions::followed_at.is_not_null())
+      .filter(person::local)
+      .select(Person::as_select())
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
 
 impl Bannable for CommunityActions {
@@ -771,6 +837,19 @@ mod tests {
       unresolved_report_count: 0,
       interactions_month: 0,
       local_removed: false,
+      posts_require_approval: false,
+      comments_require_approval: false,
+      approval_max_account_age_days: None,
+      comment_slow_mode_minutes: None,
+      posts_frozen: false,
+      comments_frozen: false,
+      freeze_expires_at: None,
+      moved_to_url: None,
+      moved_from_url: None,
+      previous_public_key: None,
+      key_rotated_at: None,
+      cross_posting_restricted_to_mods: false,
+      question_answer: false,
     };
 
     let community_follower_form = CommunityFollowerForm::new(
@@ -787,6 +866,10 @@ mod tests {
       inserted_community_follower.follow_state
     );
 
+    let local_followers =
+      CommunityActions::list_local_followers(pool, inserted_community.id).await?;
+    assert_eq!(vec![inserted_bobby.clone()], local_followers);
+
     let bobby_moderator_form =
       CommunityModeratorForm::new(inserted_community.id, inserted_bobby.id);
 
@@ -1,18 +1,51 @@
 use crate::{
-  newtypes::InstanceId,
-  source::federation_blocklist::{FederationBlockList, FederationBlockListForm},
+  newtypes::{FederationBlocklistSubscriptionId, InstanceId, PersonId},
+  source::{
+    federation_blocklist::{
+      FederationBlockList,
+      FederationBlockListForm,
+      FederationBlocklistPending,
+      FederationBlocklistPendingForm,
+      FederationBlocklistSubscription,
+      FederationBlocklistSubscriptionInsertForm,
+      FederationBlocklistSubscriptionUpdateForm,
+    },
+    instance::Instance,
+  },
+  traits::Crud,
   utils::{get_conn, DbPool},
 };
-use diesel::{delete, dsl::insert_into, ExpressionMethods, QueryDsl};
+use chrono::Utc;
+use diesel::{
+  delete,
+  dsl::{exists, insert_into, not, select},
+  BoolExpressionMethods,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
 use diesel_async::RunQueryDsl;
-use lemmy_db_schema_file::schema::federation_blocklist;
+use lemmy_db_schema_file::{
+  enums::FederationBlockSeverity,
+  schema::{
+    federation_blocklist,
+    federation_blocklist_pending,
+    federation_blocklist_subscription,
+    instance,
+    person_actions,
+  },
+};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
 impl FederationBlockList {
+  /// Blocks the instance, or updates the existing block.
   pub async fn block(pool: &mut DbPool<'_>, form: &FederationBlockListForm) -> LemmyResult<Self> {
     let conn = &mut get_conn(pool).await?;
     insert_into(federation_blocklist::table)
       .values(form)
+      .on_conflict(federation_blocklist::instance_id)
+      .do_update()
+      .set(form)
       .get_result::<Self>(conn)
       .await
       .with_lemmy_type(LemmyErrorType::CouldntCreate)
@@ -24,4 +57,275 @@ impl FederationBlockList {
       .await
       .with_lemmy_type(LemmyErrorType::Deleted)
   }
+
+  /// All blocked instances together with their block, ordered by domain.
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<(Instance, Self)>> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .inner_join(federation_blocklist::table)
+      .select((Instance::as_select(), Self::as_select()))
+      .order_by(instance::domain)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Returns true if the sender is from a silenced instance, and the recipient doesn't follow the
+  /// sender. Used to drop unsolicited private messages and mentions.
+  pub async fn is_silenced(
+    pool: &mut DbPool<'_>,
+    sender_id: PersonId,
+    sender_instance_id: InstanceId,
+    recipient_id: PersonId,
+  ) -> LemmyResult<bool> {
+    let conn = &mut get_conn(pool).await?;
+    let is_silenced = exists(
+      federation_blocklist::table
+        .filter(federation_blocklist::instance_id.eq(sender_instance_id))
+        .filter(federation_blocklist::severity.eq(FederationBlockSeverity::Silence)),
+    );
+    let is_following = exists(
+      person_actions::table
+        .find((recipient_id, sender_id))
+        .filter(person_actions::follow_pending.eq(false)),
+    );
+
+    select(is_silenced.and(not(is_following)))
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl Crud for FederationBlocklistSubscription {
+  type InsertForm = FederationBlocklistSubscriptionInsertForm;
+  type UpdateForm = FederationBlocklistSubscriptionUpdateForm;
+  type IdType = FederationBlocklistSubscriptionId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_blocklist_subscription::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::AlreadyExists)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: Self::IdType,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(federation_blocklist_subscription::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl FederationBlocklistSubscription {
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_blocklist_subscription::table
+      .order_by(federation_blocklist_subscription::id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl FederationBlocklistPending {
+  /// Stores newly added domains of a subscribed blocklist for review. Domains which are already
+  /// blocked or already waiting for review are skipped. Returns the number of new entries.
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[FederationBlocklistPendingForm],
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    let blocked_domains: Vec<String> = instance::table
+      .inner_join(federation_blocklist::table)
+      .select(instance::domain)
+      .get_results(conn)
+      .await?;
+    let forms = forms
+      .iter()
+      .filter(|f| !blocked_domains.contains(&f.domain))
+      .cloned()
+      .collect::<Vec<_>>();
+    insert_into(federation_blocklist_pending::table)
+      .values(forms)
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// All entries which are waiting for review, for the given subscription or for all.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    subscription_id: Option<FederationBlocklistSubscriptionId>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = federation_blocklist_pending::table
+      .filter(federation_blocklist_pending::discarded_at.is_null())
+      .order_by(federation_blocklist_pending::domain)
+      .into_boxed();
+    if let Some(subscription_id) = subscription_id {
+      query = query.filter(federation_blocklist_pending::subscription_id.eq(subscription_id));
+    }
+    query
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Removes all entries of the subscription which are waiting for review, and returns them.
+  pub async fn take_all(
+    pool: &mut DbPool<'_>,
+    subscription_id: FederationBlocklistSubscriptionId,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    delete(
+      federation_blocklist_pending::table
+        .filter(federation_blocklist_pending::subscription_id.eq(subscription_id))
+        .filter(federation_blocklist_pending::discarded_at.is_null()),
+    )
+    .returning(Self::as_select())
+    .get_results(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Marks all entries of the subscription which are waiting for review as discarded.
+  pub async fn discard_all(
+    pool: &mut DbPool<'_>,
+    subscription_id: FederationBlocklistSubscriptionId,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      federation_blocklist_pending::table
+        .filter(federation_blocklist_pending::subscription_id.eq(subscription_id))
+        .filter(federation_blocklist_pending::discarded_at.is_null()),
+    )
+    .set(federation_blocklist_pending::discarded_at.eq(Utc::now()))
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::{
+    source::person::{Person, PersonActions, PersonFollowerForm, PersonInsertForm},
+    traits::Followable,
+    utils::build_db_pool_for_tests,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_blocklist_severity_and_subscription() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let silenced = Instance::read_or_create(pool, "silenced.xyz".to_string()).await?;
+    let suspended = Instance::read_or_create(pool, "suspended.xyz".to_string()).await?;
+    let other = Instance::read_or_create(pool, "other.xyz".to_string()).await?;
+
+    let form = FederationBlockListForm {
+      instance_id: silenced.id,
+      severity: Some(FederationBlockSeverity::Silence),
+      public_reason: Some("noisy".to_string()),
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await?;
+    let form = FederationBlockListForm {
+      instance_id: suspended.id,
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await?;
+
+    // silenced instances are still federated with
+    assert_eq!(vec![suspended.clone()], Instance::blocklist(pool).await?);
+
+    // private messages and mentions from silenced instances require a follow
+    let sender = Person::create(pool, &PersonInsertForm::test_form(silenced.id, "sender")).await?;
+    let recipient =
+      Person::create(pool, &PersonInsertForm::test_form(other.id, "recipient")).await?;
+    assert!(FederationBlockList::is_silenced(pool, sender.id, silenced.id, recipient.id).await?);
+    let follow_form = PersonFollowerForm::new(sender.id, recipient.id, false);
+    PersonActions::follow(pool, &follow_form).await?;
+    assert!(!FederationBlockList::is_silenced(pool, sender.id, silenced.id, recipient.id).await?);
+    let list = FederationBlockList::list(pool).await?;
+    assert_eq!(2, list.len());
+    assert_eq!(
+      Some("noisy".to_string()),
+      list.first().and_then(|(_, b)| b.public_reason.clone())
+    );
+
+    // blocking again updates the existing block
+    let form = FederationBlockListForm {
+      instance_id: silenced.id,
+      severity: Some(FederationBlockSeverity::Suspend),
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &form).await?;
+    assert_eq!(2, Instance::blocklist(pool).await?.len());
+
+    let form = FederationBlocklistSubscriptionInsertForm::new(
+      "https://example.com/blocklist.csv".to_string(),
+    );
+    let subscription = FederationBlocklistSubscription::create(pool, &form).await?;
+    let pending_form = |domain: &str| FederationBlocklistPendingForm {
+      subscription_id: subscription.id,
+      domain: domain.to_string(),
+      severity: FederationBlockSeverity::Suspend,
+      public_reason: None,
+    };
+
+    // already blocked domains are skipped
+    let forms = vec![pending_form("suspended.xyz"), pending_form("new.xyz")];
+    assert_eq!(
+      1,
+      FederationBlocklistPending::create_many(pool, &forms).await?
+    );
+    let pending = FederationBlocklistPending::list(pool, Some(subscription.id)).await?;
+    assert_eq!(
+      vec!["new.xyz"],
+      pending
+        .iter()
+        .map(|p| p.domain.as_str())
+        .collect::<Vec<_>>()
+    );
+
+    // discarded domains are not suggested again
+    FederationBlocklistPending::discard_all(pool, subscription.id).await?;
+    assert!(FederationBlocklistPending::list(pool, None)
+      .await?
+      .is_empty());
+    let forms = vec![pending_form("new.xyz"), pending_form("other.xyz")];
+    assert_eq!(
+      1,
+      FederationBlocklistPending::create_many(pool, &forms).await?
+    );
+
+    let taken = FederationBlocklistPending::take_all(pool, subscription.id).await?;
+    assert_eq!(
+      vec!["other.xyz"],
+      taken.iter().map(|p| p.domain.as_str()).collect::<Vec<_>>()
+    );
+    assert!(FederationBlocklistPending::list(pool, None)
+      .await?
+      .is_empty());
+
+    FederationBlocklistSubscription::delete(pool, subscription.id).await?;
+    Instance::delete_all(pool).await?;
+
+    Ok(())
+  }
 }
@@ -0,0 +1,84 @@
+use crate::{
+  newtypes::InstanceId,
+  source::federation_queue_command::{FederationQueueCommand, FederationQueueCommandForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::federation_queue_command;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl FederationQueueCommand {
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &FederationQueueCommandForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_queue_command::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// Removes all pending commands for the instance and returns them, oldest first.
+  pub async fn take_all(pool: &mut DbPool<'_>, instance_id: InstanceId) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let mut commands = diesel::delete(
+      federation_queue_command::table.filter(federation_queue_command::instance_id.eq(instance_id)),
+    )
+    .returning(Self::as_select())
+    .get_results::<Self>(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::NotFound)?;
+    commands.sort_by_key(|c| c.id);
+    Ok(commands)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{source::instance::Instance, utils::build_db_pool_for_tests};
+  use lemmy_db_schema_file::enums::FederationQueueCommandType;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn take_all() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+
+    for command in [
+      FederationQueueCommandType::RetryNow,
+      FederationQueueCommandType::Skip,
+    ] {
+      let form = FederationQueueCommandForm {
+        instance_id: instance.id,
+        command,
+        first_activity_id: None,
+        last_activity_id: None,
+      };
+      FederationQueueCommand::create(pool, &form).await?;
+    }
+
+    let commands = FederationQueueCommand::take_all(pool, instance.id).await?;
+    assert_eq!(
+      vec![
+        FederationQueueCommandType::RetryNow,
+        FederationQueueCommandType::Skip
+      ],
+      commands.iter().map(|c| c.command).collect::<Vec<_>>()
+    );
+    // Commands are only handled once
+    assert!(FederationQueueCommand::take_all(pool, instance.id)
+      .await?
+      .is_empty());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
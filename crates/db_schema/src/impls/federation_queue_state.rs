@@ -3,7 +3,14 @@ use crate::{
   source::federation_queue_state::FederationQueueState,
   utils::{get_conn, DbPool},
 };
-use diesel::{ExpressionMethods, Insertable, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel::{
+  insert_into,
+  ExpressionMethods,
+  Insertable,
+  OptionalExtension,
+  QueryDsl,
+  SelectableHelper,
+};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema_file::schema::federation_queue_state;
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
@@ -25,6 +32,7 @@ impl FederationQueueState {
           last_retry_at: None,
           last_successful_id: None, // this value is set to the most current id for new instances
           last_successful_published_time_at: None,
+          paused: false,
         }),
     )
   }
@@ -35,9 +43,47 @@ impl FederationQueueState {
       .insert_into(federation_queue_state::table)
       .on_conflict(federation_queue_state::instance_id)
       .do_update()
-      .set(state)
+      // paused is only changed by admins, so leave it as it is
+      .set((
+        federation_queue_state::last_successful_id.eq(state.last_successful_id),
+        federation_queue_state::fail_count.eq(state.fail_count),
+        federation_queue_state::last_retry_at.eq(state.last_retry_at),
+        federation_queue_state::last_successful_published_time_at
+          .eq(state.last_successful_published_time_at),
+      ))
       .execute(conn)
       .await
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
+
+  /// Pause or resume sending activities to the instance.
+  pub async fn set_paused(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    paused: bool,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_queue_state::table)
+      .values((
+        federation_queue_state::instance_id.eq(instance_id),
+        federation_queue_state::fail_count.eq(0),
+        federation_queue_state::paused.eq(paused),
+      ))
+      .on_conflict(federation_queue_state::instance_id)
+      .do_update()
+      .set(federation_queue_state::paused.eq(paused))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  pub async fn read_paused_instances(pool: &mut DbPool<'_>) -> LemmyResult<Vec<InstanceId>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_queue_state::table
+      .filter(federation_queue_state::paused)
+      .select(federation_queue_state::instance_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
 }
@@ -0,0 +1,224 @@
+use crate::{
+  newtypes::{DbUrl, FederationRelayId},
+  source::federation_relay::{
+    FederationRelay,
+    FederationRelayInsertForm,
+    FederationRelayUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::federation_relay;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
+  CacheLock,
+  CACHE_DURATION_FEDERATION,
+};
+use moka::future::Cache;
+use std::sync::{Arc, LazyLock};
+use url::Url;
+
+/// All relays, which are needed for every incoming activity to check if it comes from a relay.
+static RELAYS: CacheLock<Arc<Vec<FederationRelay>>> = LazyLock::new(|| {
+  Cache::builder()
+    .max_capacity(1)
+    .time_to_live(CACHE_DURATION_FEDERATION)
+    .build()
+});
+
+impl Crud for FederationRelay {
+  type InsertForm = FederationRelayInsertForm;
+  type UpdateForm = FederationRelayUpdateForm;
+  type IdType = FederationRelayId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let relay = insert_into(federation_relay::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::AlreadyExists)?;
+    RELAYS.invalidate_all();
+    Ok(relay)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: Self::IdType,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let relay = diesel::update(federation_relay::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+    RELAYS.invalidate_all();
+    Ok(relay)
+  }
+
+  async fn delete(pool: &mut DbPool<'_>, id: Self::IdType) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    let deleted = diesel::delete(federation_relay::table.find(id))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)?;
+    RELAYS.invalidate_all();
+    Ok(deleted)
+  }
+}
+
+impl FederationRelay {
+  /// Mastodon style relays are subscribed by their inbox url, LitePub style relays by their actor.
+  pub fn is_inbox_url(url: &Url) -> bool {
+    url.path().ends_with("/inbox")
+  }
+
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_relay::table
+      .order_by(federation_relay::id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn read_from_apub_id(
+    pool: &mut DbPool<'_>,
+    ap_id: &DbUrl,
+  ) -> LemmyResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_relay::table
+      .filter(federation_relay::ap_id.eq(ap_id))
+      .first(conn)
+      .await
+      .optional()
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Finds the relay with the given actor id. Mastodon style relays only match once their actor
+  /// is known, see [[FederationRelay::read_from_follow_id]].
+  pub async fn read_for_actor(pool: &mut DbPool<'_>, actor: &Url) -> LemmyResult<Option<Self>> {
+    let relays = RELAYS
+      .try_get_with((), async {
+        Ok::<_, LemmyError>(Arc::new(Self::list(pool).await?))
+      })
+      .await
+      .map_err(|_e: Arc<LemmyError>| LemmyErrorType::NotFound)?;
+    Ok(
+      relays
+        .iter()
+        .find(|r| r.ap_id.as_ref().map(DbUrl::inner) == Some(actor))
+        .cloned(),
+    )
+  }
+
+  /// Finds the relay whose actor is not known yet, and which was sent the given follow.
+  pub async fn read_from_follow_id(
+    pool: &mut DbPool<'_>,
+    follow_id: &DbUrl,
+  ) -> LemmyResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_relay::table
+      .filter(federation_relay::follow_id.eq(follow_id))
+      .filter(federation_relay::ap_id.is_null())
+      .first(conn)
+      .await
+      .optional()
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Inboxes of relays which accepted the follow, and should receive activities of local public
+  /// communities.
+  pub async fn list_publish_inboxes(pool: &mut DbPool<'_>) -> LemmyResult<Vec<DbUrl>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_relay::table
+      .filter(federation_relay::publish)
+      .filter(federation_relay::follow_pending.eq(false))
+      .filter(federation_relay::inbox_url.is_not_null())
+      .select(federation_relay::inbox_url.assume_not_null())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::utils::build_db_pool_for_tests;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_relay_lookup() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let litepub_url: DbUrl = Url::parse("https://litepub.example/actor")?.into();
+    let litepub =
+      FederationRelay::create(pool, &FederationRelayInsertForm::new(litepub_url.clone())).await?;
+    let mastodon_inbox: DbUrl = Url::parse("https://mastodon.example/inbox")?.into();
+    let form = FederationRelayInsertForm {
+      inbox_url: Some(mastodon_inbox.clone()),
+      publish: Some(true),
+      ..FederationRelayInsertForm::new(mastodon_inbox.clone())
+    };
+    let mastodon = FederationRelay::create(pool, &form).await?;
+
+    let found = FederationRelay::read_for_actor(pool, litepub_url.inner()).await?;
+    assert_eq!(Some(litepub.id), found.map(|r| r.id));
+    let other = Url::parse("https://other.example/actor")?;
+    assert_eq!(None, FederationRelay::read_for_actor(pool, &other).await?);
+
+    // Mastodon style relays don't match other actors on the same domain, they are only found by
+    // the follow until the relay actor is known
+    let mastodon_actor = Url::parse("https://mastodon.example/actor")?;
+    assert_eq!(
+      None,
+      FederationRelay::read_for_actor(pool, &mastodon_actor).await?
+    );
+    let follow_id: DbUrl = Url::parse("https://lemmy.example/activities/follow/1")?.into();
+    let form = FederationRelayUpdateForm {
+      follow_id: Some(Some(follow_id.clone())),
+      ..Default::default()
+    };
+    FederationRelay::update(pool, mastodon.id, &form).await?;
+    let found = FederationRelay::read_from_follow_id(pool, &follow_id).await?;
+    assert_eq!(Some(mastodon.id), found.map(|r| r.id));
+
+    // only accepted relays receive activities
+    assert!(FederationRelay::list_publish_inboxes(pool)
+      .await?
+      .is_empty());
+    let form = FederationRelayUpdateForm {
+      ap_id: Some(Some(mastodon_actor.clone().into())),
+      follow_pending: Some(false),
+      ..Default::default()
+    };
+    FederationRelay::update(pool, mastodon.id, &form).await?;
+    assert_eq!(
+      vec![mastodon_inbox],
+      FederationRelay::list_publish_inboxes(pool).await?
+    );
+    let found = FederationRelay::read_for_actor(pool, &mastodon_actor).await?;
+    assert_eq!(Some(mastodon.id), found.map(|r| r.id));
+    let mastodon_user = Url::parse("https://mastodon.example/users/alice")?;
+    assert_eq!(
+      None,
+      FederationRelay::read_for_actor(pool, &mastodon_user).await?
+    );
+    assert_eq!(
+      None,
+      FederationRelay::read_from_follow_id(pool, &follow_id).await?
+    );
+
+    FederationRelay::delete(pool, litepub.id).await?;
+    FederationRelay::delete(pool, mastodon.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,37 @@
+use crate::{
+  newtypes::InstanceId,
+  source::federation_send_error::{FederationSendError, FederationSendErrorForm},
+  utils::{get_conn, limit_fetch, DbPool},
+};
+use diesel::{dsl::insert_into, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::federation_send_error;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl FederationSendError {
+  pub async fn create(pool: &mut DbPool<'_>, form: &FederationSendErrorForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_send_error::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// The latest errors when sending to the instance, newest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    limit: Option<i64>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    federation_send_error::table
+      .filter(federation_send_error::instance_id.eq(instance_id))
+      .order_by(federation_send_error::published_at.desc())
+      .then_order_by(federation_send_error::id.desc())
+      .limit(limit_fetch(limit)?)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
@@ -0,0 +1,125 @@
+use crate::{
+  newtypes::{ImageHash, ImageHashBlockId},
+  source::image_hash_block::{ImageHashBlock, ImageHashBlockForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::image_hash_block;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl ImageHashBlock {
+  /// Blocks the hash. If it is already blocked, only the reason is updated.
+  pub async fn create(pool: &mut DbPool<'_>, form: &ImageHashBlockForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(image_hash_block::table)
+      .values(form)
+      .on_conflict(image_hash_block::hash)
+      .do_update()
+      .set(image_hash_block::reason.eq(&form.reason))
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// Blocks all the hashes, skipping those which are already blocked. Returns the number of newly
+  /// blocked hashes.
+  pub async fn create_many(
+    pool: &mut DbPool<'_>,
+    forms: &[ImageHashBlockForm],
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(image_hash_block::table)
+      .values(forms)
+      .on_conflict(image_hash_block::hash)
+      .do_nothing()
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, id: ImageHashBlockId) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(image_hash_block::table.find(id))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  /// All blocked hashes, newest first.
+  pub async fn list(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    image_hash_block::table
+      .order_by(image_hash_block::published_at.desc())
+      .then_order_by(image_hash_block::id.desc())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn list_hashes(pool: &mut DbPool<'_>) -> LemmyResult<Vec<ImageHash>> {
+    let conn = &mut get_conn(pool).await?;
+    image_hash_block::table
+      .select(image_hash_block::hash)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::utils::build_db_pool_for_tests;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_image_hash_block() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let hash = ImageHash::try_from("f0e1d2c3b4a59687".to_string())?;
+    let forms = vec![
+      ImageHashBlockForm {
+        hash,
+        reason: Some("spam".to_string()),
+        admin_person_id: None,
+      },
+      ImageHashBlockForm {
+        hash: ImageHash(1),
+        reason: None,
+        admin_person_id: None,
+      },
+    ];
+    assert_eq!(2, ImageHashBlock::create_many(pool, &forms).await?);
+    // Blocking the same hashes again has no effect
+    assert_eq!(0, ImageHashBlock::create_many(pool, &forms).await?);
+
+    let updated = ImageHashBlock::create(
+      pool,
+      &ImageHashBlockForm {
+        hash,
+        reason: Some("ads".to_string()),
+        admin_person_id: None,
+      },
+    )
+    .await?;
+    assert_eq!(hash, updated.hash);
+    assert_eq!(Some("ads".to_string()), updated.reason);
+
+    let mut hashes = ImageHashBlock::list_hashes(pool).await?;
+    hashes.sort_by_key(|h| h.0);
+    let mut expected = vec![hash, ImageHash(1)];
+    expected.sort_by_key(|h| h.0);
+    assert_eq!(expected, hashes);
+
+    for block in ImageHashBlock::list(pool).await? {
+      ImageHashBlock::delete(pool, block.id).await?;
+    }
+    assert!(ImageHashBlock::list_hashes(pool).await?.is_empty());
+    Ok(())
+  }
+}
@@ -4,15 +4,16 @@ use crate::{
   utils::{get_conn, DbPool},
 };
 use diesel::{
-  dsl::exists,
+  dsl::{exists, sql},
   insert_into,
   select,
+  sql_types::BigInt,
   BoolExpressionMethods,
   ExpressionMethods,
   QueryDsl,
 };
 use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
-use lemmy_db_schema_file::schema::{image_details, local_image, remote_image};
+use lemmy_db_schema_file::schema::{image_details, local_image, person, remote_image};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 use url::Url;
 
@@ -41,6 +42,45 @@ impl LocalImage {
       .await
   }
 
+  /// Stores the uploaded files if they fit into the person's quota. The person row is locked
+  /// while checking, so that parallel uploads can't exceed the quota together.
+  pub async fn create_within_quota(
+    pool: &mut DbPool<'_>,
+    forms: &[(LocalImageForm, ImageDetailsInsertForm)],
+    person_id: PersonId,
+    quota: Option<i64>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          if let Some(quota) = quota {
+            person::table
+              .find(person_id)
+              .select(person::id)
+              .for_update()
+              .get_result::<PersonId>(conn)
+              .await?;
+            let usage = Self::usage_for_person(&mut conn.into(), person_id).await?;
+            let new_size = forms
+              .iter()
+              .filter_map(|(form, _)| form.file_size)
+              .sum::<i64>();
+            if usage.saturating_add(new_size) > quota {
+              Err(LemmyErrorType::MediaQuotaExceeded)?
+            }
+          }
+          let mut images = vec![];
+          for (form, details_form) in forms {
+            images.push(Self::create(&mut conn.into(), form, details_form).await?);
+          }
+          Ok(images)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
   pub async fn validate_by_alias_and_user(
     pool: &mut DbPool<'_>,
     alias: &str,
@@ -69,6 +109,19 @@ impl LocalImage {
       .with_lemmy_type(LemmyErrorType::Deleted)
   }
 
+  /// Total size of the person's uploads in bytes. Thumbnails which were generated for posts are
+  /// not counted.
+  pub async fn usage_for_person(pool: &mut DbPool<'_>, person_id: PersonId) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    local_image::table
+      .filter(local_image::person_id.eq(person_id))
+      .filter(local_image::thumbnail_for_post_id.is_null())
+      .select(sql::<BigInt>("coalesce(sum(file_size), 0)::bigint"))
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   /// Delete many aliases. Should be used with a pictrs purge.
   pub async fn delete_by_aliases(pool: &mut DbPool<'_>, aliases: &[String]) -> LemmyResult<usize> {
     let conn = &mut get_conn(pool).await?;
@@ -118,4 +171,93 @@ impl ImageDetails {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntCreate)
   }
+
+  pub async fn read(pool: &mut DbPool<'_>, link: &DbUrl) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    image_details::table
+      .find(link)
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn delete_by_links(pool: &mut DbPool<'_>, links: &[DbUrl]) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(image_details::table.filter(image_details::link.eq_any(links)))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  fn upload_forms(
+    person_id: PersonId,
+    files: &[(&str, i64)],
+  ) -> LemmyResult<Vec<(LocalImageForm, ImageDetailsInsertForm)>> {
+    files
+      .iter()
+      .map(|(alias, file_size)| {
+        let form = LocalImageForm {
+          pictrs_alias: alias.to_string(),
+          person_id,
+          thumbnail_for_post_id: None,
+          phash: None,
+          file_size: Some(*file_size),
+        };
+        let details_form = ImageDetailsInsertForm {
+          link: Url::parse(&format!("https://my_domain.tld/api/v4/image/{alias}"))?.into(),
+          width: 100,
+          height: 100,
+          content_type: "image/png".to_string(),
+          blurhash: None,
+        };
+        Ok((form, details_form))
+      })
+      .collect()
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_create_within_quota() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "quota_user");
+    let person = Person::create(pool, &person_form).await?;
+
+    // Nothing is stored if the files don't fit together
+    let forms = upload_forms(person.id, &[("quota_1.png", 60), ("quota_2.png", 60)])?;
+    let res = LocalImage::create_within_quota(pool, &forms, person.id, Some(100)).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::MediaQuotaExceeded));
+    assert_eq!(0, LocalImage::usage_for_person(pool, person.id).await?);
+
+    let forms = upload_forms(person.id, &[("quota_1.png", 60)])?;
+    LocalImage::create_within_quota(pool, &forms, person.id, Some(100)).await?;
+    assert_eq!(60, LocalImage::usage_for_person(pool, person.id).await?);
+
+    let forms = upload_forms(person.id, &[("quota_2.png", 60)])?;
+    let res = LocalImage::create_within_quota(pool, &forms, person.id, Some(100)).await;
+    assert!(res.is_err_and(|e| e.error_type == LemmyErrorType::MediaQuotaExceeded));
+    LocalImage::create_within_quota(pool, &forms, person.id, None).await?;
+    assert_eq!(120, LocalImage::usage_for_person(pool, person.id).await?);
+
+    Instance::delete(pool, instance.id).await?;
+
+    Ok(())
+  }
 }
@@ -0,0 +1,333 @@
+use crate::{
+  newtypes::{InboundActivityId, PaginationCursor},
+  source::{
+    activity::ReceivedActivity,
+    inbound_activity::{
+      inbound_activity_keys as key,
+      InboundActivity,
+      InboundActivityInsertForm,
+      InboundActivityUpdateForm,
+    },
+  },
+  traits::Crud,
+  utils::{get_conn, limit_fetch, now, paginate, DbPool},
+};
+use chrono::{TimeDelta, Utc};
+use diesel::{dsl::insert_into, sql_query, ExpressionMethods, NullableExpressionMethods, QueryDsl};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+use i_love_jesus::SortDirection;
+use lemmy_db_schema_file::schema::inbound_activity;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl Crud for InboundActivity {
+  type InsertForm = InboundActivityInsertForm;
+  type UpdateForm = InboundActivityUpdateForm;
+  type IdType = InboundActivityId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(inbound_activity::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: InboundActivityId,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(inbound_activity::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl InboundActivity {
+  /// Marks the activity as received and stores it in the queue. If the activity was received
+  /// before, nothing is stored and an error is returned.
+  pub async fn enqueue(
+    pool: &mut DbPool<'_>,
+    form: &InboundActivityInsertForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .run_transaction(|conn| {
+        async move {
+          ReceivedActivity::create(&mut conn.into(), &form.ap_id).await?;
+          Self::create(&mut conn.into(), &form).await
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Claims up to `limit` activities which are due for processing, for the duration of `lease`.
+  /// Actors which have claimed activities are skipped entirely, so that concurrent workers never
+  /// process activities of the same actor out of order. If a worker dies while processing, the
+  /// activities are picked up again once the lease is over.
+  ///
+  /// The oldest activities are claimed first, and returned in the order they were received.
+  pub async fn claim_due(
+    pool: &mut DbPool<'_>,
+    limit: i64,
+    lease: TimeDelta,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          // Claims are made one at a time, otherwise two workers could each claim a different
+          // activity of the same actor
+          sql_query("SELECT pg_advisory_xact_lock(hashtext('inbound_activity_claim'))")
+            .execute(conn)
+            .await?;
+
+          let claimed_actors = inbound_activity::table
+            .filter(inbound_activity::claimed_until.gt(now().nullable()))
+            .select(inbound_activity::actor_apub_id);
+          let ids: Vec<InboundActivityId> = inbound_activity::table
+            .filter(inbound_activity::failed_at.is_null())
+            .filter(inbound_activity::next_attempt_at.le(now()))
+            .filter(inbound_activity::actor_apub_id.ne_all(claimed_actors))
+            .order_by((inbound_activity::published_at, inbound_activity::id))
+            .limit(limit)
+            .select(inbound_activity::id)
+            .for_update()
+            .skip_locked()
+            .get_results(conn)
+            .await?;
+
+          let mut claimed =
+            diesel::update(inbound_activity::table.filter(inbound_activity::id.eq_any(ids)))
+              .set(inbound_activity::claimed_until.eq(Utc::now() + lease))
+              .get_results::<Self>(conn)
+              .await
+              .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+          // Updated rows are returned in no particular order
+          claimed.sort_by_key(|a| (a.published_at, a.id.0));
+          Ok(claimed)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Puts an activity from the dead-letter list back into the queue, to be processed right away.
+  pub async fn replay(pool: &mut DbPool<'_>, id: InboundActivityId) -> LemmyResult<Self> {
+    let form = InboundActivityUpdateForm {
+      attempts: Some(0),
+      next_attempt_at: Some(Utc::now()),
+      last_error: Some(None),
+      failed_at: Some(None),
+      claimed_until: Some(None),
+    };
+    Self::update(pool, id, &form).await
+  }
+
+  /// Lists the queue, newest first. With `failed`, only lists the dead-letter list or only the
+  /// activities which are still waiting to be processed.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    failed: Option<bool>,
+    cursor_data: Option<InboundActivity>,
+    page_back: Option<bool>,
+    limit: Option<i64>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let limit = limit_fetch(limit)?;
+    let mut query = inbound_activity::table.limit(limit).into_boxed();
+    query = match failed {
+      Some(true) => query.filter(inbound_activity::failed_at.is_not_null()),
+      Some(false) => query.filter(inbound_activity::failed_at.is_null()),
+      None => query,
+    };
+    let paginated_query = paginate(query, SortDirection::Desc, cursor_data, None, page_back)
+      .then_order_by(key::published_at)
+      .then_order_by(key::id);
+
+    paginated_query
+      .load::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// The number of activities waiting to be processed, and the number in the dead-letter list.
+  pub async fn counts(pool: &mut DbPool<'_>) -> LemmyResult<(i64, i64)> {
+    let conn = &mut get_conn(pool).await?;
+    let pending = inbound_activity::table
+      .filter(inbound_activity::failed_at.is_null())
+      .count()
+      .get_result(conn)
+      .await?;
+    let failed = inbound_activity::table
+      .filter(inbound_activity::failed_at.is_not_null())
+      .count()
+      .get_result(conn)
+      .await?;
+    Ok((pending, failed))
+  }
+
+  pub fn to_cursor(&self) -> PaginationCursor {
+    PaginationCursor::new_single('I', self.id.0)
+  }
+
+  pub async fn from_cursor(cursor: &PaginationCursor, pool: &mut DbPool<'_>) -> LemmyResult<Self> {
+    let [(_, id)] = cursor.prefixes_and_ids()?;
+    Self::read(pool, InboundActivityId(id)).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::build_db_pool_for_tests;
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn claim_and_replay() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let form = InboundActivityInsertForm {
+      ap_id: Url::parse("http://example.com/activity/981")?.into(),
+      actor_apub_id: Url::parse("http://example.com/u/exampleuser")?.into(),
+      data: "{}".to_string(),
+    };
+    let activity = InboundActivity::create(pool, &form).await?;
+
+    // Once claimed, the activity is leased and not returned again
+    let claimed = InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![activity.id],
+      claimed.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+    assert!(InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5))
+      .await?
+      .is_empty());
+
+    // Move it to the dead-letter list
+    let form = InboundActivityUpdateForm {
+      attempts: Some(5),
+      last_error: Some(Some("timeout".to_string())),
+      failed_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    InboundActivity::update(pool, activity.id, &form).await?;
+    assert_eq!((0, 1), InboundActivity::counts(pool).await?);
+    let failed = InboundActivity::list(pool, Some(true), None, None, None).await?;
+    assert_eq!(1, failed.len());
+
+    // After replaying, it can be claimed again right away
+    let replayed = InboundActivity::replay(pool, activity.id).await?;
+    assert_eq!(0, replayed.attempts);
+    assert!(replayed.failed_at.is_none());
+    assert_eq!(
+      1,
+      InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5))
+        .await?
+        .len()
+    );
+
+    InboundActivity::delete(pool, activity.id).await?;
+    assert_eq!((0, 0), InboundActivity::counts(pool).await?);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn claim_lease_and_retry() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let mut activities = vec![];
+    for i in 0..3 {
+      let form = InboundActivityInsertForm {
+        ap_id: Url::parse(&format!("http://example.com/activity/lease-{i}"))?.into(),
+        actor_apub_id: Url::parse("http://example.com/u/exampleuser")?.into(),
+        data: "{}".to_string(),
+      };
+      activities.push(InboundActivity::enqueue(pool, &form).await?.id);
+    }
+
+    // The same activity can't be queued twice
+    let duplicate = InboundActivityInsertForm {
+      ap_id: Url::parse("http://example.com/activity/lease-0")?.into(),
+      actor_apub_id: Url::parse("http://example.com/u/exampleuser")?.into(),
+      data: "{}".to_string(),
+    };
+    assert!(InboundActivity::enqueue(pool, &duplicate).await.is_err());
+    assert_eq!((3, 0), InboundActivity::counts(pool).await?);
+    let &[first, second, third] = activities.as_slice() else {
+      Err(LemmyErrorType::NotFound)?
+    };
+
+    // Activities are claimed oldest first, and returned in the order they were received. Here
+    // the lease is already over, as if the worker died while processing them.
+    let claimed = InboundActivity::claim_due(pool, 2, TimeDelta::minutes(-5)).await?;
+    assert_eq!(
+      vec![first, second],
+      claimed.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+
+    // An expired lease makes the activities available to other workers again
+    let claimed = InboundActivity::claim_due(pool, 2, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![first, second],
+      claimed.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+
+    // While activities of an actor are claimed, its other activities are skipped so that they
+    // are processed in order. Other actors are not affected.
+    let other_actor = InboundActivityInsertForm {
+      ap_id: Url::parse("http://example.com/activity/lease-other")?.into(),
+      actor_apub_id: Url::parse("http://example.com/u/otheruser")?.into(),
+      data: "{}".to_string(),
+    };
+    let other_actor = InboundActivity::enqueue(pool, &other_actor).await?.id;
+    let claimed_other = InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![other_actor],
+      claimed_other.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+    InboundActivity::delete(pool, other_actor).await?;
+
+    // A failed activity is only retried once its next attempt is due, and the remaining activity
+    // of the actor can be claimed once the previous ones are done
+    let form = InboundActivityUpdateForm {
+      attempts: Some(1),
+      next_attempt_at: Some(Utc::now() - TimeDelta::seconds(1)),
+      last_error: Some(Some("fetch failed".to_string())),
+      claimed_until: Some(None),
+      ..Default::default()
+    };
+    InboundActivity::update(pool, first, &form).await?;
+    InboundActivity::delete(pool, second).await?;
+    let claimed = InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![first, third],
+      claimed.iter().map(|a| a.id).collect::<Vec<_>>()
+    );
+    assert_eq!(Some(1), claimed.first().map(|a| a.attempts));
+    assert!(InboundActivity::claim_due(pool, 10, TimeDelta::minutes(5))
+      .await?
+      .is_empty());
+
+    for id in [first, third] {
+      InboundActivity::delete(pool, id).await?;
+    }
+    assert_eq!((0, 0), InboundActivity::counts(pool).await?);
+
+    Ok(())
+  }
+}
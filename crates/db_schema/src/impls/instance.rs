@@ -2,6 +2,7 @@ use crate::{
   diesel::dsl::IntervalDsl,
   newtypes::{InstanceId, PersonId},
   source::{
+    federation_blocklist::FederationBlockList,
     federation_queue_state::FederationQueueState,
     instance::{
       Instance,
@@ -23,7 +24,9 @@ use crate::{
 use chrono::Utc;
 use diesel::{
   dsl::{count_star, exists, insert_into, not, select},
+  BoolExpressionMethods,
   ExpressionMethods,
+  JoinOnDsl,
   NullableExpressionMethods,
   OptionalExtension,
   QueryDsl,
@@ -31,14 +34,17 @@ use diesel::{
 };
 use diesel_async::RunQueryDsl;
 use diesel_uplete::{uplete, UpleteCount};
-use lemmy_db_schema_file::schema::{
-  federation_allowlist,
-  federation_blocklist,
-  federation_queue_state,
-  instance,
-  instance_actions,
-  local_site,
-  site,
+use lemmy_db_schema_file::{
+  enums::FederationBlockSeverity,
+  schema::{
+    federation_allowlist,
+    federation_blocklist,
+    federation_queue_state,
+    instance,
+    instance_actions,
+    local_site,
+    site,
+  },
 };
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
@@ -139,10 +145,13 @@ impl Instance {
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
 
+  /// Instances which are suspended, so that no activities are exchanged with them. Silenced
+  /// instances are not included.
   pub async fn blocklist(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
     let conn = &mut get_conn(pool).await?;
     instance::table
       .inner_join(federation_blocklist::table)
+      .filter(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend))
       .select(Self::as_select())
       .get_results(conn)
       .await
@@ -175,8 +184,15 @@ impl Instance {
         .await
         .with_lemmy_type(LemmyErrorType::NotFound)
     } else {
+      // silenced instances are still federated with
       instance::table
-        .left_join(federation_blocklist::table)
+        .left_join(
+          federation_blocklist::table.on(
+            federation_blocklist::instance_id
+              .eq(instance::id)
+              .and(federation_blocklist::severity.eq(FederationBlockSeverity::Suspend)),
+          ),
+        )
         .select((
           Self::as_select(),
           federation_blocklist::instance_id.nullable().is_null(),
@@ -189,10 +205,17 @@ impl Instance {
     }
   }
 
-  /// returns (instance, blocked, allowed, fed queue state) tuples
+  /// returns (instance, fed queue state, block, allowed) tuples
   pub async fn read_all_with_fed_state(
     pool: &mut DbPool<'_>,
-  ) -> LemmyResult<Vec<(Self, Option<FederationQueueState>, bool, bool)>> {
+  ) -> LemmyResult<
+    Vec<(
+      Self,
+      Option<FederationQueueState>,
+      Option<FederationBlockList>,
+      bool,
+    )>,
+  > {
     let conn = &mut get_conn(pool).await?;
     instance::table
       // omit instance representing the local site
@@ -204,7 +227,7 @@ impl Instance {
       .select((
         Self::as_select(),
         Option::<FederationQueueState>::as_select(),
-        federation_blocklist::instance_id.nullable().is_not_null(),
+        Option::<FederationBlockList>::as_select(),
         federation_allowlist::instance_id.nullable().is_not_null(),
       ))
       .get_results(conn)
@@ -1,5 +1,7 @@
 pub mod activity;
+pub mod actor_key_rotation;
 pub mod actor_language;
+pub mod automod_rule;
 pub mod captcha_answer;
 pub mod comment;
 pub mod comment_report;
@@ -10,8 +12,13 @@ pub mod custom_emoji;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+pub mod federation_queue_command;
 pub mod federation_queue_state;
+pub mod federation_relay;
+pub mod federation_send_error;
+pub mod image_hash_block;
 pub mod images;
+pub mod inbound_activity;
 pub mod instance;
 pub mod keyword_block;
 pub mod language;
@@ -28,6 +35,7 @@ pub mod oauth_provider;
 pub mod password_reset_request;
 pub mod person;
 pub mod post;
+pub mod post_gallery;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
@@ -36,3 +44,5 @@ pub mod secret;
 pub mod site;
 pub mod tag;
 pub mod tagline;
+pub mod video;
+pub mod websub_subscription;
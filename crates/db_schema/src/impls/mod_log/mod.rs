@@ -86,6 +86,8 @@ mod tests {
       post_id: inserted_post.id,
       locked: None,
       reason: None,
+      expires_at: None,
+      max_account_age_days: None,
     };
     let inserted_mod_lock_post = ModLockPost::create(pool, &mod_lock_post_form).await?;
     let read_mod_lock_post = ModLockPost::read(pool, inserted_mod_lock_post.id).await?;
@@ -96,6 +98,8 @@ mod tests {
       locked: true,
       reason: None,
       published_at: inserted_mod_lock_post.published_at,
+      expires_at: None,
+      max_account_age_days: None,
     };
 
     // feature post
@@ -1,23 +1,32 @@
 use crate::{
   newtypes::{
+    AutomodRuleId,
+    CommentId,
     ModAddToCommunityId,
+    ModAutomodActionId,
     ModBanFromCommunityId,
     ModChangeCommunityVisibilityId,
     ModFeaturePostId,
+    ModFreezeCommunityId,
     ModLockPostId,
     ModRemoveCommentId,
     ModRemovePostId,
     ModTransferCommunityId,
+    PostId,
   },
   source::mod_log::moderator::{
     ModAddToCommunity,
     ModAddToCommunityForm,
+    ModAutomodAction,
+    ModAutomodActionForm,
     ModBanFromCommunity,
     ModBanFromCommunityForm,
     ModChangeCommunityVisibility,
     ModChangeCommunityVisibilityForm,
     ModFeaturePost,
     ModFeaturePostForm,
+    ModFreezeCommunity,
+    ModFreezeCommunityForm,
     ModLockPost,
     ModLockPostForm,
     ModRemoveComment,
@@ -30,13 +39,19 @@ use crate::{
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use diesel::{dsl::insert_into, QueryDsl};
+use diesel::{
+  dsl::{exists, insert_into, select},
+  ExpressionMethods,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema_file::schema::{
   mod_add_to_community,
+  mod_automod_action,
   mod_ban_from_community,
   mod_change_community_visibility,
   mod_feature_post,
+  mod_freeze_community,
   mod_lock_post,
   mod_remove_comment,
   mod_remove_post,
@@ -295,3 +310,85 @@ impl Crud for ModTransferCommunity {
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
 }
+
+impl Crud for ModAutomodAction {
+  type InsertForm = ModAutomodActionForm;
+  type UpdateForm = ModAutomodActionForm;
+  type IdType = ModAutomodActionId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_automod_action::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: Self::IdType,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_automod_action::table.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl Crud for ModFreezeCommunity {
+  type InsertForm = ModFreezeCommunityForm;
+  type UpdateForm = ModFreezeCommunityForm;
+  type IdType = ModFreezeCommunityId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_freeze_community::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: Self::IdType,
+    form: &Self::UpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_freeze_community::table.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
+impl ModAutomodAction {
+  /// Whether the given rule already acted on this post or comment. Used so that rules are only
+  /// applied once, even if the same object is received multiple times over federation.
+  pub async fn exists_for_rule(
+    pool: &mut DbPool<'_>,
+    rule_id: AutomodRuleId,
+    post_id: PostId,
+    comment_id: Option<CommentId>,
+  ) -> LemmyResult<bool> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = mod_automod_action::table
+      .filter(mod_automod_action::automod_rule_id.eq(rule_id))
+      .filter(mod_automod_action::post_id.eq(post_id))
+      .into_boxed();
+    query = if let Some(comment_id) = comment_id {
+      query.filter(mod_automod_action::comment_id.eq(comment_id))
+    } else {
+      query.filter(mod_automod_action::comment_id.is_null())
+    };
+    select(exists(query))
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
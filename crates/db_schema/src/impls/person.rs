@@ -1,6 +1,6 @@
 use crate::{
   diesel::{BoolExpressionMethods, NullableExpressionMethods, OptionalExtension},
-  newtypes::{CommunityId, DbUrl, InstanceId, LocalUserId, PersonId},
+  newtypes::{DbUrl, InstanceId, LocalUserId, PersonId},
   source::person::{
     Person,
     PersonActions,
@@ -11,24 +11,28 @@ use crate::{
     PersonUpdateForm,
   },
   traits::{ApubActor, Blockable, Crud, Followable},
-  utils::{format_actor_url, functions::lower, get_conn, DbPool},
+  utils::{
+    format_actor_url,
+    functions::{coalesce, lower},
+    get_conn,
+    DbPool,
+    SITEMAP_LIMIT,
+  },
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::{
   dsl::{exists, insert_into, not, select},
   expression::SelectableHelper,
+  pg::Pg,
   ExpressionMethods,
   JoinOnDsl,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
 use diesel_uplete::{uplete, UpleteCount};
-use lemmy_db_schema_file::schema::{
-  instance,
-  instance_actions,
-  local_user,
-  person,
-  person_actions,
+use lemmy_db_schema_file::{
+  enums::PersonNotificationsMode,
+  schema::{instance, instance_actions, local_user, person, person_actions},
 };
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
@@ -148,6 +152,49 @@ impl Person {
     .then_some(())
     .ok_or(LemmyErrorType::UsernameAlreadyTaken.into())
   }
+
+  /// Active local users which are listed in the sitemap, with the time of their last profile
+  /// change. Users without any posts or comments, and deleted or banned users are left out. Pages
+  /// start at 1.
+  pub async fn list_for_sitemap(
+    pool: &mut DbPool<'_>,
+    page: i64,
+  ) -> LemmyResult<Vec<(DbUrl, DateTime<Utc>)>> {
+    let conn = &mut get_conn(pool).await?;
+    Self::sitemap_query()
+      .select((
+        person::ap_id,
+        coalesce(person::updated_at, person::published_at),
+      ))
+      .order_by(person::id)
+      .offset(page.saturating_sub(1).saturating_mul(SITEMAP_LIMIT))
+      .limit(SITEMAP_LIMIT)
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn count_for_sitemap(pool: &mut DbPool<'_>) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    Self::sitemap_query()
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  fn sitemap_query() -> person::BoxedQuery<'static, Pg> {
+    let banned = instance_actions::table
+      .filter(instance_actions::person_id.eq(person::id))
+      .filter(instance_actions::instance_id.eq(person::instance_id))
+      .filter(instance_actions::received_ban_at.is_not_null());
+    person::table
+      .filter(person::local.eq(true))
+      .filter(person::deleted.eq(false))
+      .filter(person::post_count.gt(0).or(person::comment_count.gt(0)))
+      .filter(not(exists(banned)))
+      .into_boxed()
+  }
 }
 
 impl PersonInsertForm {
@@ -241,9 +288,21 @@ impl Followable for PersonActions {
       .with_lemmy_type(LemmyErrorType::AlreadyExists)
   }
 
-  /// Currently no user following
-  async fn follow_accepted(_: &mut DbPool<'_>, _: CommunityId, _: PersonId) -> LemmyResult<Self> {
-    Err(LemmyErrorType::NotFound.into())
+  async fn follow_accepted(
+    pool: &mut DbPool<'_>,
+    target_id: PersonId,
+    person_id: PersonId,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let find_action = person_actions::table
+      .find((person_id, target_id))
+      .filter(person_actions::follow_pending.is_not_null());
+    diesel::update(find_action)
+      .set(person_actions::follow_pending.eq(Some(false)))
+      .returning(Self::as_select())
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
 
   async fn unfollow(
@@ -255,6 +314,7 @@ impl Followable for PersonActions {
     uplete(person_actions::table.find((person_id, target_id)))
       .set_null(person_actions::followed_at)
       .set_null(person_actions::follow_pending)
+      .set_null(person_actions::notifications)
       .get_result(conn)
       .await
       .with_lemmy_type(LemmyErrorType::AlreadyExists)
@@ -329,6 +389,72 @@ impl Blockable for PersonActions {
 }
 
 impl PersonActions {
+  /// Number of persons following the given person, for the followers collection.
+  pub async fn count_followers(pool: &mut DbPool<'_>, person_id: PersonId) -> LemmyResult<i32> {
+    let conn = &mut get_conn(pool).await?;
+    person_actions::table
+      .filter(person_actions::target_id.eq(person_id))
+      .filter(person_actions::follow_pending.eq(false))
+      .count()
+      .get_result::<i64>(conn)
+      .await
+      .map(i32::try_from)?
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Local users who follow the given person and want to be notified about their new posts, or
+  /// also about their new comments if `is_post` is false.
+  pub async fn list_subscribers(
+    creator_id: PersonId,
+    is_post: bool,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Vec<PersonId>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = person_actions::table
+      .inner_join(local_user::table.on(person_actions::person_id.eq(local_user::person_id)))
+      .filter(person_actions::target_id.eq(creator_id))
+      .filter(person_actions::follow_pending.eq(false))
+      .select(local_user::person_id)
+      .into_boxed();
+    if is_post {
+      query = query.filter(
+        person_actions::notifications
+          .eq(PersonNotificationsMode::AllPosts)
+          .or(person_actions::notifications.eq(PersonNotificationsMode::AllPostsAndComments)),
+      );
+    } else {
+      query = query
+        .filter(person_actions::notifications.eq(PersonNotificationsMode::AllPostsAndComments));
+    }
+    query
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Changes notification settings for a followed person. Only works for existing follows.
+  pub async fn update_notification_state(
+    target_id: PersonId,
+    person_id: PersonId,
+    new_state: PersonNotificationsMode,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+    let find_action = person_actions::table
+      .find((person_id, target_id))
+      .filter(person_actions::followed_at.is_not_null());
+    let updated = diesel::update(find_action)
+      .set(person_actions::notifications.eq(new_state))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+    if updated == 0 {
+      Err(LemmyErrorType::NotFound)?
+    }
+    Ok(())
+  }
+
   pub async fn follower_inboxes(
     pool: &mut DbPool<'_>,
     for_person_id: PersonId,
@@ -466,6 +592,7 @@ mod tests {
     utils::build_db_pool_for_tests,
   };
   use diesel_uplete::UpleteCount;
+  use lemmy_db_schema_file::enums::PersonNotificationsMode;
   use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -505,6 +632,8 @@ mod tests {
       post_score: 0,
       comment_count: 0,
       comment_score: 0,
+      previous_public_key: None,
+      key_rotated_at: None,
     };
 
     let read_person = Person::read(pool, inserted_person.id).await?;
@@ -538,11 +667,33 @@ mod tests {
     let person_form_2 = PersonInsertForm::test_form(inserted_instance.id, "michele");
     let person_2 = Person::create(pool, &person_form_2).await?;
 
-    let follow_form = PersonFollowerForm::new(person_1.id, person_2.id, false);
+    let follow_form = PersonFollowerForm::new(person_1.id, person_2.id, true);
     let person_follower = PersonActions::follow(pool, &follow_form).await?;
     assert_eq!(person_1.id, person_follower.target_id);
     assert_eq!(person_2.id, person_follower.person_id);
+    assert!(person_follower.follow_pending.is_some_and(|x| x));
+    assert_eq!(0, PersonActions::count_followers(pool, person_1.id).await?);
+
+    let person_follower = PersonActions::follow_accepted(pool, person_1.id, person_2.id).await?;
     assert!(person_follower.follow_pending.is_some_and(|x| !x));
+    assert_eq!(1, PersonActions::count_followers(pool, person_1.id).await?);
+
+    PersonActions::update_notification_state(
+      person_1.id,
+      person_2.id,
+      PersonNotificationsMode::AllPosts,
+      pool,
+    )
+    .await?;
+    // Notifications can only be changed for followed persons
+    assert!(PersonActions::update_notification_state(
+      person_2.id,
+      person_1.id,
+      PersonNotificationsMode::AllPosts,
+      pool,
+    )
+    .await
+    .is_err());
 
     let followers = PersonActions::follower_inboxes(pool, person_1.id).await?;
     assert_eq!(vec![person_2.inbox_url], followers);
@@ -13,21 +13,21 @@ use crate::{
   },
   traits::{Crud, Likeable, Saveable},
   utils::{
-    functions::{coalesce, hot_rank, scaled_rank},
+    functions::{coalesce, date_trunc, greatest, hot_rank, scaled_rank},
     get_conn,
     now,
     validate_like,
     DbPool,
     DELETED_REPLACEMENT_TEXT,
     FETCH_LIMIT_MAX,
-    SITEMAP_DAYS,
     SITEMAP_LIMIT,
   },
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Months, Utc};
 use diesel::{
-  dsl::{count, insert_into, not, update},
+  dsl::{count, insert_into, not, update, InnerJoin, IntoBoxed},
   expression::SelectableHelper,
+  pg::Pg,
   BoolExpressionMethods,
   DecoratableTarget,
   ExpressionMethods,
@@ -39,7 +39,7 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use diesel_uplete::{uplete, UpleteCount};
 use lemmy_db_schema_file::{
-  enums::PostNotificationsMode,
+  enums::{CommunityVisibility, PostNotificationsMode},
   schema::{community, local_user, person, post, post_actions},
 };
 use lemmy_utils::{
@@ -120,19 +120,137 @@ impl Post {
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
 
-  pub async fn list_for_sitemap(
+  /// Ids of all visible posts in the community, oldest first.
+  pub async fn list_ap_ids_for_community(
     pool: &mut DbPool<'_>,
-  ) -> LemmyResult<Vec<(DbUrl, chrono::DateTime<Utc>)>> {
+    community_id: CommunityId,
+    limit: i64,
+  ) -> LemmyResult<Vec<DbUrl>> {
     let conn = &mut get_conn(pool).await?;
     post::table
-      .select((post::ap_id, coalesce(post::updated_at, post::published_at)))
+      .filter(post::community_id.eq(community_id))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .filter(post::pending_approval.eq(false))
+      .filter(post::federation_pending.eq(false))
+      .filter(post::scheduled_publish_time_at.is_null())
+      .order_by(post::published_at)
+      .limit(limit)
+      .select(post::ap_id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Moves the post with all its comments into another community. Used when a community is
+  /// imported from the archive of its old instance.
+  pub async fn move_to_community(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    community_id: CommunityId,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    update(post::table.find(post_id))
+      .set(post::community_id.eq(community_id))
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Local posts which are listed in the sitemap. Posts in private or unlisted communities are
+  /// left out, and NSFW posts if configured.
+  fn sitemap_query(
+    exclude_nsfw: bool,
+  ) -> IntoBoxed<'static, InnerJoin<post::table, community::table>, Pg> {
+    let mut query = post::table
+      .inner_join(community::table)
       .filter(post::local.eq(true))
       .filter(post::deleted.eq(false))
       .filter(post::removed.eq(false))
-      .filter(post::published_at.ge(Utc::now().naive_utc() - SITEMAP_DAYS))
-      .order(post::published_at.desc())
+      .filter(post::pending_approval.eq(false))
+      .filter(post::scheduled_publish_time_at.is_null())
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::visibility.eq_any([
+        CommunityVisibility::Public,
+        CommunityVisibility::LocalOnlyPublic,
+      ]))
+      .into_boxed();
+    if exclude_nsfw {
+      query = query
+        .filter(post::nsfw.eq(false))
+        .filter(community::nsfw.eq(false));
+    }
+    query
+  }
+
+  /// The months with posts in the sitemap, and the last modification of a post in each month.
+  pub async fn list_sitemap_months(
+    pool: &mut DbPool<'_>,
+    exclude_nsfw: bool,
+  ) -> LemmyResult<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+    let conn = &mut get_conn(pool).await?;
+    let month = || date_trunc("month", post::published_at, "UTC");
+    let last_modified = || {
+      greatest(
+        coalesce(post::updated_at, post::published_at),
+        post::newest_comment_time_at,
+      )
+    };
+    Self::sitemap_query(exclude_nsfw)
+      .select((month(), last_modified()))
+      .distinct_on(month())
+      .order_by((month(), last_modified().desc()))
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// The number of posts in the sitemap which were published in the given month.
+  pub async fn count_for_sitemap(
+    pool: &mut DbPool<'_>,
+    month_start: DateTime<Utc>,
+    exclude_nsfw: bool,
+  ) -> LemmyResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+    let month_end = month_start
+      .checked_add_months(Months::new(1))
+      .ok_or(LemmyErrorType::NotFound)?;
+    Self::sitemap_query(exclude_nsfw)
+      .filter(post::published_at.ge(month_start))
+      .filter(post::published_at.lt(month_end))
+      .count()
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// A page of the posts in the sitemap which were published in the given month, with the time of
+  /// their last edit or comment. Pages start at 1.
+  pub async fn list_for_sitemap(
+    pool: &mut DbPool<'_>,
+    month_start: DateTime<Utc>,
+    page: i64,
+    exclude_nsfw: bool,
+  ) -> LemmyResult<Vec<(DbUrl, DateTime<Utc>)>> {
+    let conn = &mut get_conn(pool).await?;
+    let month_end = month_start
+      .checked_add_months(Months::new(1))
+      .ok_or(LemmyErrorType::NotFound)?;
+    Self::sitemap_query(exclude_nsfw)
+      .select((
+        post::ap_id,
+        greatest(
+          coalesce(post::updated_at, post::published_at),
+          post::newest_comment_time_at,
+        ),
+      ))
+      .filter(post::published_at.ge(month_start))
+      .filter(post::published_at.lt(month_end))
+      .order_by((post::published_at, post::id))
+      .offset(page.saturating_sub(1).saturating_mul(SITEMAP_LIMIT))
       .limit(SITEMAP_LIMIT)
-      .load::<(DbUrl, chrono::DateTime<Utc>)>(conn)
+      .load::<(DbUrl, DateTime<Utc>)>(conn)
       .await
       .with_lemmy_type(LemmyErrorType::NotFound)
   }
@@ -325,6 +443,34 @@ impl Post {
       .await
       .with_lemmy_type(LemmyErrorType::CouldntUpdate)
   }
+
+  /// The original post of a cross-post, or the post itself if it isn't a cross-post.
+  pub fn cross_post_original_id(&self) -> PostId {
+    self.cross_post_of_id.unwrap_or(self.id)
+  }
+
+  /// Ids of the original post and all other cross-posts of it, oldest first. The post itself is
+  /// not included.
+  pub async fn list_cross_post_ids(&self, pool: &mut DbPool<'_>) -> LemmyResult<Vec<PostId>> {
+    let conn = &mut get_conn(pool).await?;
+    let original_id = self.cross_post_original_id();
+    post::table
+      .filter(
+        post::id
+          .eq(original_id)
+          .or(post::cross_post_of_id.eq(original_id)),
+      )
+      .filter(post::id.ne(self.id))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .order_by(post::published_at)
+      .limit(FETCH_LIMIT_MAX.try_into()?)
+      .select(post::id)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
   pub fn local_url(&self, settings: &Settings) -> LemmyResult<Url> {
     let domain = settings.get_protocol_and_hostname();
     Ok(Url::parse(&format!("{domain}/post/{}", self.id))?)
@@ -341,6 +487,25 @@ impl Post {
     }
     Ok(())
   }
+
+  /// Sets the thumbnail of posts linking to `url` which don't have one yet. Used once the poster
+  /// frame of an uploaded video is available.
+  pub async fn set_missing_thumbnail(
+    pool: &mut DbPool<'_>,
+    url: &DbUrl,
+    thumbnail_url: &DbUrl,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    update(
+      post::table
+        .filter(post::url.eq(url))
+        .filter(post::thumbnail_url.is_null()),
+    )
+    .set(post::thumbnail_url.eq(thumbnail_url))
+    .get_results(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
 }
 
 impl Likeable for PostActions {
@@ -609,6 +774,7 @@ mod tests {
   };
   use chrono::DateTime;
   use diesel_uplete::UpleteCount;
+  use lemmy_db_schema_file::enums::CommentDisplayMode;
   use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -693,6 +859,13 @@ mod tests {
       scaled_rank: RANK_DEFAULT,
       unresolved_report_count: 0,
       federation_pending: false,
+      pending_approval: false,
+      lock_expires_at: None,
+      lock_max_account_age_days: None,
+      comment_slow_mode_minutes: None,
+      cross_post_of_id: None,
+      comment_display_mode: CommentDisplayMode::Threaded,
+      solved: false,
     };
 
     // Post Like
@@ -725,6 +898,23 @@ mod tests {
     let scheduled_post_count = Post::user_scheduled_post_count(inserted_person.id, pool).await?;
     assert_eq!(1, scheduled_post_count);
 
+    // Scheduled posts are not listed
+    let post_ap_ids = Post::list_ap_ids_for_community(pool, inserted_community.id, 10).await?;
+    assert_eq!(
+      vec![inserted_post.ap_id.clone(), inserted_post2.ap_id.clone()],
+      post_ap_ids
+    );
+
+    let other_community = CommunityInsertForm::new(
+      inserted_instance.id,
+      "test community_4".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let other_community = Community::create(pool, &other_community).await?;
+    let moved_post = Post::move_to_community(pool, inserted_post2.id, other_community.id).await?;
+    assert_eq!(other_community.id, moved_post.community_id);
+
     let like_removed = PostActions::remove_like(pool, inserted_person.id, inserted_post.id).await?;
     assert_eq!(UpleteCount::only_updated(1), like_removed);
     let saved_removed = PostActions::unsave(pool, &post_saved_form).await?;
@@ -744,6 +934,7 @@ mod tests {
 
     assert_eq!(3, num_deleted);
     Community::delete(pool, inserted_community.id).await?;
+    Community::delete(pool, other_community.id).await?;
     Person::delete(pool, inserted_person.id).await?;
     Instance::delete(pool, inserted_instance.id).await?;
 
@@ -753,6 +944,63 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_cross_posts() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "cross_poster");
+    let inserted_person = Person::create(pool, &new_person).await?;
+    let new_community = CommunityInsertForm::new(
+      inserted_instance.id,
+      "test community_cross".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let inserted_community = Community::create(pool, &new_community).await?;
+
+    let new_post =
+      PostInsertForm::new("Original".into(), inserted_person.id, inserted_community.id);
+    let original = Post::create(pool, &new_post).await?;
+    let new_post = PostInsertForm {
+      cross_post_of_id: Some(original.id),
+      ..PostInsertForm::new("Cross 1".into(), inserted_person.id, inserted_community.id)
+    };
+    let cross_1 = Post::create(pool, &new_post).await?;
+    let new_post = PostInsertForm {
+      cross_post_of_id: Some(original.id),
+      ..PostInsertForm::new("Cross 2".into(), inserted_person.id, inserted_community.id)
+    };
+    let cross_2 = Post::create(pool, &new_post).await?;
+
+    assert_eq!(
+      vec![cross_1.id, cross_2.id],
+      original.list_cross_post_ids(pool).await?
+    );
+    assert_eq!(
+      vec![original.id, cross_2.id],
+      cross_1.list_cross_post_ids(pool).await?
+    );
+
+    // Removed cross-posts are not listed
+    let form = PostUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, cross_2.id, &form).await?;
+    assert_eq!(vec![original.id], cross_1.list_cross_post_ids(pool).await?);
+
+    // The link is removed when the original is purged
+    Post::delete(pool, original.id).await?;
+    let cross_1 = Post::read(pool, cross_1.id).await?;
+    assert_eq!(None, cross_1.cross_post_of_id);
+
+    Instance::delete(pool, inserted_instance.id).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_aggregates() -> LemmyResult<()> {
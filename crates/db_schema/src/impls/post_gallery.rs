@@ -0,0 +1,162 @@
+use crate::{
+  diesel::SelectableHelper,
+  newtypes::PostId,
+  source::post_gallery::{PostGalleryItem, PostGalleryItemForm, PostGalleryView},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  delete,
+  deserialize::FromSql,
+  insert_into,
+  pg::{Pg, PgValue},
+  serialize::ToSql,
+  sql_types::{Json, Nullable},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+use lemmy_db_schema_file::schema::post_gallery_item;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl PostGalleryItem {
+  /// Replaces all images of the post's gallery. An empty list turns it back into a regular post.
+  pub async fn replace(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    forms: &[PostGalleryItemForm],
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          delete(post_gallery_item::table.filter(post_gallery_item::post_id.eq(post_id)))
+            .execute(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::Deleted)?;
+          if forms.is_empty() {
+            return Ok(Vec::new());
+          }
+
+          insert_into(post_gallery_item::table)
+            .values(forms)
+            .returning(Self::as_select())
+            .get_results(conn)
+            .await
+            .with_lemmy_type(LemmyErrorType::CouldntCreate)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    post_gallery_item::table
+      .filter(post_gallery_item::post_id.eq(post_id))
+      .order_by(post_gallery_item::position)
+      .select(Self::as_select())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl FromSql<Nullable<Json>, Pg> for PostGalleryView {
+  fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+    let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+    Ok(serde_json::from_value::<PostGalleryView>(value)?)
+  }
+  fn from_nullable_sql(
+    bytes: Option<<Pg as diesel::backend::Backend>::RawValue<'_>>,
+  ) -> diesel::deserialize::Result<Self> {
+    match bytes {
+      Some(bytes) => Self::from_sql(bytes),
+      None => Ok(Self(vec![])),
+    }
+  }
+}
+
+impl ToSql<Nullable<Json>, Pg> for PostGalleryView {
+  fn to_sql(&self, out: &mut diesel::serialize::Output<Pg>) -> diesel::serialize::Result {
+    let value = serde_json::to_value(self)?;
+    <serde_json::Value as ToSql<Json, Pg>>::to_sql(&value, &mut out.reborrow())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_replace_gallery() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "gallery_creator");
+    let person = Person::create(pool, &person_form).await?;
+    let community_form = CommunityInsertForm::new(
+      instance.id,
+      "gallery_community".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::new("gallery".to_string(), person.id, community.id);
+    let post = Post::create(pool, &post_form).await?;
+
+    let form = |position: i32, caption: Option<&str>| -> LemmyResult<PostGalleryItemForm> {
+      Ok(PostGalleryItemForm {
+        post_id: post.id,
+        position,
+        url: Url::parse(&format!(
+          "https://my_domain.tld/api/v4/image/{position}.jpg"
+        ))?
+        .into(),
+        url_content_type: Some("image/jpeg".to_string()),
+        alt_text: None,
+        caption: caption.map(ToString::to_string),
+      })
+    };
+    let forms = vec![form(1, Some("second"))?, form(0, Some("first"))?];
+    PostGalleryItem::replace(pool, post.id, &forms).await?;
+
+    let gallery = PostGalleryItem::list_for_post(pool, post.id).await?;
+    assert_eq!(
+      vec![Some("first"), Some("second")],
+      gallery
+        .iter()
+        .map(|i| i.caption.as_deref())
+        .collect::<Vec<_>>()
+    );
+
+    PostGalleryItem::replace(pool, post.id, &[form(0, None)?]).await?;
+    assert_eq!(
+      1,
+      PostGalleryItem::list_for_post(pool, post.id).await?.len()
+    );
+
+    PostGalleryItem::replace(pool, post.id, &[]).await?;
+    assert!(PostGalleryItem::list_for_post(pool, post.id)
+      .await?
+      .is_empty());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -1,6 +1,9 @@
 use crate::{
   newtypes::{PersonId, PostId, PostReportId},
-  source::post_report::{PostReport, PostReportForm},
+  source::{
+    person::Person,
+    post_report::{PostReport, PostReportForm},
+  },
   traits::Reportable,
   utils::{get_conn, DbPool},
 };
@@ -9,10 +12,12 @@ use diesel::{
   dsl::{insert_into, update},
   BoolExpressionMethods,
   ExpressionMethods,
+  JoinOnDsl,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
-use lemmy_db_schema_file::schema::post_report;
+use lemmy_db_schema_file::schema::{person, post_report};
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
 impl Reportable for PostReport {
@@ -89,6 +94,51 @@ impl Reportable for PostReport {
   }
 }
 
+impl PostReport {
+  /// Returns the creators of all unresolved reports in the case of the given post, which is
+  /// either the reports violating instance rules or the other ones.
+  pub async fn list_unresolved_creators(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    violates_instance_rules: bool,
+  ) -> LemmyResult<Vec<Person>> {
+    let conn = &mut get_conn(pool).await?;
+    post_report::table
+      .inner_join(person::table.on(post_report::creator_id.eq(person::id)))
+      .filter(post_report::post_id.eq(post_id))
+      .filter(post_report::resolved.eq(false))
+      .filter(post_report::violates_instance_rules.eq(violates_instance_rules))
+      .select(Person::as_select())
+      .load(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  /// Resolves the unresolved reports in the case of the given post.
+  pub async fn resolve_case(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    violates_instance_rules: bool,
+    by_resolver_id: PersonId,
+  ) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    update(
+      post_report::table
+        .filter(post_report::post_id.eq(post_id))
+        .filter(post_report::resolved.eq(false))
+        .filter(post_report::violates_instance_rules.eq(violates_instance_rules)),
+    )
+    .set((
+      post_report::resolved.eq(true),
+      post_report::resolver_id.eq(by_resolver_id),
+      post_report::updated_at.eq(Utc::now()),
+    ))
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+}
+
 #[cfg(test)]
 mod tests {
 
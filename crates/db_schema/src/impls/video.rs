@@ -0,0 +1,338 @@
+use crate::{
+  diesel::SelectableHelper,
+  newtypes::DbUrl,
+  source::video::{
+    VideoDetails,
+    VideoDetailsInsertForm,
+    VideoDetailsUpdateForm,
+    VideoTranscodeJob,
+    VideoTranscodeJobInsertForm,
+    VideoTranscodeJobUpdateForm,
+    VideoVariant,
+    VideoVariantForm,
+    VideoVariantsView,
+  },
+  utils::{get_conn, now, DbPool},
+};
+use chrono::{TimeDelta, Utc};
+use diesel::{
+  deserialize::FromSql,
+  insert_into,
+  pg::{Pg, PgValue},
+  serialize::ToSql,
+  sql_types::{Json, Nullable},
+  upsert::excluded,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+use lemmy_db_schema_file::schema::{video_details, video_transcode_job, video_variant};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl VideoDetails {
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &VideoDetailsInsertForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(video_details::table)
+      .values(form)
+      .on_conflict(video_details::link)
+      .do_update()
+      .set(form)
+      .returning(Self::as_select())
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn read(pool: &mut DbPool<'_>, link: &DbUrl) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    video_details::table
+      .find(link)
+      .select(Self::as_select())
+      .first(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    link: &DbUrl,
+    form: &VideoDetailsUpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(video_details::table.find(link))
+      .set(form)
+      .returning(Self::as_select())
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Variants are removed along with the details.
+  pub async fn delete(pool: &mut DbPool<'_>, link: &DbUrl) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(video_details::table.find(link))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+impl VideoVariant {
+  pub async fn upsert_many(
+    pool: &mut DbPool<'_>,
+    forms: &[VideoVariantForm],
+  ) -> LemmyResult<Vec<Self>> {
+    if forms.is_empty() {
+      return Ok(Vec::new());
+    }
+    let conn = &mut get_conn(pool).await?;
+    insert_into(video_variant::table)
+      .values(forms)
+      .on_conflict((video_variant::link, video_variant::height))
+      .do_update()
+      .set((
+        video_variant::url.eq(excluded(video_variant::url)),
+        video_variant::content_type.eq(excluded(video_variant::content_type)),
+      ))
+      .returning(Self::as_select())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// Ordered from the highest to the lowest resolution.
+  pub async fn list(pool: &mut DbPool<'_>, link: &DbUrl) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    video_variant::table
+      .filter(video_variant::link.eq(link))
+      .order_by(video_variant::height.desc())
+      .select(Self::as_select())
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+}
+
+impl VideoTranscodeJob {
+  /// Queues a video for transcoding. If it is queued already, the job is replaced.
+  pub async fn enqueue(
+    pool: &mut DbPool<'_>,
+    form: &VideoTranscodeJobInsertForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(video_transcode_job::table)
+      .values(form)
+      .on_conflict(video_transcode_job::link)
+      .do_update()
+      .set(form)
+      .returning(Self::as_select())
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  /// Claims up to `limit` jobs which are due, and postpones their next attempt by `lease`. This
+  /// way concurrent workers skip them, and if the server stops while transcoding, the jobs are
+  /// picked up again once the lease is over. The oldest jobs are claimed first.
+  pub async fn claim_due(
+    pool: &mut DbPool<'_>,
+    limit: i64,
+    lease: TimeDelta,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let links: Vec<DbUrl> = video_transcode_job::table
+            .filter(video_transcode_job::next_attempt_at.le(now()))
+            .order_by(video_transcode_job::published_at)
+            .limit(limit)
+            .select(video_transcode_job::link)
+            .for_update()
+            .skip_locked()
+            .get_results(conn)
+            .await?;
+
+          let mut claimed = diesel::update(
+            video_transcode_job::table.filter(video_transcode_job::link.eq_any(links)),
+          )
+          .set(video_transcode_job::next_attempt_at.eq(Utc::now() + lease))
+          .returning(Self::as_select())
+          .get_results(conn)
+          .await
+          .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+          // Updated rows are returned in no particular order
+          claimed.sort_by_key(|j| j.published_at);
+          Ok(claimed)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn update(
+    pool: &mut DbPool<'_>,
+    link: &DbUrl,
+    form: &VideoTranscodeJobUpdateForm,
+  ) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(video_transcode_job::table.find(link))
+      .set(form)
+      .returning(Self::as_select())
+      .get_result(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, link: &DbUrl) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(video_transcode_job::table.find(link))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+impl FromSql<Nullable<Json>, Pg> for VideoVariantsView {
+  fn from_sql(bytes: PgValue) -> diesel::deserialize::Result<Self> {
+    let value = <serde_json::Value as FromSql<Json, Pg>>::from_sql(bytes)?;
+    Ok(serde_json::from_value::<VideoVariantsView>(value)?)
+  }
+  fn from_nullable_sql(
+    bytes: Option<<Pg as diesel::backend::Backend>::RawValue<'_>>,
+  ) -> diesel::deserialize::Result<Self> {
+    match bytes {
+      Some(bytes) => Self::from_sql(bytes),
+      None => Ok(Self(vec![])),
+    }
+  }
+}
+
+impl ToSql<Nullable<Json>, Pg> for VideoVariantsView {
+  fn to_sql(&self, out: &mut diesel::serialize::Output<Pg>) -> diesel::serialize::Result {
+    let value = serde_json::to_value(self)?;
+    <serde_json::Value as ToSql<Json, Pg>>::to_sql(&value, &mut out.reborrow())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    source::{
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_video_variants() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let link: DbUrl = Url::parse("https://my_domain.tld/api/v4/image/video.mp4")?.into();
+    let form = VideoDetailsInsertForm {
+      link: link.clone(),
+      width: Some(1920),
+      height: Some(1080),
+      duration_ms: Some(12_000),
+      poster_url: None,
+    };
+    let details = VideoDetails::upsert(pool, &form).await?;
+    assert_eq!(None, details.transcoded_at);
+
+    let variant = |height: i32| -> LemmyResult<VideoVariantForm> {
+      Ok(VideoVariantForm {
+        link: link.clone(),
+        height,
+        url: Url::parse(&format!("https://my_domain.tld/api/v4/image/{height}.mp4"))?.into(),
+        content_type: "video/mp4".to_string(),
+      })
+    };
+    VideoVariant::upsert_many(pool, &[variant(480)?, variant(1080)?, variant(720)?]).await?;
+    // Inserting the same variant again replaces it
+    VideoVariant::upsert_many(pool, &[variant(720)?]).await?;
+
+    let variants = VideoVariant::list(pool, &link).await?;
+    assert_eq!(
+      vec![1080, 720, 480],
+      variants.iter().map(|v| v.height).collect::<Vec<_>>()
+    );
+
+    let update_form = VideoDetailsUpdateForm {
+      transcoded_at: Some(Some(chrono::Utc::now())),
+      ..Default::default()
+    };
+    let details = VideoDetails::update(pool, &link, &update_form).await?;
+    assert!(details.transcoded_at.is_some());
+
+    VideoDetails::delete(pool, &link).await?;
+    assert!(VideoVariant::list(pool, &link).await?.is_empty());
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_transcode_job() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "video_uploader");
+    let person = Person::create(pool, &person_form).await?;
+
+    let link: DbUrl = Url::parse("https://my_domain.tld/api/v4/image/job.mp4")?.into();
+    let form = VideoDetailsInsertForm {
+      link: link.clone(),
+      width: Some(640),
+      height: Some(360),
+      duration_ms: Some(3_000),
+      poster_url: None,
+    };
+    VideoDetails::upsert(pool, &form).await?;
+    let job_form = VideoTranscodeJobInsertForm {
+      link: link.clone(),
+      alias: "job.mp4".to_string(),
+      person_id: person.id,
+    };
+    VideoTranscodeJob::enqueue(pool, &job_form).await?;
+
+    // Once claimed, the job is leased and not returned again
+    let claimed = VideoTranscodeJob::claim_due(pool, 10, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![link.clone()],
+      claimed.into_iter().map(|j| j.link).collect::<Vec<_>>()
+    );
+    assert!(
+      VideoTranscodeJob::claim_due(pool, 10, TimeDelta::minutes(5))
+        .await?
+        .is_empty()
+    );
+
+    // A failed job can be retried right away
+    let update_form = VideoTranscodeJobUpdateForm {
+      attempts: Some(1),
+      next_attempt_at: Some(Utc::now()),
+    };
+    VideoTranscodeJob::update(pool, &link, &update_form).await?;
+    let claimed = VideoTranscodeJob::claim_due(pool, 10, TimeDelta::minutes(5)).await?;
+    assert_eq!(
+      vec![1],
+      claimed.iter().map(|j| j.attempts).collect::<Vec<_>>()
+    );
+
+    // Jobs are removed along with the video
+    VideoDetails::delete(pool, &link).await?;
+    assert_eq!(0, VideoTranscodeJob::delete(pool, &link).await?);
+
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,255 @@
+use crate::{
+  source::{
+    community::Community,
+    person::Person,
+    post::Post,
+    websub_subscription::{WebsubSubscription, WebsubSubscriptionForm},
+  },
+  traits::Crud,
+  utils::{get_conn, now, DbPool},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  delete,
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema_file::schema::{multi_community, multi_community_entry, websub_subscription};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+impl WebsubSubscription {
+  /// Creates the subscription, or renews it if the callback is already subscribed to the topic.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &WebsubSubscriptionForm) -> LemmyResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(websub_subscription::table)
+      .values(form)
+      .on_conflict((websub_subscription::topic, websub_subscription::callback))
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntCreate)
+  }
+
+  pub async fn delete(pool: &mut DbPool<'_>, topic: &str, callback: &str) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    delete(
+      websub_subscription::table
+        .filter(websub_subscription::topic.eq(topic))
+        .filter(websub_subscription::callback.eq(callback)),
+    )
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+
+  /// Marks subscriptions to the feeds which contain the post as changed, or only the comment feed
+  /// of the post for a new comment. Feed names are compared in lowercase, like the feed urls.
+  pub async fn mark_changed(
+    pool: &mut DbPool<'_>,
+    post: &Post,
+    new_comment: bool,
+  ) -> LemmyResult<usize> {
+    let mut feeds = vec![format!("post/{}", post.id)];
+    if !new_comment {
+      let community = Community::read(pool, post.community_id).await?;
+      let creator = Person::read(pool, post.creator_id).await?;
+      // Search feeds can't be matched here, so they are checked for every post
+      feeds = vec!["all".to_string(), "search".to_string()];
+      if community.local {
+        feeds.push("local".to_string());
+        feeds.push(format!("c/{}", community.name.to_lowercase()));
+      }
+      if creator.local {
+        feeds.push(format!("u/{}", creator.name.to_lowercase()));
+      }
+      let conn = &mut get_conn(pool).await?;
+      let multi_names: Vec<String> = multi_community_entry::table
+        .inner_join(multi_community::table)
+        .filter(multi_community_entry::community_id.eq(post.community_id))
+        .filter(multi_community::local)
+        .select(multi_community::name)
+        .load(conn)
+        .await?;
+      feeds.extend(
+        multi_names
+          .iter()
+          .map(|n| format!("m/{}", n.to_lowercase())),
+      );
+    }
+
+    let conn = &mut get_conn(pool).await?;
+    update(
+      websub_subscription::table
+        .filter(websub_subscription::feed.eq_any(feeds))
+        .filter(websub_subscription::lease_expires_at.gt(now())),
+    )
+    .set(websub_subscription::changed_at.eq(now().nullable()))
+    .execute(conn)
+    .await
+    .with_lemmy_type(LemmyErrorType::CouldntUpdate)
+  }
+
+  /// Active subscriptions whose feed changed since their last push.
+  pub async fn list_pending_push(pool: &mut DbPool<'_>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    websub_subscription::table
+      .filter(websub_subscription::lease_expires_at.gt(now()))
+      .filter(websub_subscription::changed_at.gt(websub_subscription::last_pushed_at.nullable()))
+      .order_by(websub_subscription::topic)
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn mark_pushed(
+    pool: &mut DbPool<'_>,
+    ids: &[i32],
+    pushed_at: DateTime<Utc>,
+  ) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+    update(websub_subscription::table.filter(websub_subscription::id.eq_any(ids)))
+      .set(websub_subscription::last_pushed_at.eq(pushed_at))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdate)?;
+    Ok(())
+  }
+
+  /// Active subscriptions whose lease ends before the given time.
+  pub async fn list_expiring(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    websub_subscription::table
+      .filter(websub_subscription::lease_expires_at.gt(now()))
+      .filter(websub_subscription::lease_expires_at.lt(before))
+      .get_results(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)
+  }
+
+  pub async fn delete_expired(pool: &mut DbPool<'_>) -> LemmyResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    delete(websub_subscription::table.filter(websub_subscription::lease_expires_at.le(now())))
+      .execute(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::Deleted)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::Days;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_websub_subscription() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+
+    let lease_expires_at = Utc::now() + Days::new(10);
+    let form = WebsubSubscriptionForm {
+      topic: "https://lemmy.tld/feeds/c/Websub_Community.xml".to_string(),
+      feed: "c/websub_community".to_string(),
+      callback: "https://reader.tld/callback".to_string(),
+      secret: Some("secret".to_string()),
+      lease_expires_at,
+    };
+    let subscription = WebsubSubscription::upsert(pool, &form).await?;
+
+    // Subscribing again renews the lease and replaces the secret
+    let renewed_form = WebsubSubscriptionForm {
+      secret: None,
+      lease_expires_at: lease_expires_at + Days::new(1),
+      ..form.clone()
+    };
+    let renewed = WebsubSubscription::upsert(pool, &renewed_form).await?;
+    assert_eq!(subscription.id, renewed.id);
+    assert_eq!(None, renewed.secret);
+    assert!(renewed.lease_expires_at > subscription.lease_expires_at);
+
+    let expiring = WebsubSubscription::list_expiring(pool, Utc::now() + Days::new(20)).await?;
+    assert_eq!(vec![renewed.clone()], expiring);
+    let expiring = WebsubSubscription::list_expiring(pool, Utc::now() + Days::new(1)).await?;
+    assert!(expiring.is_empty());
+
+    // A new post in the community makes the subscription pending, until it is pushed
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "websub_person");
+    let person = Person::create(pool, &person_form).await?;
+    let community_form = CommunityInsertForm::new(
+      instance.id,
+      "websub_community".to_string(),
+      "websub".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+    let other_community_form = CommunityInsertForm::new(
+      instance.id,
+      "websub_other".to_string(),
+      "websub".to_owned(),
+      "pubkey".to_string(),
+    );
+    let other_community = Community::create(pool, &other_community_form).await?;
+
+    let post_form = PostInsertForm::new("Other post".into(), person.id, other_community.id);
+    let other_post = Post::create(pool, &post_form).await?;
+    assert_eq!(
+      0,
+      WebsubSubscription::mark_changed(pool, &other_post, false).await?
+    );
+    assert!(WebsubSubscription::list_pending_push(pool)
+      .await?
+      .is_empty());
+
+    let post_form = PostInsertForm::new("A post".into(), person.id, community.id);
+    let post = Post::create(pool, &post_form).await?;
+    // Comments only change the comment feed of the post
+    assert_eq!(
+      0,
+      WebsubSubscription::mark_changed(pool, &post, true).await?
+    );
+    assert_eq!(
+      1,
+      WebsubSubscription::mark_changed(pool, &post, false).await?
+    );
+
+    let pending = WebsubSubscription::list_pending_push(pool).await?;
+    assert_eq!(
+      vec![renewed.id],
+      pending.iter().map(|s| s.id).collect::<Vec<_>>()
+    );
+    WebsubSubscription::mark_pushed(pool, &[renewed.id], Utc::now()).await?;
+    assert!(WebsubSubscription::list_pending_push(pool)
+      .await?
+      .is_empty());
+
+    assert_eq!(0, WebsubSubscription::delete_expired(pool).await?);
+    assert_eq!(
+      1,
+      WebsubSubscription::delete(pool, &form.topic, &form.callback).await?
+    );
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -112,6 +112,8 @@ pub enum ModlogActionType {
   AdminPurgeComment,
   AdminBlockInstance,
   AdminAllowInstance,
+  ModAutomodAction,
+  ModFreezeCommunity,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
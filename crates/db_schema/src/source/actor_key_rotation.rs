@@ -0,0 +1,33 @@
+use crate::newtypes::{DbUrl, PersonId};
+use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::ActorType;
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::actor_key_rotation;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = actor_key_rotation))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The signing key of a local actor was replaced.
+pub struct ActorKeyRotation {
+  pub id: i32,
+  pub actor_type: ActorType,
+  pub actor_ap_id: DbUrl,
+  /// The admin who rotated the key, or none if it was rotated from the command line.
+  pub admin_person_id: Option<PersonId>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = actor_key_rotation))]
+pub struct ActorKeyRotationForm {
+  pub actor_type: ActorType,
+  pub actor_ap_id: DbUrl,
+  pub admin_person_id: Option<PersonId>,
+}
@@ -0,0 +1,104 @@
+use crate::newtypes::{AutomodRuleId, CommunityId, LanguageId, PersonId, TagId};
+use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::AutomodAction;
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::automod_rule;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An automod rule, created by community moderators.
+///
+/// All conditions which are set must match for the rule to take its action. Rules are checked
+/// for new posts and comments in the community, both local and federated.
+pub struct AutomodRule {
+  pub id: AutomodRuleId,
+  pub community_id: CommunityId,
+  /// The moderator who created the rule. Automod actions are attributed to this person.
+  pub creator_id: PersonId,
+  pub name: String,
+  pub enabled: bool,
+  pub apply_to_posts: bool,
+  pub apply_to_comments: bool,
+  /// Matches against post titles. Never matches comments.
+  pub title_regex: Option<String>,
+  /// Matches against the post or comment body.
+  pub body_regex: Option<String>,
+  /// Matches against the domain of the post url.
+  pub url_domain_regex: Option<String>,
+  /// Matches against the domain of the creator's instance.
+  pub instance_domain_regex: Option<String>,
+  /// Matches creators whose account is younger than this many days.
+  pub max_account_age_days: Option<i32>,
+  /// Matches creators whose combined post and comment score is below this value.
+  pub max_karma: Option<i32>,
+  pub language_id: Option<LanguageId>,
+  pub action: AutomodAction,
+  /// The removal or report reason, or the text of the reply.
+  pub action_message: Option<String>,
+  /// The tag to add, for the `AddTag` action.
+  pub tag_id: Option<TagId>,
+  pub published_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+pub struct AutomodRuleInsertForm {
+  pub community_id: CommunityId,
+  pub creator_id: PersonId,
+  pub name: String,
+  pub action: AutomodAction,
+  #[new(default)]
+  pub enabled: Option<bool>,
+  #[new(default)]
+  pub apply_to_posts: Option<bool>,
+  #[new(default)]
+  pub apply_to_comments: Option<bool>,
+  #[new(default)]
+  pub title_regex: Option<String>,
+  #[new(default)]
+  pub body_regex: Option<String>,
+  #[new(default)]
+  pub url_domain_regex: Option<String>,
+  #[new(default)]
+  pub instance_domain_regex: Option<String>,
+  #[new(default)]
+  pub max_account_age_days: Option<i32>,
+  #[new(default)]
+  pub max_karma: Option<i32>,
+  #[new(default)]
+  pub language_id: Option<LanguageId>,
+  #[new(default)]
+  pub action_message: Option<String>,
+  #[new(default)]
+  pub tag_id: Option<TagId>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = automod_rule))]
+pub struct AutomodRuleUpdateForm {
+  pub name: Option<String>,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<Option<String>>,
+  pub body_regex: Option<Option<String>>,
+  pub url_domain_regex: Option<Option<String>>,
+  pub instance_domain_regex: Option<Option<String>>,
+  pub max_account_age_days: Option<Option<i32>>,
+  pub max_karma: Option<Option<i32>>,
+  pub language_id: Option<Option<LanguageId>>,
+  pub action: Option<AutomodAction>,
+  pub action_message: Option<Option<String>>,
+  pub tag_id: Option<Option<TagId>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
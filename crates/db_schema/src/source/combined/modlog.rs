@@ -9,6 +9,7 @@ use crate::newtypes::{
   AdminPurgePostId,
   AdminRemoveCommunityId,
   ModAddToCommunityId,
+  ModAutomodActionId,
   ModBanFromCommunityId,
   ModChangeCommunityVisibilityId,
   ModFeaturePostId,
@@ -54,4 +55,5 @@ pub struct ModlogCombined {
   pub admin_remove_community_id: Option<AdminRemoveCommunityId>,
   pub mod_remove_post_id: Option<ModRemovePostId>,
   pub mod_transfer_community_id: Option<ModTransferCommunityId>,
+  pub mod_automod_action_id: Option<ModAutomodActionId>,
 }
@@ -1,6 +1,9 @@
 use crate::newtypes::{
+  CommentId,
   CommentReportId,
+  CommunityId,
   CommunityReportId,
+  PostId,
   PostReportId,
   PrivateMessageReportId,
   ReportCombinedId,
@@ -8,8 +11,9 @@ use crate::newtypes::{
 use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use i_love_jesus::CursorKeysModule;
+use lemmy_db_schema_file::enums::ReportSeverity;
 #[cfg(feature = "full")]
-use lemmy_db_schema_file::schema::report_combined;
+use lemmy_db_schema_file::schema::{community_actions, report_combined};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
@@ -31,3 +35,45 @@ pub struct ReportCombined {
   pub private_message_report_id: Option<PrivateMessageReportId>,
   pub community_report_id: Option<CommunityReportId>,
 }
+
+// A view in the replaceable `r` schema, which isn't included by `diesel print-schema`
+#[cfg(feature = "full")]
+diesel::table! {
+  r.report_case (id) {
+    id -> Int4,
+    published_at -> Timestamptz,
+    oldest_published_at -> Timestamptz,
+    report_count -> Int8,
+    violates_instance_rules -> Bool,
+    severity -> lemmy_db_schema_file::schema::sql_types::ReportSeverityEnum,
+    reasons -> Array<Text>,
+    post_id -> Nullable<Int4>,
+    comment_id -> Nullable<Int4>,
+    community_id -> Int4,
+  }
+}
+
+#[cfg(feature = "full")]
+diesel::allow_tables_to_appear_in_same_query!(report_case, community_actions);
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, CursorKeysModule))]
+#[cfg_attr(feature = "full", diesel(table_name = report_case))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", cursor_keys_module(name = report_case_keys))]
+/// All unresolved reports on a single post or comment, grouped in SQL.
+pub struct ReportCase {
+  /// The newest report of the case
+  pub id: ReportCombinedId,
+  pub published_at: DateTime<Utc>,
+  pub oldest_published_at: DateTime<Utc>,
+  pub report_count: i64,
+  pub violates_instance_rules: bool,
+  /// The highest severity of all reports
+  pub severity: ReportSeverity,
+  /// The reasons of all reports, newest first
+  pub reasons: Vec<String>,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub community_id: CommunityId,
+}
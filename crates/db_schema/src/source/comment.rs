@@ -63,6 +63,10 @@ pub struct Comment {
   /// If a local user comments in a remote community, the comment is hidden until it is confirmed
   /// accepted by the community (by receiving it back via federation).
   pub federation_pending: bool,
+  /// Whether the comment is hidden until a moderator approves it.
+  pub pending_approval: bool,
+  /// Whether the post creator accepted this comment as answer to their question.
+  pub accepted_answer: bool,
 }
 
 #[derive(Debug, Clone, derive_new::new)]
@@ -93,6 +97,8 @@ pub struct CommentInsertForm {
   pub language_id: Option<LanguageId>,
   #[new(default)]
   pub federation_pending: Option<bool>,
+  #[new(default)]
+  pub pending_approval: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -109,6 +115,8 @@ pub struct CommentUpdateForm {
   pub distinguished: Option<bool>,
   pub language_id: Option<LanguageId>,
   pub federation_pending: Option<bool>,
+  pub pending_approval: Option<bool>,
+  pub accepted_answer: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -1,5 +1,6 @@
 use crate::newtypes::{CommentId, CommentReportId, PersonId};
 use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::ReportSeverity;
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::comment_report;
 use serde::{Deserialize, Serialize};
@@ -28,6 +29,7 @@ pub struct CommentReport {
   pub published_at: DateTime<Utc>,
   pub updated_at: Option<DateTime<Utc>>,
   pub violates_instance_rules: bool,
+  pub severity: ReportSeverity,
 }
 
 #[derive(Clone)]
@@ -39,4 +41,5 @@ pub struct CommentReportForm {
   pub original_comment_text: String,
   pub reason: String,
   pub violates_instance_rules: bool,
+  pub severity: ReportSeverity,
 }
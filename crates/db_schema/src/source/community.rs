@@ -98,6 +98,35 @@ pub struct Community {
   #[serde(skip)]
   pub interactions_month: i32,
   pub local_removed: bool,
+  /// Whether new posts are held for moderator approval before they become visible.
+  pub posts_require_approval: bool,
+  /// Whether new comments are held for moderator approval before they become visible.
+  pub comments_require_approval: bool,
+  /// Only hold content of accounts which are younger than this many days. If empty, content of
+  /// all users except mods and admins is held.
+  pub approval_max_account_age_days: Option<i32>,
+  /// Each user can only comment once per this many minutes in the community, unless the post has
+  /// its own setting.
+  pub comment_slow_mode_minutes: Option<i32>,
+  /// Whether new posts are temporarily disabled for everyone except mods and admins.
+  pub posts_frozen: bool,
+  /// Whether new comments are temporarily disabled for everyone except mods and admins.
+  pub comments_frozen: bool,
+  /// When the freeze is lifted automatically. If empty, it lasts until a mod lifts it.
+  pub freeze_expires_at: Option<DateTime<Utc>>,
+  /// The community moved to this new address, and should not be used anymore.
+  pub moved_to_url: Option<DbUrl>,
+  /// The community was moved here from this old address.
+  pub moved_from_url: Option<DbUrl>,
+  /// The public key which was used before the last key rotation.
+  #[serde(skip)]
+  pub previous_public_key: Option<String>,
+  #[serde(skip)]
+  pub key_rotated_at: Option<DateTime<Utc>>,
+  /// Whether only mods and admins can cross-post into this community.
+  pub cross_posting_restricted_to_mods: bool,
+  /// Whether posts in this community are questions, which can be marked as solved.
+  pub question_answer: bool,
 }
 
 #[derive(Debug, Clone, derive_new::new)]
@@ -148,6 +177,28 @@ pub struct CommunityInsertForm {
   pub description: Option<String>,
   #[new(default)]
   pub local_removed: Option<bool>,
+  #[new(default)]
+  pub posts_require_approval: Option<bool>,
+  #[new(default)]
+  pub comments_require_approval: Option<bool>,
+  #[new(default)]
+  pub approval_max_account_age_days: Option<i32>,
+  #[new(default)]
+  pub comment_slow_mode_minutes: Option<i32>,
+  #[new(default)]
+  pub posts_frozen: Option<bool>,
+  #[new(default)]
+  pub comments_frozen: Option<bool>,
+  #[new(default)]
+  pub freeze_expires_at: Option<DateTime<Utc>>,
+  #[new(default)]
+  pub moved_to_url: Option<DbUrl>,
+  #[new(default)]
+  pub moved_from_url: Option<DbUrl>,
+  #[new(default)]
+  pub cross_posting_restricted_to_mods: Option<bool>,
+  #[new(default)]
+  pub question_answer: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -176,6 +227,17 @@ pub struct CommunityUpdateForm {
   pub visibility: Option<CommunityVisibility>,
   pub description: Option<Option<String>>,
   pub local_removed: Option<bool>,
+  pub posts_require_approval: Option<bool>,
+  pub comments_require_approval: Option<bool>,
+  pub approval_max_account_age_days: Option<Option<i32>>,
+  pub comment_slow_mode_minutes: Option<Option<i32>>,
+  pub posts_frozen: Option<bool>,
+  pub comments_frozen: Option<bool>,
+  pub freeze_expires_at: Option<Option<DateTime<Utc>>>,
+  pub moved_to_url: Option<Option<DbUrl>>,
+  pub moved_from_url: Option<Option<DbUrl>>,
+  pub cross_posting_restricted_to_mods: Option<bool>,
+  pub question_answer: Option<bool>,
 }
 
 #[skip_serializing_none]
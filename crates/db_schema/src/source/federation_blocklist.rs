@@ -1,10 +1,17 @@
-use crate::newtypes::InstanceId;
+use crate::newtypes::{FederationBlocklistSubscriptionId, InstanceId};
 use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::FederationBlockSeverity;
 #[cfg(feature = "full")]
-use lemmy_db_schema_file::schema::federation_blocklist;
+use lemmy_db_schema_file::schema::{
+  federation_blocklist,
+  federation_blocklist_pending,
+  federation_blocklist_subscription,
+};
 use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
 use std::fmt::Debug;
 
+#[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(
   feature = "full",
@@ -24,6 +31,13 @@ pub struct FederationBlockList {
   pub published_at: DateTime<Utc>,
   pub updated_at: Option<DateTime<Utc>>,
   pub expires_at: Option<DateTime<Utc>>,
+  pub severity: FederationBlockSeverity,
+  /// The reason for the block, which is shown publicly.
+  pub public_reason: Option<String>,
+  /// A comment which is only visible to admins.
+  pub private_comment: Option<String>,
+  /// The subscribed blocklist which this block was imported from.
+  pub subscription_id: Option<FederationBlocklistSubscriptionId>,
 }
 
 #[derive(Clone, Default)]
@@ -33,4 +47,70 @@ pub struct FederationBlockListForm {
   pub instance_id: InstanceId,
   pub updated_at: Option<DateTime<Utc>>,
   pub expires_at: Option<DateTime<Utc>>,
+  pub severity: Option<FederationBlockSeverity>,
+  pub public_reason: Option<String>,
+  pub private_comment: Option<String>,
+  pub subscription_id: Option<FederationBlocklistSubscriptionId>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A remote blocklist in Mastodon's domain block CSV format, which is refreshed regularly.
+pub struct FederationBlocklistSubscription {
+  pub id: FederationBlocklistSubscriptionId,
+  pub url: String,
+  pub last_refreshed_at: Option<DateTime<Utc>>,
+  /// Set if the last refresh failed.
+  pub last_error: Option<String>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+pub struct FederationBlocklistSubscriptionInsertForm {
+  pub url: String,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_subscription))]
+pub struct FederationBlocklistSubscriptionUpdateForm {
+  pub last_refreshed_at: Option<Option<DateTime<Utc>>>,
+  pub last_error: Option<Option<String>>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_pending))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A domain which was newly added to a subscribed blocklist, and is waiting for an admin to
+/// review and apply it. Discarded domains are kept so that they aren't suggested again.
+pub struct FederationBlocklistPending {
+  pub id: i32,
+  pub subscription_id: FederationBlocklistSubscriptionId,
+  pub domain: String,
+  pub severity: FederationBlockSeverity,
+  pub public_reason: Option<String>,
+  pub published_at: DateTime<Utc>,
+  /// Set if an admin decided not to block the domain.
+  pub discarded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_blocklist_pending))]
+pub struct FederationBlocklistPendingForm {
+  pub subscription_id: FederationBlocklistSubscriptionId,
+  pub domain: String,
+  pub severity: FederationBlockSeverity,
+  pub public_reason: Option<String>,
 }
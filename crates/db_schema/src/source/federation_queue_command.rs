@@ -0,0 +1,33 @@
+use crate::newtypes::{ActivityId, InstanceId};
+use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::FederationQueueCommandType;
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::federation_queue_command;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_queue_command))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+/// A command from an admin, which the federation worker for the instance handles the next time
+/// it checks for commands.
+pub struct FederationQueueCommand {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  pub command: FederationQueueCommandType,
+  /// First activity to resend
+  pub first_activity_id: Option<ActivityId>,
+  /// Last activity to skip or resend
+  pub last_activity_id: Option<ActivityId>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_queue_command))]
+pub struct FederationQueueCommandForm {
+  pub instance_id: InstanceId,
+  pub command: FederationQueueCommandType,
+  pub first_activity_id: Option<ActivityId>,
+  pub last_activity_id: Option<ActivityId>,
+}
@@ -24,4 +24,6 @@ pub struct FederationQueueState {
   pub fail_count: i32,
   /// timestamp of the last retry attempt (when the last failing activity was resent)
   pub last_retry_at: Option<DateTime<Utc>>,
+  /// admins can pause sending activities to the instance
+  pub paused: bool,
 }
@@ -0,0 +1,66 @@
+use crate::newtypes::{DbUrl, FederationRelayId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::federation_relay;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_relay))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An ActivityPub relay which the local site follows. Relays announce public content from all
+/// their subscribers, so it shows up in the `All` listing without users following communities.
+pub struct FederationRelay {
+  pub id: FederationRelayId,
+  /// Either the relay actor (LitePub style) or the relay inbox (Mastodon style).
+  pub url: DbUrl,
+  /// The relay actor, only known once it was fetched.
+  pub ap_id: Option<DbUrl>,
+  pub inbox_url: Option<DbUrl>,
+  #[serde(skip)]
+  pub public_key: Option<String>,
+  /// True until the relay accepts the follow.
+  pub follow_pending: bool,
+  /// Whether activities of local public communities are sent to the relay.
+  pub publish: bool,
+  /// Id of the last follow activity, used to recognize the relay actor when it accepts.
+  #[serde(skip)]
+  pub follow_id: Option<DbUrl>,
+  /// How many posts and comments forwarded by the relay are fetched and stored per hour.
+  pub max_objects_per_hour: i32,
+  pub published_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_relay))]
+pub struct FederationRelayInsertForm {
+  pub url: DbUrl,
+  #[new(default)]
+  pub ap_id: Option<DbUrl>,
+  #[new(default)]
+  pub inbox_url: Option<DbUrl>,
+  #[new(default)]
+  pub publish: Option<bool>,
+  #[new(default)]
+  pub max_objects_per_hour: Option<i32>,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_relay))]
+pub struct FederationRelayUpdateForm {
+  pub ap_id: Option<Option<DbUrl>>,
+  pub inbox_url: Option<Option<DbUrl>>,
+  pub public_key: Option<Option<String>>,
+  pub follow_pending: Option<bool>,
+  pub publish: Option<bool>,
+  pub follow_id: Option<Option<DbUrl>>,
+  pub max_objects_per_hour: Option<i32>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
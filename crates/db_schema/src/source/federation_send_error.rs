@@ -0,0 +1,34 @@
+use crate::newtypes::{ActivityId, InstanceId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::federation_send_error;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_send_error))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A failed attempt to send an activity to another instance.
+pub struct FederationSendError {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  pub activity_id: ActivityId,
+  /// The HTTP status returned by the other instance, if it responded at all
+  pub http_status: Option<i32>,
+  pub error: String,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_send_error))]
+pub struct FederationSendErrorForm {
+  pub instance_id: InstanceId,
+  pub activity_id: ActivityId,
+  pub http_status: Option<i32>,
+  pub error: String,
+}
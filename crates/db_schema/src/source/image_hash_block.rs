@@ -0,0 +1,34 @@
+use crate::newtypes::{ImageHash, ImageHashBlockId, PersonId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::image_hash_block;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = image_hash_block))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A blocked perceptual image hash. Uploads and remote thumbnails which are similar to it are
+/// rejected. Only for admins.
+pub struct ImageHashBlock {
+  pub id: ImageHashBlockId,
+  #[cfg_attr(feature = "ts-rs", ts(type = "string"))]
+  pub hash: ImageHash,
+  pub reason: Option<String>,
+  /// The admin who added the block, if it wasn't imported.
+  pub admin_person_id: Option<PersonId>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = image_hash_block))]
+pub struct ImageHashBlockForm {
+  pub hash: ImageHash,
+  pub reason: Option<String>,
+  pub admin_person_id: Option<PersonId>,
+}
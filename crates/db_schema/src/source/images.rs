@@ -1,4 +1,4 @@
-use crate::newtypes::{DbUrl, PersonId, PostId};
+use crate::newtypes::{DbUrl, ImageHash, PersonId, PostId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -28,6 +28,11 @@ pub struct LocalImage {
   pub person_id: Option<PersonId>,
   /// This means the image is an auto-generated thumbnail, for a post.
   pub thumbnail_for_post_id: Option<PostId>,
+  /// Perceptual hash of the image, used to find images similar to blocked ones.
+  #[cfg_attr(feature = "ts-rs", ts(type = "string"))]
+  pub phash: Option<ImageHash>,
+  /// In bytes. Unknown for old uploads.
+  pub file_size: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +42,8 @@ pub struct LocalImageForm {
   pub pictrs_alias: String,
   pub person_id: PersonId,
   pub thumbnail_for_post_id: Option<Option<PostId>>,
+  pub phash: Option<ImageHash>,
+  pub file_size: Option<i64>,
 }
 
 /// Stores all images which are hosted on remote domains. When attempting to proxy an image, it
@@ -0,0 +1,56 @@
+use crate::newtypes::{DbUrl, InboundActivityId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use {i_love_jesus::CursorKeysModule, lemmy_db_schema_file::schema::inbound_activity};
+
+#[skip_serializing_none]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, CursorKeysModule)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = inbound_activity))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", cursor_keys_module(name = inbound_activity_keys))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An incoming activity which is waiting to be processed, or which failed too often and is kept
+/// in the dead-letter list.
+pub struct InboundActivity {
+  pub id: InboundActivityId,
+  pub ap_id: DbUrl,
+  pub actor_apub_id: DbUrl,
+  /// The activity json, as it was received.
+  pub data: String,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub last_error: Option<String>,
+  /// Set once the activity was moved to the dead-letter list.
+  pub failed_at: Option<DateTime<Utc>>,
+  /// Set while a worker processes the activity. Other activities of the same actor are not
+  /// claimed until then, so that they are processed in order.
+  pub claimed_until: Option<DateTime<Utc>>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = inbound_activity))]
+pub struct InboundActivityInsertForm {
+  pub ap_id: DbUrl,
+  pub actor_apub_id: DbUrl,
+  pub data: String,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = inbound_activity))]
+pub struct InboundActivityUpdateForm {
+  pub attempts: Option<i32>,
+  pub next_attempt_at: Option<DateTime<Utc>>,
+  pub last_error: Option<Option<String>>,
+  pub failed_at: Option<Option<DateTime<Utc>>>,
+  pub claimed_until: Option<Option<DateTime<Utc>>>,
+}
@@ -97,6 +97,15 @@ pub struct LocalSite {
   pub suggested_communities: Option<MultiCommunityId>,
   pub multi_comm_follower: PersonId,
   pub default_items_per_page: i32,
+  /// Leave NSFW posts and communities out of the sitemap.
+  pub sitemap_exclude_nsfw: bool,
+  /// Images whose perceptual hash differs from a blocked hash in at most this many bits are
+  /// rejected.
+  pub image_hash_block_threshold: i32,
+  /// Upload quota of regular users in bytes. Unlimited if not set.
+  pub media_quota_user: Option<i64>,
+  /// Upload quota of community moderators in bytes. Unlimited if not set.
+  pub media_quota_moderator: Option<i64>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -164,6 +173,14 @@ pub struct LocalSiteInsertForm {
   pub suggested_communities: Option<MultiCommunityId>,
   #[new(default)]
   pub multi_comm_follower: Option<PersonId>,
+  #[new(default)]
+  pub sitemap_exclude_nsfw: Option<bool>,
+  #[new(default)]
+  pub image_hash_block_threshold: Option<i32>,
+  #[new(default)]
+  pub media_quota_user: Option<i64>,
+  #[new(default)]
+  pub media_quota_moderator: Option<i64>,
 }
 
 #[derive(Clone, Default)]
@@ -201,4 +218,8 @@ pub struct LocalSiteUpdateForm {
   pub disable_email_notifications: Option<bool>,
   pub suggested_communities: Option<MultiCommunityId>,
   pub default_items_per_page: Option<i32>,
+  pub sitemap_exclude_nsfw: Option<bool>,
+  pub image_hash_block_threshold: Option<i32>,
+  pub media_quota_user: Option<Option<i64>>,
+  pub media_quota_moderator: Option<Option<i64>>,
 }
@@ -85,6 +85,8 @@ pub struct LocalUser {
   pub show_upvote_percentage: bool,
   pub show_person_votes: bool,
   pub default_items_per_page: i32,
+  /// Upload quota in bytes set by an admin, overriding the quota for the user's class.
+  pub media_quota: Option<i64>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -200,4 +202,5 @@ pub struct LocalUserUpdateForm {
   pub show_upvote_percentage: Option<bool>,
   pub show_person_votes: Option<bool>,
   pub default_items_per_page: Option<i32>,
+  pub media_quota: Option<Option<i64>>,
 }
@@ -3,7 +3,9 @@ use url::Url;
 
 #[cfg(feature = "full")]
 pub mod activity;
+pub mod actor_key_rotation;
 pub mod actor_language;
+pub mod automod_rule;
 pub mod captcha_answer;
 pub mod combined;
 pub mod comment;
@@ -17,8 +19,13 @@ pub mod custom_emoji_keyword;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+pub mod federation_queue_command;
 pub mod federation_queue_state;
+pub mod federation_relay;
+pub mod federation_send_error;
+pub mod image_hash_block;
 pub mod images;
+pub mod inbound_activity;
 pub mod instance;
 pub mod keyword_block;
 pub mod language;
@@ -35,6 +42,7 @@ pub mod oauth_provider;
 pub mod password_reset_request;
 pub mod person;
 pub mod post;
+pub mod post_gallery;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
@@ -43,6 +51,9 @@ pub mod secret;
 pub mod site;
 pub mod tag;
 pub mod tagline;
+pub mod video;
+#[cfg(feature = "full")]
+pub mod websub_subscription;
 
 /// Default value for columns like [community::Community.inbox_url] which are marked as serde(skip).
 ///
@@ -14,6 +14,7 @@ use crate::newtypes::{
   PostId,
 };
 use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::FederationBlockSeverity;
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::{
   admin_add,
@@ -176,6 +177,8 @@ pub struct AdminBlockInstance {
   pub reason: Option<String>,
   pub expires_at: Option<DateTime<Utc>>,
   pub published_at: DateTime<Utc>,
+  /// Whether the instance was silenced or suspended. Empty if it was unblocked.
+  pub severity: Option<FederationBlockSeverity>,
 }
 
 #[derive(Clone, Default)]
@@ -186,6 +189,7 @@ pub struct AdminBlockInstanceForm {
   pub admin_person_id: PersonId,
   pub blocked: bool,
   pub reason: Option<String>,
+  pub severity: Option<FederationBlockSeverity>,
 }
 
 #[skip_serializing_none]
@@ -1,10 +1,13 @@
 use crate::newtypes::{
+  AutomodRuleId,
   CommentId,
   CommunityId,
   ModAddToCommunityId,
+  ModAutomodActionId,
   ModBanFromCommunityId,
   ModChangeCommunityVisibilityId,
   ModFeaturePostId,
+  ModFreezeCommunityId,
   ModLockPostId,
   ModRemoveCommentId,
   ModRemovePostId,
@@ -13,13 +16,15 @@ use crate::newtypes::{
   PostId,
 };
 use chrono::{DateTime, Utc};
-use lemmy_db_schema_file::enums::CommunityVisibility;
+use lemmy_db_schema_file::enums::{AutomodAction, CommunityVisibility};
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::{
   mod_add_to_community,
+  mod_automod_action,
   mod_ban_from_community,
   mod_change_community_visibility,
   mod_feature_post,
+  mod_freeze_community,
   mod_lock_post,
   mod_remove_comment,
   mod_remove_post,
@@ -68,6 +73,10 @@ pub struct ModLockPost {
   pub locked: bool,
   pub published_at: DateTime<Utc>,
   pub reason: Option<String>,
+  /// Time at which the lock is lifted.
+  pub expires_at: Option<DateTime<Utc>>,
+  /// If set, the lock only applies to accounts which are younger than this many days.
+  pub max_account_age_days: Option<i32>,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -77,6 +86,8 @@ pub struct ModLockPostForm {
   pub post_id: PostId,
   pub locked: Option<bool>,
   pub reason: Option<String>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub max_account_age_days: Option<i32>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -230,3 +241,71 @@ pub struct ModTransferCommunityForm {
   pub other_person_id: PersonId,
   pub community_id: CommunityId,
 }
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_automod_action))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// When an automod rule takes action on a post or comment.
+///
+/// The moderator is the creator of the rule. For comments, the post is the one the comment belongs
+/// to.
+pub struct ModAutomodAction {
+  pub id: ModAutomodActionId,
+  pub mod_person_id: PersonId,
+  pub automod_rule_id: Option<AutomodRuleId>,
+  pub community_id: CommunityId,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_automod_action))]
+pub struct ModAutomodActionForm {
+  pub mod_person_id: PersonId,
+  pub automod_rule_id: Option<AutomodRuleId>,
+  pub community_id: CommunityId,
+  pub post_id: PostId,
+  pub comment_id: Option<CommentId>,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_freeze_community))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// When a moderator freezes or unfreezes a community.
+///
+/// If both posts and comments are unfrozen, the freeze was lifted. The moderator is empty if it
+/// was lifted automatically after expiring.
+pub struct ModFreezeCommunity {
+  pub id: ModFreezeCommunityId,
+  pub mod_person_id: Option<PersonId>,
+  pub community_id: CommunityId,
+  pub posts_frozen: bool,
+  pub comments_frozen: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub reason: Option<String>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_freeze_community))]
+pub struct ModFreezeCommunityForm {
+  pub mod_person_id: Option<PersonId>,
+  pub community_id: CommunityId,
+  pub posts_frozen: bool,
+  pub comments_frozen: bool,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub reason: Option<String>,
+}
@@ -6,6 +6,7 @@ use crate::{
 use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use i_love_jesus::CursorKeysModule;
+use lemmy_db_schema_file::enums::PersonNotificationsMode;
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::{person, person_actions};
 use serde::{Deserialize, Serialize};
@@ -62,6 +63,11 @@ pub struct Person {
   pub comment_count: i32,
   #[serde(skip)]
   pub comment_score: i32,
+  /// The public key which was used before the last key rotation.
+  #[serde(skip)]
+  pub previous_public_key: Option<String>,
+  #[serde(skip)]
+  pub key_rotated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -138,9 +144,9 @@ pub struct PersonActions {
   pub person_id: PersonId,
   #[serde(skip)]
   pub target_id: PersonId,
-  #[serde(skip)]
+  /// When the person was followed.
   pub followed_at: Option<DateTime<Utc>>,
-  #[serde(skip)]
+  /// Whether a follow of a remote person is still waiting to be accepted.
   pub follow_pending: Option<bool>,
   /// When the person was blocked.
   pub blocked_at: Option<DateTime<Utc>>,
@@ -154,6 +160,7 @@ pub struct PersonActions {
   pub upvotes: Option<i32>,
   /// A total of downvotes given to this person
   pub downvotes: Option<i32>,
+  pub notifications: Option<PersonNotificationsMode>,
 }
 
 #[derive(Clone, derive_new::new)]
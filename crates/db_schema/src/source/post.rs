@@ -1,6 +1,6 @@
 use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
 use chrono::{DateTime, Utc};
-use lemmy_db_schema_file::enums::PostNotificationsMode;
+use lemmy_db_schema_file::enums::{CommentDisplayMode, PostNotificationsMode};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -85,6 +85,19 @@ pub struct Post {
   /// If a local user posts in a remote community, the comment is hidden until it is confirmed
   /// accepted by the community (by receiving it back via federation).
   pub federation_pending: bool,
+  /// Whether the post is hidden until a moderator approves it.
+  pub pending_approval: bool,
+  /// Time at which the lock is lifted. None means the lock is permanent.
+  pub lock_expires_at: Option<DateTime<Utc>>,
+  /// If set, the lock only applies to accounts which are younger than this many days.
+  pub lock_max_account_age_days: Option<i32>,
+  /// Each user can only comment once per this many minutes. Overrides the community setting.
+  pub comment_slow_mode_minutes: Option<i32>,
+  /// If this post is a cross-post, the original post.
+  pub cross_post_of_id: Option<PostId>,
+  pub comment_display_mode: CommentDisplayMode,
+  /// Whether a comment was accepted as answer to this question.
+  pub solved: bool,
 }
 
 // TODO: FromBytes, ToBytes are only needed to develop wasm plugin, could be behind feature flag
@@ -140,6 +153,20 @@ pub struct PostInsertForm {
   pub scheduled_publish_time_at: Option<DateTime<Utc>>,
   #[new(default)]
   pub federation_pending: Option<bool>,
+  #[new(default)]
+  pub pending_approval: Option<bool>,
+  #[new(default)]
+  pub lock_expires_at: Option<DateTime<Utc>>,
+  #[new(default)]
+  pub lock_max_account_age_days: Option<i32>,
+  #[new(default)]
+  pub comment_slow_mode_minutes: Option<i32>,
+  #[new(default)]
+  pub cross_post_of_id: Option<PostId>,
+  #[new(default)]
+  pub comment_display_mode: Option<CommentDisplayMode>,
+  #[new(default)]
+  pub solved: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -168,6 +195,13 @@ pub struct PostUpdateForm {
   pub alt_text: Option<Option<String>>,
   pub scheduled_publish_time_at: Option<Option<DateTime<Utc>>>,
   pub federation_pending: Option<bool>,
+  pub pending_approval: Option<bool>,
+  pub lock_expires_at: Option<Option<DateTime<Utc>>>,
+  pub lock_max_account_age_days: Option<Option<i32>>,
+  pub comment_slow_mode_minutes: Option<Option<i32>>,
+  pub cross_post_of_id: Option<Option<PostId>>,
+  pub comment_display_mode: Option<CommentDisplayMode>,
+  pub solved: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
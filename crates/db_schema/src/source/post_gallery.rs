@@ -0,0 +1,51 @@
+use crate::newtypes::{DbUrl, PostId};
+#[cfg(feature = "full")]
+use diesel::{sql_types::Nullable, AsExpression, FromSqlRow};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::post_gallery_item;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// An image of a gallery post.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Associations, Identifiable)
+)]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post::Post)))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_item))]
+#[cfg_attr(feature = "full", diesel(primary_key(post_id, position)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct PostGalleryItem {
+  pub post_id: PostId,
+  /// The images are shown in ascending order of position, starting with zero.
+  pub position: i32,
+  pub url: DbUrl,
+  pub url_content_type: Option<String>,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_gallery_item))]
+pub struct PostGalleryItemForm {
+  pub post_id: PostId,
+  pub position: i32,
+  pub url: DbUrl,
+  pub url_content_type: Option<String>,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
+}
+
+/// We wrap this in a struct so we can implement FromSqlRow<Json> for it
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(transparent)]
+#[cfg_attr(feature = "full", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "full", diesel(sql_type = Nullable<diesel::sql_types::Json>))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct PostGalleryView(pub Vec<PostGalleryItem>);
@@ -1,5 +1,6 @@
 use crate::newtypes::{DbUrl, PersonId, PostId, PostReportId};
 use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::enums::ReportSeverity;
 #[cfg(feature = "full")]
 use lemmy_db_schema_file::schema::post_report;
 use serde::{Deserialize, Serialize};
@@ -33,6 +34,7 @@ pub struct PostReport {
   pub published_at: DateTime<Utc>,
   pub updated_at: Option<DateTime<Utc>>,
   pub violates_instance_rules: bool,
+  pub severity: ReportSeverity,
 }
 
 #[derive(Clone, Default)]
@@ -46,4 +48,5 @@ pub struct PostReportForm {
   pub original_post_body: Option<String>,
   pub reason: String,
   pub violates_instance_rules: bool,
+  pub severity: ReportSeverity,
 }
@@ -44,6 +44,11 @@ pub struct Site {
   /// If present, nsfw content is visible by default. Should be displayed by frontends/clients
   /// when the site is first opened by a user.
   pub content_warning: Option<String>,
+  /// The public key which was used before the last key rotation.
+  #[serde(skip)]
+  pub previous_public_key: Option<String>,
+  #[serde(skip)]
+  pub key_rotated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, derive_new::new)]
@@ -0,0 +1,121 @@
+use crate::newtypes::{DbUrl, PersonId};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "full")]
+use diesel::{sql_types::Nullable, AsExpression, FromSqlRow};
+#[cfg(feature = "full")]
+use lemmy_db_schema_file::schema::{video_details, video_transcode_job, video_variant};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Metadata of a video, either uploaded locally or received from a remote instance.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = video_details))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", diesel(primary_key(link)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct VideoDetails {
+  pub link: DbUrl,
+  pub width: Option<i32>,
+  pub height: Option<i32>,
+  pub duration_ms: Option<i32>,
+  /// A frame of the video, used as post thumbnail.
+  pub poster_url: Option<DbUrl>,
+  /// Set once all variants have been generated. Remote videos are never transcoded.
+  pub transcoded_at: Option<DateTime<Utc>>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = video_details))]
+pub struct VideoDetailsInsertForm {
+  pub link: DbUrl,
+  pub width: Option<i32>,
+  pub height: Option<i32>,
+  pub duration_ms: Option<i32>,
+  pub poster_url: Option<DbUrl>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = video_details))]
+pub struct VideoDetailsUpdateForm {
+  pub poster_url: Option<Option<DbUrl>>,
+  pub transcoded_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A playable version of a video in a given resolution.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, Associations)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = video_variant))]
+#[cfg_attr(feature = "full", diesel(belongs_to(VideoDetails, foreign_key = link)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", diesel(primary_key(link, height)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct VideoVariant {
+  pub link: DbUrl,
+  pub height: i32,
+  pub url: DbUrl,
+  pub content_type: String,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = video_variant))]
+pub struct VideoVariantForm {
+  pub link: DbUrl,
+  pub height: i32,
+  pub url: DbUrl,
+  pub content_type: String,
+}
+
+/// A local upload which is waiting to be transcoded.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = video_transcode_job))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", diesel(primary_key(link)))]
+pub struct VideoTranscodeJob {
+  pub link: DbUrl,
+  /// Alias of the uploaded file in the media backend.
+  pub alias: String,
+  /// The uploader, who owns the generated files.
+  pub person_id: PersonId,
+  pub attempts: i32,
+  pub next_attempt_at: DateTime<Utc>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = video_transcode_job))]
+pub struct VideoTranscodeJobInsertForm {
+  pub link: DbUrl,
+  pub alias: String,
+  pub person_id: PersonId,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = video_transcode_job))]
+pub struct VideoTranscodeJobUpdateForm {
+  pub attempts: Option<i32>,
+  pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+/// We wrap this in a struct so we can implement FromSqlRow<Json> for it
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(transparent)]
+#[cfg_attr(feature = "full", derive(FromSqlRow, AsExpression))]
+#[cfg_attr(feature = "full", diesel(sql_type = Nullable<diesel::sql_types::Json>))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct VideoVariantsView(pub Vec<VideoVariant>);
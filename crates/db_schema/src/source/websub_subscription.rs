@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use lemmy_db_schema_file::schema::websub_subscription;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = websub_subscription))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+/// A subscriber which gets new content of a public feed pushed with WebSub.
+pub struct WebsubSubscription {
+  pub id: i32,
+  /// The url of the feed.
+  pub topic: String,
+  /// The feed of the topic, independent of its format and parameters, for example `c/news`.
+  pub feed: String,
+  /// Where new feed content is sent to.
+  pub callback: String,
+  /// If set, pushed content is signed with this secret.
+  pub secret: Option<String>,
+  pub lease_expires_at: DateTime<Utc>,
+  /// Feed items up to this time were delivered to the subscriber.
+  pub last_pushed_at: DateTime<Utc>,
+  /// When content was last published in the feed.
+  pub changed_at: Option<DateTime<Utc>>,
+  pub published_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = websub_subscription))]
+#[cfg_attr(feature = "full", diesel(treat_none_as_null = true))]
+pub struct WebsubSubscriptionForm {
+  pub topic: String,
+  pub feed: String,
+  pub callback: String,
+  pub secret: Option<String>,
+  pub lease_expires_at: DateTime<Utc>,
+}
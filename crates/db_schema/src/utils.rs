@@ -1,7 +1,6 @@
 pub mod queries;
 
 use crate::newtypes::DbUrl;
-use chrono::TimeDelta;
 use deadpool::Runtime;
 use diesel::{
   dsl,
@@ -59,7 +58,6 @@ use url::Url;
 const FETCH_LIMIT_DEFAULT: i64 = 20;
 pub const FETCH_LIMIT_MAX: usize = 50;
 pub const SITEMAP_LIMIT: i64 = 50000;
-pub const SITEMAP_DAYS: TimeDelta = TimeDelta::days(31);
 pub const RANK_DEFAULT: f64 = 0.0001;
 
 pub type ActualDbPool = Pool<AsyncPgConnection>;
@@ -198,6 +196,29 @@ where
   }
 }
 
+/// Necessary to be able to use cursors with a negated bool column, which sorts `false` first when
+/// sorting descending
+pub struct NotKey<K>(pub K);
+
+impl<K, C> CursorKey<C> for NotKey<K>
+where
+  K: CursorKey<C, SqlType = sql_types::Bool>,
+  K::CursorValue: Expression<SqlType = sql_types::Bool>,
+  K::SqlValue: Expression<SqlType = sql_types::Bool>,
+{
+  type SqlType = sql_types::Bool;
+  type CursorValue = dsl::not<K::CursorValue>;
+  type SqlValue = dsl::not<K::SqlValue>;
+
+  fn get_cursor_value(cursor: &C) -> Self::CursorValue {
+    dsl::not(K::get_cursor_value(cursor))
+  }
+
+  fn get_sql_value() -> Self::SqlValue {
+    dsl::not(K::get_sql_value())
+  }
+}
+
 /// Necessary to be able to use cursors with the subpath SQL function
 pub struct Subpath<K>(pub K);
 
@@ -218,6 +239,26 @@ where
   }
 }
 
+/// Necessary to be able to use cursors with the nlevel SQL function
+pub struct Nlevel<K>(pub K);
+
+impl<K, C> CursorKey<C> for Nlevel<K>
+where
+  K: CursorKey<C, SqlType = diesel_ltree::sql_types::Ltree>,
+{
+  type SqlType = sql_types::Integer;
+  type CursorValue = diesel_ltree::nlevel<K::CursorValue>;
+  type SqlValue = diesel_ltree::nlevel<K::SqlValue>;
+
+  fn get_cursor_value(cursor: &C) -> Self::CursorValue {
+    diesel_ltree::nlevel(K::get_cursor_value(cursor))
+  }
+
+  fn get_sql_value() -> Self::SqlValue {
+    diesel_ltree::nlevel(K::get_sql_value())
+  }
+}
+
 /// Includes an SQL comment before `T`, which can be used to label auto_explain output
 #[derive(QueryId)]
 pub struct Commented<T> {
@@ -311,10 +352,10 @@ pub fn diesel_string_update(opt: Option<&str>) -> Option<Option<String>> {
 }
 
 /// Takes an API optional number, and converts it to an optional diesel DB update. Zero means erase.
-pub fn diesel_opt_number_update(opt: Option<i32>) -> Option<Option<i32>> {
+pub fn diesel_opt_number_update<T: Default + PartialEq>(opt: Option<T>) -> Option<Option<T>> {
   match opt {
     // Zero is an erase
-    Some(0) => Some(None),
+    Some(num) if num == T::default() => Some(None),
     Some(num) => Some(Some(num)),
     None => None,
   }
@@ -509,6 +550,11 @@ pub mod functions {
     fn json_agg<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(obj: T) -> Json
   }
 
+  // really this function is variadic, this just adds the two-argument version
+  define_sql_function!(fn greatest<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(x: T, y: T) -> T);
+
+  define_sql_function!(fn date_trunc(field: Text, source: Timestamptz, time_zone: Text) -> Timestamptz);
+
   define_sql_function!(#[sql_name = "coalesce"] fn coalesce_2_nullable<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(x: diesel::sql_types::Nullable<T>, y: diesel::sql_types::Nullable<T>) -> diesel::sql_types::Nullable<T>);
 }
 
@@ -27,12 +27,13 @@ use diesel::{
   QueryDsl,
 };
 use lemmy_db_schema_file::{
-  enums::{CommunityFollowerState, CommunityVisibility},
+  enums::{CommunityFollowerState, CommunityVisibility, FederationBlockSeverity},
   schema::{
     comment,
     comment_actions,
     community,
     community_actions,
+    federation_blocklist,
     image_details,
     instance_actions,
     local_site,
@@ -43,8 +44,11 @@ use lemmy_db_schema_file::{
     person_actions,
     post,
     post_actions,
+    post_gallery_item,
     post_tag,
     tag,
+    video_details,
+    video_variant,
   },
 };
 
@@ -239,6 +243,7 @@ pub fn comment_select_remove_deletes() -> _ {
     comment::report_count,
     comment::unresolved_report_count,
     comment::federation_pending,
+    comment::pending_approval,
   )
 }
 
@@ -254,6 +259,30 @@ pub fn post_tags_fragment() -> _ {
     .single_value()
 }
 
+#[diesel::dsl::auto_type]
+/// Gets the images of a gallery post, in their order
+pub fn post_gallery_fragment() -> _ {
+  let sel: SqlLiteral<Json> = diesel::dsl::sql::<diesel::sql_types::Json>(
+    "json_agg(post_gallery_item.* ORDER BY post_gallery_item.position)",
+  );
+  post_gallery_item::table
+    .select(sel)
+    .filter(post_gallery_item::post_id.eq(post::id))
+    .single_value()
+}
+
+#[diesel::dsl::auto_type]
+/// The playable versions of a video post, highest resolution first.
+pub fn post_video_variants_fragment() -> _ {
+  let sel: SqlLiteral<Json> = diesel::dsl::sql::<diesel::sql_types::Json>(
+    "json_agg(video_variant.* ORDER BY video_variant.height DESC)",
+  );
+  video_variant::table
+    .select(sel)
+    .filter(post::url.eq(video_variant::link.nullable()))
+    .single_value()
+}
+
 #[diesel::dsl::auto_type]
 /// Gets the post tags available within a specific community
 pub fn community_post_tags_fragment() -> _ {
@@ -282,6 +311,13 @@ pub fn filter_is_subscribed() -> IsSubscribedType {
   community_actions::follow_state.eq(Some(CommunityFollowerState::Accepted))
 }
 
+type IsFollowingCreatorType =
+  Eq<lemmy_db_schema_file::schema::person_actions::follow_pending, Option<bool>>;
+
+pub fn filter_is_following_creator() -> IsFollowingCreatorType {
+  person_actions::follow_pending.eq(Some(false))
+}
+
 type IsNotUnlistedType =
   NotEq<lemmy_db_schema_file::schema::community::visibility, CommunityVisibility>;
 
@@ -292,6 +328,26 @@ pub fn filter_not_unlisted_or_is_subscribed() -> _ {
   not_unlisted.or(is_subscribed)
 }
 
+type IsSilencedType =
+  Eq<lemmy_db_schema_file::schema::federation_blocklist::severity, FederationBlockSeverity>;
+
+/// Hide content from silenced instances, unless the user follows the community. Applies to both
+/// the instance of the community and the instance of the creator.
+#[diesel::dsl::auto_type]
+pub fn filter_not_silenced() -> _ {
+  let is_silenced: IsSilencedType =
+    federation_blocklist::severity.eq(FederationBlockSeverity::Silence);
+  let is_subscribed: IsSubscribedType = filter_is_subscribed();
+  not(exists(
+    federation_blocklist::table.filter(is_silenced).filter(
+      federation_blocklist::instance_id
+        .eq(community::instance_id)
+        .or(federation_blocklist::instance_id.eq(person::instance_id)),
+    ),
+  ))
+  .or(is_subscribed)
+}
+
 #[diesel::dsl::auto_type]
 pub fn community_join() -> _ {
   community::table.on(post::community_id.eq(community::id))
@@ -389,6 +445,11 @@ pub fn image_details_join() -> _ {
   image_details::table.on(post::thumbnail_url.eq(image_details::link.nullable()))
 }
 
+#[diesel::dsl::auto_type]
+pub fn video_details_join() -> _ {
+  video_details::table.on(post::url.eq(video_details::link.nullable()))
+}
+
 #[diesel::dsl::auto_type]
 pub fn my_community_actions_join(my_person_id: Option<PersonId>) -> _ {
   community_actions::table.on(
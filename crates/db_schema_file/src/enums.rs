@@ -29,6 +29,60 @@ pub enum PostSortType {
   Scaled,
 }
 
+#[derive(
+  EnumString,
+  Display,
+  Debug,
+  Serialize,
+  Deserialize,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Default,
+  Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::ReportSeverityEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// How urgently a report needs to be handled, as chosen by the reporter.
+pub enum ReportSeverity {
+  Low,
+  #[default]
+  Medium,
+  High,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::CommentDisplayModeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// How the comments of a post are displayed.
+pub enum CommentDisplayMode {
+  /// Replies are nested below their parent comment.
+  #[default]
+  Threaded,
+  /// All comments in chronological order, like a chat.
+  Flat,
+  /// Top-level comments are answers to the post. Answers accepted by the post creator are shown
+  /// first.
+  QuestionAnswer,
+}
+
 #[derive(
   EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
 )]
@@ -74,6 +128,8 @@ pub enum ListingType {
   ModeratorView,
   /// Communities which are recommended by local instance admins
   Suggested,
+  /// Content only from persons you follow.
+  FollowedPersons,
 }
 
 #[derive(
@@ -180,12 +236,15 @@ pub enum FederationMode {
   Disable,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(DbEnum))]
 #[cfg_attr(
   feature = "full",
   ExistingTypePath = "crate::schema::sql_types::ActorTypeEnum"
 )]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// The type of an ActivityPub actor.
 pub enum ActorType {
   Site,
   Community,
@@ -266,6 +325,45 @@ pub enum CommunityNotificationsMode {
   Mute,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::FederationBlockSeverityEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// How strictly an instance on the federation blocklist is blocked.
+pub enum FederationBlockSeverity {
+  /// Federation continues, but content from the instance is hidden from the `All` listing.
+  Silence,
+  /// Activities from and to the instance are rejected entirely.
+  #[default]
+  Suspend,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::PersonNotificationsModeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// Available settings for notifications about new content of followed persons
+pub enum PersonNotificationsMode {
+  AllPostsAndComments,
+  AllPosts,
+  #[default]
+  Disabled,
+}
+
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(DbEnum))]
 #[cfg_attr(
@@ -282,3 +380,47 @@ pub enum NotificationTypes {
   Subscribed,
   PrivateMessage,
 }
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::AutomodActionEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// The action which an automod rule takes when it matches a post or comment.
+pub enum AutomodAction {
+  /// Remove the content as a moderator.
+  Remove,
+  /// Hide the content until a moderator approves it.
+  Hold,
+  /// File a report for moderators to review.
+  Report,
+  /// Mark the post as NSFW.
+  MarkNsfw,
+  /// Add the rule's tag to the post.
+  AddTag,
+  /// Reply with the rule's message as a distinguished comment.
+  Reply,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::FederationQueueCommandEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+/// A command from an admin for the federation worker of an instance.
+pub enum FederationQueueCommandType {
+  /// Retry sending a failed activity right away, instead of waiting for the retry delay.
+  RetryNow,
+  /// Stop trying to send the activities up to the given id.
+  Skip,
+  /// Send the activities in the given range again.
+  Resend,
+}
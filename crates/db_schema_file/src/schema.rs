@@ -1,10 +1,18 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "automod_action_enum"))]
+  pub struct AutomodActionEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "actor_type_enum"))]
   pub struct ActorTypeEnum;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "comment_display_mode_enum"))]
+  pub struct CommentDisplayModeEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "comment_sort_type_enum"))]
   pub struct CommentSortTypeEnum;
@@ -21,10 +29,18 @@ pub mod sql_types {
   #[diesel(postgres_type(name = "community_visibility"))]
   pub struct CommunityVisibility;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "federation_block_severity_enum"))]
+  pub struct FederationBlockSeverityEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "federation_mode_enum"))]
   pub struct FederationModeEnum;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "federation_queue_command_enum"))]
+  pub struct FederationQueueCommandEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "listing_type_enum"))]
   pub struct ListingTypeEnum;
@@ -37,6 +53,10 @@ pub mod sql_types {
   #[diesel(postgres_type(name = "notification_type_enum"))]
   pub struct NotificationTypeEnum;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "person_notifications_mode_enum"))]
+  pub struct PersonNotificationsModeEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "post_listing_mode_enum"))]
   pub struct PostListingModeEnum;
@@ -53,11 +73,29 @@ pub mod sql_types {
   #[diesel(postgres_type(name = "registration_mode_enum"))]
   pub struct RegistrationModeEnum;
 
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "report_severity_enum"))]
+  pub struct ReportSeverityEnum;
+
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "vote_show_enum"))]
   pub struct VoteShowEnum;
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ActorTypeEnum;
+
+    actor_key_rotation (id) {
+        id -> Int4,
+        actor_type -> ActorTypeEnum,
+        #[max_length = 255]
+        actor_ap_id -> Varchar,
+        admin_person_id -> Nullable<Int4>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     admin_add (id) {
         id -> Int4,
@@ -93,6 +131,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationBlockSeverityEnum;
+
     admin_block_instance (id) {
         id -> Int4,
         instance_id -> Int4,
@@ -101,6 +142,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         expires_at -> Nullable<Timestamptz>,
         published_at -> Timestamptz,
+        severity -> Nullable<FederationBlockSeverityEnum>,
     }
 }
 
@@ -153,6 +195,34 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AutomodActionEnum;
+
+    automod_rule (id) {
+        id -> Int4,
+        community_id -> Int4,
+        creator_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        enabled -> Bool,
+        apply_to_posts -> Bool,
+        apply_to_comments -> Bool,
+        title_regex -> Nullable<Text>,
+        body_regex -> Nullable<Text>,
+        url_domain_regex -> Nullable<Text>,
+        instance_domain_regex -> Nullable<Text>,
+        max_account_age_days -> Nullable<Int4>,
+        max_karma -> Nullable<Int4>,
+        language_id -> Nullable<Int4>,
+        action -> AutomodActionEnum,
+        action_message -> Nullable<Text>,
+        tag_id -> Nullable<Int4>,
+        published_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     captcha_answer (uuid) {
         uuid -> Uuid,
@@ -189,6 +259,8 @@ diesel::table! {
         report_count -> Int2,
         unresolved_report_count -> Int2,
         federation_pending -> Bool,
+        pending_approval -> Bool,
+        accepted_answer -> Bool,
     }
 }
 
@@ -203,6 +275,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ReportSeverityEnum;
+
     comment_report (id) {
         id -> Int4,
         creator_id -> Int4,
@@ -214,6 +289,7 @@ diesel::table! {
         published_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
         violates_instance_rules -> Bool,
+        severity -> ReportSeverityEnum,
     }
 }
 
@@ -268,6 +344,21 @@ diesel::table! {
         unresolved_report_count -> Int2,
         interactions_month -> Int4,
         local_removed -> Bool,
+        posts_require_approval -> Bool,
+        comments_require_approval -> Bool,
+        approval_max_account_age_days -> Nullable<Int4>,
+        comment_slow_mode_minutes -> Nullable<Int4>,
+        posts_frozen -> Bool,
+        comments_frozen -> Bool,
+        freeze_expires_at -> Nullable<Timestamptz>,
+        #[max_length = 255]
+        moved_to_url -> Nullable<Varchar>,
+        #[max_length = 255]
+        moved_from_url -> Nullable<Varchar>,
+        previous_public_key -> Nullable<Text>,
+        key_rotated_at -> Nullable<Timestamptz>,
+        cross_posting_restricted_to_mods -> Bool,
+        question_answer -> Bool,
     }
 }
 
@@ -364,11 +455,57 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationBlockSeverityEnum;
+
     federation_blocklist (instance_id) {
         instance_id -> Int4,
         published_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
         expires_at -> Nullable<Timestamptz>,
+        severity -> FederationBlockSeverityEnum,
+        public_reason -> Nullable<Text>,
+        private_comment -> Nullable<Text>,
+        subscription_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationBlockSeverityEnum;
+
+    federation_blocklist_pending (id) {
+        id -> Int4,
+        subscription_id -> Int4,
+        domain -> Text,
+        severity -> FederationBlockSeverityEnum,
+        public_reason -> Nullable<Text>,
+        published_at -> Timestamptz,
+        discarded_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    federation_blocklist_subscription (id) {
+        id -> Int4,
+        url -> Text,
+        last_refreshed_at -> Nullable<Timestamptz>,
+        last_error -> Nullable<Text>,
+        published_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FederationQueueCommandEnum;
+
+    federation_queue_command (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        command -> FederationQueueCommandEnum,
+        first_activity_id -> Nullable<Int8>,
+        last_activity_id -> Nullable<Int8>,
+        published_at -> Timestamptz,
     }
 }
 
@@ -379,6 +516,34 @@ diesel::table! {
         fail_count -> Int4,
         last_retry_at -> Nullable<Timestamptz>,
         last_successful_published_time_at -> Nullable<Timestamptz>,
+        paused -> Bool,
+    }
+}
+
+diesel::table! {
+    federation_relay (id) {
+        id -> Int4,
+        url -> Text,
+        ap_id -> Nullable<Text>,
+        inbox_url -> Nullable<Text>,
+        public_key -> Nullable<Text>,
+        follow_pending -> Bool,
+        publish -> Bool,
+        follow_id -> Nullable<Text>,
+        max_objects_per_hour -> Int4,
+        published_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    federation_send_error (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        activity_id -> Int8,
+        http_status -> Nullable<Int4>,
+        error -> Text,
+        published_at -> Timestamptz,
     }
 }
 
@@ -393,6 +558,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    image_hash_block (id) {
+        id -> Int4,
+        hash -> Int8,
+        reason -> Nullable<Text>,
+        admin_person_id -> Nullable<Int4>,
+        published_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    inbound_activity (id) {
+        id -> Int4,
+        ap_id -> Text,
+        actor_apub_id -> Text,
+        data -> Text,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_error -> Nullable<Text>,
+        failed_at -> Nullable<Timestamptz>,
+        claimed_until -> Nullable<Timestamptz>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     instance (id) {
         id -> Int4,
@@ -433,6 +623,8 @@ diesel::table! {
         published_at -> Timestamptz,
         person_id -> Nullable<Int4>,
         thumbnail_for_post_id -> Nullable<Int4>,
+        phash -> Nullable<Int8>,
+        file_size -> Nullable<Int8>,
     }
 }
 
@@ -490,6 +682,10 @@ diesel::table! {
         suggested_communities -> Nullable<Int4>,
         multi_comm_follower -> Int4,
         default_items_per_page -> Int4,
+        sitemap_exclude_nsfw -> Bool,
+        image_hash_block_threshold -> Int4,
+        media_quota_user -> Nullable<Int8>,
+        media_quota_moderator -> Nullable<Int8>,
     }
 }
 
@@ -571,6 +767,7 @@ diesel::table! {
         show_upvote_percentage -> Bool,
         show_person_votes -> Bool,
         default_items_per_page -> Int4,
+        media_quota -> Nullable<Int8>,
     }
 }
 
@@ -610,6 +807,23 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AutomodActionEnum;
+
+    mod_automod_action (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        automod_rule_id -> Nullable<Int4>,
+        community_id -> Int4,
+        post_id -> Int4,
+        comment_id -> Nullable<Int4>,
+        action -> AutomodActionEnum,
+        reason -> Nullable<Text>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     mod_ban_from_community (id) {
         id -> Int4,
@@ -647,6 +861,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mod_freeze_community (id) {
+        id -> Int4,
+        mod_person_id -> Nullable<Int4>,
+        community_id -> Int4,
+        posts_frozen -> Bool,
+        comments_frozen -> Bool,
+        expires_at -> Nullable<Timestamptz>,
+        reason -> Nullable<Text>,
+        published_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     mod_lock_post (id) {
         id -> Int4,
@@ -655,6 +882,8 @@ diesel::table! {
         locked -> Bool,
         published_at -> Timestamptz,
         reason -> Nullable<Text>,
+        expires_at -> Nullable<Timestamptz>,
+        max_account_age_days -> Nullable<Int4>,
     }
 }
 
@@ -711,6 +940,8 @@ diesel::table! {
         mod_remove_post_id -> Nullable<Int4>,
         mod_transfer_community_id -> Nullable<Int4>,
         mod_change_community_visibility_id -> Nullable<Int4>,
+        mod_automod_action_id -> Nullable<Int4>,
+        mod_freeze_community_id -> Nullable<Int4>,
     }
 }
 
@@ -840,10 +1071,15 @@ diesel::table! {
         post_score -> Int4,
         comment_count -> Int4,
         comment_score -> Int4,
+        previous_public_key -> Nullable<Text>,
+        key_rotated_at -> Nullable<Timestamptz>,
     }
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PersonNotificationsModeEnum;
+
     person_actions (person_id, target_id) {
         person_id -> Int4,
         target_id -> Int4,
@@ -855,6 +1091,7 @@ diesel::table! {
         voted_at -> Nullable<Timestamptz>,
         upvotes -> Nullable<Int4>,
         downvotes -> Nullable<Int4>,
+        notifications -> Nullable<PersonNotificationsModeEnum>,
     }
 }
 
@@ -889,6 +1126,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::CommentDisplayModeEnum;
+
     post (id) {
         id -> Int4,
         #[max_length = 200]
@@ -930,6 +1170,13 @@ diesel::table! {
         report_count -> Int2,
         unresolved_report_count -> Int2,
         federation_pending -> Bool,
+        pending_approval -> Bool,
+        lock_expires_at -> Nullable<Timestamptz>,
+        lock_max_account_age_days -> Nullable<Int4>,
+        comment_slow_mode_minutes -> Nullable<Int4>,
+        cross_post_of_id -> Nullable<Int4>,
+        comment_display_mode -> CommentDisplayModeEnum,
+        solved -> Bool,
     }
 }
 
@@ -952,6 +1199,20 @@ diesel::table! {
 }
 
 diesel::table! {
+    post_gallery_item (post_id, position) {
+        post_id -> Int4,
+        position -> Int4,
+        url -> Text,
+        url_content_type -> Nullable<Text>,
+        alt_text -> Nullable<Text>,
+        caption -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ReportSeverityEnum;
+
     post_report (id) {
         id -> Int4,
         creator_id -> Int4,
@@ -966,6 +1227,7 @@ diesel::table! {
         published_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
         violates_instance_rules -> Bool,
+        severity -> ReportSeverityEnum,
     }
 }
 
@@ -1102,6 +1364,8 @@ diesel::table! {
         public_key -> Text,
         instance_id -> Int4,
         content_warning -> Nullable<Text>,
+        previous_public_key -> Nullable<Text>,
+        key_rotated_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -1137,6 +1401,53 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    video_details (link) {
+        link -> Text,
+        width -> Nullable<Int4>,
+        height -> Nullable<Int4>,
+        duration_ms -> Nullable<Int4>,
+        poster_url -> Nullable<Text>,
+        transcoded_at -> Nullable<Timestamptz>,
+        published_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    video_transcode_job (link) {
+        link -> Text,
+        alias -> Text,
+        person_id -> Int4,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        published_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    video_variant (link, height) {
+        link -> Text,
+        height -> Int4,
+        url -> Text,
+        content_type -> Text,
+    }
+}
+
+diesel::table! {
+    websub_subscription (id) {
+        id -> Int4,
+        topic -> Text,
+        feed -> Text,
+        callback -> Text,
+        secret -> Nullable<Text>,
+        lease_expires_at -> Timestamptz,
+        last_pushed_at -> Timestamptz,
+        changed_at -> Nullable<Timestamptz>,
+        published_at -> Timestamptz,
+    }
+}
+
+diesel::joinable!(actor_key_rotation -> person (admin_person_id));
 diesel::joinable!(admin_allow_instance -> instance (instance_id));
 diesel::joinable!(admin_allow_instance -> person (admin_person_id));
 diesel::joinable!(admin_ban -> instance (instance_id));
@@ -1150,6 +1461,10 @@ diesel::joinable!(admin_purge_post -> community (community_id));
 diesel::joinable!(admin_purge_post -> person (admin_person_id));
 diesel::joinable!(admin_remove_community -> community (community_id));
 diesel::joinable!(admin_remove_community -> person (mod_person_id));
+diesel::joinable!(automod_rule -> community (community_id));
+diesel::joinable!(automod_rule -> language (language_id));
+diesel::joinable!(automod_rule -> person (creator_id));
+diesel::joinable!(automod_rule -> tag (tag_id));
 diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
@@ -1164,8 +1479,13 @@ diesel::joinable!(community_report -> community (community_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
+diesel::joinable!(federation_blocklist -> federation_blocklist_subscription (subscription_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_blocklist_pending -> federation_blocklist_subscription (subscription_id));
+diesel::joinable!(federation_queue_command -> instance (instance_id));
 diesel::joinable!(federation_queue_state -> instance (instance_id));
+diesel::joinable!(federation_send_error -> instance (instance_id));
+diesel::joinable!(image_hash_block -> person (admin_person_id));
 diesel::joinable!(instance_actions -> instance (instance_id));
 diesel::joinable!(instance_actions -> person (person_id));
 diesel::joinable!(local_image -> person (person_id));
@@ -1180,11 +1500,18 @@ diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
 diesel::joinable!(login_token -> local_user (user_id));
 diesel::joinable!(mod_add_to_community -> community (community_id));
+diesel::joinable!(mod_automod_action -> automod_rule (automod_rule_id));
+diesel::joinable!(mod_automod_action -> comment (comment_id));
+diesel::joinable!(mod_automod_action -> community (community_id));
+diesel::joinable!(mod_automod_action -> person (mod_person_id));
+diesel::joinable!(mod_automod_action -> post (post_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_change_community_visibility -> community (community_id));
 diesel::joinable!(mod_change_community_visibility -> person (mod_person_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
 diesel::joinable!(mod_feature_post -> post (post_id));
+diesel::joinable!(mod_freeze_community -> community (community_id));
+diesel::joinable!(mod_freeze_community -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> person (mod_person_id));
 diesel::joinable!(mod_lock_post -> post (post_id));
 diesel::joinable!(mod_remove_comment -> comment (comment_id));
@@ -1202,9 +1529,11 @@ diesel::joinable!(modlog_combined -> admin_purge_person (admin_purge_person_id))
 diesel::joinable!(modlog_combined -> admin_purge_post (admin_purge_post_id));
 diesel::joinable!(modlog_combined -> admin_remove_community (admin_remove_community_id));
 diesel::joinable!(modlog_combined -> mod_add_to_community (mod_add_to_community_id));
+diesel::joinable!(modlog_combined -> mod_automod_action (mod_automod_action_id));
 diesel::joinable!(modlog_combined -> mod_ban_from_community (mod_ban_from_community_id));
 diesel::joinable!(modlog_combined -> mod_change_community_visibility (mod_change_community_visibility_id));
 diesel::joinable!(modlog_combined -> mod_feature_post (mod_feature_post_id));
+diesel::joinable!(modlog_combined -> mod_freeze_community (mod_freeze_community_id));
 diesel::joinable!(modlog_combined -> mod_lock_post (mod_lock_post_id));
 diesel::joinable!(modlog_combined -> mod_remove_comment (mod_remove_comment_id));
 diesel::joinable!(modlog_combined -> mod_remove_post (mod_remove_post_id));
@@ -1236,6 +1565,7 @@ diesel::joinable!(post -> language (language_id));
 diesel::joinable!(post -> person (creator_id));
 diesel::joinable!(post_actions -> person (person_id));
 diesel::joinable!(post_actions -> post (post_id));
+diesel::joinable!(post_gallery_item -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_tag -> post (post_id));
 diesel::joinable!(post_tag -> tag (tag_id));
@@ -1255,8 +1585,12 @@ diesel::joinable!(site -> instance (instance_id));
 diesel::joinable!(site_language -> language (language_id));
 diesel::joinable!(site_language -> site (site_id));
 diesel::joinable!(tag -> community (community_id));
+diesel::joinable!(video_transcode_job -> person (person_id));
+diesel::joinable!(video_transcode_job -> video_details (link));
+diesel::joinable!(video_variant -> video_details (link));
 
 diesel::allow_tables_to_appear_in_same_query!(
+  actor_key_rotation,
   admin_add,
   admin_allow_instance,
   admin_ban,
@@ -1266,6 +1600,7 @@ diesel::allow_tables_to_appear_in_same_query!(
   admin_purge_person,
   admin_purge_post,
   admin_remove_community,
+  automod_rule,
   captcha_answer,
   comment,
   comment_actions,
@@ -1280,8 +1615,15 @@ diesel::allow_tables_to_appear_in_same_query!(
   email_verification,
   federation_allowlist,
   federation_blocklist,
+  federation_blocklist_pending,
+  federation_blocklist_subscription,
+  federation_queue_command,
   federation_queue_state,
+  federation_relay,
+  federation_send_error,
   image_details,
+  image_hash_block,
+  inbound_activity,
   instance,
   instance_actions,
   language,
@@ -1294,9 +1636,11 @@ diesel::allow_tables_to_appear_in_same_query!(
   local_user_language,
   login_token,
   mod_add_to_community,
+  mod_automod_action,
   mod_ban_from_community,
   mod_change_community_visibility,
   mod_feature_post,
+  mod_freeze_community,
   mod_lock_post,
   mod_remove_comment,
   mod_remove_post,
@@ -1316,6 +1660,7 @@ diesel::allow_tables_to_appear_in_same_query!(
   person_saved_combined,
   post,
   post_actions,
+  post_gallery_item,
   post_report,
   post_tag,
   private_message,
@@ -1331,4 +1676,8 @@ diesel::allow_tables_to_appear_in_same_query!(
   site_language,
   tag,
   tagline,
+  video_details,
+  video_transcode_job,
+  video_variant,
+  websub_subscription,
 );
@@ -396,6 +396,32 @@ CREATE TRIGGER change_values
     BEFORE INSERT ON private_message
     FOR EACH ROW
     EXECUTE FUNCTION r.private_message_change_values ();
+-- Keep the previous public key when the key of an actor changes, for both local rotations and
+-- refetched remote actors
+CREATE FUNCTION r.actor_key_change_values ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    IF NEW.public_key IS DISTINCT FROM OLD.public_key THEN
+        NEW.previous_public_key = OLD.public_key;
+        NEW.key_rotated_at = now();
+    END IF;
+    RETURN NEW;
+END
+$$;
+CREATE TRIGGER key_change_values
+    BEFORE UPDATE OF public_key ON person
+    FOR EACH ROW
+    EXECUTE FUNCTION r.actor_key_change_values ();
+CREATE TRIGGER key_change_values
+    BEFORE UPDATE OF public_key ON community
+    FOR EACH ROW
+    EXECUTE FUNCTION r.actor_key_change_values ();
+CREATE TRIGGER key_change_values
+    BEFORE UPDATE OF public_key ON site
+    FOR EACH ROW
+    EXECUTE FUNCTION r.actor_key_change_values ();
 -- Combined tables triggers
 -- These insert (published_at, item_id) into X_combined tables
 -- Reports (comment_report, post_report, private_message_report)
@@ -613,6 +639,8 @@ CALL r.create_modlog_combined_trigger ('mod_remove_comment');
 CALL r.create_modlog_combined_trigger ('admin_remove_community');
 CALL r.create_modlog_combined_trigger ('mod_remove_post');
 CALL r.create_modlog_combined_trigger ('mod_transfer_community');
+CALL r.create_modlog_combined_trigger ('mod_automod_action');
+CALL r.create_modlog_combined_trigger ('mod_freeze_community');
 -- Prevent using delete instead of uplete on action tables
 CREATE FUNCTION r.require_uplete ()
     RETURNS TRIGGER
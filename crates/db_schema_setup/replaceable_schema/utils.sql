@@ -287,3 +287,32 @@ BEGIN
 END;
 $$;
 
+-- All unresolved reports on a single post or comment, grouped into one case. Reports which
+-- violate instance rules are only visible to admins, so they form a separate case. The `id` is
+-- the newest report in the case, which is used to read the full report view.
+CREATE VIEW r.report_case AS
+SELECT
+    (array_agg(report_combined.id ORDER BY report_combined.published_at DESC, report_combined.id DESC))[1] AS id,
+    max(report_combined.published_at) AS published_at,
+    min(report_combined.published_at) AS oldest_published_at,
+    count(*) AS report_count,
+    coalesce(post_report.violates_instance_rules, comment_report.violates_instance_rules) AS violates_instance_rules,
+    max(coalesce(post_report.severity, comment_report.severity)) AS severity,
+    array_agg(coalesce(post_report.reason, comment_report.reason) ORDER BY report_combined.published_at DESC, report_combined.id DESC) AS reasons,
+    post_report.post_id,
+    comment_report.comment_id,
+    post.community_id
+FROM
+    report_combined
+    LEFT JOIN post_report ON post_report.id = report_combined.post_report_id
+    LEFT JOIN comment_report ON comment_report.id = report_combined.comment_report_id
+    LEFT JOIN comment ON comment.id = comment_report.comment_id
+    INNER JOIN post ON post.id = coalesce(post_report.post_id, comment.post_id)
+WHERE
+    NOT coalesce(post_report.resolved, comment_report.resolved)
+GROUP BY
+    post_report.post_id,
+    comment_report.comment_id,
+    post.community_id,
+    coalesce(post_report.violates_instance_rules, comment_report.violates_instance_rules);
+
@@ -1,10 +1,33 @@
 use crate::{CommentSlimView, CommentView};
 use lemmy_db_schema::newtypes::{CommentId, CommunityId, LanguageId, PaginationCursor, PostId};
-use lemmy_db_schema_file::enums::{CommentSortType, ListingType};
+use lemmy_db_schema_file::enums::{CommentDisplayMode, CommentSortType, ListingType};
 use lemmy_db_views_vote::VoteView;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Mark a top-level comment as accepted answer, which marks the post as solved. Only one answer
+/// can be accepted per post. Doable by the post creator and mods, for posts in question and answer
+/// mode or in question and answer communities.
+pub struct AcceptAnswer {
+  pub comment_id: CommentId,
+  pub accepted: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Approve or reject a comment which is held for moderator approval.
+pub struct ApprovePendingComment {
+  pub comment_id: CommentId,
+  pub approve: bool,
+  /// The reason for a rejection, which is sent to the comment creator.
+  pub reason: Option<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -14,6 +37,19 @@ pub struct CommentResponse {
   pub comment_view: CommentView,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A comment in a comment tree.
+pub struct CommentNode {
+  pub comment_view: CommentView,
+  /// The number of replies which were left out below this comment.
+  pub more_replies: i32,
+  /// To load the remaining replies, pass this as `page_cursor` and the comment as `parent_id`.
+  pub more_replies_cursor: Option<PaginationCursor>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -65,6 +101,38 @@ pub struct GetComment {
   pub id: CommentId,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Get a page of the comment tree of a post, in the display mode of the post. The top-level
+/// comments are paginated, and below each of them only the first `children_limit` replies per
+/// comment are included, up to `max_depth`.
+pub struct GetCommentTree {
+  pub post_id: Option<PostId>,
+  /// List the replies to this comment, instead of the top-level comments.
+  pub parent_id: Option<CommentId>,
+  pub sort: Option<CommentSortType>,
+  pub max_depth: Option<i32>,
+  pub children_limit: Option<i32>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub limit: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A comment tree response.
+pub struct GetCommentTreeResponse {
+  pub display_mode: CommentDisplayMode,
+  /// The comments in depth-first order. In flat mode, all comments of the post in chronological
+  /// order.
+  pub comments: Vec<CommentNode>,
+  /// The pagination cursor for the next page of top-level comments.
+  pub next_page: Option<PaginationCursor>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -129,6 +197,19 @@ pub struct ListCommentLikesResponse {
   pub prev_page: Option<PaginationCursor>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// List the comments which are held for moderator approval, oldest first. Without a community,
+/// lists the comments of all moderated communities, or of all communities for admins.
+pub struct ListPendingComments {
+  pub community_id: Option<CommunityId>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub page_back: Option<bool>,
+  pub limit: Option<i64>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
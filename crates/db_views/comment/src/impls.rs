@@ -1,6 +1,7 @@
-use crate::{CommentSlimView, CommentView};
+use crate::{api::CommentNode, CommentSlimView, CommentView};
 use diesel::{
-  dsl::exists,
+  dsl::{exists, sql},
+  sql_types::{BigInt, Bool, Integer, Nullable},
   BoolExpressionMethods,
   ExpressionMethods,
   JoinOnDsl,
@@ -9,7 +10,7 @@ use diesel::{
   SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
-use diesel_ltree::{nlevel, Ltree, LtreeExtensions};
+use diesel_ltree::{nlevel, subpath, Ltree, LtreeExtensions};
 use i_love_jesus::asc_if;
 use lemmy_db_schema::{
   impls::local_user::LocalUserOptionHelper,
@@ -31,6 +32,9 @@ use lemmy_db_schema::{
       creator_home_instance_actions_join,
       creator_local_instance_actions_join,
       filter_blocked,
+      filter_is_following_creator,
+      filter_not_silenced,
+      local_user_can_mod_comment,
       my_comment_actions_join,
       my_community_actions_join,
       my_instance_communities_actions_join,
@@ -41,11 +45,13 @@ use lemmy_db_schema::{
     },
     seconds_to_pg_interval,
     DbPool,
+    Nlevel,
     Subpath,
   },
 };
 use lemmy_db_schema_file::{
   enums::{
+    CommentDisplayMode,
     CommentSortType::{self, *},
     CommunityFollowerState,
     CommunityVisibility,
@@ -54,6 +60,7 @@ use lemmy_db_schema_file::{
   schema::{comment, community, community_actions, local_user_language, person, post},
 };
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::collections::HashMap;
 
 impl PaginationCursorBuilder for CommentView {
   type CursorData = Comment;
@@ -118,6 +125,14 @@ impl CommentView {
 
     query = my_local_user.visible_communities_only(query);
 
+    // Comments which are held for approval are only visible to their creator and mods
+    query = query.filter(
+      comment::pending_approval
+        .eq(false)
+        .or(comment::creator_id.nullable().eq(my_local_user.person_id()))
+        .or(local_user_can_mod_comment()),
+    );
+
     // Check permissions to view private community content.
     // Specifically, if the community is private then only accepted followers may view its
     // content, otherwise it is filtered out. Admins can view private community content
@@ -161,6 +176,17 @@ pub struct CommentQuery<'a> {
   pub parent_path: Option<Ltree>,
   pub local_user: Option<&'a LocalUser>,
   pub max_depth: Option<i32>,
+  /// Only list comments which are held for moderator approval. Permissions need to be checked by
+  /// the caller.
+  pub pending_approval_only: Option<bool>,
+  /// Only list the direct replies to `parent_path`, or the top-level comments of the post.
+  pub top_level_only: Option<bool>,
+  /// Only list the replies within these subtrees, which must all be on the same level.
+  pub subtrees: Option<Vec<Ltree>>,
+  /// Order accepted answers first, for posts in question and answer mode.
+  pub accepted_first: Option<bool>,
+  /// Only list the first replies of each comment in sort order. Requires `post_id`.
+  pub children_limit: Option<i64>,
   pub cursor_data: Option<Comment>,
   pub page_back: Option<bool>,
   pub limit: Option<i64>,
@@ -187,6 +213,21 @@ impl CommentQuery<'_> {
       query = query.filter(comment::path.contained_by(parent_path));
     };
 
+    if o.top_level_only.unwrap_or_default() {
+      // The root "0" is the parent of top-level comments
+      let parent_level = o.parent_path.as_ref().map(path_level).unwrap_or(Ok(1))?;
+      query = query.filter(nlevel(comment::path).eq(parent_level + 1));
+    }
+
+    if let Some(subtrees) = o.subtrees {
+      let level = subtrees.first().map(path_level).transpose()?;
+      if let Some(level) = level {
+        query = query
+          .filter(subpath(comment::path, 0, level).eq_any(subtrees))
+          .filter(nlevel(comment::path).gt(level));
+      }
+    }
+
     if let Some(community_id) = o.community_id {
       query = query.filter(post::community_id.eq(community_id));
     }
@@ -198,11 +239,12 @@ impl CommentQuery<'_> {
     query = match o.listing_type.unwrap_or_default() {
       ListingType::Subscribed => query.filter(is_subscribed),
       ListingType::Local => query.filter(community::local.eq(true)),
-      ListingType::All => query,
+      ListingType::All => query.filter(filter_not_silenced()),
       ListingType::ModeratorView => {
         query.filter(community_actions::became_moderator_at.is_not_null())
       }
       ListingType::Suggested => query.filter(suggested_communities()),
+      ListingType::FollowedPersons => query.filter(filter_is_following_creator()),
     };
 
     if !o.local_user.show_bot_accounts() {
@@ -239,6 +281,16 @@ impl CommentQuery<'_> {
         .or(comment::creator_id.nullable().eq(my_person_id)),
     );
 
+    if o.pending_approval_only.unwrap_or_default() {
+      query = query.filter(comment::pending_approval.eq(true));
+    } else {
+      query = query.filter(
+        comment::pending_approval
+          .eq(false)
+          .or(comment::creator_id.nullable().eq(my_person_id)),
+      );
+    }
+
     if !o.local_user.is_admin() {
       query = query.filter(
         community::visibility
@@ -253,11 +305,48 @@ impl CommentQuery<'_> {
         query.filter(comment::published_at.gt(now() - seconds_to_pg_interval(time_range_seconds)));
     }
 
+    // Only sort by ascending for Old
+    let sort = o.sort.unwrap_or(Hot);
+    let accepted_first = o.accepted_first.unwrap_or_default() && sort != Old;
+
+    // Number the replies of each comment in sort order, and keep the first ones
+    if let (Some(children_limit), Some(post_id)) = (o.children_limit, o.post_id) {
+      let mut order = vec![];
+      if accepted_first {
+        order.push("c.accepted_answer DESC");
+      }
+      if sort != New && sort != Old {
+        order.push("c.distinguished DESC");
+      }
+      match sort {
+        Hot => order.extend(["c.hot_rank DESC", "c.score DESC"]),
+        Controversial => order.push("c.controversy_rank DESC"),
+        Old => order.push("c.published_at ASC"),
+        New => order.push("c.published_at DESC"),
+        Top => order.push("c.score DESC"),
+      }
+      order.push(if sort == Old { "c.id ASC" } else { "c.id DESC" });
+
+      query = query.filter(
+        sql::<Bool>(
+          "comment.id IN (SELECT ranked.id FROM (SELECT c.id, row_number() OVER \
+           (PARTITION BY subpath(c.path, 0, -1) ORDER BY ",
+        )
+        .sql(&order.join(", "))
+        .sql(") AS rn FROM comment c WHERE c.post_id = ")
+        .bind::<Integer, _>(post_id)
+        .sql(" AND ((NOT c.pending_approval AND NOT c.federation_pending) OR c.creator_id = ")
+        .bind::<Nullable<Integer>, _>(my_person_id)
+        .sql(")) AS ranked WHERE ranked.rn <= ")
+        .bind::<BigInt, _>(children_limit)
+        .sql(")"),
+      );
+    }
+
     // A Max depth given means its a tree fetch
     let limit = if let Some(max_depth) = o.max_depth {
       let depth_limit = if let Some(parent_path) = o.parent_path.as_ref() {
-        let count: i32 = parent_path.0.split('.').count().try_into()?;
-        count + max_depth
+        path_level(parent_path)? + max_depth
         // Add one because of root "0"
       } else {
         max_depth + 1
@@ -265,14 +354,9 @@ impl CommentQuery<'_> {
 
       query = query.filter(nlevel(comment::path).le(depth_limit));
 
-      // TODO limit question. Limiting does not work for comment threads ATM, only max_depth
-      // For now, don't do any limiting for tree fetches
-      // https://stackoverflow.com/questions/72983614/postgres-ltree-how-to-limit-the-max-number-of-children-at-any-given-level
-
       // Don't use the regular error-checking one, many more comments must ofter be fetched.
-      // This does not work for comment trees, and the limit should be manually set to a high number
+      // Use `children_limit` to limit the replies of each comment instead.
       //
-      // If a max depth is given, then you know its a tree fetch, and limits should be ignored
       // TODO a kludge to prevent attacks. Limit comments to 300 for now.
       // (i64::MAX, 0)
       300
@@ -281,8 +365,6 @@ impl CommentQuery<'_> {
     };
     query = query.limit(limit);
 
-    // Only sort by ascending for Old
-    let sort = o.sort.unwrap_or(Hot);
     let sort_direction = asc_if(sort == Old);
 
     let mut pq = paginate(query, sort_direction, o.cursor_data, None, o.page_back);
@@ -291,10 +373,20 @@ impl CommentQuery<'_> {
     // Only order if filtering by a post id, or parent_path. DOS potential otherwise and max_depth
     // + !post_id isn't used anyways (afaik)
     if o.max_depth.is_some() && (o.post_id.is_some() || o.parent_path.is_some()) {
+      // With a children limit, go level by level so that the row limit cuts off the deepest
+      // replies instead of whole subtrees
+      if o.children_limit.is_some() {
+        pq = pq.then_order_by(Nlevel(key::path));
+      }
       // Always order by the parent path first
       pq = pq.then_order_by(Subpath(key::path));
     }
 
+    // Accepted answers go first, unless reading the thread chronologically
+    if accepted_first {
+      pq = pq.then_order_by(key::accepted_answer);
+    }
+
     // Distinguished comments should go first when viewing post
     // Don't do for new / old sorts
     if sort != New && sort != Old && (o.post_id.is_some() || o.parent_path.is_some()) {
@@ -314,6 +406,146 @@ impl CommentQuery<'_> {
   }
 }
 
+/// The number of labels in a comment path, including the root "0".
+fn path_level(path: &Ltree) -> LemmyResult<i32> {
+  Ok(path.0.split('.').count().try_into()?)
+}
+
+const DEFAULT_TREE_DEPTH: i32 = 6;
+const DEFAULT_CHILDREN_LIMIT: i32 = 5;
+const MAX_CHILDREN_LIMIT: i32 = 50;
+
+/// Fetches a page of the comment tree of a post. In threaded mode this takes two queries: one
+/// for the page of top-level comments, and one for the replies below them. Replies beyond the
+/// children limit are left out, and counted in [CommentNode::more_replies].
+pub struct CommentTreeQuery<'a> {
+  pub post_id: PostId,
+  pub parent_path: Option<Ltree>,
+  pub display_mode: CommentDisplayMode,
+  pub sort: Option<CommentSortType>,
+  pub max_depth: Option<i32>,
+  pub children_limit: Option<i32>,
+  pub local_user: Option<&'a LocalUser>,
+  pub cursor_data: Option<Comment>,
+  pub limit: Option<i64>,
+}
+
+impl CommentTreeQuery<'_> {
+  /// Returns the comments in depth-first order, and the cursor for the next page.
+  pub async fn list(
+    self,
+    site: &Site,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<(Vec<CommentNode>, Option<PaginationCursor>)> {
+    let o = self;
+    if o.display_mode == CommentDisplayMode::Flat {
+      let comments = CommentQuery {
+        post_id: Some(o.post_id),
+        parent_path: o.parent_path,
+        sort: Some(Old),
+        local_user: o.local_user,
+        cursor_data: o.cursor_data,
+        limit: o.limit,
+        ..Default::default()
+      }
+      .list(site, pool)
+      .await?;
+      let next_page = comments.last().map(PaginationCursorBuilder::to_cursor);
+      let nodes = comments
+        .into_iter()
+        .map(|comment_view| CommentNode {
+          comment_view,
+          more_replies: 0,
+          more_replies_cursor: None,
+        })
+        .collect();
+      return Ok((nodes, next_page));
+    }
+
+    let accepted_first = Some(o.display_mode == CommentDisplayMode::QuestionAnswer);
+    let top_level = CommentQuery {
+      post_id: Some(o.post_id),
+      parent_path: o.parent_path.clone(),
+      sort: o.sort,
+      local_user: o.local_user,
+      top_level_only: Some(true),
+      accepted_first,
+      cursor_data: o.cursor_data,
+      limit: o.limit,
+      ..Default::default()
+    }
+    .list(site, pool)
+    .await?;
+    let next_page = top_level.last().map(PaginationCursorBuilder::to_cursor);
+
+    let max_depth = o.max_depth.unwrap_or(DEFAULT_TREE_DEPTH);
+    let children_limit = o
+      .children_limit
+      .unwrap_or(DEFAULT_CHILDREN_LIMIT)
+      .clamp(0, MAX_CHILDREN_LIMIT);
+    let replies = if max_depth > 1 && !top_level.is_empty() {
+      CommentQuery {
+        post_id: Some(o.post_id),
+        parent_path: o.parent_path,
+        sort: o.sort,
+        local_user: o.local_user,
+        max_depth: Some(max_depth),
+        subtrees: Some(top_level.iter().map(|c| c.comment.path.clone()).collect()),
+        accepted_first,
+        children_limit: Some(children_limit.into()),
+        ..Default::default()
+      }
+      .list(site, pool)
+      .await?
+    } else {
+      vec![]
+    };
+
+    Ok((build_tree(top_level, replies), next_page))
+  }
+}
+
+/// Arranges the comments depth-first below the top-level comments. Replies need to be in sort
+/// order among their siblings, and replies whose parent was left out are dropped.
+fn build_tree(top_level: Vec<CommentView>, replies: Vec<CommentView>) -> Vec<CommentNode> {
+  let mut children: HashMap<String, Vec<CommentView>> = HashMap::new();
+  for reply in replies {
+    if let Some((parent, _)) = reply.comment.path.0.rsplit_once('.') {
+      children.entry(parent.to_string()).or_default().push(reply);
+    }
+  }
+  let mut nodes = vec![];
+  for comment_view in top_level {
+    push_node(comment_view, &mut children, &mut nodes);
+  }
+  nodes
+}
+
+fn push_node(
+  comment_view: CommentView,
+  children: &mut HashMap<String, Vec<CommentView>>,
+  nodes: &mut Vec<CommentNode>,
+) {
+  let shown = children
+    .remove(&comment_view.comment.path.0)
+    .unwrap_or_default();
+  let shown_replies: i32 = shown.iter().map(|c| c.comment.child_count + 1).sum();
+  let more_replies = (comment_view.comment.child_count - shown_replies).max(0);
+  let more_replies_cursor = if more_replies > 0 {
+    shown.last().map(PaginationCursorBuilder::to_cursor)
+  } else {
+    None
+  };
+  nodes.push(CommentNode {
+    comment_view,
+    more_replies,
+    more_replies_cursor,
+  });
+  for child in shown {
+    push_node(child, children, nodes);
+  }
+}
+
 #[cfg(test)]
 #[expect(clippy::indexing_slicing)]
 mod tests {
@@ -609,6 +841,80 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_comment_tree_pagination() -> LemmyResult<()> {
+    fn contents(nodes: &[CommentNode]) -> Vec<(&str, i32)> {
+      nodes
+        .iter()
+        .map(|n| (n.comment_view.comment.content.as_str(), n.more_replies))
+        .collect()
+    }
+
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // Comment 0 has the replies 1 and 2, and comment 1 has the replies 3 and 4
+    let (nodes, next_page) = CommentTreeQuery {
+      post_id: data.post.id,
+      parent_path: None,
+      display_mode: CommentDisplayMode::Threaded,
+      sort: Some(CommentSortType::Old),
+      max_depth: None,
+      children_limit: Some(1),
+      local_user: None,
+      cursor_data: None,
+      limit: None,
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(
+      vec![("Comment 0", 1), ("Comment 1", 2), ("Comment 3", 0)],
+      contents(&nodes)
+    );
+    assert!(next_page.is_some());
+
+    // Continue with the remaining replies to comment 0
+    let cursor = nodes[0].more_replies_cursor.clone();
+    let cursor_data = match cursor {
+      Some(cursor) => Some(CommentView::from_cursor(&cursor, pool).await?),
+      None => None,
+    };
+    let (nodes, _) = CommentTreeQuery {
+      post_id: data.post.id,
+      parent_path: Some(data.comment_0.path.clone()),
+      display_mode: CommentDisplayMode::Threaded,
+      sort: Some(CommentSortType::Old),
+      max_depth: Some(1),
+      children_limit: Some(1),
+      local_user: None,
+      cursor_data,
+      limit: None,
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![("Comment 2", 0)], contents(&nodes));
+
+    // Flat mode lists all comments chronologically
+    let (nodes, _) = CommentTreeQuery {
+      post_id: data.post.id,
+      parent_path: None,
+      display_mode: CommentDisplayMode::Flat,
+      sort: None,
+      max_depth: None,
+      children_limit: None,
+      local_user: None,
+      cursor_data: None,
+      limit: None,
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_length!(6, nodes);
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_languages() -> LemmyResult<()> {
@@ -1,10 +1,24 @@
 use crate::{CommunityView, MultiCommunityView};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, MultiCommunityId, PaginationCursor, PersonId, TagId},
-  source::site::Site,
+  newtypes::{
+    AutomodRuleId,
+    CommunityId,
+    DbUrl,
+    LanguageId,
+    MultiCommunityId,
+    PaginationCursor,
+    PersonId,
+    TagId,
+  },
+  source::{automod_rule::AutomodRule, site::Site},
   CommunitySortType,
 };
-use lemmy_db_schema_file::enums::{CommunityNotificationsMode, CommunityVisibility, ListingType};
+use lemmy_db_schema_file::enums::{
+  AutomodAction,
+  CommunityNotificationsMode,
+  CommunityVisibility,
+  ListingType,
+};
 use lemmy_db_views_community_moderator::CommunityModeratorView;
 use lemmy_db_views_person::PersonView;
 use serde::{Deserialize, Serialize};
@@ -37,6 +51,81 @@ pub struct ApproveCommunityPendingFollower {
   pub approve: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Fetch older posts and their comments of a remote community in the background. Only for admins.
+pub struct BackfillCommunity {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Export a local community as archive, so that it can be imported on another instance. Only for
+/// moderators.
+pub struct ExportCommunity {
+  pub community_id: CommunityId,
+}
+
+/// Archive of a community, which can be imported on another instance to move the community there.
+/// Posts, comments and moderators are referenced by their ActivityPub ids, and fetched from the
+/// old instance during import.
+///
+/// This data should not be parsed by apps/clients, but directly downloaded as a file.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct CommunityArchive {
+  /// ActivityPub id of the exported community.
+  pub ap_id: DbUrl,
+  pub name: String,
+  pub title: String,
+  pub sidebar: Option<String>,
+  pub description: Option<String>,
+  pub icon: Option<DbUrl>,
+  pub banner: Option<DbUrl>,
+  pub nsfw: bool,
+  pub posting_restricted_to_mods: bool,
+  #[serde(default)]
+  pub cross_posting_restricted_to_mods: bool,
+  #[serde(default)]
+  pub question_answer: bool,
+  pub visibility: CommunityVisibility,
+  #[serde(default)]
+  pub moderators: Vec<DbUrl>,
+  #[serde(default)]
+  pub tags: Vec<CommunityArchiveTag>,
+  #[serde(default)]
+  pub posts: Vec<DbUrl>,
+  #[serde(default)]
+  pub comments: Vec<DbUrl>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A post tag which is part of a community archive.
+pub struct CommunityArchiveTag {
+  pub name: String,
+  pub display_name: Option<String>,
+  pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Move a local community to another instance, where it was previously imported from an archive.
+/// Local and remote followers are moved to the new community. Only for the top moderator or admins.
+pub struct MoveCommunity {
+  pub community_id: CommunityId,
+  /// ActivityPub id of the new community.
+  pub new_community: String,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -124,8 +213,20 @@ pub struct CreateCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Whether to restrict cross-posting into the community only to moderators.
+  pub cross_posting_restricted_to_mods: Option<bool>,
+  /// Whether posts are questions, which can be marked as solved by accepting an answer.
+  pub question_answer: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Whether new posts need to be approved by a moderator.
+  pub posts_require_approval: Option<bool>,
+  /// Whether new comments need to be approved by a moderator.
+  pub comments_require_approval: Option<bool>,
+  /// Only require approval for accounts younger than this many days.
+  pub approval_max_account_age_days: Option<i32>,
+  /// Each user can only comment once per this many minutes in the community.
+  pub comment_slow_mode_minutes: Option<i32>,
 }
 
 #[skip_serializing_none]
@@ -155,8 +256,20 @@ pub struct EditCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Whether to restrict cross-posting into the community only to moderators.
+  pub cross_posting_restricted_to_mods: Option<bool>,
+  /// Whether posts are questions, which can be marked as solved by accepting an answer.
+  pub question_answer: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Whether new posts need to be approved by a moderator.
+  pub posts_require_approval: Option<bool>,
+  /// Whether new comments need to be approved by a moderator.
+  pub comments_require_approval: Option<bool>,
+  /// Only require approval for accounts younger than this many days.
+  pub approval_max_account_age_days: Option<i32>,
+  /// Each user can only comment once per this many minutes in the community.
+  pub comment_slow_mode_minutes: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
@@ -168,6 +281,22 @@ pub struct FollowCommunity {
   pub follow: bool,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Temporarily disable new posts and/or comments in a community (only doable by moderators).
+/// Setting both to false lifts the freeze.
+pub struct FreezeCommunity {
+  pub community_id: CommunityId,
+  pub posts_frozen: bool,
+  pub comments_frozen: bool,
+  /// A time that the freeze will be lifted, in unix epoch seconds. If empty, it lasts until a
+  /// moderator lifts it.
+  pub expires_at: Option<i64>,
+  pub reason: Option<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -376,3 +505,78 @@ pub struct UpdateCommunityTag {
 pub struct DeleteCommunityTag {
   pub tag_id: TagId,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Create an automod rule for a community.
+///
+/// All conditions which are given must match for the action to be taken.
+pub struct CreateAutomodRule {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub action: AutomodAction,
+  /// The removal or report reason, or the text of the reply. Required for `Reply`.
+  pub action_message: Option<String>,
+  /// The tag to add. Required for `AddTag`.
+  pub tag_id: Option<TagId>,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub url_domain_regex: Option<String>,
+  pub instance_domain_regex: Option<String>,
+  pub max_account_age_days: Option<i32>,
+  pub max_karma: Option<i32>,
+  pub language_id: Option<LanguageId>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Edit an automod rule. Empty strings clear the respective condition.
+pub struct EditAutomodRule {
+  pub automod_rule_id: AutomodRuleId,
+  pub name: Option<String>,
+  pub action: Option<AutomodAction>,
+  pub action_message: Option<String>,
+  /// `null` removes the tag from the rule.
+  #[serde(default, with = "::serde_with::rust::double_option")]
+  pub tag_id: Option<Option<TagId>>,
+  pub enabled: Option<bool>,
+  pub apply_to_posts: Option<bool>,
+  pub apply_to_comments: Option<bool>,
+  pub title_regex: Option<String>,
+  pub body_regex: Option<String>,
+  pub url_domain_regex: Option<String>,
+  pub instance_domain_regex: Option<String>,
+  pub max_account_age_days: Option<i32>,
+  pub max_karma: Option<i32>,
+  pub language_id: Option<LanguageId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Delete an automod rule.
+pub struct DeleteAutomodRule {
+  pub automod_rule_id: AutomodRuleId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// List the automod rules of a community. Only available to its moderators.
+pub struct ListAutomodRules {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct ListAutomodRulesResponse {
+  pub automod_rules: Vec<AutomodRule>,
+}
@@ -136,7 +136,10 @@ impl CommunityQuery<'_> {
 
     if let Some(listing_type) = o.listing_type {
       query = match listing_type {
-        ListingType::All => query.filter(filter_not_unlisted_or_is_subscribed()),
+        // Following persons doesn't affect which communities are listed
+        ListingType::All | ListingType::FollowedPersons => {
+          query.filter(filter_not_unlisted_or_is_subscribed())
+        }
         ListingType::Subscribed => query.filter(filter_is_subscribed()),
         ListingType::Local => query
           .filter(community::local.eq(true))
@@ -339,6 +342,8 @@ mod tests {
       public_key: String::new(),
       instance_id: Default::default(),
       content_warning: None,
+      previous_public_key: None,
+      key_rotated_at: None,
     };
 
     Ok(Data {
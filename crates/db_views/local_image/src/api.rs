@@ -1,5 +1,5 @@
-use crate::LocalImageView;
-use lemmy_db_schema::newtypes::PaginationCursor;
+use crate::{LocalImageView, PersonMediaUsage};
+use lemmy_db_schema::newtypes::{PaginationCursor, PersonId};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use url::Url;
@@ -49,6 +49,22 @@ pub struct ListMediaResponse {
   /// the pagination cursor to use to fetch the next page
   pub next_page: Option<PaginationCursor>,
   pub prev_page: Option<PaginationCursor>,
+  /// Total size of your uploads in bytes. Only for your own media.
+  pub total_size: Option<i64>,
+  /// Your upload quota in bytes. Only for your own media, and not set if uploads are unlimited.
+  pub quota: Option<i64>,
+  /// The users with the largest uploads. Only for admins listing all media, on the first page.
+  pub top_uploaders: Option<Vec<PersonMediaUsage>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Sets the upload quota of a local user in bytes, overriding the quota of their user class. Leave
+/// it out to remove the override. Only for admins.
+pub struct AdminSetMediaQuota {
+  pub person_id: PersonId,
+  pub quota: Option<i64>,
 }
 
 #[skip_serializing_none]
@@ -1,10 +1,19 @@
-use crate::LocalImageView;
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use crate::{LocalImageView, PersonMediaUsage};
+use diesel::{
+  dsl::{count_star, sql},
+  sql_types::BigInt,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
 use diesel_async::RunQueryDsl;
 use i_love_jesus::SortDirection;
 use lemmy_db_schema::{
   newtypes::{PaginationCursor, PersonId},
-  source::images::{local_image_keys as key, LocalImage},
+  source::{
+    images::{local_image_keys as key, LocalImage},
+    person::Person,
+  },
   traits::PaginationCursorBuilder,
   utils::{get_conn, limit_fetch, paginate, DbPool},
 };
@@ -79,6 +88,38 @@ impl LocalImageView {
   }
 }
 
+impl PersonMediaUsage {
+  /// The users with the largest total size of uploads. Thumbnails which were generated for posts
+  /// are not counted.
+  pub async fn top_uploaders(pool: &mut DbPool<'_>, limit: Option<i64>) -> LemmyResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let limit = limit_fetch(limit)?;
+    let total_size = sql::<BigInt>("coalesce(sum(local_image.file_size), 0)::bigint");
+
+    let res = local_image::table
+      .inner_join(person::table)
+      .filter(local_image::thumbnail_for_post_id.is_null())
+      .group_by(person::id)
+      .select((Person::as_select(), total_size.clone(), count_star()))
+      .order_by(total_size.desc())
+      .limit(limit)
+      .load::<(Person, i64, i64)>(conn)
+      .await
+      .with_lemmy_type(LemmyErrorType::NotFound)?;
+
+    Ok(
+      res
+        .into_iter()
+        .map(|(person, total_size, upload_count)| PersonMediaUsage {
+          person,
+          total_size,
+          upload_count,
+        })
+        .collect(),
+    )
+  }
+}
+
 impl PaginationCursorBuilder for LocalImageView {
   type CursorData = LocalImage;
   fn to_cursor(&self) -> PaginationCursor {
@@ -9,9 +9,11 @@ use crate::{
   AdminPurgePostView,
   AdminRemoveCommunityView,
   ModAddToCommunityView,
+  ModAutomodActionView,
   ModBanFromCommunityView,
   ModChangeCommunityVisibilityView,
   ModFeaturePostView,
+  ModFreezeCommunityView,
   ModLockPostView,
   ModRemoveCommentView,
   ModRemovePostView,
@@ -64,9 +66,11 @@ use lemmy_db_schema_file::{
     community_actions,
     instance,
     mod_add_to_community,
+    mod_automod_action,
     mod_ban_from_community,
     mod_change_community_visibility,
     mod_feature_post,
+    mod_freeze_community,
     mod_lock_post,
     mod_remove_comment,
     mod_remove_post,
@@ -105,7 +109,9 @@ impl ModlogCombinedViewInternal {
         .or(mod_remove_comment::mod_person_id.eq(person::id))
         .or(admin_remove_community::mod_person_id.eq(person::id))
         .or(mod_remove_post::mod_person_id.eq(person::id))
-        .or(mod_transfer_community::mod_person_id.eq(person::id)),
+        .or(mod_transfer_community::mod_person_id.eq(person::id))
+        .or(mod_automod_action::mod_person_id.eq(person::id))
+        .or(mod_freeze_community::mod_person_id.eq(person::id.nullable())),
     );
 
     let other_person_join = aliases::person1.on(
@@ -135,10 +141,25 @@ impl ModlogCombinedViewInternal {
             .is_not_null()
             .and(post::creator_id.eq(other_person)),
         )
-        .or(mod_transfer_community::other_person_id.eq(other_person)),
+        .or(mod_transfer_community::other_person_id.eq(other_person))
+        .or(
+          mod_automod_action::comment_id
+            .is_not_null()
+            .and(comment::creator_id.eq(other_person)),
+        )
+        .or(
+          mod_automod_action::id
+            .is_not_null()
+            .and(mod_automod_action::comment_id.is_null())
+            .and(post::creator_id.eq(other_person)),
+        ),
     );
 
-    let comment_join = comment::table.on(mod_remove_comment::comment_id.eq(comment::id));
+    let comment_join = comment::table.on(
+      mod_remove_comment::comment_id
+        .eq(comment::id)
+        .or(mod_automod_action::comment_id.eq(comment::id.nullable())),
+    );
 
     let post_join = post::table.on(
       admin_purge_comment::post_id
@@ -150,7 +171,8 @@ impl ModlogCombinedViewInternal {
             .is_not_null()
             .and(comment::post_id.eq(post::id)),
         )
-        .or(mod_remove_post::post_id.eq(post::id)),
+        .or(mod_remove_post::post_id.eq(post::id))
+        .or(mod_automod_action::post_id.eq(post::id)),
     );
 
     let community_join = community::table.on(
@@ -180,7 +202,9 @@ impl ModlogCombinedViewInternal {
             .is_not_null()
             .and(post::community_id.eq(community::id)),
         )
-        .or(mod_transfer_community::community_id.eq(community::id)),
+        .or(mod_transfer_community::community_id.eq(community::id))
+        .or(mod_automod_action::community_id.eq(community::id))
+        .or(mod_freeze_community::community_id.eq(community::id)),
     );
 
     let instance_join = instance::table.on(
@@ -213,6 +237,8 @@ impl ModlogCombinedViewInternal {
       .left_join(admin_remove_community::table)
       .left_join(mod_remove_post::table)
       .left_join(mod_transfer_community::table)
+      .left_join(mod_automod_action::table)
+      .left_join(mod_freeze_community::table)
       .left_join(moderator_join)
       .left_join(comment_join)
       .left_join(post_join)
@@ -245,6 +271,8 @@ impl PaginationCursorBuilder for ModlogCombinedView {
       AdminRemoveCommunity(v) => ('O', v.admin_remove_community.id.0),
       ModRemovePost(v) => ('P', v.mod_remove_post.id.0),
       ModTransferCommunity(v) => ('Q', v.mod_transfer_community.id.0),
+      ModAutomodAction(v) => ('R', v.mod_automod_action.id.0),
+      ModFreezeCommunity(v) => ('S', v.mod_freeze_community.id.0),
     };
     PaginationCursor::new_single(prefix, id)
   }
@@ -278,6 +306,8 @@ impl PaginationCursorBuilder for ModlogCombinedView {
       'O' => query.filter(modlog_combined::admin_remove_community_id.eq(id)),
       'P' => query.filter(modlog_combined::mod_remove_post_id.eq(id)),
       'Q' => query.filter(modlog_combined::mod_transfer_community_id.eq(id)),
+      'R' => query.filter(modlog_combined::mod_automod_action_id.eq(id)),
+      'S' => query.filter(modlog_combined::mod_freeze_community_id.eq(id)),
       _ => return Err(LemmyErrorType::CouldntParsePaginationToken.into()),
     };
 
@@ -368,11 +398,13 @@ impl ModlogCombinedQuery<'_> {
         AdminPurgeComment => query.filter(modlog_combined::admin_purge_comment_id.is_not_null()),
         AdminBlockInstance => query.filter(modlog_combined::admin_block_instance_id.is_not_null()),
         AdminAllowInstance => query.filter(modlog_combined::admin_allow_instance_id.is_not_null()),
+        ModAutomodAction => query.filter(modlog_combined::mod_automod_action_id.is_not_null()),
+        ModFreezeCommunity => query.filter(modlog_combined::mod_freeze_community_id.is_not_null()),
       }
     }
 
     query = match self.listing_type.unwrap_or(ListingType::All) {
-      ListingType::All => query,
+      ListingType::All | ListingType::FollowedPersons => query,
       ListingType::Subscribed => query.filter(filter_is_subscribed()),
       ListingType::Local => query
         .filter(community::local.eq(true))
@@ -567,7 +599,7 @@ impl InternalToCombinedView for ModlogCombinedViewInternal {
       v.other_person.clone(),
       v.community.clone(),
       v.post.clone(),
-      v.comment,
+      v.comment.clone(),
     ) {
       Some(ModlogCombinedView::ModRemoveComment(ModRemoveCommentView {
         mod_remove_comment,
@@ -613,6 +645,30 @@ impl InternalToCombinedView for ModlogCombinedViewInternal {
           community,
         },
       ))
+    } else if let (Some(mod_automod_action), Some(other_person), Some(community), Some(post)) = (
+      v.mod_automod_action,
+      v.other_person,
+      v.community.clone(),
+      v.post,
+    ) {
+      Some(ModlogCombinedView::ModAutomodAction(ModAutomodActionView {
+        mod_automod_action,
+        moderator: v.moderator,
+        other_person,
+        community,
+        post,
+        comment: v.comment,
+      }))
+    } else if let (Some(mod_freeze_community), Some(community)) =
+      (v.mod_freeze_community, v.community)
+    {
+      Some(ModlogCombinedView::ModFreezeCommunity(
+        ModFreezeCommunityView {
+          mod_freeze_community,
+          moderator: v.moderator,
+          community,
+        },
+      ))
     } else {
       None
     }
@@ -627,6 +683,7 @@ mod tests {
   use lemmy_db_schema::{
     newtypes::PersonId,
     source::{
+      automod_rule::{AutomodRule, AutomodRuleInsertForm},
       comment::{Comment, CommentInsertForm},
       community::{Community, CommunityInsertForm},
       instance::Instance,
@@ -653,12 +710,16 @@ mod tests {
         moderator::{
           ModAddToCommunity,
           ModAddToCommunityForm,
+          ModAutomodAction,
+          ModAutomodActionForm,
           ModBanFromCommunity,
           ModBanFromCommunityForm,
           ModChangeCommunityVisibility,
           ModChangeCommunityVisibilityForm,
           ModFeaturePost,
           ModFeaturePostForm,
+          ModFreezeCommunity,
+          ModFreezeCommunityForm,
           ModLockPost,
           ModLockPostForm,
           ModRemoveComment,
@@ -676,7 +737,7 @@ mod tests {
     utils::{build_db_pool_for_tests, DbPool},
     ModlogActionType,
   };
-  use lemmy_db_schema_file::enums::CommunityVisibility;
+  use lemmy_db_schema_file::enums::{AutomodAction, CommunityVisibility, FederationBlockSeverity};
   use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -777,6 +838,7 @@ mod tests {
       admin_person_id: data.timmy.id,
       blocked: true,
       reason: None,
+      severity: Some(FederationBlockSeverity::Suspend),
     };
     AdminBlockInstance::create(pool, &form).await?;
 
@@ -1035,6 +1097,8 @@ mod tests {
       post_id: data.post.id,
       locked: Some(true),
       reason: None,
+      expires_at: None,
+      max_account_age_days: None,
     };
     ModLockPost::create(pool, &form).await?;
 
@@ -1382,6 +1446,154 @@ mod tests {
     Ok(())
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn automod_actions() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // Jessica is a mod, and creates a rule
+    let rule_form = AutomodRuleInsertForm::new(
+      data.community.id,
+      data.jessica.id,
+      "no spam".to_string(),
+      AutomodAction::Remove,
+    );
+    let rule = AutomodRule::create(pool, &rule_form).await?;
+
+    let post_action_form = ModAutomodActionForm {
+      mod_person_id: data.jessica.id,
+      automod_rule_id: Some(rule.id),
+      community_id: data.community.id,
+      post_id: data.post.id,
+      comment_id: None,
+      action: AutomodAction::Remove,
+      reason: Some("spam".to_string()),
+    };
+    ModAutomodAction::create(pool, &post_action_form).await?;
+
+    let comment_action_form = ModAutomodActionForm {
+      comment_id: Some(data.comment.id),
+      action: AutomodAction::Report,
+      ..post_action_form
+    };
+    ModAutomodAction::create(pool, &comment_action_form).await?;
+
+    assert!(ModAutomodAction::exists_for_rule(pool, rule.id, data.post.id, None).await?);
+    assert!(
+      ModAutomodAction::exists_for_rule(pool, rule.id, data.post.id, Some(data.comment.id)).await?
+    );
+    assert!(
+      !ModAutomodAction::exists_for_rule(pool, rule.id, data.post_2.id, Some(data.comment_2.id))
+        .await?
+    );
+
+    let modlog = ModlogCombinedQuery {
+      type_: Some(ModlogActionType::ModAutomodAction),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(2, modlog.len());
+
+    // The comment action is the newest
+    if let ModlogCombinedView::ModAutomodAction(v) = &modlog[0] {
+      assert_eq!(AutomodAction::Report, v.mod_automod_action.action);
+      assert_eq!(
+        data.jessica.id,
+        v.moderator.as_ref().map(|a| a.id).unwrap_or(PersonId(-1))
+      );
+      assert_eq!(data.timmy.id, v.other_person.id);
+      assert_eq!(data.post.id, v.post.id);
+      assert_eq!(Some(data.comment.id), v.comment.as_ref().map(|c| c.id));
+      assert_eq!(data.community.id, v.community.id);
+    } else {
+      panic!("wrong type");
+    }
+
+    if let ModlogCombinedView::ModAutomodAction(v) = &modlog[1] {
+      assert_eq!(AutomodAction::Remove, v.mod_automod_action.action);
+      assert_eq!(data.timmy.id, v.other_person.id);
+      assert_eq!(data.post.id, v.post.id);
+      assert!(v.comment.is_none());
+    } else {
+      panic!("wrong type");
+    }
+
+    // Filtering by comment only returns the comment action
+    let modlog_comment_filter = ModlogCombinedQuery {
+      comment_id: Some(data.comment.id),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(1, modlog_comment_filter.len());
+
+    cleanup(data, pool).await?;
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn freeze_community() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // Jessica freezes posts, and later the freeze is lifted automatically
+    let freeze_form = ModFreezeCommunityForm {
+      mod_person_id: Some(data.jessica.id),
+      community_id: data.community.id,
+      posts_frozen: true,
+      comments_frozen: false,
+      expires_at: None,
+      reason: Some("raid".to_string()),
+    };
+    ModFreezeCommunity::create(pool, &freeze_form).await?;
+
+    let lift_form = ModFreezeCommunityForm {
+      mod_person_id: None,
+      posts_frozen: false,
+      reason: None,
+      ..freeze_form
+    };
+    ModFreezeCommunity::create(pool, &lift_form).await?;
+
+    let modlog = ModlogCombinedQuery {
+      type_: Some(ModlogActionType::ModFreezeCommunity),
+      community_id: Some(data.community.id),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(2, modlog.len());
+
+    if let ModlogCombinedView::ModFreezeCommunity(v) = &modlog[0] {
+      assert!(!v.mod_freeze_community.posts_frozen);
+      assert!(v.moderator.is_none());
+      assert_eq!(data.community.id, v.community.id);
+    } else {
+      panic!("wrong type");
+    }
+
+    if let ModlogCombinedView::ModFreezeCommunity(v) = &modlog[1] {
+      assert!(v.mod_freeze_community.posts_frozen);
+      assert_eq!(
+        data.jessica.id,
+        v.moderator.as_ref().map(|a| a.id).unwrap_or(PersonId(-1))
+      );
+      assert_eq!(Some("raid".to_string()), v.mod_freeze_community.reason);
+    } else {
+      panic!("wrong type");
+    }
+
+    cleanup(data, pool).await?;
+
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn hide_modlog_names() -> LemmyResult<()> {
@@ -16,9 +16,11 @@ use lemmy_db_schema::source::{
     },
     moderator::{
       ModAddToCommunity,
+      ModAutomodAction,
       ModBanFromCommunity,
       ModChangeCommunityVisibility,
       ModFeaturePost,
+      ModFreezeCommunity,
       ModLockPost,
       ModRemoveComment,
       ModRemovePost,
@@ -271,6 +273,35 @@ pub struct AdminAllowInstanceView {
   pub admin: Option<Person>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// When an automod rule takes action on a post or comment.
+pub struct ModAutomodActionView {
+  pub mod_automod_action: ModAutomodAction,
+  pub moderator: Option<Person>,
+  pub other_person: Person,
+  pub post: Post,
+  pub comment: Option<Comment>,
+  pub community: Community,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// When a community is frozen or unfrozen.
+pub struct ModFreezeCommunityView {
+  pub mod_freeze_community: ModFreezeCommunity,
+  pub moderator: Option<Person>,
+  pub community: Community,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(Queryable, Selectable))]
 #[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
@@ -311,6 +342,10 @@ pub(crate) struct ModlogCombinedViewInternal {
   pub mod_remove_post: Option<ModRemovePost>,
   #[cfg_attr(feature = "full", diesel(embed))]
   pub mod_transfer_community: Option<ModTransferCommunity>,
+  #[cfg_attr(feature = "full", diesel(embed))]
+  pub mod_automod_action: Option<ModAutomodAction>,
+  #[cfg_attr(feature = "full", diesel(embed))]
+  pub mod_freeze_community: Option<ModFreezeCommunity>,
   // Specific fields
 
   // Shared
@@ -356,4 +391,6 @@ pub enum ModlogCombinedView {
   AdminRemoveCommunity(AdminRemoveCommunityView),
   ModRemovePost(ModRemovePostView),
   ModTransferCommunity(ModTransferCommunityView),
+  ModAutomodAction(ModAutomodActionView),
+  ModFreezeCommunity(ModFreezeCommunityView),
 }
@@ -37,6 +37,7 @@ use lemmy_db_schema::{
       my_local_user_admin_join,
       my_person_actions_join,
       my_post_actions_join,
+      video_details_join,
     },
     DbPool,
   },
@@ -117,6 +118,7 @@ impl NotificationView {
       .inner_join(item_creator_join)
       .inner_join(recipient_join)
       .left_join(image_details_join())
+      .left_join(video_details_join())
       .left_join(creator_community_actions_join())
       .left_join(creator_local_user_admin_join())
       .left_join(creator_home_instance_actions_join())
@@ -303,11 +305,14 @@ fn map_to_enum(v: NotificationViewInternal) -> Option<NotificationView> {
       community,
       creator: v.creator,
       image_details: v.image_details,
+      video_details: v.video_details,
       community_actions: v.community_actions,
       post_actions: v.post_actions,
       person_actions: v.person_actions,
       creator_is_admin: v.creator_is_admin,
       tags: v.post_tags,
+      gallery: v.post_gallery,
+      video_variants: v.post_video_variants,
       can_mod: v.can_mod,
       creator_banned: v.creator_banned,
       creator_is_moderator: v.creator_is_moderator,
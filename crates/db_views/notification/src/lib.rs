@@ -7,8 +7,10 @@ use lemmy_db_schema::{
     notification::Notification,
     person::{Person, PersonActions},
     post::{Post, PostActions},
+    post_gallery::PostGalleryView,
     private_message::PrivateMessage,
     tag::TagsView,
+    video::{VideoDetails, VideoVariantsView},
   },
   NotificationDataType,
 };
@@ -28,7 +30,9 @@ use {
       creator_is_moderator,
       local_user_can_mod,
       person1_select,
+      post_gallery_fragment,
       post_tags_fragment,
+      post_video_variants_fragment,
     },
     Person1AliasAllColumnsTuple,
   },
@@ -64,6 +68,8 @@ struct NotificationViewInternal {
   #[cfg_attr(feature = "full", diesel(embed))]
   image_details: Option<ImageDetails>,
   #[cfg_attr(feature = "full", diesel(embed))]
+  video_details: Option<VideoDetails>,
+  #[cfg_attr(feature = "full", diesel(embed))]
   community_actions: Option<CommunityActions>,
   #[cfg_attr(feature = "full", diesel(embed))]
   post_actions: Option<PostActions>,
@@ -83,6 +89,18 @@ struct NotificationViewInternal {
     )
   )]
   post_tags: TagsView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_gallery_fragment()
+    )
+  )]
+  post_gallery: PostGalleryView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_video_variants_fragment()
+    )
+  )]
+  post_video_variants: VideoVariantsView,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = local_user_can_mod()
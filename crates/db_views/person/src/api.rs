@@ -1,5 +1,6 @@
 use crate::PersonView;
 use lemmy_db_schema::{newtypes::PersonId, source::site::Site};
+use lemmy_db_schema_file::enums::PersonNotificationsMode;
 use lemmy_db_views_community_moderator::CommunityModeratorView;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -66,6 +67,32 @@ pub struct BlockPersonResponse {
   pub blocked: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Follow a person, to see their posts and comments with the `FollowedPersons` listing type.
+pub struct FollowPerson {
+  pub person_id: PersonId,
+  pub follow: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The response for a person follow.
+pub struct FollowPersonResponse {
+  pub person_view: PersonView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Change notification settings for a followed person
+pub struct UpdatePersonNotifications {
+  pub person_id: PersonId,
+  pub mode: PersonNotificationsMode,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
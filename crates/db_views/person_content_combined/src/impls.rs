@@ -10,6 +10,7 @@ use diesel::{
   ExpressionMethods,
   JoinOnDsl,
   NullableExpressionMethods,
+  PgExpressionMethods,
   QueryDsl,
   SelectableHelper,
 };
@@ -36,6 +37,7 @@ use lemmy_db_schema::{
       my_local_user_admin_join,
       my_person_actions_join,
       my_post_actions_join,
+      video_details_join,
     },
     DbPool,
   },
@@ -85,6 +87,7 @@ impl PersonContentCombinedViewInternal {
       .inner_join(item_creator_join)
       .inner_join(community_join())
       .left_join(image_details_join())
+      .left_join(video_details_join())
       .left_join(creator_community_actions_join())
       .left_join(creator_local_user_admin_join())
       .left_join(creator_home_instance_actions_join())
@@ -184,6 +187,13 @@ impl PersonContentCombinedQuery {
       query = query.limit(limit);
     }
 
+    // Content which is held for moderator approval is only visible to its creator
+    if my_person_id != Some(self.creator_id) {
+      query = query
+        .filter(post::pending_approval.eq(false))
+        .filter(comment::pending_approval.is_distinct_from(true));
+    }
+
     if let Some(type_) = self.type_ {
       query = match type_ {
         PersonContentType::All => query,
@@ -249,11 +259,14 @@ impl InternalToCombinedView for PersonContentCombinedViewInternal {
         community: v.community,
         creator: v.item_creator,
         image_details: v.image_details,
+        video_details: v.video_details,
         community_actions: v.community_actions,
         post_actions: v.post_actions,
         person_actions: v.person_actions,
         creator_is_admin: v.item_creator_is_admin,
         tags: v.post_tags,
+        gallery: v.post_gallery,
+        video_variants: v.post_video_variants,
         can_mod: v.can_mod,
         creator_banned: v.creator_banned,
         creator_is_moderator: v.creator_is_moderator,
@@ -273,12 +286,14 @@ mod tests {
       comment::{Comment, CommentInsertForm},
       community::{Community, CommunityInsertForm},
       instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
       person::{Person, PersonInsertForm},
       post::{Post, PostInsertForm},
     },
     traits::Crud,
     utils::{build_db_pool_for_tests, DbPool},
   };
+  use lemmy_db_views_local_user::LocalUserView;
   use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -420,4 +435,57 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_pending_approval() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // sara makes a post which is held for approval
+    let pending_post_form = PostInsertForm {
+      pending_approval: Some(true),
+      ..PostInsertForm::new(
+        "sara pending post".into(),
+        data.sara.id,
+        data.sara_post.community_id,
+      )
+    };
+    let pending_post = Post::create(pool, &pending_post_form).await?;
+    let pending_comment_form = CommentInsertForm {
+      pending_approval: Some(true),
+      ..CommentInsertForm::new(data.sara.id, data.timmy_post.id, "sara pending".into())
+    };
+    let pending_comment = Comment::create(pool, &pending_comment_form, None).await?;
+
+    // Others don't see the pending content on her profile
+    let sara_content = PersonContentCombinedQuery::new(data.sara.id)
+      .list(pool, None, data.instance.id)
+      .await?;
+    assert_eq!(3, sara_content.len());
+
+    // sara herself does
+    let sara_local_user =
+      LocalUser::create(pool, &LocalUserInsertForm::test_form(data.sara.id), vec![]).await?;
+    let sara_view = LocalUserView::read(pool, sara_local_user.id).await?;
+    let sara_content = PersonContentCombinedQuery::new(data.sara.id)
+      .list(pool, Some(&sara_view), data.instance.id)
+      .await?;
+    assert_eq!(5, sara_content.len());
+    if let PersonContentCombinedView::Comment(v) = &sara_content[0] {
+      assert_eq!(pending_comment.id, v.comment.id);
+    } else {
+      panic!("wrong type");
+    }
+    if let PersonContentCombinedView::Post(v) = &sara_content[1] {
+      assert_eq!(pending_post.id, v.post.id);
+    } else {
+      panic!("wrong type");
+    }
+
+    cleanup(data, pool).await?;
+
+    Ok(())
+  }
 }
@@ -7,7 +7,9 @@ use lemmy_db_schema::{
     images::ImageDetails,
     person::{Person, PersonActions},
     post::{Post, PostActions},
+    post_gallery::PostGalleryView,
     tag::TagsView,
+    video::{VideoDetails, VideoVariantsView},
   },
   PersonContentType,
 };
@@ -24,7 +26,9 @@ use {
     creator_is_admin,
     creator_is_moderator,
     local_user_can_mod,
+    post_gallery_fragment,
     post_tags_fragment,
+    post_video_variants_fragment,
   },
 
   lemmy_db_views_local_user::LocalUserView,
@@ -58,6 +62,8 @@ pub(crate) struct PersonSavedCombinedViewInternal {
   pub comment_actions: Option<CommentActions>,
   #[cfg_attr(feature = "full", diesel(embed))]
   pub image_details: Option<ImageDetails>,
+  #[cfg_attr(feature = "full", diesel(embed))]
+  pub video_details: Option<VideoDetails>,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = creator_is_admin()
@@ -70,6 +76,18 @@ pub(crate) struct PersonSavedCombinedViewInternal {
     )
   )]
   pub post_tags: TagsView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_gallery_fragment()
+    )
+  )]
+  pub post_gallery: PostGalleryView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_video_variants_fragment()
+    )
+  )]
+  pub post_video_variants: VideoVariantsView,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = local_user_can_mod()
@@ -12,12 +12,29 @@ use lemmy_db_schema::{
   },
   PostFeatureType,
 };
-use lemmy_db_schema_file::enums::{ListingType, PostNotificationsMode, PostSortType};
+use lemmy_db_schema_file::enums::{
+  CommentDisplayMode,
+  ListingType,
+  PostNotificationsMode,
+  PostSortType,
+};
 use lemmy_db_views_community::CommunityView;
 use lemmy_db_views_vote::VoteView;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Approve or reject a post which is held for moderator approval.
+pub struct ApprovePendingPost {
+  pub post_id: PostId,
+  pub approve: bool,
+  /// The reason for a rejection, which is sent to the post creator.
+  pub reason: Option<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
@@ -40,6 +57,23 @@ pub struct CreatePost {
   pub tags: Option<Vec<TagId>>,
   /// Time when this post should be scheduled. Null means publish immediately.
   pub scheduled_publish_time_at: Option<i64>,
+  /// Create the post as a cross-post of this post.
+  pub cross_post_of_id: Option<PostId>,
+  /// Makes this a gallery post with the given images. The post url is set to the first image, so
+  /// it can't be given separately.
+  pub gallery: Option<Vec<GalleryItem>>,
+  pub comment_display_mode: Option<CommentDisplayMode>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// An image of a gallery post. Only images which were uploaded by the post creator can be used.
+pub struct GalleryItem {
+  pub url: String,
+  pub alt_text: Option<String>,
+  pub caption: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -81,6 +115,10 @@ pub struct EditPost {
   /// Time when this post should be scheduled. Null means publish immediately.
   pub scheduled_publish_time_at: Option<i64>,
   pub tags: Option<Vec<TagId>>,
+  /// Replaces the images of a gallery post. The post url is set to the first image. An empty list
+  /// turns it into a regular post.
+  pub gallery: Option<Vec<GalleryItem>>,
+  pub comment_display_mode: Option<CommentDisplayMode>,
 }
 
 #[skip_serializing_none]
@@ -92,6 +130,9 @@ pub struct ModEditPost {
   pub post_id: PostId,
   pub nsfw: Option<bool>,
   pub tags: Option<Vec<TagId>>,
+  /// Each user can only comment once per this many minutes. Zero removes the slow mode.
+  pub comment_slow_mode_minutes: Option<i32>,
+  pub comment_display_mode: Option<CommentDisplayMode>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -132,7 +173,8 @@ pub struct GetPost {
 pub struct GetPostResponse {
   pub post_view: PostView,
   pub community_view: CommunityView,
-  /// A list of cross-posts, or other times / communities this link has been posted to.
+  /// A list of cross-posts, or other times / communities this link has been posted to. This
+  /// includes the original post if this post is a cross-post.
   pub cross_posts: Vec<PostView>,
 }
 
@@ -162,6 +204,10 @@ pub struct GetPosts {
   pub mark_as_read: Option<bool>,
   /// If true, then only show posts with no comments
   pub no_comments_only: Option<bool>,
+  /// Only show solved questions if true, or unsolved ones if false.
+  pub solved: Option<bool>,
+  /// If true, then show unsolved questions before solved ones.
+  pub unsolved_first: Option<bool>,
   pub page_cursor: Option<PaginationCursor>,
   pub page_back: Option<bool>,
   pub limit: Option<i64>,
@@ -239,6 +285,19 @@ pub struct ListPostLikesResponse {
   pub prev_page: Option<PaginationCursor>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// List the posts which are held for moderator approval, oldest first. Without a community, lists
+/// the posts of all moderated communities, or of all communities for admins.
+pub struct ListPendingPosts {
+  pub community_id: Option<CommunityId>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub page_back: Option<bool>,
+  pub limit: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
@@ -247,6 +306,10 @@ pub struct LockPost {
   pub post_id: PostId,
   pub locked: bool,
   pub reason: Option<String>,
+  /// A time when the lock is lifted automatically, in unix epoch seconds.
+  pub expires_at: Option<i64>,
+  /// Only lock the post for accounts which are younger than this many days.
+  pub max_account_age_days: Option<i32>,
 }
 
 #[skip_serializing_none]
@@ -196,5 +196,7 @@ fn site() -> LemmyResult<Site> {
     public_key: String::new(),
     instance_id: Default::default(),
     content_warning: None,
+    previous_public_key: None,
+    key_rotated_at: None,
   })
 }
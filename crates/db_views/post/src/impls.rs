@@ -17,7 +17,15 @@ use diesel_async::RunQueryDsl;
 use i_love_jesus::{asc_if, SortDirection};
 use lemmy_db_schema::{
   impls::local_user::LocalUserOptionHelper,
-  newtypes::{CommunityId, InstanceId, MultiCommunityId, PaginationCursor, PersonId, PostId},
+  newtypes::{
+    CommunityId,
+    InstanceId,
+    MultiCommunityId,
+    PaginationCursor,
+    PersonId,
+    PostId,
+    TagId,
+  },
   source::{
     community::CommunityActions,
     local_user::LocalUser,
@@ -37,7 +45,9 @@ use lemmy_db_schema::{
       creator_home_instance_actions_join,
       creator_local_instance_actions_join,
       filter_blocked,
+      filter_is_following_creator,
       filter_is_subscribed,
+      filter_not_silenced,
       filter_not_unlisted_or_is_subscribed,
       image_details_join,
       my_community_actions_join,
@@ -47,10 +57,12 @@ use lemmy_db_schema::{
       my_person_actions_join,
       my_post_actions_join,
       suggested_communities,
+      video_details_join,
     },
     seconds_to_pg_interval,
     Commented,
     DbPool,
+    NotKey,
   },
 };
 use lemmy_db_schema_file::{
@@ -68,6 +80,7 @@ use lemmy_db_schema_file::{
     person,
     post,
     post_actions,
+    post_tag,
   },
 };
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
@@ -111,6 +124,7 @@ impl PostView {
       .inner_join(person::table)
       .inner_join(community::table)
       .left_join(image_details_join())
+      .left_join(video_details_join())
       .left_join(creator_home_instance_actions_join())
       .left_join(creator_community_instance_actions_join())
       .left_join(creator_local_instance_actions_join)
@@ -162,6 +176,12 @@ impl PostView {
             .eq(false)
             .or(post::creator_id.nullable().eq(my_person_id)),
         )
+        // posts which are held for approval are only visible to their creator
+        .filter(
+          post::pending_approval
+            .eq(false)
+            .or(post::creator_id.nullable().eq(my_person_id)),
+        )
         // private communities can only by browsed by accepted followers
         .filter(
           community::visibility
@@ -264,12 +284,21 @@ pub struct PostQuery<'a> {
   pub time_range_seconds: Option<i32>,
   pub community_id: Option<CommunityId>,
   pub multi_community_id: Option<MultiCommunityId>,
+  /// Only list posts which have this community tag.
+  pub tag_id: Option<TagId>,
   pub local_user: Option<&'a LocalUser>,
   pub show_hidden: Option<bool>,
   pub show_read: Option<bool>,
   pub show_nsfw: Option<bool>,
   pub hide_media: Option<bool>,
   pub no_comments_only: Option<bool>,
+  /// Only list posts which are solved, or which are unsolved.
+  pub solved: Option<bool>,
+  /// List unsolved posts before solved ones.
+  pub unsolved_first: Option<bool>,
+  /// Only list posts which are held for moderator approval. Permissions need to be checked by the
+  /// caller.
+  pub pending_approval_only: Option<bool>,
   pub keyword_blocks: Option<Vec<String>>,
   pub cursor_data: Option<Post>,
   pub page_back: Option<bool>,
@@ -388,6 +417,13 @@ impl PostQuery<'_> {
       }
     }
 
+    if let Some(tag_id) = o.tag_id {
+      let tagged_posts = post_tag::table
+        .filter(post_tag::tag_id.eq(tag_id))
+        .select(post_tag::post_id);
+      query = query.filter(post::id.eq_any(tagged_posts));
+    }
+
     let conn = &mut get_conn(pool).await?;
     match o.listing_type.unwrap_or_default() {
       ListingType::Subscribed => query = query.filter(filter_is_subscribed()),
@@ -396,11 +432,19 @@ impl PostQuery<'_> {
           .filter(community::local.eq(true))
           .filter(filter_not_unlisted_or_is_subscribed());
       }
-      ListingType::All => query = query.filter(filter_not_unlisted_or_is_subscribed()),
+      ListingType::All => {
+        // The approval queue also needs to include unlisted communities
+        if !o.pending_approval_only.unwrap_or_default() {
+          query = query
+            .filter(filter_not_unlisted_or_is_subscribed())
+            .filter(filter_not_silenced());
+        }
+      }
       ListingType::ModeratorView => {
         query = query.filter(community_actions::became_moderator_at.is_not_null());
       }
       ListingType::Suggested => query = query.filter(suggested_communities()),
+      ListingType::FollowedPersons => query = query.filter(filter_is_following_creator()),
     }
 
     if !o.show_nsfw.unwrap_or(o.local_user.show_nsfw(site)) {
@@ -418,6 +462,10 @@ impl PostQuery<'_> {
       query = query.filter(post::comments.eq(0));
     };
 
+    if let Some(solved) = o.solved {
+      query = query.filter(post::solved.eq(solved));
+    }
+
     if !o.show_read.unwrap_or(o.local_user.show_read_posts()) {
       query = query.filter(post_actions::read_at.is_null());
     }
@@ -444,6 +492,16 @@ impl PostQuery<'_> {
         .or(post::creator_id.nullable().eq(my_person_id)),
     );
 
+    if o.pending_approval_only.unwrap_or_default() {
+      query = query.filter(post::pending_approval.eq(true));
+    } else {
+      query = query.filter(
+        post::pending_approval
+          .eq(false)
+          .or(post::creator_id.nullable().eq(my_person_id)),
+      );
+    }
+
     if !o.local_user.is_admin() {
       query = query
         .filter(
@@ -516,6 +574,15 @@ impl PostQuery<'_> {
       };
     }
 
+    // unsolved questions first. For ascending sort, false already comes first.
+    if o.unsolved_first.unwrap_or_default() {
+      pq = if sort == Old {
+        pq.then_order_by(key::solved)
+      } else {
+        pq.then_order_by(NotKey(key::solved))
+      };
+    }
+
     // then use the main sort
     pq = match sort {
       Active => pq.then_order_by(key::hot_rank_active),
@@ -587,6 +654,7 @@ mod tests {
         CommunityPersonBanForm,
         CommunityUpdateForm,
       },
+      federation_blocklist::{FederationBlockList, FederationBlockListForm},
       instance::{
         Instance,
         InstanceActions,
@@ -599,7 +667,14 @@ mod tests {
       local_site::{LocalSite, LocalSiteUpdateForm},
       local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
       multi_community::{MultiCommunity, MultiCommunityInsertForm},
-      person::{Person, PersonActions, PersonBlockForm, PersonInsertForm, PersonNoteForm},
+      person::{
+        Person,
+        PersonActions,
+        PersonBlockForm,
+        PersonFollowerForm,
+        PersonInsertForm,
+        PersonNoteForm,
+      },
       post::{
         Post,
         PostActions,
@@ -616,7 +691,12 @@ mod tests {
     traits::{Bannable, Blockable, Crud, Followable, Likeable},
     utils::{build_db_pool, get_conn, ActualDbPool, DbPool},
   };
-  use lemmy_db_schema_file::enums::{CommunityFollowerState, CommunityVisibility, ListingType};
+  use lemmy_db_schema_file::enums::{
+    CommunityFollowerState,
+    CommunityVisibility,
+    FederationBlockSeverity,
+    ListingType,
+  };
   use lemmy_db_views_local_user::LocalUserView;
   use lemmy_utils::error::{LemmyErrorType, LemmyResult};
   use pretty_assertions::assert_eq;
@@ -1636,6 +1716,79 @@ mod tests {
     Ok(())
   }
 
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_instance_silenced(data: &mut Data) -> LemmyResult<()> {
+    const POST_FROM_SILENCED_INSTANCE: &str = "post on silenced instance";
+    const POST_LISTING_WITH_SILENCED: [&str; 4] = [
+      POST_FROM_SILENCED_INSTANCE,
+      POST_WITH_TAGS,
+      POST_BY_BOT,
+      POST,
+    ];
+
+    let pool = &data.pool();
+    let pool = &mut pool.into();
+
+    let silenced_instance =
+      Instance::read_or_create(pool, "another_domain.tld".to_string()).await?;
+    let community_form = CommunityInsertForm::new(
+      silenced_instance.id,
+      "test_community_4".to_string(),
+      "none".to_owned(),
+      "pubkey".to_string(),
+    );
+    let inserted_community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm {
+      language_id: Some(LanguageId(1)),
+      ..PostInsertForm::new(
+        POST_FROM_SILENCED_INSTANCE.to_string(),
+        data.bot.person.id,
+        inserted_community.id,
+      )
+    };
+    Post::create(pool, &post_form).await?;
+
+    let query = PostQuery {
+      listing_type: Some(ListingType::All),
+      ..data.default_post_query()
+    };
+    let post_listings = query.clone().list(&data.site, pool).await?;
+    assert_eq!(POST_LISTING_WITH_SILENCED, *names(&post_listings));
+
+    // silencing the instance hides its content from the All feed
+    let block_form = FederationBlockListForm {
+      instance_id: silenced_instance.id,
+      severity: Some(FederationBlockSeverity::Silence),
+      ..Default::default()
+    };
+    FederationBlockList::block(pool, &block_form).await?;
+    let post_listings = query.clone().list(&data.site, pool).await?;
+    assert_eq!(
+      vec![POST_WITH_TAGS, POST_BY_BOT, POST],
+      names(&post_listings)
+    );
+
+    // but not for followers of the community
+    let follow_form = CommunityFollowerForm::new(
+      inserted_community.id,
+      data.tegan.person.id,
+      CommunityFollowerState::Accepted,
+    );
+    CommunityActions::follow(pool, &follow_form).await?;
+    let post_listings = query.clone().list(&data.site, pool).await?;
+    assert_eq!(POST_LISTING_WITH_SILENCED, *names(&post_listings));
+    CommunityActions::unfollow(pool, data.tegan.person.id, inserted_community.id).await?;
+
+    FederationBlockList::unblock(pool, silenced_instance.id).await?;
+    let post_listings = query.list(&data.site, pool).await?;
+    assert_eq!(POST_LISTING_WITH_SILENCED, *names(&post_listings));
+
+    Instance::delete(pool, silenced_instance.id).await?;
+    Ok(())
+  }
+
   #[test_context(Data)]
   #[tokio::test]
   #[serial]
@@ -2203,6 +2356,102 @@ mod tests {
     Ok(())
   }
 
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_solved(data: &mut Data) -> LemmyResult<()> {
+    let pool = &data.pool();
+    let pool = &mut pool.into();
+
+    Post::update(
+      pool,
+      data.post_with_tags.id,
+      &PostUpdateForm {
+        solved: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    let post_listings_solved = PostQuery {
+      solved: Some(true),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_WITH_TAGS], names(&post_listings_solved));
+
+    let post_listings_unsolved_first = PostQuery {
+      unsolved_first: Some(true),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(
+      vec![POST_BY_BOT, POST, POST_WITH_TAGS],
+      names(&post_listings_unsolved_first)
+    );
+
+    Ok(())
+  }
+
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_pending_approval(data: &mut Data) -> LemmyResult<()> {
+    let pool = &data.pool();
+    let pool = &mut pool.into();
+
+    // Hold the bot post for approval
+    let update_form = PostUpdateForm {
+      pending_approval: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, data.bot_post.id, &update_form).await?;
+
+    // Make sure it's hidden from other users
+    let post_listings = data.default_post_query().list(&data.site, pool).await?;
+    assert_eq!(vec![POST_WITH_TAGS, POST], names(&post_listings));
+    let read_post = PostView::read(
+      pool,
+      data.bot_post.id,
+      Some(&data.tegan.local_user),
+      data.instance.id,
+      false,
+    )
+    .await;
+    assert!(read_post.is_err());
+
+    // The creator and mods can still read it
+    PostView::read(
+      pool,
+      data.bot_post.id,
+      Some(&data.bot.local_user),
+      data.instance.id,
+      false,
+    )
+    .await?;
+    PostView::read(
+      pool,
+      data.bot_post.id,
+      Some(&data.tegan.local_user),
+      data.instance.id,
+      true,
+    )
+    .await?;
+
+    // The approval queue only contains the held post
+    let post_listings_pending = PostQuery {
+      pending_approval_only: Some(true),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_BY_BOT], names(&post_listings_pending));
+
+    Ok(())
+  }
+
   #[test_context(Data)]
   #[tokio::test]
   #[serial]
@@ -2460,6 +2709,15 @@ mod tests {
     assert_eq!(0, all_posts[1].tags.0.len()); // bot post
     assert_eq!(0, all_posts[2].tags.0.len()); // normal post
 
+    let tagged_posts = PostQuery {
+      tag_id: Some(data.tag_1.id),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    let tagged_post_ids = tagged_posts.iter().map(|p| p.post.id).collect::<Vec<_>>();
+    assert_eq!(vec![data.post_with_tags.id], tagged_post_ids);
+
     Ok(())
   }
 
@@ -2545,4 +2803,36 @@ mod tests {
 
     Ok(())
   }
+
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_followed_persons(data: &mut Data) -> LemmyResult<()> {
+    let pool = &data.pool();
+    let pool = &mut pool.into();
+
+    let query = PostQuery {
+      listing_type: Some(ListingType::FollowedPersons),
+      local_user: Some(&data.john.local_user),
+      ..Default::default()
+    };
+    assert!(query.clone().list(&data.site, pool).await?.is_empty());
+
+    // A pending follow doesn't show any posts yet
+    let form = PersonFollowerForm::new(data.tegan.person.id, data.john.person.id, true);
+    PersonActions::follow(pool, &form).await?;
+    assert!(query.clone().list(&data.site, pool).await?.is_empty());
+
+    PersonActions::follow_accepted(pool, data.tegan.person.id, data.john.person.id).await?;
+    let listing = query.clone().list(&data.site, pool).await?;
+    assert_eq!(
+      HashSet::from([data.post.id, data.post_with_tags.id]),
+      listing.iter().map(|l| l.post.id).collect::<HashSet<_>>()
+    );
+
+    PersonActions::unfollow(pool, data.john.person.id, data.tegan.person.id).await?;
+    assert!(query.list(&data.site, pool).await?.is_empty());
+
+    Ok(())
+  }
 }
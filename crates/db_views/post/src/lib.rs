@@ -3,7 +3,9 @@ use lemmy_db_schema::source::{
   images::ImageDetails,
   person::{Person, PersonActions},
   post::{Post, PostActions},
+  post_gallery::PostGalleryView,
   tag::TagsView,
+  video::{VideoDetails, VideoVariantsView},
 };
 use serde::{Deserialize, Serialize};
 #[cfg(test)]
@@ -18,7 +20,9 @@ use {
     creator_is_moderator,
     local_user_can_mod_post,
     post_creator_is_admin,
+    post_gallery_fragment,
     post_tags_fragment,
+    post_video_variants_fragment,
   },
 };
 
@@ -42,6 +46,8 @@ pub struct PostView {
   #[cfg_attr(feature = "full", diesel(embed))]
   pub image_details: Option<ImageDetails>,
   #[cfg_attr(feature = "full", diesel(embed))]
+  pub video_details: Option<VideoDetails>,
+  #[cfg_attr(feature = "full", diesel(embed))]
   pub community_actions: Option<CommunityActions>,
   #[cfg_attr(feature = "full", diesel(embed))]
   pub person_actions: Option<PersonActions>,
@@ -59,6 +65,20 @@ pub struct PostView {
     )
   )]
   pub tags: TagsView,
+  /// The images of a gallery post. Empty for other posts.
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_gallery_fragment()
+    )
+  )]
+  pub gallery: PostGalleryView,
+  /// Transcoded versions of a video post, highest resolution first.
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_video_variants_fragment()
+    )
+  )]
+  pub video_variants: VideoVariantsView,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = local_user_can_mod_post()
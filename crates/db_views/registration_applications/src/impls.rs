@@ -223,6 +223,7 @@ mod tests {
         default_comment_sort_type: sara_local_user.default_comment_sort_type,
         default_listing_type: sara_local_user.default_listing_type,
         default_items_per_page: sara_local_user.default_items_per_page,
+        media_quota: sara_local_user.media_quota,
         interface_language: sara_local_user.interface_language,
         show_avatars: sara_local_user.show_avatars,
         send_notifications_to_email: sara_local_user.send_notifications_to_email,
@@ -274,6 +275,8 @@ mod tests {
         post_score: 0,
         comment_count: 0,
         comment_score: 0,
+        previous_public_key: None,
+        key_rotated_at: None,
       },
       admin: None,
     };
@@ -345,6 +348,8 @@ mod tests {
       post_score: 0,
       comment_count: 0,
       comment_score: 0,
+      previous_public_key: None,
+      key_rotated_at: None,
     });
     assert_eq!(read_sara_app_view_after_approve, expected_sara_app_view);
 
@@ -3,6 +3,7 @@ use crate::{
   CommunityReportView,
   PostReportView,
   PrivateMessageReportView,
+  ReportCaseView,
   ReportCombinedView,
 };
 use lemmy_db_schema::{
@@ -19,6 +20,7 @@ use lemmy_db_schema::{
   },
   ReportType,
 };
+use lemmy_db_schema_file::enums::ReportSeverity;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
@@ -56,6 +58,49 @@ pub struct ListReportsResponse {
   pub prev_page: Option<PaginationCursor>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// List unresolved post and comment reports, grouped by the reported item.
+///
+/// Cases are sorted so that items reported for violating instance rules come first, followed
+/// by the most severe cases and the items with the most reports.
+pub struct ListReportCases {
+  /// if no community is given, it returns cases for all communities moderated by the auth user
+  pub community_id: Option<CommunityId>,
+  /// Only for admins: also show reports with `violates_instance_rules=false`
+  pub show_community_rule_violations: Option<bool>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub page_back: Option<bool>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The report cases response.
+pub struct ListReportCasesResponse {
+  pub cases: Vec<ReportCaseView>,
+  /// the pagination cursor to use to fetch the next page
+  pub next_page: Option<PaginationCursor>,
+  pub prev_page: Option<PaginationCursor>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Resolve all reports in the case of a post or a comment at once (mods only).
+///
+/// Exactly one of `post_id` and `comment_id` must be given.
+pub struct ResolveReportCase {
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  /// Resolve the case of reports which violate instance rules (admins only).
+  pub violates_instance_rules: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
 #[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
@@ -80,6 +125,7 @@ pub struct CreateCommentReport {
   pub comment_id: CommentId,
   pub reason: String,
   pub violates_instance_rules: Option<bool>,
+  pub severity: Option<ReportSeverity>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
@@ -99,6 +145,7 @@ pub struct CreatePostReport {
   pub post_id: PostId,
   pub reason: String,
   pub violates_instance_rules: Option<bool>,
+  pub severity: Option<ReportSeverity>,
 }
 
 #[skip_serializing_none]
@@ -4,6 +4,7 @@ use crate::{
   LocalUserView,
   PostReportView,
   PrivateMessageReportView,
+  ReportCaseView,
   ReportCombinedView,
   ReportCombinedViewInternal,
 };
@@ -18,7 +19,7 @@ use diesel::{
   SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
-use i_love_jesus::asc_if;
+use i_love_jesus::{asc_if, SortDirection};
 use lemmy_db_schema::{
   aliases::{self, creator_community_actions},
   newtypes::{
@@ -33,7 +34,13 @@ use lemmy_db_schema::{
     PrivateMessageReportId,
   },
   source::{
-    combined::report::{report_combined_keys as key, ReportCombined},
+    combined::report::{
+      report_case,
+      report_case_keys as case_key,
+      report_combined_keys as key,
+      ReportCase,
+      ReportCombined,
+    },
     person::Person,
   },
   traits::{InternalToCombinedView, PaginationCursorBuilder},
@@ -68,6 +75,7 @@ use lemmy_db_schema_file::schema::{
   report_combined,
 };
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use std::collections::HashMap;
 
 impl ReportCombinedViewInternal {
   #[diesel::dsl::auto_type(no_type_alias)]
@@ -429,6 +437,141 @@ impl ReportCombinedQuery {
   }
 }
 
+#[derive(Default)]
+pub struct ReportCaseQuery {
+  pub community_id: Option<CommunityId>,
+  /// For admins, also show reports with `violates_instance_rules=false`
+  pub show_community_rule_violations: Option<bool>,
+  pub cursor_data: Option<ReportCase>,
+  pub page_back: Option<bool>,
+  pub limit: Option<i64>,
+}
+
+impl ReportCaseQuery {
+  /// Lists unresolved post and comment reports grouped by the reported item. Cases which violate
+  /// instance rules come first, then the most severe ones, then the ones with the most reports,
+  /// then the newest.
+  pub async fn list(
+    self,
+    pool: &mut DbPool<'_>,
+    user: &LocalUserView,
+  ) -> LemmyResult<Vec<ReportCaseView>> {
+    let conn = &mut get_conn(pool).await?;
+    let limit = limit_fetch(self.limit)?;
+    let mut query = report_case::table
+      .select(ReportCase::as_select())
+      .limit(limit)
+      .into_boxed();
+
+    if let Some(community_id) = self.community_id {
+      query = query.filter(report_case::community_id.eq(community_id));
+    }
+
+    let moderated_communities = community_actions::table
+      .filter(community_actions::person_id.eq(user.person.id))
+      .filter(community_actions::became_moderator_at.is_not_null())
+      .select(community_actions::community_id);
+
+    if user.local_user.admin {
+      let show_community_rule_violations = self.show_community_rule_violations.unwrap_or_default();
+      if !show_community_rule_violations {
+        // Same as `filter_admin_reports`, but mods get three days from the oldest report
+        query = query.filter(
+          report_case::violates_instance_rules
+            .or(report_case::oldest_published_at.lt(Utc::now() - Days::new(3)))
+            .or(report_case::community_id.eq_any(moderated_communities)),
+        );
+      }
+    } else {
+      query = query.filter(
+        report_case::violates_instance_rules
+          .eq(false)
+          .and(report_case::community_id.eq_any(moderated_communities)),
+      );
+    }
+
+    let cases = paginate(
+      query,
+      SortDirection::Desc,
+      self.cursor_data,
+      None,
+      self.page_back,
+    )
+    .then_order_by(case_key::violates_instance_rules)
+    .then_order_by(case_key::severity)
+    .then_order_by(case_key::report_count)
+    .then_order_by(case_key::published_at)
+    // Tie breaker
+    .then_order_by(case_key::id)
+    .load::<ReportCase>(conn)
+    .await?;
+
+    // Read the newest report of each case
+    let ids = cases.iter().map(|c| c.id).collect::<Vec<_>>();
+    let mut reports = ReportCombinedViewInternal::joins(user.person.id, user.person.instance_id)
+      .select(ReportCombinedViewInternal::as_select())
+      .filter(report_combined::id.eq_any(ids))
+      .load::<ReportCombinedViewInternal>(conn)
+      .await?
+      .into_iter()
+      .filter_map(|r| Some((r.report_combined.id, r.map_to_enum()?)))
+      .collect::<HashMap<_, _>>();
+
+    let out = cases
+      .into_iter()
+      .filter_map(|c| {
+        Some(ReportCaseView {
+          report: reports.remove(&c.id)?,
+          report_count: c.report_count,
+          reasons: c.reasons,
+          violates_instance_rules: c.violates_instance_rules,
+          severity: c.severity,
+        })
+      })
+      .collect();
+
+    Ok(out)
+  }
+}
+
+impl PaginationCursorBuilder for ReportCaseView {
+  type CursorData = ReportCase;
+
+  fn to_cursor(&self) -> PaginationCursor {
+    let (prefix, id) = match &self.report {
+      ReportCombinedView::Comment(v) => ('C', v.comment.id.0),
+      ReportCombinedView::Post(v) => ('P', v.post.id.0),
+      // Cases only contain post and comment reports
+      ReportCombinedView::PrivateMessage(v) => ('M', v.private_message_report.id.0),
+      ReportCombinedView::Community(v) => ('Y', v.community_report.id.0),
+    };
+    // An item can have a separate case for reports which violate instance rules
+    PaginationCursor::new(&[(prefix, id), ('V', self.violates_instance_rules.into())])
+  }
+
+  async fn from_cursor(
+    cursor: &PaginationCursor,
+    pool: &mut DbPool<'_>,
+  ) -> LemmyResult<Self::CursorData> {
+    let conn = &mut get_conn(pool).await?;
+    let [(prefix, id), (_, violates_instance_rules)] = cursor.prefixes_and_ids()?;
+
+    let mut query = report_case::table
+      .select(Self::CursorData::as_select())
+      .filter(report_case::violates_instance_rules.eq(violates_instance_rules != 0))
+      .into_boxed();
+
+    query = match prefix {
+      'C' => query.filter(report_case::comment_id.eq(id)),
+      'P' => query.filter(report_case::post_id.eq(id)),
+      _ => return Err(LemmyErrorType::CouldntParsePaginationToken.into()),
+    };
+    let token = query.first(conn).await?;
+
+    Ok(token)
+  }
+}
+
 /// Mods can only see reports for posts/comments inside of communities where they are moderator,
 /// and which have `violates_instance_rules == false`.
 #[diesel::dsl::auto_type]
@@ -566,8 +709,9 @@ impl InternalToCombinedView for ReportCombinedViewInternal {
 mod tests {
 
   use crate::{
-    impls::ReportCombinedQuery,
+    impls::{ReportCaseQuery, ReportCombinedQuery},
     LocalUserView,
+    ReportCaseView,
     ReportCombinedView,
     ReportCombinedViewInternal,
   };
@@ -589,11 +733,11 @@ mod tests {
       private_message::{PrivateMessage, PrivateMessageInsertForm},
       private_message_report::{PrivateMessageReport, PrivateMessageReportForm},
     },
-    traits::{Crud, Reportable},
+    traits::{Crud, PaginationCursorBuilder, Reportable},
     utils::{build_db_pool_for_tests, get_conn, DbPool},
     ReportType,
   };
-  use lemmy_db_schema_file::schema::report_combined;
+  use lemmy_db_schema_file::{enums::ReportSeverity, schema::report_combined};
   use lemmy_utils::error::LemmyResult;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
@@ -726,6 +870,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
     let inserted_post_report = PostReport::report(pool, &sara_report_post_form).await?;
 
@@ -736,6 +881,7 @@ mod tests {
       original_comment_text: "A test comment rv".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
     CommentReport::report(pool, &sara_report_comment_form).await?;
 
@@ -948,6 +1094,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
 
     PostReport::report(pool, &sara_report_form).await?;
@@ -961,6 +1108,7 @@ mod tests {
       original_post_body: None,
       reason: "from jessica".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
 
     let inserted_jessica_report = PostReport::report(pool, &jessica_report_form).await?;
@@ -1080,6 +1228,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
 
     CommentReport::report(pool, &sara_report_form).await?;
@@ -1091,6 +1240,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from jessica".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
 
     let inserted_jessica_report = CommentReport::report(pool, &jessica_report_form).await?;
@@ -1278,6 +1428,7 @@ mod tests {
       original_post_body: None,
       reason: "from sara".into(),
       violates_instance_rules: true,
+      severity: ReportSeverity::default(),
     };
     PostReport::report(pool, &report_form).await?;
 
@@ -1307,6 +1458,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
     let comment_report = CommentReport::report(pool, &report_form).await?;
 
@@ -1366,6 +1518,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from sara".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
     CommentReport::report(pool, &sara_report_form).await?;
 
@@ -1376,6 +1529,7 @@ mod tests {
       original_comment_text: "this was it at time of creation".into(),
       reason: "from timmy".into(),
       violates_instance_rules: false,
+      severity: ReportSeverity::default(),
     };
     CommentReport::report(pool, &timmy_report_form).await?;
 
@@ -1402,4 +1556,130 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn report_cases() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests();
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    // sara and jessica both report the comment
+    let sara_report_form = CommentReportForm {
+      creator_id: data.sara.id,
+      comment_id: data.comment.id,
+      original_comment_text: "this was it at time of creation".into(),
+      reason: "from sara".into(),
+      violates_instance_rules: false,
+      severity: ReportSeverity::Low,
+    };
+    CommentReport::report(pool, &sara_report_form).await?;
+
+    let jessica_report_form = CommentReportForm {
+      creator_id: data.jessica.id,
+      comment_id: data.comment.id,
+      original_comment_text: "this was it at time of creation".into(),
+      reason: "from jessica".into(),
+      violates_instance_rules: false,
+      severity: ReportSeverity::Medium,
+    };
+    CommentReport::report(pool, &jessica_report_form).await?;
+
+    // sara also reports the post, with a higher severity
+    let sara_post_report_form = PostReportForm {
+      creator_id: data.sara.id,
+      post_id: data.post.id,
+      original_post_name: "Orig post".into(),
+      original_post_url: None,
+      original_post_body: None,
+      reason: "from sara".into(),
+      violates_instance_rules: false,
+      severity: ReportSeverity::High,
+    };
+    PostReport::report(pool, &sara_post_report_form).await?;
+
+    // timmy reports the comment to the admins
+    let timmy_report_form = CommentReportForm {
+      creator_id: data.timmy.id,
+      comment_id: data.comment.id,
+      original_comment_text: "this was it at time of creation".into(),
+      reason: "from timmy".into(),
+      violates_instance_rules: true,
+      severity: ReportSeverity::Low,
+    };
+    CommentReport::report(pool, &timmy_report_form).await?;
+
+    // The more severe post case comes first, then the two comment reports grouped into one case.
+    // The report for admins isn't visible to the mod.
+    let cases = ReportCaseQuery::default()
+      .list(pool, &data.timmy_view)
+      .await?;
+    assert_length!(2, cases);
+    assert_eq!(1, cases[0].report_count);
+    assert_eq!(ReportSeverity::High, cases[0].severity);
+    if let ReportCombinedView::Post(v) = &cases[0].report {
+      assert_eq!(data.post.id, v.post.id);
+    } else {
+      panic!("wrong type");
+    }
+    assert_eq!(2, cases[1].report_count);
+    assert_eq!(ReportSeverity::Medium, cases[1].severity);
+    assert_eq!(vec!["from jessica", "from sara"], cases[1].reasons);
+    assert!(!cases[1].violates_instance_rules);
+    if let ReportCombinedView::Comment(v) = &cases[1].report {
+      assert_eq!(data.comment.id, v.comment.id);
+      assert_eq!(data.jessica.id, v.creator.id);
+    } else {
+      panic!("wrong type");
+    }
+
+    // Admins see the report for admins as a separate case, which comes first
+    let admin_cases = ReportCaseQuery {
+      show_community_rule_violations: Some(true),
+      ..Default::default()
+    }
+    .list(pool, &data.admin_view)
+    .await?;
+    assert_length!(3, admin_cases);
+    assert!(admin_cases[0].violates_instance_rules);
+    assert_eq!(vec!["from timmy"], admin_cases[0].reasons);
+
+    // Paginate through the cases one by one, including the two cases of the comment
+    let mut pages = vec![];
+    let mut cursor_data = None;
+    for _ in 0..3 {
+      let page = ReportCaseQuery {
+        show_community_rule_violations: Some(true),
+        cursor_data,
+        limit: Some(1),
+        ..Default::default()
+      }
+      .list(pool, &data.admin_view)
+      .await?;
+      assert_length!(1, page);
+      cursor_data = Some(ReportCaseView::from_cursor(&page[0].to_cursor(), pool).await?);
+      pages.extend(page);
+    }
+    assert_eq!(admin_cases, pages);
+
+    let creators = CommentReport::list_unresolved_creators(pool, data.comment.id, false).await?;
+    assert_length!(2, creators);
+
+    // Resolving the case resolves both comment reports, but not the one for admins
+    CommentReport::resolve_case(pool, data.comment.id, false, data.timmy.id).await?;
+    let creators = CommentReport::list_unresolved_creators(pool, data.comment.id, false).await?;
+    assert_length!(0, creators);
+    let creators = CommentReport::list_unresolved_creators(pool, data.comment.id, true).await?;
+    assert_length!(1, creators);
+
+    let cases = ReportCaseQuery::default()
+      .list(pool, &data.timmy_view)
+      .await?;
+    assert_length!(1, cases);
+    assert!(matches!(cases[0].report, ReportCombinedView::Post(_)));
+
+    cleanup(data, pool).await?;
+
+    Ok(())
+  }
 }
@@ -10,6 +10,7 @@ use lemmy_db_schema::source::{
   private_message::PrivateMessage,
   private_message_report::PrivateMessageReport,
 };
+use lemmy_db_schema_file::enums::ReportSeverity;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -189,3 +190,21 @@ pub struct PostReportView {
   pub creator_banned: bool,
   pub creator_banned_from_community: bool,
 }
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// All unresolved reports for a single post or comment, grouped into one case.
+pub struct ReportCaseView {
+  /// The most recent report on the item.
+  pub report: ReportCombinedView,
+  /// The number of unresolved reports on the item.
+  pub report_count: i64,
+  /// The reasons given by all reporters, newest first.
+  pub reasons: Vec<String>,
+  /// True if the reports were marked as violating instance rules. These are grouped separately
+  /// from the other reports on the item, and only visible to admins.
+  pub violates_instance_rules: bool,
+  /// The highest severity given by any of the reporters.
+  pub severity: ReportSeverity,
+}
@@ -38,7 +38,9 @@ use lemmy_db_schema::{
       creator_home_instance_actions_join,
       creator_local_instance_actions_join,
       creator_local_user_admin_join,
+      filter_is_following_creator,
       filter_is_subscribed,
+      filter_not_silenced,
       filter_not_unlisted_or_is_subscribed,
       image_details_join,
       my_comment_actions_join,
@@ -47,6 +49,7 @@ use lemmy_db_schema::{
       my_person_actions_join,
       my_post_actions_join,
       suggested_communities,
+      video_details_join,
     },
     seconds_to_pg_interval,
     DbPool,
@@ -101,7 +104,8 @@ impl SearchCombinedViewInternal {
       search_combined::comment_id
         .eq(comment::id.nullable())
         .and(not(comment::removed))
-        .and(not(comment::deleted)),
+        .and(not(comment::deleted))
+        .and(not(comment::pending_approval)),
     );
 
     let post_join = post::table.on(
@@ -109,7 +113,8 @@ impl SearchCombinedViewInternal {
         .eq(post::id.nullable())
         .or(comment::post_id.eq(post::id))
         .and(not(post::removed))
-        .and(not(post::deleted)),
+        .and(not(post::deleted))
+        .and(not(post::pending_approval)),
     );
 
     let community_join = community::table.on(
@@ -143,6 +148,7 @@ impl SearchCombinedViewInternal {
       .left_join(item_creator_join)
       .left_join(community_join)
       .left_join(image_details_join())
+      .left_join(video_details_join())
       .left_join(creator_community_actions_join())
       .left_join(creator_local_user_admin_join())
       .left_join(creator_home_instance_actions_join())
@@ -330,15 +336,18 @@ impl SearchCombinedQuery {
           .or(search_combined::person_id.is_not_null().and(person::local))
           .or(multi_community::local),
       ),
-      ListingType::All => query.filter(
-        filter_not_unlisted_or_is_subscribed()
-          .or(search_combined::person_id.is_not_null())
-          .or(search_combined::multi_community_id.is_not_null()),
-      ),
+      ListingType::All => query
+        .filter(
+          filter_not_unlisted_or_is_subscribed()
+            .or(search_combined::person_id.is_not_null())
+            .or(search_combined::multi_community_id.is_not_null()),
+        )
+        .filter(filter_not_silenced()),
       ListingType::ModeratorView => {
         query.filter(community_actions::became_moderator_at.is_not_null())
       }
       ListingType::Suggested => query.filter(suggested_communities()),
+      ListingType::FollowedPersons => query.filter(filter_is_following_creator()),
     };
     // Filter by the time range
     if let Some(time_range_seconds) = self.time_range_seconds {
@@ -442,10 +451,13 @@ impl InternalToCombinedView for SearchCombinedViewInternal {
         creator,
         creator_is_admin: v.item_creator_is_admin,
         image_details: v.image_details,
+        video_details: v.video_details,
         community_actions: v.community_actions,
         person_actions: v.person_actions,
         post_actions: v.post_actions,
         tags: v.post_tags,
+        gallery: v.post_gallery,
+        video_variants: v.post_video_variants,
         can_mod: v.can_mod,
         creator_banned: v.creator_banned,
         creator_is_moderator: v.creator_is_moderator,
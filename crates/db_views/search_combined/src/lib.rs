@@ -8,7 +8,9 @@ use lemmy_db_schema::{
     multi_community::MultiCommunity,
     person::{Person, PersonActions},
     post::{Post, PostActions},
+    post_gallery::PostGalleryView,
     tag::TagsView,
+    video::{VideoDetails, VideoVariantsView},
   },
   SearchSortType,
   SearchType,
@@ -28,7 +30,9 @@ use {
     creator_banned,
     creator_is_admin,
     local_user_can_mod,
+    post_gallery_fragment,
     post_tags_fragment,
+    post_video_variants_fragment,
   },
   lemmy_db_schema::utils::queries::{creator_banned_from_community, creator_is_moderator},
   lemmy_db_views_local_user::LocalUserView,
@@ -64,6 +68,8 @@ pub(crate) struct SearchCombinedViewInternal {
   pub comment_actions: Option<CommentActions>,
   #[cfg_attr(feature = "full", diesel(embed))]
   pub image_details: Option<ImageDetails>,
+  #[cfg_attr(feature = "full", diesel(embed))]
+  pub video_details: Option<VideoDetails>,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = creator_is_admin()
@@ -77,6 +83,18 @@ pub(crate) struct SearchCombinedViewInternal {
   )]
   /// tags of this post
   pub post_tags: TagsView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_gallery_fragment()
+    )
+  )]
+  pub post_gallery: PostGalleryView,
+  #[cfg_attr(feature = "full",
+    diesel(
+      select_expression = post_video_variants_fragment()
+    )
+  )]
+  pub post_video_variants: VideoVariantsView,
   #[cfg_attr(feature = "full",
     diesel(
       select_expression = community_post_tags_fragment()
@@ -2,6 +2,13 @@ use crate::SiteView;
 use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
   newtypes::{
+    ActivityId,
+    DbUrl,
+    FederationBlocklistSubscriptionId,
+    FederationRelayId,
+    ImageHash,
+    ImageHashBlockId,
+    InboundActivityId,
     InstanceId,
     LanguageId,
     MultiCommunityId,
@@ -11,8 +18,18 @@ use lemmy_db_schema::{
   },
   sensitive::SensitiveString,
   source::{
+    actor_key_rotation::ActorKeyRotation,
     comment::Comment,
     community::Community,
+    federation_blocklist::{
+      FederationBlockList,
+      FederationBlocklistPending,
+      FederationBlocklistSubscription,
+    },
+    federation_relay::FederationRelay,
+    federation_send_error::FederationSendError,
+    image_hash_block::ImageHashBlock,
+    inbound_activity::InboundActivity,
     instance::Instance,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
@@ -27,6 +44,7 @@ use lemmy_db_schema::{
 };
 use lemmy_db_schema_file::enums::{
   CommentSortType,
+  FederationBlockSeverity,
   FederationMode,
   ListingType,
   PostListingMode,
@@ -64,8 +82,300 @@ pub struct AdminAllowInstanceParams {
 pub struct AdminBlockInstanceParams {
   pub instance: String,
   pub block: bool,
+  /// The reason for the block, which is shown publicly.
   pub reason: Option<String>,
   pub expires_at: Option<DateTime<Utc>>,
+  /// Defaults to suspend.
+  pub severity: Option<FederationBlockSeverity>,
+  /// A comment which is only visible to admins.
+  pub private_comment: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// A blocked instance, including the private comment. Only for admins.
+pub struct FederationBlocklistEntry {
+  pub instance: Instance,
+  pub block: FederationBlockList,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// All blocked instances. Only for admins.
+pub struct ListFederationBlocklistResponse {
+  pub blocklist: Vec<FederationBlocklistEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Blocks all instances from a blocklist in Mastodon's domain block CSV format. Only for admins.
+pub struct AdminImportFederationBlocklist {
+  pub csv: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct AdminImportFederationBlocklistResponse {
+  /// The number of blocked instances.
+  pub blocked: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The federation blocklist in Mastodon's domain block CSV format. Private comments are not
+/// included.
+pub struct ExportFederationBlocklistResponse {
+  pub csv: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Subscribe to a remote blocklist in Mastodon's domain block CSV format. It is refreshed every
+/// hour, and newly added domains are shown for review before they are blocked. Only for admins.
+pub struct AdminAddFederationBlocklistSubscription {
+  pub url: Url,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Removes a blocklist subscription. Instances which were blocked through it stay blocked. Only
+/// for admins.
+pub struct AdminRemoveFederationBlocklistSubscription {
+  pub id: FederationBlocklistSubscriptionId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Blocks (apply = true) or discards all domains from a subscribed blocklist which are waiting for
+/// review. Only for admins.
+pub struct AdminReviewFederationBlocklistSubscription {
+  pub id: FederationBlocklistSubscriptionId,
+  pub apply: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct FederationBlocklistSubscriptionView {
+  pub subscription: FederationBlocklistSubscription,
+  /// Domains which were newly added to the blocklist, and are not blocked yet.
+  pub pending: Vec<FederationBlocklistPending>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct FederationBlocklistSubscriptionResponse {
+  pub subscription: FederationBlocklistSubscriptionView,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// All subscribed blocklists. Only for admins.
+pub struct ListFederationBlocklistSubscriptionsResponse {
+  pub subscriptions: Vec<FederationBlocklistSubscriptionView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Blocks an image hash, so that similar images can't be uploaded and are left out as thumbnails.
+/// Either give the hash directly, or the filename of a local image to block. Only for admins.
+pub struct CreateImageHashBlock {
+  #[cfg_attr(feature = "ts-rs", ts(type = "string"))]
+  pub hash: Option<ImageHash>,
+  pub filename: Option<String>,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct ImageHashBlockResponse {
+  pub image_hash_block: ImageHashBlock,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Removes a blocked image hash. Only for admins.
+pub struct DeleteImageHashBlock {
+  pub id: ImageHashBlockId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// All blocked image hashes. Only for admins.
+pub struct ListImageHashBlocksResponse {
+  pub image_hash_blocks: Vec<ImageHashBlock>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Blocks all hashes from a hash list, with one hash in hex per line and optionally a comma and
+/// the reason. Only for admins.
+pub struct AdminImportImageHashBlocklist {
+  pub list: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct AdminImportImageHashBlocklistResponse {
+  /// The number of newly blocked hashes.
+  pub blocked: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The image hash blocklist, in the same format which is used for imports.
+pub struct ExportImageHashBlocklistResponse {
+  pub list: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Subscribe to an ActivityPub relay. The url is either the relay actor (LitePub style) or the
+/// relay inbox (Mastodon style, ending with `/inbox`). Only for admins.
+pub struct AdminAddRelay {
+  pub url: Url,
+  /// Send activities of local public communities to the relay. Defaults to false.
+  pub publish: Option<bool>,
+  /// How many posts and comments forwarded by the relay are fetched and stored per hour, including
+  /// their communities. Defaults to 100.
+  pub max_objects_per_hour: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Only for admins.
+pub struct AdminEditRelay {
+  pub id: FederationRelayId,
+  pub publish: Option<bool>,
+  pub max_objects_per_hour: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Unsubscribes from a relay. Only for admins.
+pub struct AdminRemoveRelay {
+  pub id: FederationRelayId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct RelayResponse {
+  pub relay: FederationRelay,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// All relays which the site is subscribed to. Only for admins.
+pub struct ListRelaysResponse {
+  pub relays: Vec<FederationRelay>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Pause or resume sending activities to an instance.
+pub struct AdminPauseFederation {
+  pub instance_id: InstanceId,
+  pub paused: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Retry sending to an instance right away, instead of waiting for the retry delay.
+pub struct AdminRetryFederation {
+  pub instance_id: InstanceId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Stop trying to send the activities up to the given one to an instance. Use this if an
+/// activity keeps failing and blocks the queue.
+pub struct AdminSkipFederation {
+  pub instance_id: InstanceId,
+  pub activity_id: ActivityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Send a range of activities to an instance again.
+pub struct AdminResendFederation {
+  pub instance_id: InstanceId,
+  pub first_activity_id: ActivityId,
+  pub last_activity_id: ActivityId,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Lists the latest errors when sending activities to an instance.
+pub struct ListFederationSendErrors {
+  pub instance_id: InstanceId,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct ListFederationSendErrorsResponse {
+  pub errors: Vec<FederationSendError>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Replace the signing key of a local actor, or of all local actors. The site key is only used for
+/// signed fetches after Lemmy was restarted.
+pub struct AdminRotateKeys {
+  /// The local site, person or community whose key is replaced.
+  pub actor_ap_id: Option<DbUrl>,
+  /// Replace the keys of all local actors. This runs in the background.
+  pub all: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Lists the latest key rotations of local actors.
+pub struct ListKeyRotations {
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+pub struct ListKeyRotationsResponse {
+  pub rotations: Vec<ActorKeyRotation>,
 }
 
 #[skip_serializing_none]
@@ -157,6 +467,10 @@ pub struct CreateSite {
   pub disallow_nsfw_content: Option<bool>,
   pub disable_email_notifications: Option<bool>,
   pub suggested_communities: Option<MultiCommunityId>,
+  pub sitemap_exclude_nsfw: Option<bool>,
+  pub image_hash_block_threshold: Option<i32>,
+  pub media_quota_user: Option<i64>,
+  pub media_quota_moderator: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -281,6 +595,15 @@ pub struct EditSite {
   pub disable_email_notifications: Option<bool>,
   /// A multicommunity with suggested communities which is shown on the homepage
   pub suggested_communities: Option<MultiCommunityId>,
+  /// Leave NSFW posts and communities out of the sitemap.
+  pub sitemap_exclude_nsfw: Option<bool>,
+  /// Images whose perceptual hash differs from a blocked hash in at most this many bits are
+  /// rejected.
+  pub image_hash_block_threshold: Option<i32>,
+  /// Upload quota of regular users in bytes. Zero removes the quota.
+  pub media_quota_user: Option<i64>,
+  /// Upload quota of community moderators in bytes. Zero removes the quota.
+  pub media_quota_moderator: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -291,6 +614,8 @@ pub struct FederatedInstances {
   pub linked: Vec<InstanceWithFederationState>,
   pub allowed: Vec<InstanceWithFederationState>,
   pub blocked: Vec<InstanceWithFederationState>,
+  /// Instances whose content is hidden from the All feed, but which are still federated with.
+  pub silenced: Vec<InstanceWithFederationState>,
 }
 
 #[skip_serializing_none]
@@ -336,6 +661,8 @@ pub struct InstanceWithFederationState {
   /// if federation to this instance is or was active, show state of outgoing federation to this
   /// instance
   pub federation_state: Option<ReadableFederationState>,
+  /// The publicly visible reason, if the instance is blocked or silenced.
+  pub block_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -689,6 +1016,43 @@ pub struct UpdateTagline {
   pub content: String,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Lists the queue of incoming activities. Only for admins.
+pub struct ListInboundActivities {
+  /// Only list the dead-letter list (true), or only activities waiting to be processed (false).
+  pub failed: Option<bool>,
+  pub page_cursor: Option<PaginationCursor>,
+  pub page_back: Option<bool>,
+  pub limit: Option<i64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// The queue of incoming activities.
+pub struct ListInboundActivitiesResponse {
+  pub inbound_activities: Vec<InboundActivity>,
+  /// Number of activities waiting to be processed
+  pub pending_count: i64,
+  /// Number of activities which failed too often, and are not retried anymore
+  pub failed_count: i64,
+  /// the pagination cursor to use to fetch the next page
+  pub next_page: Option<PaginationCursor>,
+  pub prev_page: Option<PaginationCursor>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Puts an activity from the dead-letter list back into the queue. Only for admins.
+pub struct ReplayInboundActivity {
+  pub id: InboundActivityId,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "full", derive(FromBytes))]
 #[cfg_attr(feature = "full", encoding(Json))]
@@ -288,6 +288,8 @@ mod tests {
       public_key: "test_key".to_string(),
       instance_id: InstanceId(1),
       content_warning: None,
+      previous_public_key: None,
+      key_rotated_at: None,
     };
 
     collector
@@ -411,6 +413,8 @@ mod tests {
       public_key: "test_key".to_string(),
       instance_id: InstanceId(1),
       content_warning: None,
+      previous_public_key: None,
+      key_rotated_at: None,
     };
 
     collector
@@ -528,6 +532,8 @@ mod tests {
       public_key: "test_key".to_string(),
       instance_id: InstanceId(1),
       content_warning: None,
+      previous_public_key: None,
+      key_rotated_at: None,
     };
 
     collector
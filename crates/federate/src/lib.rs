@@ -1,8 +1,12 @@
 use crate::{util::CancellableTask, worker::InstanceWorker};
 use activitypub_federation::config::FederationConfig;
 use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_schema::{newtypes::InstanceId, source::instance::Instance};
+use lemmy_db_schema::{
+  newtypes::InstanceId,
+  source::{federation_queue_state::FederationQueueState, instance::Instance},
+};
 use lemmy_utils::{error::LemmyResult, settings::structs::FederationWorkerConfig};
+pub use send::ResponseStatusMiddleware;
 use stats::receive_print_stats;
 use std::{collections::HashMap, time::Duration};
 use tokio::{
@@ -108,6 +112,8 @@ impl SendManager {
       let mut total_count = 0;
       let mut dead_count = 0;
       let mut disallowed_count = 0;
+      let mut paused_count = 0;
+      let paused = FederationQueueState::read_paused_instances(&mut pool).await?;
       for (instance, allowed, is_dead) in
         Instance::read_federated_with_blocked_and_dead(&mut pool).await?
       {
@@ -124,7 +130,11 @@ impl SendManager {
         if is_dead {
           dead_count += 1;
         }
-        let should_federate = allowed && !is_dead;
+        let is_paused = paused.contains(&instance.id);
+        if is_paused {
+          paused_count += 1;
+        }
+        let should_federate = allowed && !is_dead && !is_paused;
         if should_federate {
           if self.workers.contains_key(&instance.id) {
             // worker already running
@@ -159,7 +169,7 @@ impl SendManager {
         }
       }
       let worker_count = self.workers.len();
-      tracing::info!("Federating to {worker_count}/{total_count} instances ({dead_count} dead, {disallowed_count} disallowed)");
+      tracing::info!("Federating to {worker_count}/{total_count} instances ({dead_count} dead, {disallowed_count} disallowed, {paused_count} paused)");
       tokio::select! {
         () = sleep(INSTANCES_RECHECK_DELAY) => {},
         _ = cancel.cancelled() => { return Ok(()) }
@@ -237,6 +247,7 @@ mod test {
 
       let federation_worker_config = FederationWorkerConfig {
         concurrent_sends_per_instance,
+        incoming_workers: 0,
       };
       let pool = &mut context.pool();
       let instances = vec![
@@ -324,8 +335,7 @@ mod test {
     let person = Person::create(&mut data.context.pool(), &form).await?;
     let form = FederationBlockListForm {
       instance_id,
-      updated_at: None,
-      expires_at: None,
+      ..Default::default()
     };
     FederationBlockList::block(&mut data.context.pool(), &form).await?;
     data.run().await?;
@@ -363,6 +373,24 @@ mod test {
     Ok(())
   }
 
+  /// Pause sending to instance, there should be no worker created for it
+  #[tokio::test]
+  #[serial]
+  async fn test_send_manager_paused() -> LemmyResult<()> {
+    let mut data = TestData::init(1, 1).await?;
+
+    FederationQueueState::set_paused(&mut data.context.pool(), data.instances[0].id, true).await?;
+
+    data.run().await?;
+    let workers = &data.send_manager.workers;
+    assert_eq!(2, workers.len());
+    assert!(workers.contains_key(&data.instances[1].id));
+    assert!(workers.contains_key(&data.instances[2].id));
+
+    data.cleanup().await?;
+    Ok(())
+  }
+
   /// Mark instance as dead, there should be no worker created for it
   #[tokio::test]
   #[serial]
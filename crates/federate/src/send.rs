@@ -2,23 +2,41 @@ use crate::util::get_actor_cached;
 use activitypub_federation::{
   activity_sending::SendActivityTask,
   config::Data,
+  error::Error as ActivityPubError,
   protocol::context::WithContext,
   traits::Activity,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use http::Extensions;
 use lemmy_api_utils::context::LemmyContext;
-use lemmy_db_schema::{newtypes::ActivityId, source::activity::SentActivity};
+use lemmy_db_schema::{
+  newtypes::{ActivityId, InstanceId},
+  source::{
+    activity::SentActivity,
+    federation_send_error::{FederationSendError, FederationSendErrorForm},
+  },
+  utils::DbPool,
+};
 use lemmy_utils::{
   error::{LemmyError, LemmyResult},
   federate_retry_sleep_duration,
   FEDERATION_CONTEXT,
 };
-use reqwest::Url;
+use reqwest::{Request, Response, StatusCode, Url};
+use reqwest_middleware::{Middleware, Next};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::ops::Deref;
-use tokio::{sync::mpsc::UnboundedSender, time::sleep};
+use std::{
+  cell::Cell,
+  fmt::{self, Display, Formatter},
+  ops::Deref,
+  sync::Arc,
+};
+use tokio::{
+  sync::{mpsc::UnboundedSender, Notify},
+  time::sleep,
+};
 use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Eq)]
@@ -89,8 +107,14 @@ pub(crate) struct SendRetryTask<'a> {
   pub initial_fail_count: i32,
   /// For logging purposes
   pub domain: String,
+  /// For recording send errors
+  pub instance_id: InstanceId,
   pub context: Data<LemmyContext>,
   pub stop: CancellationToken,
+  /// Cancelled if an admin skips the activity, which is then reported as skipped
+  pub skip: CancellationToken,
+  /// Notified if an admin wants failed sends to be retried right away
+  pub retry_now: Arc<Notify>,
 }
 
 impl SendRetryTask<'_> {
@@ -104,27 +128,22 @@ impl SendRetryTask<'_> {
       report,
       initial_fail_count,
       domain,
+      instance_id,
       context,
       stop,
+      skip,
+      retry_now,
     } = self;
     debug_assert!(!inbox_urls.is_empty());
 
     let pool = &mut context.pool();
-    let Some(actor_apub_id) = &activity.actor_apub_id else {
-      return Err(anyhow::anyhow!("activity is from before lemmy 0.19"));
-    };
-    let actor = get_actor_cached(pool, activity.actor_type, actor_apub_id)
-      .await
-      .context("failed getting actor instance (was it marked deleted / removed?)")?;
-
-    let object: DummyActivity = serde_json::from_value(object.clone())?;
-    let object = WithContext::new(object, FEDERATION_CONTEXT.deref().clone());
-    let requests = SendActivityTask::prepare(&object, actor.as_ref(), inbox_urls, &context).await?;
+    let requests = prepare_requests(activity, object, inbox_urls, &context).await?;
     for task in requests {
       // usually only one due to shared inbox
       tracing::debug!("sending out {}", task);
       let mut fail_count = initial_fail_count;
-      while let Err(e) = task.sign_and_send(&context).await {
+      while let Err(e) = sign_and_send(&task, &context).await {
+        record_send_error(pool, instance_id, activity.id, &e).await;
         fail_count += 1;
         report.send(SendActivityResult::Failure {
           fail_count,
@@ -139,6 +158,16 @@ impl SendRetryTask<'_> {
         );
         tokio::select! {
           () = sleep(retry_delay) => {},
+          () = retry_now.notified() => {},
+          () = skip.cancelled() => {
+            tracing::info!("{}: skipping {:?} as requested by admin", domain, activity.id);
+            report.send(SendActivityResult::Success(SendSuccessInfo {
+              activity_id: activity.id,
+              published_at: Some(activity.published_at),
+              was_skipped: true,
+            }))?;
+            return Ok(());
+          }
           () = stop.cancelled() => {
             // cancel sending without reporting any result.
             // the InstanceWorker needs to be careful to not hang on receive of that
@@ -157,6 +186,117 @@ impl SendRetryTask<'_> {
   }
 }
 
+/// Sends the activity a single time without retrying, for activities which an admin wants to
+/// resend. Errors are only recorded, and don't affect the queue state of the instance.
+pub(crate) async fn resend_activity(
+  activity: &SentActivity,
+  inbox_urls: Vec<Url>,
+  instance_id: InstanceId,
+  context: &Data<LemmyContext>,
+) -> Result<()> {
+  let pool = &mut context.pool();
+  for task in prepare_requests(activity, &activity.data, inbox_urls, context).await? {
+    if let Err(e) = sign_and_send(&task, context).await {
+      record_send_error(pool, instance_id, activity.id, &e).await;
+    }
+  }
+  Ok(())
+}
+
+async fn prepare_requests(
+  activity: &SentActivity,
+  object: &Value,
+  inbox_urls: Vec<Url>,
+  context: &Data<LemmyContext>,
+) -> Result<Vec<SendActivityTask>> {
+  let Some(actor_apub_id) = &activity.actor_apub_id else {
+    return Err(anyhow::anyhow!("activity is from before lemmy 0.19"));
+  };
+  let actor = get_actor_cached(&mut context.pool(), activity.actor_type, actor_apub_id)
+    .await
+    .context("failed getting actor instance (was it marked deleted / removed?)")?;
+
+  let object: DummyActivity = serde_json::from_value(object.clone())?;
+  let object = WithContext::new(object, FEDERATION_CONTEXT.deref().clone());
+  Ok(SendActivityTask::prepare(&object, actor.as_ref(), inbox_urls, context).await?)
+}
+
+tokio::task_local! {
+  /// The status of the last HTTP response received by the current send task.
+  static RESPONSE_STATUS: Cell<Option<StatusCode>>;
+}
+
+/// Records the status of HTTP responses for [`sign_and_send`], because the federation library
+/// only includes it in the error message. Does nothing for requests which aren't activity sends.
+pub struct ResponseStatusMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for ResponseStatusMiddleware {
+  async fn handle(
+    &self,
+    req: Request,
+    extensions: &mut Extensions,
+    next: Next<'_>,
+  ) -> reqwest_middleware::Result<Response> {
+    let res = next.run(req, extensions).await;
+    if let Ok(res) = &res {
+      RESPONSE_STATUS
+        .try_with(|status| status.set(Some(res.status())))
+        .ok();
+    }
+    res
+  }
+}
+
+/// An error from sending an activity, with the HTTP status if the inbox responded.
+#[derive(Debug)]
+struct SendError {
+  error: ActivityPubError,
+  http_status: Option<StatusCode>,
+}
+
+impl Display for SendError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.error)
+  }
+}
+
+async fn sign_and_send(
+  task: &SendActivityTask,
+  context: &Data<LemmyContext>,
+) -> Result<(), SendError> {
+  RESPONSE_STATUS
+    .scope(Cell::new(None), async {
+      task
+        .sign_and_send(context)
+        .await
+        .map_err(|error| SendError {
+          error,
+          http_status: RESPONSE_STATUS.with(Cell::get),
+        })
+    })
+    .await
+}
+
+/// Stores the error so that admins can see why sending to the instance fails.
+async fn record_send_error(
+  pool: &mut DbPool<'_>,
+  instance_id: InstanceId,
+  activity_id: ActivityId,
+  error: &SendError,
+) {
+  let form = FederationSendErrorForm {
+    instance_id,
+    activity_id,
+    http_status: error.http_status.map(|s| i32::from(s.as_u16())),
+    error: error.to_string(),
+  };
+  FederationSendError::create(pool, &form)
+    .await
+    .inspect_err(|e| tracing::warn!("Failed to record send error: {e}"))
+    .ok();
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct DummyActivity {
   id: Url,
@@ -17,7 +17,7 @@ use lemmy_db_schema::{
   utils::{get_conn, DbPool},
 };
 use lemmy_db_schema_file::enums::ActorType;
-use lemmy_utils::error::LemmyError;
+use lemmy_utils::{error::LemmyError, CACHE_DURATION_FEDERATION};
 use moka::future::Cache;
 use reqwest::Url;
 use std::{
@@ -130,15 +130,20 @@ impl CancellableTask {
   }
 }
 
-/// assuming apub priv key and ids are immutable, then we don't need to have TTL
+/// The cache needs a TTL because private keys can be rotated, after which activities need to be
+/// signed with the new key
 /// TODO: capacity should be configurable maybe based on memory use
 pub(crate) async fn get_actor_cached(
   pool: &mut DbPool<'_>,
   actor_type: ActorType,
   actor_apub_id: &Url,
 ) -> Result<Arc<SiteOrMultiOrCommunityOrUser>> {
-  static CACHE: LazyLock<Cache<Url, Arc<SiteOrMultiOrCommunityOrUser>>> =
-    LazyLock::new(|| Cache::builder().max_capacity(10000).build());
+  static CACHE: LazyLock<Cache<Url, Arc<SiteOrMultiOrCommunityOrUser>>> = LazyLock::new(|| {
+    Cache::builder()
+      .max_capacity(10000)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
   CACHE
     .try_get_with(actor_apub_id.clone(), async {
       let url = actor_apub_id.clone().into();
@@ -1,6 +1,6 @@
 use crate::{
   inboxes::RealCommunityInboxCollector,
-  send::{SendActivityResult, SendRetryTask, SendSuccessInfo},
+  send::{resend_activity, SendActivityResult, SendRetryTask, SendSuccessInfo},
   util::{
     get_activity_cached,
     get_latest_activity_id,
@@ -15,19 +15,30 @@ use lemmy_api_utils::context::LemmyContext;
 use lemmy_db_schema::{
   newtypes::ActivityId,
   source::{
+    federation_queue_command::FederationQueueCommand,
     federation_queue_state::FederationQueueState,
     instance::{Instance, InstanceForm},
   },
   utils::{ActualDbPool, DbPool},
 };
+use lemmy_db_schema_file::enums::FederationQueueCommandType;
 use lemmy_utils::{
   error::LemmyResult,
   federate_retry_sleep_duration,
   settings::structs::FederationWorkerConfig,
 };
-use std::{cmp::max, collections::BinaryHeap, ops::Add, time::Duration};
+use std::{
+  cmp::max,
+  collections::{BinaryHeap, HashMap},
+  ops::Add,
+  sync::Arc,
+  time::Duration,
+};
 use tokio::{
-  sync::mpsc::{self, UnboundedSender},
+  sync::{
+    mpsc::{self, UnboundedSender},
+    Notify,
+  },
   time::sleep,
 };
 use tokio_util::sync::CancellationToken;
@@ -41,6 +52,11 @@ static SAVE_STATE_EVERY_TIME: Duration = Duration::from_secs(60);
 static SAVE_STATE_EVERY_TIME: Duration = Duration::from_secs(0);
 /// Maximum number of successful sends to allow out of order
 const MAX_SUCCESSFULS: usize = 1000;
+/// How often to check for commands from admins, like skipping an activity
+#[cfg(not(test))]
+static CHECK_COMMANDS_EVERY: Duration = Duration::from_secs(5);
+#[cfg(test)]
+static CHECK_COMMANDS_EVERY: Duration = Duration::from_millis(100);
 
 /// in prod mode, try to collect multiple send results at the same time to reduce load
 #[cfg(not(test))]
@@ -78,6 +94,13 @@ pub(crate) struct InstanceWorker {
   successfuls: BinaryHeap<SendSuccessInfo>,
   // number of activities that currently have a task spawned to send it
   in_flight: i8,
+  // for each activity that is currently being sent, a token to skip it on admin request
+  skip_tokens: HashMap<ActivityId, CancellationToken>,
+  // activities up to this id are skipped instead of sent, on admin request
+  skip_until: Option<ActivityId>,
+  // wakes up send tasks which are waiting to retry, on admin request
+  retry_now: Arc<Notify>,
+  last_command_check: DateTime<Utc>,
 }
 
 impl InstanceWorker {
@@ -110,6 +133,10 @@ impl InstanceWorker {
       report_send_result,
       successfuls: BinaryHeap::<SendSuccessInfo>::new(),
       in_flight: 0,
+      skip_tokens: HashMap::new(),
+      skip_until: None,
+      retry_now: Arc::new(Notify::new()),
+      last_command_check: Utc.timestamp_nanos(0),
     };
 
     worker.loop_until_stopped().await
@@ -122,6 +149,13 @@ impl InstanceWorker {
     let (mut last_sent_id, mut newest_id) = self.get_latest_ids().await?;
 
     while !self.stop.is_cancelled() {
+      self.handle_commands().await?;
+      if self.in_flight == 0 && self.successfuls.is_empty() {
+        // an admin may have skipped activities, which moves the queue forward
+        if let Some(last_successful_id) = self.state.last_successful_id {
+          last_sent_id = max(last_sent_id, last_successful_id);
+        }
+      }
       // check if we need to wait for a send to finish before sending the next one
       // we wait if (a) the last request failed, only if a request is already in flight (not at the
       // start of the loop) or (b) if we have too many successfuls in memory or (c) if we have
@@ -214,9 +248,11 @@ impl InstanceWorker {
         self.instance.domain,
         remaining
       );
+      let stop = self.stop.clone();
       tokio::select! {
         () = sleep(remaining) => {},
-        () = self.stop.cancelled() => {
+        res = self.wait_for_retry_command() => res?,
+        () = stop.cancelled() => {
           tracing::debug!("cancelled worker loop during initial fail sleep")
         }
       }
@@ -224,6 +260,114 @@ impl InstanceWorker {
     Ok(())
   }
 
+  /// Returns once an admin wants failed sends to be retried right away
+  async fn wait_for_retry_command(&mut self) -> Result<()> {
+    loop {
+      sleep(CHECK_COMMANDS_EVERY).await;
+      if self.handle_commands().await? {
+        return Ok(());
+      }
+    }
+  }
+
+  /// Handles pending commands from admins, if enough time has passed since the last check.
+  /// Returns true if sending should continue right away, instead of waiting for the next retry.
+  async fn handle_commands(&mut self) -> Result<bool> {
+    let check_commands_every = chrono::Duration::from_std(CHECK_COMMANDS_EVERY)?;
+    if Utc::now() - self.last_command_check < check_commands_every {
+      return Ok(false);
+    }
+    self.last_command_check = Utc::now();
+    let commands = FederationQueueCommand::take_all(&mut self.pool(), self.instance.id)
+      .await
+      .map_err(|e| anyhow::anyhow!(e))?;
+    let mut retry_now = false;
+    for command in commands {
+      tracing::info!(
+        "{}: handling admin command {:?}",
+        self.instance.domain,
+        command
+      );
+      match (
+        command.command,
+        command.first_activity_id,
+        command.last_activity_id,
+      ) {
+        (FederationQueueCommandType::RetryNow, _, _) => {
+          retry_now = true;
+          self.retry_now.notify_waiters();
+        }
+        (FederationQueueCommandType::Skip, _, Some(last)) => {
+          // the send tasks report the skipped activities as successful
+          self.skip_tokens.retain(|id, token| {
+            if *id <= last {
+              token.cancel();
+            }
+            *id > last
+          });
+          // the skipped activities were most likely the reason for failing
+          self.state.fail_count = 0;
+          self.skip_unsent(last).await?;
+          retry_now = true;
+        }
+        (FederationQueueCommandType::Resend, Some(first), Some(last)) => {
+          self.resend(first, last).await?;
+        }
+        _ => tracing::warn!("{}: invalid admin command", self.instance.domain),
+      }
+    }
+    Ok(retry_now)
+  }
+
+  /// Skips activities up to `last` which are not being sent yet. If nothing is being sent, the
+  /// queue is moved past them right away. Otherwise they are reported as skipped once the worker
+  /// gets to them, so that the queue state stays consistent.
+  async fn skip_unsent(&mut self, last: ActivityId) -> Result<()> {
+    let Some(last_successful_id) = self.state.last_successful_id else {
+      return Ok(());
+    };
+    if last <= last_successful_id {
+      return Ok(());
+    }
+    if self.in_flight == 0 && self.successfuls.is_empty() {
+      self.state.last_successful_id = Some(last);
+    } else {
+      self.skip_until = max(self.skip_until, Some(last));
+    }
+    self.save_and_send_state().await?;
+    Ok(())
+  }
+
+  /// Sends the activities in the range to the instance again. This happens independently of the
+  /// queue, so the queue state is not affected.
+  async fn resend(&mut self, first: ActivityId, last: ActivityId) -> Result<()> {
+    for id in first.0..=last.0 {
+      let Some(activity) = get_activity_cached(&mut self.pool(), ActivityId(id))
+        .await
+        .context("failed reading activity from db")?
+      else {
+        continue;
+      };
+      let inbox_urls = self
+        .inbox_collector
+        .get_inbox_urls(&activity)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+      if inbox_urls.is_empty() {
+        continue;
+      }
+      let instance_id = self.instance.id;
+      let context = self.federation_lib_config.to_request_data();
+      tokio::spawn(async move {
+        resend_activity(&activity, inbox_urls, instance_id, &context)
+          .await
+          .inspect_err(|e| tracing::warn!("resending {} errored: {e:?}", activity.ap_id))
+          .ok();
+      });
+    }
+    Ok(())
+  }
+
   /// return the last successfully sent id and the newest activity id in the database
   /// sets last_successful_id in database if it's the first time this instance is seen
   async fn get_latest_ids(&mut self) -> Result<(ActivityId, ActivityId)> {
@@ -255,6 +399,10 @@ impl InstanceWorker {
     // InstanceWorker holds a copy of the send result channel as well, that won't happen.
     tokio::select! {
       _ = self.receive_send_result.recv_many(&mut events, 1000) => {},
+      // return regularly so that commands from admins are handled while waiting
+      () = sleep(CHECK_COMMANDS_EVERY) => {
+        return Ok(());
+      }
       () = self.stop.cancelled() => {
         tracing::debug!("cancelled worker loop while waiting for send results");
         return Ok(());
@@ -264,6 +412,7 @@ impl InstanceWorker {
       match event {
         SendActivityResult::Success(s) => {
           self.in_flight -= 1;
+          self.skip_tokens.remove(&s.activity_id);
           if !s.was_skipped {
             self.state.fail_count = max(0, self.state.fail_count - 1);
             self.mark_instance_alive().await?;
@@ -352,6 +501,24 @@ impl InstanceWorker {
   /// if we have inboxes to send to this limits CPU usage and reduces overhead for the (many)
   /// cases where we don't have any inboxes
   async fn spawn_send_if_needed(&mut self, activity_id: ActivityId) -> LemmyResult<()> {
+    if self
+      .skip_until
+      .is_some_and(|skip_until| activity_id <= skip_until)
+    {
+      tracing::debug!(
+        "{}: skipping {:?} as requested by admin",
+        self.instance.domain,
+        activity_id
+      );
+      self
+        .report_send_result
+        .send(SendActivityResult::Success(SendSuccessInfo {
+          activity_id,
+          published_at: None,
+          was_skipped: true,
+        }))?;
+      return Ok(());
+    }
     let Some(ele) = get_activity_cached(&mut self.pool(), activity_id)
       .await
       .context("failed reading activity from db")?
@@ -390,7 +557,11 @@ impl InstanceWorker {
     let initial_fail_count = self.state.fail_count;
     let data = self.federation_lib_config.to_request_data();
     let stop = self.stop.clone();
+    let skip = CancellationToken::new();
+    self.skip_tokens.insert(activity_id, skip.clone());
+    let retry_now = self.retry_now.clone();
     let domain = self.instance.domain.clone();
+    let instance_id = self.instance.id;
     let mut report = self.report_send_result.clone();
     tokio::spawn(async move {
       let res = SendRetryTask {
@@ -400,8 +571,11 @@ impl InstanceWorker {
         report: &mut report,
         initial_fail_count,
         domain,
+        instance_id,
         context: data,
         stop,
+        skip,
+        retry_now,
       }
       .send_retry_loop()
       .await;
@@ -463,6 +637,8 @@ mod test {
     newtypes::DbUrl,
     source::{
       activity::{SentActivity, SentActivityForm},
+      federation_queue_command::FederationQueueCommandForm,
+      federation_send_error::FederationSendError,
       person::{Person, PersonInsertForm},
     },
     traits::Crud,
@@ -523,6 +699,7 @@ mod test {
 
       let fed_config = FederationWorkerConfig {
         concurrent_sends_per_instance,
+        incoming_workers: 0,
       };
       spawn(InstanceWorker::init_and_loop(
         instance.clone(),
@@ -718,6 +895,47 @@ mod test {
     Ok(())
   }
 
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn test_skip(data: &mut Data) -> LemmyResult<()> {
+    let form = InstanceForm::new(data.instance.domain.clone());
+    Instance::update(&mut data.context.pool(), data.instance.id, form).await?;
+
+    let rcv = data.stats_receiver.recv().await.unwrap();
+    assert_eq!(0, rcv.state.fail_count);
+
+    // the activity keeps failing
+    *data.respond_with_error.write().unwrap() = true;
+    let sent = send_activity(data.person.ap_id.clone(), &data.context, false).await?;
+    wait_receive(2, &mut data.stats_receiver).await;
+
+    // the failures are recorded for admins
+    let errors =
+      FederationSendError::list(&mut data.context.pool(), data.instance.id, None).await?;
+    assert!(!errors.is_empty());
+    assert_eq!(sent.id, errors[0].activity_id);
+
+    // admin skips it
+    let form = FederationQueueCommandForm {
+      instance_id: data.instance.id,
+      command: FederationQueueCommandType::Skip,
+      first_activity_id: None,
+      last_activity_id: Some(sent.id),
+    };
+    FederationQueueCommand::create(&mut data.context.pool(), &form).await?;
+
+    // the activity counts as sent, and the fail count is reset
+    for _ in 0..5 {
+      let rcv = data.stats_receiver.recv().await.unwrap();
+      if rcv.state.last_successful_id == Some(sent.id) {
+        assert_eq!(0, rcv.state.fail_count);
+        return Ok(());
+      }
+    }
+    panic!();
+  }
+
   async fn wait_receive(
     expected_fail_count: i32,
     rec: &mut UnboundedReceiver<FederationQueueStateWithDomain>,
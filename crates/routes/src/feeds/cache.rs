@@ -0,0 +1,126 @@
+use super::{
+  format::{item_date, FeedFormat},
+  websub::link_header,
+};
+use actix_web::{
+  http::header::{
+    ETag,
+    EntityTag,
+    Header,
+    HttpDate,
+    IfModifiedSince,
+    IfNoneMatch,
+    LastModified,
+    IF_NONE_MATCH,
+    LINK,
+  },
+  HttpRequest,
+  HttpResponse,
+};
+use lemmy_api_utils::context::LemmyContext;
+use lemmy_utils::{error::LemmyResult, CACHE_DURATION_FEED};
+use moka::future::Cache;
+use rss::Channel;
+use std::{
+  future::Future,
+  hash::{DefaultHasher, Hash, Hasher},
+  sync::LazyLock,
+  time::SystemTime,
+};
+
+const FEED_CACHE_CAPACITY: u64 = 500;
+
+/// A feed which was rendered for a request url.
+#[derive(Clone, Debug)]
+struct RenderedFeed {
+  body: String,
+  format: FeedFormat,
+  etag: EntityTag,
+  last_modified: HttpDate,
+}
+
+/// Rendered feeds by hash of the request url and format.
+static FEED_CACHE: LazyLock<Cache<u64, RenderedFeed>> = LazyLock::new(|| {
+  Cache::builder()
+    .max_capacity(FEED_CACHE_CAPACITY)
+    .time_to_live(CACHE_DURATION_FEED)
+    .build()
+});
+
+/// Responds with the feed in the given format. Feed readers poll the same urls over and over, so
+/// the channel is only built if the feed isn't cached. Conditional requests with `If-None-Match`
+/// or `If-Modified-Since` get an empty response if the feed didn't change.
+///
+/// Public feeds pass the url of the WebSub hub, which is advertised in the feed and the `Link`
+/// header.
+pub(super) async fn feed_response(
+  req: &HttpRequest,
+  format: FeedFormat,
+  context: &LemmyContext,
+  hub_url: Option<&str>,
+  channel: impl Future<Output = LemmyResult<Channel>>,
+) -> LemmyResult<HttpResponse> {
+  let feed_url = format!(
+    "{}{}",
+    context.settings().get_protocol_and_hostname(),
+    req.uri()
+  );
+  // Private feeds have a token in the url, so it is hashed instead of being kept in memory
+  let key = hash(&(req.uri().to_string(), format));
+  let feed = if let Some(feed) = FEED_CACHE.get(&key).await {
+    feed
+  } else {
+    let channel = channel.await?;
+    let body = format.render(&channel, &feed_url, hub_url)?;
+    let last_modified = channel
+      .items
+      .iter()
+      .filter_map(item_date)
+      .max()
+      .map(SystemTime::from)
+      .unwrap_or_else(SystemTime::now);
+    let feed = RenderedFeed {
+      etag: EntityTag::new_strong(format!("{:x}", hash(&body))),
+      body,
+      format,
+      last_modified: last_modified.into(),
+    };
+    FEED_CACHE.insert(key, feed.clone()).await;
+    feed
+  };
+
+  if is_not_modified(req, &feed) {
+    return Ok(
+      HttpResponse::NotModified()
+        .insert_header(ETag(feed.etag))
+        .finish(),
+    );
+  }
+  let mut response = HttpResponse::Ok();
+  response
+    .content_type(feed.format.content_type())
+    .insert_header(ETag(feed.etag))
+    .insert_header(LastModified(feed.last_modified));
+  if let Some(hub_url) = hub_url {
+    response.insert_header((LINK, link_header(hub_url, &feed_url)));
+  }
+  Ok(response.body(feed.body))
+}
+
+fn is_not_modified(req: &HttpRequest, feed: &RenderedFeed) -> bool {
+  // If-None-Match takes precedence, see https://www.rfc-editor.org/rfc/rfc9110#section-13.1.3
+  if req.headers().contains_key(IF_NONE_MATCH) {
+    return match IfNoneMatch::parse(req) {
+      Ok(IfNoneMatch::Any) => true,
+      Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|t| t.weak_eq(&feed.etag)),
+      Err(_) => false,
+    };
+  }
+  IfModifiedSince::parse(req).is_ok_and(|since| feed.last_modified <= since.0)
+}
+
+fn hash(value: &impl Hash) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  value.hash(&mut hasher);
+  hasher.finish()
+}
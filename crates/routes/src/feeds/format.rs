@@ -0,0 +1,399 @@
+use actix_web::{
+  http::header::{Accept, Header},
+  HttpRequest,
+};
+use atom_syndication::{Category, Content, Entry, Feed, FixedDateTime, Link, Person, Text};
+use chrono::{DateTime, Utc};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use rss::{extension::atom::AtomExtension, Channel, Item};
+use serde::Serialize;
+
+/// The formats in which each feed is available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum FeedFormat {
+  Rss,
+  Atom,
+  Json,
+}
+
+impl FeedFormat {
+  /// Selects the format by the extension of the feed url. Feeds with `.xml` are RSS by default,
+  /// but a client can request one of the other formats with the `Accept` header.
+  pub(super) fn from_request(extension: &str, req: &HttpRequest) -> LemmyResult<Self> {
+    match Self::from_extension(extension)? {
+      FeedFormat::Rss => Ok(
+        Accept::parse(req)
+          .ok()
+          .and_then(|accept| {
+            accept
+              .ranked()
+              .iter()
+              .find_map(|mime| Self::from_media_type(mime.essence_str()))
+          })
+          .unwrap_or(FeedFormat::Rss),
+      ),
+      format => Ok(format),
+    }
+  }
+
+  pub(super) fn from_extension(extension: &str) -> LemmyResult<Self> {
+    match extension {
+      "xml" => Ok(FeedFormat::Rss),
+      "atom" => Ok(FeedFormat::Atom),
+      "json" => Ok(FeedFormat::Json),
+      _ => Err(LemmyErrorType::NotFound.into()),
+    }
+  }
+
+  fn from_media_type(media_type: &str) -> Option<Self> {
+    match media_type {
+      "application/rss+xml" => Some(FeedFormat::Rss),
+      "application/atom+xml" => Some(FeedFormat::Atom),
+      "application/feed+json" => Some(FeedFormat::Json),
+      _ => None,
+    }
+  }
+
+  pub(super) fn content_type(self) -> &'static str {
+    match self {
+      FeedFormat::Rss => "application/rss+xml",
+      FeedFormat::Atom => "application/atom+xml",
+      FeedFormat::Json => "application/feed+json",
+    }
+  }
+
+  /// Converts the channel into this format. `feed_url` is the url under which the feed was
+  /// requested. Feeds which can be subscribed with WebSub link to the `hub_url`.
+  pub(super) fn render(
+    self,
+    channel: &Channel,
+    feed_url: &str,
+    hub_url: Option<&str>,
+  ) -> LemmyResult<String> {
+    Ok(match self {
+      FeedFormat::Rss => to_rss(channel, feed_url, hub_url).to_string(),
+      FeedFormat::Atom => to_atom(channel, feed_url, hub_url).to_string(),
+      FeedFormat::Json => serde_json::to_string(&to_json_feed(channel, feed_url, hub_url))?,
+    })
+  }
+}
+
+/// The publish date of the item.
+pub(super) fn item_date(item: &Item) -> Option<FixedDateTime> {
+  item
+    .pub_date
+    .as_deref()
+    .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+}
+
+/// The guid is a permalink for all items, so it also works as id.
+fn item_id(item: &Item) -> String {
+  item
+    .guid
+    .as_ref()
+    .map(|guid| guid.value.clone())
+    .or_else(|| item.link.clone())
+    .unwrap_or_default()
+}
+
+/// Post items name the creator in the Dublin Core extension, the other items in the author
+/// field.
+fn item_authors(item: &Item) -> Vec<String> {
+  match &item.dublin_core_ext {
+    Some(dc) if !dc.creators.is_empty() => dc.creators.clone(),
+    _ => item.author.clone().into_iter().collect(),
+  }
+}
+
+fn self_link(feed_url: &str, format: FeedFormat) -> Link {
+  Link {
+    href: feed_url.to_string(),
+    rel: "self".to_string(),
+    mime_type: Some(format.content_type().to_string()),
+    ..Default::default()
+  }
+}
+
+/// Together with the self link, this allows subscribing to the feed with WebSub, see
+/// https://www.w3.org/TR/websub/#discovery
+fn hub_link(hub_url: &str) -> Link {
+  Link {
+    href: hub_url.to_string(),
+    rel: "hub".to_string(),
+    ..Default::default()
+  }
+}
+
+fn to_rss(channel: &Channel, feed_url: &str, hub_url: Option<&str>) -> Channel {
+  let mut channel = channel.clone();
+  if let Some(hub_url) = hub_url {
+    channel.set_atom_ext(AtomExtension {
+      links: vec![hub_link(hub_url), self_link(feed_url, FeedFormat::Rss)],
+    });
+  }
+  channel
+}
+
+fn to_atom(channel: &Channel, feed_url: &str, hub_url: Option<&str>) -> Feed {
+  let entries = channel
+    .items
+    .iter()
+    .map(|item| {
+      let published = item_date(item);
+      let mut links = item
+        .link
+        .clone()
+        .map(|href| Link {
+          href,
+          ..Default::default()
+        })
+        .into_iter()
+        .collect::<Vec<_>>();
+      if let Some(enclosure) = &item.enclosure {
+        links.push(Link {
+          href: enclosure.url.clone(),
+          rel: "enclosure".to_string(),
+          mime_type: Some(enclosure.mime_type.clone()),
+          ..Default::default()
+        });
+      }
+      Entry {
+        title: Text::plain(item.title.clone().unwrap_or_default()),
+        id: item_id(item),
+        updated: published.unwrap_or_default(),
+        published,
+        authors: item_authors(item)
+          .into_iter()
+          .map(|name| Person {
+            name,
+            ..Default::default()
+          })
+          .collect(),
+        categories: item
+          .categories
+          .iter()
+          .map(|c| Category {
+            term: c.name.clone(),
+            scheme: c.domain.clone(),
+            label: None,
+          })
+          .collect(),
+        links,
+        content: item.description.clone().map(|value| Content {
+          value: Some(value),
+          content_type: Some("html".to_string()),
+          ..Default::default()
+        }),
+        ..Default::default()
+      }
+    })
+    .collect::<Vec<_>>();
+
+  let mut links = vec![
+    Link {
+      href: channel.link.clone(),
+      ..Default::default()
+    },
+    self_link(feed_url, FeedFormat::Atom),
+  ];
+  links.extend(hub_url.map(hub_link));
+
+  Feed {
+    title: Text::plain(channel.title.clone()),
+    id: feed_url.to_string(),
+    updated: entries
+      .iter()
+      .map(|e| e.updated)
+      .max()
+      .unwrap_or_else(|| Utc::now().fixed_offset()),
+    links,
+    subtitle: Some(Text::html(channel.description.clone())).filter(|s| !s.value.is_empty()),
+    entries,
+    ..Default::default()
+  }
+}
+
+/// A feed in the JSON Feed 1.1 format, see https://www.jsonfeed.org/version/1.1/
+#[derive(Serialize)]
+struct JsonFeed {
+  version: &'static str,
+  title: String,
+  home_page_url: String,
+  feed_url: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  description: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  hubs: Vec<JsonFeedHub>,
+  items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedHub {
+  #[serde(rename = "type")]
+  type_: &'static str,
+  url: String,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+  id: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  url: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  title: Option<String>,
+  content_html: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  date_published: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  authors: Vec<JsonFeedAuthor>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  tags: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+  name: String,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAttachment {
+  url: String,
+  mime_type: String,
+}
+
+fn to_json_feed(channel: &Channel, feed_url: &str, hub_url: Option<&str>) -> JsonFeed {
+  let items = channel
+    .items
+    .iter()
+    .map(|item| JsonFeedItem {
+      id: item_id(item),
+      url: item.link.clone(),
+      title: item.title.clone(),
+      content_html: item.description.clone().unwrap_or_default(),
+      date_published: item_date(item).map(|d| d.to_rfc3339()),
+      authors: item_authors(item)
+        .into_iter()
+        .map(|name| JsonFeedAuthor { name })
+        .collect(),
+      tags: item.categories.iter().map(|c| c.name.clone()).collect(),
+      attachments: item
+        .enclosure
+        .iter()
+        .map(|e| JsonFeedAttachment {
+          url: e.url.clone(),
+          mime_type: e.mime_type.clone(),
+        })
+        .collect(),
+    })
+    .collect();
+
+  JsonFeed {
+    version: "https://jsonfeed.org/version/1.1",
+    title: channel.title.clone(),
+    home_page_url: channel.link.clone(),
+    feed_url: feed_url.to_string(),
+    description: Some(channel.description.clone()).filter(|d| !d.is_empty()),
+    hubs: hub_url
+      .map(|url| JsonFeedHub {
+        type_: "WebSub",
+        url: url.to_string(),
+      })
+      .into_iter()
+      .collect(),
+    items,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use actix_web::test::TestRequest;
+  use pretty_assertions::assert_eq;
+  use rss::Guid;
+  use serde_json::Value;
+
+  fn test_channel() -> Channel {
+    let item = Item {
+      title: Some("A post".to_string()),
+      link: Some("https://lemmy.tld/post/1".to_string()),
+      guid: Some(Guid {
+        permalink: true,
+        value: "https://lemmy.tld/post/1".to_string(),
+      }),
+      pub_date: Some("Tue, 12 Aug 2025 09:00:00 +0000".to_string()),
+      description: Some("<p>Hello</p>".to_string()),
+      ..Default::default()
+    };
+    Channel {
+      title: "Lemmy - All".to_string(),
+      link: "https://lemmy.tld".to_string(),
+      items: vec![item],
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_format_from_request() -> LemmyResult<()> {
+    let req = TestRequest::default().to_http_request();
+    assert_eq!(FeedFormat::Rss, FeedFormat::from_request("xml", &req)?);
+    assert_eq!(FeedFormat::Atom, FeedFormat::from_request("atom", &req)?);
+    assert_eq!(FeedFormat::Json, FeedFormat::from_request("json", &req)?);
+    assert!(FeedFormat::from_request("html", &req).is_err());
+
+    let req = TestRequest::default()
+      .insert_header(("Accept", "application/rss+xml;q=0.5, application/feed+json"))
+      .to_http_request();
+    assert_eq!(FeedFormat::Json, FeedFormat::from_request("xml", &req)?);
+    // An explicit extension wins over the header
+    assert_eq!(FeedFormat::Atom, FeedFormat::from_request("atom", &req)?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_atom() -> LemmyResult<()> {
+    let feed = to_atom(&test_channel(), "https://lemmy.tld/feeds/all.atom", None);
+    assert_eq!("https://lemmy.tld/feeds/all.atom", feed.id);
+    let entry = feed.entries.first().ok_or(LemmyErrorType::NotFound)?;
+    assert_eq!("https://lemmy.tld/post/1", entry.id);
+    assert_eq!(Some(entry.updated), entry.published);
+    assert_eq!(feed.updated, entry.updated);
+    Ok(())
+  }
+
+  #[test]
+  fn test_json_feed() -> LemmyResult<()> {
+    let json =
+      FeedFormat::Json.render(&test_channel(), "https://lemmy.tld/feeds/all.json", None)?;
+    let json: Value = serde_json::from_str(&json)?;
+    assert_eq!("https://jsonfeed.org/version/1.1", json["version"]);
+    assert_eq!("https://lemmy.tld/post/1", json["items"][0]["id"]);
+    assert_eq!(
+      "2025-08-12T09:00:00+00:00",
+      json["items"][0]["date_published"]
+    );
+    assert!(json.get("hubs").is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_websub_discovery() -> LemmyResult<()> {
+    let hub_url = Some("https://lemmy.tld/feeds/hub");
+    let rss =
+      FeedFormat::Rss.render(&test_channel(), "https://lemmy.tld/feeds/all.xml", hub_url)?;
+    assert!(rss.contains(r#"<atom:link href="https://lemmy.tld/feeds/hub" rel="hub"/>"#));
+
+    let feed = to_atom(&test_channel(), "https://lemmy.tld/feeds/all.atom", hub_url);
+    assert!(feed
+      .links
+      .iter()
+      .any(|l| l.rel == "hub" && l.href == "https://lemmy.tld/feeds/hub"));
+
+    let json =
+      FeedFormat::Json.render(&test_channel(), "https://lemmy.tld/feeds/all.json", hub_url)?;
+    let json: Value = serde_json::from_str(&json)?;
+    assert_eq!("https://lemmy.tld/feeds/hub", json["hubs"][0]["url"]);
+    Ok(())
+  }
+}
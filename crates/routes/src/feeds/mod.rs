@@ -1,24 +1,32 @@
 use actix_web::{error::ErrorBadRequest, web, Error, HttpRequest, HttpResponse, Result};
 use anyhow::anyhow;
+use cache::feed_response;
 use chrono::{DateTime, Utc};
+use format::FeedFormat;
 use lemmy_api_utils::{
   context::LemmyContext,
   utils::{check_private_instance, local_user_view_from_jwt},
 };
 use lemmy_db_schema::{
-  source::{community::Community, person::Person},
+  newtypes::PostId,
+  source::{community::Community, multi_community::MultiCommunity, person::Person, tag::Tag},
   traits::ApubActor,
   PersonContentType,
+  SearchSortType,
+  SearchType,
 };
-use lemmy_db_schema_file::enums::{ListingType, PostSortType};
+use lemmy_db_schema_file::enums::{CommentSortType, ListingType, PostSortType};
+use lemmy_db_views_comment::{impls::CommentQuery, CommentView};
 use lemmy_db_views_modlog_combined::{impls::ModlogCombinedQuery, ModlogCombinedView};
 use lemmy_db_views_notification::{impls::NotificationQuery, NotificationData, NotificationView};
 use lemmy_db_views_person_content_combined::impls::PersonContentCombinedQuery;
 use lemmy_db_views_post::{impls::PostQuery, PostView};
+use lemmy_db_views_search_combined::impls::SearchCombinedQuery;
 use lemmy_db_views_site::SiteView;
 use lemmy_utils::{
   cache_header::cache_1hour,
   error::{LemmyError, LemmyErrorType, LemmyResult},
+  rate_limit::RateLimit,
   settings::structs::Settings,
   utils::markdown::markdown_to_html,
 };
@@ -32,6 +40,11 @@ use rss::{
 };
 use serde::Deserialize;
 use std::{collections::BTreeMap, str::FromStr, sync::LazyLock};
+use url::Url;
+
+mod cache;
+mod format;
+pub mod websub;
 
 const RSS_FETCH_LIMIT: i64 = 20;
 
@@ -39,41 +52,92 @@ const RSS_FETCH_LIMIT: i64 = 20;
 struct Params {
   sort: Option<String>,
   limit: Option<i64>,
+  /// Only for community feeds, the name of a community tag to filter by
+  tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+  q: String,
+  community_name: Option<String>,
+  limit: Option<i64>,
 }
 
 impl Params {
-  fn sort_type(&self) -> Result<PostSortType, Error> {
+  fn sort_type(&self) -> LemmyResult<PostSortType> {
     let sort_query = self
       .sort
       .clone()
       .unwrap_or_else(|| PostSortType::Hot.to_string());
-    PostSortType::from_str(&sort_query).map_err(ErrorBadRequest)
+    Ok(PostSortType::from_str(&sort_query)?)
   }
   fn get_limit(&self) -> i64 {
     self.limit.unwrap_or(RSS_FETCH_LIMIT)
   }
 }
 
+#[derive(Clone, Copy)]
 enum RequestType {
   Community,
+  MultiCommunity,
   User,
   Front,
   Inbox,
   Modlog,
+  PostComments,
+}
+
+impl RequestType {
+  fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "u" => RequestType::User,
+      "c" => RequestType::Community,
+      "m" => RequestType::MultiCommunity,
+      "front" => RequestType::Front,
+      "inbox" => RequestType::Inbox,
+      "modlog" => RequestType::Modlog,
+      "post" => RequestType::PostComments,
+      _ => return None,
+    })
+  }
+
+  /// Front, inbox and modlog feeds are private, with an auth token in the url.
+  fn is_public(&self) -> bool {
+    !matches!(
+      self,
+      RequestType::Front | RequestType::Inbox | RequestType::Modlog
+    )
+  }
 }
 
-pub fn config(cfg: &mut web::ServiceConfig) {
+/// All feeds are available as RSS (`.xml`), Atom (`.atom`) and JSON Feed (`.json`). Public feeds
+/// can also be subscribed with WebSub.
+pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimit) {
   cfg.service(
     web::scope("/feeds")
-      .route("/{type}/{name}.xml", web::get().to(get_feed))
-      .route("/all.xml", web::get().to(get_all_feed).wrap(cache_1hour()))
       .route(
-        "/local.xml",
+        "/hub",
+        web::post()
+          .to(websub::handle_hub_request)
+          .wrap(rate_limit.message()),
+      )
+      .route("/{type}/{name}.{format}", web::get().to(get_feed))
+      .route("/search.{format}", web::get().to(get_search_feed))
+      .route(
+        "/all.{format}",
+        web::get().to(get_all_feed).wrap(cache_1hour()),
+      )
+      .route(
+        "/local.{format}",
         web::get().to(get_local_feed).wrap(cache_1hour()),
       ),
   );
 }
 
+fn feed_format(req: &HttpRequest) -> Result<FeedFormat, Error> {
+  FeedFormat::from_request(req.match_info().query("format"), req).map_err(ErrorBadRequest)
+}
+
 static RSS_NAMESPACE: LazyLock<BTreeMap<String, String>> = LazyLock::new(|| {
   let mut h = BTreeMap::new();
   h.insert(
@@ -88,33 +152,45 @@ static RSS_NAMESPACE: LazyLock<BTreeMap<String, String>> = LazyLock::new(|| {
 });
 
 async fn get_all_feed(
+  req: HttpRequest,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::All,
-      info.sort_type()?,
-      info.get_limit(),
-    )
-    .await?,
-  )
+  let channel = get_feed_data(
+    &context,
+    ListingType::All,
+    info.sort_type().map_err(ErrorBadRequest)?,
+    info.get_limit(),
+  );
+  let hub_url = websub::hub_url(context.settings());
+  Ok(feed_response(&req, feed_format(&req)?, &context, Some(&hub_url), channel).await?)
 }
 
 async fn get_local_feed(
+  req: HttpRequest,
   info: web::Query<Params>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  Ok(
-    get_feed_data(
-      &context,
-      ListingType::Local,
-      info.sort_type()?,
-      info.get_limit(),
-    )
-    .await?,
-  )
+  let channel = get_feed_data(
+    &context,
+    ListingType::Local,
+    info.sort_type().map_err(ErrorBadRequest)?,
+    info.get_limit(),
+  );
+  let hub_url = websub::hub_url(context.settings());
+  Ok(feed_response(&req, feed_format(&req)?, &context, Some(&hub_url), channel).await?)
+}
+
+async fn get_search_feed(
+  req: HttpRequest,
+  info: web::Query<SearchParams>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let channel = get_feed_search(&context, &info);
+  let hub_url = websub::hub_url(context.settings());
+  feed_response(&req, feed_format(&req)?, &context, Some(&hub_url), channel)
+    .await
+    .map_err(ErrorBadRequest)
 }
 
 async fn get_feed_data(
@@ -122,7 +198,7 @@ async fn get_feed_data(
   listing_type: ListingType,
   sort_type: PostSortType,
   limit: i64,
-) -> LemmyResult<HttpResponse> {
+) -> LemmyResult<Channel> {
   let site_view = SiteView::read_local(&mut context.pool()).await?;
 
   check_private_instance(&None, &site_view.local_site)?;
@@ -150,12 +226,7 @@ async fn get_feed_data(
     channel.set_description(&site_desc);
   }
 
-  let rss = channel.to_string();
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+  Ok(channel)
 }
 
 async fn get_feed(
@@ -166,35 +237,110 @@ async fn get_feed(
   let req_type: String = req.match_info().get("type").unwrap_or("none").parse()?;
   let param: String = req.match_info().get("name").unwrap_or("none").parse()?;
 
-  let request_type = match req_type.as_str() {
-    "u" => RequestType::User,
-    "c" => RequestType::Community,
-    "front" => RequestType::Front,
-    "inbox" => RequestType::Inbox,
-    "modlog" => RequestType::Modlog,
-    _ => return Err(ErrorBadRequest(LemmyError::from(anyhow!("wrong_type")))),
-  };
+  let request_type = RequestType::from_name(&req_type)
+    .ok_or_else(|| ErrorBadRequest(LemmyError::from(anyhow!("wrong_type"))))?;
+  let format = feed_format(&req)?;
+  let hub_url = request_type
+    .is_public()
+    .then(|| websub::hub_url(context.settings()));
+  let channel = get_feed_channel(&context, request_type, &param, &info);
+
+  feed_response(&req, format, &context, hub_url.as_deref(), channel)
+    .await
+    .map_err(ErrorBadRequest)
+}
 
-  let builder = match request_type {
-    RequestType::User => get_feed_user(&context, &info.get_limit(), &param).await,
+async fn get_feed_channel(
+  context: &LemmyContext,
+  request_type: RequestType,
+  param: &str,
+  info: &Params,
+) -> LemmyResult<Channel> {
+  let sort_type = info.sort_type()?;
+  let limit = info.get_limit();
+  match request_type {
+    RequestType::User => get_feed_user(context, &limit, param).await,
     RequestType::Community => {
-      get_feed_community(&context, &info.sort_type()?, &info.get_limit(), &param).await
+      get_feed_community(context, &sort_type, &limit, param, info.tag.as_deref()).await
     }
-    RequestType::Front => {
-      get_feed_front(&context, &info.sort_type()?, &info.get_limit(), &param).await
+    RequestType::MultiCommunity => {
+      get_feed_multi_community(context, &sort_type, &limit, param).await
     }
-    RequestType::Inbox => get_feed_inbox(&context, &param).await,
-    RequestType::Modlog => get_feed_modlog(&context, &param).await,
+    RequestType::Front => get_feed_front(context, &sort_type, &limit, param).await,
+    RequestType::Inbox => get_feed_inbox(context, param).await,
+    RequestType::Modlog => get_feed_modlog(context, param).await,
+    RequestType::PostComments => get_feed_post_comments(context, &limit, param).await,
   }
-  .map_err(ErrorBadRequest)?;
+}
 
-  let rss = builder.to_string();
+/// A public feed of this instance, identified by its url so that it can be subscribed with
+/// WebSub.
+struct PublicFeed {
+  format: FeedFormat,
+  /// The feed independent of its format and parameters, in lowercase like `c/news`.
+  key: String,
+  kind: PublicFeedKind,
+}
 
-  Ok(
-    HttpResponse::Ok()
-      .content_type("application/rss+xml")
-      .body(rss),
-  )
+enum PublicFeedKind {
+  Listing(ListingType, Params),
+  Search(SearchParams),
+  Other(RequestType, String, Params),
+}
+
+impl PublicFeed {
+  /// Private feeds and urls which don't belong to a feed of this instance are rejected.
+  fn parse(feed_url: &str, settings: &Settings) -> LemmyResult<Self> {
+    let prefix = format!("{}/feeds/", settings.get_protocol_and_hostname());
+    let url = feed_url
+      .strip_prefix(&prefix)
+      .ok_or(LemmyErrorType::NotFound)?;
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let (path, extension) = path.rsplit_once('.').ok_or(LemmyErrorType::NotFound)?;
+    let format = FeedFormat::from_extension(extension)?;
+
+    let kind = if path == "search" {
+      PublicFeedKind::Search(web::Query::<SearchParams>::from_query(query)?.into_inner())
+    } else {
+      let params = web::Query::<Params>::from_query(query)?.into_inner();
+      match path.split_once('/') {
+        None if path == "all" => PublicFeedKind::Listing(ListingType::All, params),
+        None if path == "local" => PublicFeedKind::Listing(ListingType::Local, params),
+        Some((request_type, param)) if !param.is_empty() && !param.contains('/') => {
+          match RequestType::from_name(request_type) {
+            Some(request_type) if request_type.is_public() => {
+              PublicFeedKind::Other(request_type, param.to_string(), params)
+            }
+            _ => Err(LemmyErrorType::NotFound)?,
+          }
+        }
+        _ => Err(LemmyErrorType::NotFound)?,
+      }
+    };
+    Ok(PublicFeed {
+      format,
+      key: path.to_lowercase(),
+      kind,
+    })
+  }
+
+  async fn channel(&self, context: &LemmyContext) -> LemmyResult<Channel> {
+    match &self.kind {
+      PublicFeedKind::Listing(listing_type, params) => {
+        get_feed_data(
+          context,
+          *listing_type,
+          params.sort_type()?,
+          params.get_limit(),
+        )
+        .await
+      }
+      PublicFeedKind::Search(params) => get_feed_search(context, params).await,
+      PublicFeedKind::Other(request_type, param, params) => {
+        get_feed_channel(context, *request_type, param, params).await
+      }
+    }
+  }
 }
 
 async fn get_feed_user(
@@ -244,6 +390,7 @@ async fn get_feed_community(
   sort_type: &PostSortType,
   limit: &i64,
   community_name: &str,
+  tag_name: Option<&str>,
 ) -> LemmyResult<Channel> {
   let site_view = SiteView::read_local(&mut context.pool()).await?;
   let community = Community::read_from_name(&mut context.pool(), community_name, false)
@@ -255,9 +402,21 @@ async fn get_feed_community(
 
   check_private_instance(&None, &site_view.local_site)?;
 
+  let tag = if let Some(tag_name) = tag_name {
+    let tag = Tag::read_for_community(&mut context.pool(), community.id)
+      .await?
+      .into_iter()
+      .find(|t| t.name == tag_name)
+      .ok_or(LemmyErrorType::NotFound)?;
+    Some(tag)
+  } else {
+    None
+  };
+
   let posts = PostQuery {
     sort: (Some(*sort_type)),
     community_id: (Some(community.id)),
+    tag_id: tag.as_ref().map(|t| t.id),
     limit: (Some(*limit)),
     ..Default::default()
   }
@@ -266,9 +425,18 @@ async fn get_feed_community(
 
   let items = create_post_items(posts, context.settings())?;
 
+  let title = if let Some(tag) = tag {
+    let tag_name = tag.display_name.unwrap_or(tag.name);
+    format!(
+      "{} - {} - {}",
+      site_view.site.name, community.name, tag_name
+    )
+  } else {
+    format!("{} - {}", site_view.site.name, community.name)
+  };
   let mut channel = Channel {
     namespaces: RSS_NAMESPACE.clone(),
-    title: format!("{} - {}", site_view.site.name, community.name),
+    title,
     link: community.ap_id.to_string(),
     items,
     ..Default::default()
@@ -281,6 +449,140 @@ async fn get_feed_community(
   Ok(channel)
 }
 
+async fn get_feed_multi_community(
+  context: &LemmyContext,
+  sort_type: &PostSortType,
+  limit: &i64,
+  multi_name: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  let multi = MultiCommunity::read_from_name(&mut context.pool(), multi_name).await?;
+
+  check_private_instance(&None, &site_view.local_site)?;
+
+  let posts = PostQuery {
+    sort: (Some(*sort_type)),
+    multi_community_id: (Some(multi.id)),
+    limit: (Some(*limit)),
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?;
+
+  let items = create_post_items(posts, context.settings())?;
+
+  let mut channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!(
+      "{} - {}",
+      site_view.site.name,
+      multi.title.unwrap_or(multi.name)
+    ),
+    link: multi.ap_id.to_string(),
+    items,
+    ..Default::default()
+  };
+
+  if let Some(multi_desc) = multi.description {
+    channel.set_description(markdown_to_html(&multi_desc));
+  }
+
+  Ok(channel)
+}
+
+/// The newest comments of a post.
+async fn get_feed_post_comments(
+  context: &LemmyContext,
+  limit: &i64,
+  post_id: &str,
+) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  check_private_instance(&None, &site_view.local_site)?;
+
+  let post_id = PostId(post_id.parse()?);
+  let post_view = PostView::read(
+    &mut context.pool(),
+    post_id,
+    None,
+    site_view.site.instance_id,
+    false,
+  )
+  .await?;
+  if !post_view.community.visibility.can_view_without_login() {
+    return Err(LemmyErrorType::NotFound.into());
+  }
+
+  let comments = CommentQuery {
+    post_id: Some(post_id),
+    sort: Some(CommentSortType::New),
+    limit: Some(*limit),
+    ..Default::default()
+  }
+  .list(&site_view.site, &mut context.pool())
+  .await?;
+
+  let items = create_comment_items(comments, context.settings())?;
+
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!("{} - {}", site_view.site.name, post_view.post.name),
+    link: post_view.post.local_url(context.settings())?.to_string(),
+    items,
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
+/// The newest posts for a search term, so that a search can be followed in a feed reader.
+async fn get_feed_search(context: &LemmyContext, params: &SearchParams) -> LemmyResult<Channel> {
+  let site_view = SiteView::read_local(&mut context.pool()).await?;
+  check_private_instance(&None, &site_view.local_site)?;
+
+  let community_id = if let Some(community_name) = &params.community_name {
+    let community = Community::read_from_name(&mut context.pool(), community_name, false)
+      .await?
+      .ok_or(LemmyErrorType::NotFound)?;
+    if !community.visibility.can_view_without_login() {
+      return Err(LemmyErrorType::NotFound.into());
+    }
+    Some(community.id)
+  } else {
+    None
+  };
+
+  let posts = SearchCombinedQuery {
+    search_term: Some(params.q.clone()),
+    community_id,
+    type_: Some(SearchType::Posts),
+    sort: Some(SearchSortType::New),
+    limit: Some(params.limit.unwrap_or(RSS_FETCH_LIMIT)),
+    ..Default::default()
+  }
+  .list(&mut context.pool(), &None, &site_view.site)
+  .await?
+  .iter()
+  .filter_map(|f| f.to_post_view())
+  .cloned()
+  .collect::<Vec<PostView>>();
+
+  let items = create_post_items(posts, context.settings())?;
+
+  let link = Url::parse_with_params(
+    &format!("{}/search", context.settings().get_protocol_and_hostname()),
+    &[("q", params.q.as_str()), ("type", "Posts")],
+  )?;
+  let channel = Channel {
+    namespaces: RSS_NAMESPACE.clone(),
+    title: format!("{} - Search: {}", site_view.site.name, params.q),
+    link: link.to_string(),
+    items,
+    ..Default::default()
+  };
+
+  Ok(channel)
+}
+
 async fn get_feed_front(
   context: &LemmyContext,
   sort_type: &PostSortType,
@@ -644,6 +946,39 @@ fn create_modlog_items(
         &None,
         settings,
       ),
+      ModlogCombinedView::ModAutomodAction(v) => build_modlog_item(
+        &v.moderator,
+        &v.mod_automod_action.published_at,
+        &modlog_url,
+        &format!(
+          "Automod {} {} in /c/{}",
+          &v.mod_automod_action.action,
+          if v.comment.is_some() {
+            "comment"
+          } else {
+            "post"
+          },
+          &v.community.name
+        ),
+        &v.mod_automod_action.reason,
+        settings,
+      ),
+      ModlogCombinedView::ModFreezeCommunity(v) => build_modlog_item(
+        &v.moderator,
+        &v.mod_freeze_community.published_at,
+        &modlog_url,
+        &format!(
+          "{} /c/{}",
+          if v.mod_freeze_community.posts_frozen || v.mod_freeze_community.comments_frozen {
+            "Froze"
+          } else {
+            "Unfroze"
+          },
+          &v.community.name
+        ),
+        &v.mod_freeze_community.reason,
+        settings,
+      ),
     })
     .collect::<LemmyResult<Vec<Item>>>()?;
 
@@ -737,6 +1072,22 @@ fn build_item(
   })
 }
 
+fn create_comment_items(comments: Vec<CommentView>, settings: &Settings) -> LemmyResult<Vec<Item>> {
+  comments
+    .iter()
+    .map(|c| {
+      let comment_url = c.comment.local_url(settings)?;
+      build_item(
+        &c.creator,
+        &c.comment.published_at,
+        comment_url.as_str(),
+        &c.comment.content,
+        settings,
+      )
+    })
+    .collect()
+}
+
 fn create_post_items(posts: Vec<PostView>, settings: &Settings) -> LemmyResult<Vec<Item>> {
   let mut items: Vec<Item> = Vec::new();
 
@@ -0,0 +1,358 @@
+//! Lemmy acts as its own WebSub hub for public feeds, see https://www.w3.org/TR/websub/
+//!
+//! Subscribers send a request to the hub, which confirms their intent with a request to the
+//! callback url. Then they get the feed content pushed whenever new items appear in the feed,
+//! instead of having to poll it. Subscriptions expire after the lease, unless they are renewed.
+use super::{
+  format::{item_date, FeedFormat},
+  PublicFeed,
+};
+use actix_web::{error::ErrorBadRequest, web, Error, HttpResponse};
+use anyhow::anyhow;
+use chrono::{TimeDelta, Utc};
+use hmac::{Hmac, Mac};
+use lemmy_api_utils::{context::LemmyContext, notify::FEEDS_CHANGED, request::check_public_host};
+use lemmy_db_schema::source::websub_subscription::{WebsubSubscription, WebsubSubscriptionForm};
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  settings::structs::Settings,
+  spawn_try_task,
+};
+use rand::{distr::Alphanumeric, Rng};
+use reqwest::header::{CONTENT_TYPE, LINK};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use tracing::warn;
+use url::Url;
+
+/// Lease in seconds if the subscriber doesn't ask for one, and the limits for requested leases.
+const DEFAULT_LEASE_SECONDS: i64 = 10 * 24 * 60 * 60;
+const MIN_LEASE_SECONDS: i64 = 60 * 60;
+const MAX_LEASE_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// The spec limits secrets to less than 200 bytes.
+const MAX_SECRET_LENGTH: usize = 199;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum HubMode {
+  Subscribe,
+  Unsubscribe,
+}
+
+impl HubMode {
+  fn as_str(self) -> &'static str {
+    match self {
+      HubMode::Subscribe => "subscribe",
+      HubMode::Unsubscribe => "unsubscribe",
+    }
+  }
+}
+
+#[derive(Deserialize)]
+pub(super) struct HubRequest {
+  #[serde(rename = "hub.mode")]
+  mode: HubMode,
+  #[serde(rename = "hub.topic")]
+  topic: String,
+  #[serde(rename = "hub.callback")]
+  callback: Url,
+  #[serde(rename = "hub.lease_seconds")]
+  lease_seconds: Option<i64>,
+  #[serde(rename = "hub.secret")]
+  secret: Option<String>,
+}
+
+pub(super) fn hub_url(settings: &Settings) -> String {
+  format!("{}/feeds/hub", settings.get_protocol_and_hostname())
+}
+
+/// The `Link` header for WebSub discovery.
+pub(super) fn link_header(hub_url: &str, topic: &str) -> String {
+  format!(r#"<{hub_url}>; rel="hub", <{topic}>; rel="self""#)
+}
+
+/// Accepts a subscription or unsubscription request. The intent of the subscriber is verified
+/// afterwards, so the subscription only takes effect if the callback confirms it.
+pub(super) async fn handle_hub_request(
+  form: web::Form<HubRequest>,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  let form = form.into_inner();
+  if !matches!(form.callback.scheme(), "http" | "https") {
+    return Err(ErrorBadRequest(LemmyError::from(
+      LemmyErrorType::InvalidUrlScheme,
+    )));
+  }
+  check_public_host(&form.callback)
+    .await
+    .map_err(ErrorBadRequest)?;
+  if form
+    .secret
+    .as_ref()
+    .is_some_and(|s| s.len() > MAX_SECRET_LENGTH)
+  {
+    return Err(ErrorBadRequest(LemmyError::from(anyhow!(
+      "secret_too_long"
+    ))));
+  }
+  // Only public feeds of this instance can be subscribed. Anyone may unsubscribe from a feed
+  // which doesn't exist anymore.
+  let feed = PublicFeed::parse(&form.topic, context.settings()).map_err(ErrorBadRequest)?;
+  if form.mode == HubMode::Subscribe {
+    feed.channel(&context).await.map_err(ErrorBadRequest)?;
+  }
+
+  spawn_try_task(async move { verify_and_apply(&context, form, feed.key).await });
+  Ok(HttpResponse::Accepted().finish())
+}
+
+async fn verify_and_apply(
+  context: &LemmyContext,
+  form: HubRequest,
+  feed: String,
+) -> LemmyResult<()> {
+  let callback = form.callback.to_string();
+  match form.mode {
+    HubMode::Subscribe => {
+      let lease_seconds = form
+        .lease_seconds
+        .unwrap_or(DEFAULT_LEASE_SECONDS)
+        .clamp(MIN_LEASE_SECONDS, MAX_LEASE_SECONDS);
+      verify_intent(
+        context,
+        form.mode,
+        &form.topic,
+        &callback,
+        Some(lease_seconds),
+      )
+      .await?;
+      let form = WebsubSubscriptionForm {
+        topic: form.topic,
+        feed,
+        callback,
+        secret: form.secret.filter(|s| !s.is_empty()),
+        lease_expires_at: Utc::now() + TimeDelta::seconds(lease_seconds),
+      };
+      WebsubSubscription::upsert(&mut context.pool(), &form).await?;
+    }
+    HubMode::Unsubscribe => {
+      verify_intent(context, form.mode, &form.topic, &callback, None).await?;
+      WebsubSubscription::delete(&mut context.pool(), &form.topic, &callback).await?;
+    }
+  }
+  Ok(())
+}
+
+/// Sends a challenge to the callback, which has to echo it to confirm the request.
+async fn verify_intent(
+  context: &LemmyContext,
+  mode: HubMode,
+  topic: &str,
+  callback: &str,
+  lease_seconds: Option<i64>,
+) -> LemmyResult<()> {
+  let challenge: String = rand::rng()
+    .sample_iter(&Alphanumeric)
+    .take(32)
+    .map(char::from)
+    .collect();
+  let mut url = Url::parse(callback)?;
+  // The domain may resolve to another address by now
+  check_public_host(&url).await?;
+  url
+    .query_pairs_mut()
+    .append_pair("hub.mode", mode.as_str())
+    .append_pair("hub.topic", topic)
+    .append_pair("hub.challenge", &challenge);
+  if let Some(lease_seconds) = lease_seconds {
+    url
+      .query_pairs_mut()
+      .append_pair("hub.lease_seconds", &lease_seconds.to_string());
+  }
+
+  let res = context.client().get(url).send().await?.error_for_status()?;
+  if res.text().await? != challenge {
+    Err(anyhow!(
+      "WebSub subscriber at {callback} didn't confirm {topic}"
+    ))?;
+  }
+  Ok(())
+}
+
+/// Pushes feeds to their subscribers whenever new content is published in them. Subscriptions
+/// which were marked as changed before a restart are pushed right away.
+pub fn start_websub_pushes(context: LemmyContext) {
+  tokio::task::spawn(async move {
+    loop {
+      push_websub_updates(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to push WebSub updates: {e}"))
+        .ok();
+      FEEDS_CHANGED.notified().await;
+    }
+  });
+}
+
+/// Pushes feeds with new items to their subscribers. Only subscriptions whose feed changed since
+/// their last push are checked, and each of their feeds is built once.
+async fn push_websub_updates(context: &LemmyContext) -> LemmyResult<()> {
+  let checked_at = Utc::now();
+  let hub_url = hub_url(context.settings());
+  let mut topics: BTreeMap<String, Vec<WebsubSubscription>> = BTreeMap::new();
+  for subscription in WebsubSubscription::list_pending_push(&mut context.pool()).await? {
+    topics
+      .entry(subscription.topic.clone())
+      .or_default()
+      .push(subscription);
+  }
+
+  for (topic, subscriptions) in topics {
+    push_topic(context, &topic, &subscriptions, &hub_url)
+      .await
+      .inspect_err(|e| warn!("Failed to push WebSub topic {topic}: {e}"))
+      .ok();
+    // Also for failures, otherwise a feed which was deleted would be retried over and over
+    let ids = subscriptions.iter().map(|s| s.id).collect::<Vec<_>>();
+    WebsubSubscription::mark_pushed(&mut context.pool(), &ids, checked_at).await?;
+  }
+  Ok(())
+}
+
+async fn push_topic(
+  context: &LemmyContext,
+  topic: &str,
+  subscriptions: &[WebsubSubscription],
+  hub_url: &str,
+) -> LemmyResult<()> {
+  let feed = PublicFeed::parse(topic, context.settings())?;
+  let channel = feed.channel(context).await?;
+  let Some(newest) = channel.items.iter().filter_map(item_date).max() else {
+    return Ok(());
+  };
+  let body = feed.format.render(&channel, topic, Some(hub_url))?;
+
+  for subscription in subscriptions.iter().filter(|s| newest > s.last_pushed_at) {
+    push(context, subscription, feed.format, &body, hub_url)
+      .await
+      .inspect_err(|e| warn!("Failed to push to {}: {e}", subscription.callback))
+      .ok();
+  }
+  Ok(())
+}
+
+async fn push(
+  context: &LemmyContext,
+  subscription: &WebsubSubscription,
+  format: FeedFormat,
+  body: &str,
+  hub_url: &str,
+) -> LemmyResult<()> {
+  check_public_host(&Url::parse(&subscription.callback)?).await?;
+  let mut request = context
+    .client()
+    .post(&subscription.callback)
+    .header(CONTENT_TYPE, format.content_type())
+    .header(LINK, link_header(hub_url, &subscription.topic));
+  if let Some(secret) = &subscription.secret {
+    request = request.header("X-Hub-Signature", signature(secret, body)?);
+  }
+  request
+    .body(body.to_string())
+    .send()
+    .await?
+    .error_for_status()?;
+  Ok(())
+}
+
+/// Lets the subscriber verify that the content comes from the hub, see
+/// https://www.w3.org/TR/websub/#signing-content
+fn signature(secret: &str, body: &str) -> LemmyResult<String> {
+  let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+    .map_err(|e| anyhow!("Invalid WebSub secret: {e}"))?;
+  mac.update(body.as_bytes());
+  Ok(format!(
+    "sha256={}",
+    hex::encode(mac.finalize().into_bytes())
+  ))
+}
+
+/// Renews subscriptions which expire within a day by verifying the intent of the subscriber again,
+/// and deletes expired ones.
+pub(crate) async fn renew_websub_subscriptions(context: &LemmyContext) -> LemmyResult<()> {
+  let renew_before = Utc::now() + TimeDelta::days(1);
+  for subscription in WebsubSubscription::list_expiring(&mut context.pool(), renew_before).await? {
+    let renewed = verify_intent(
+      context,
+      HubMode::Subscribe,
+      &subscription.topic,
+      &subscription.callback,
+      Some(DEFAULT_LEASE_SECONDS),
+    )
+    .await;
+    if let Err(e) = renewed {
+      warn!(
+        "Failed to renew WebSub subscription of {}: {e}",
+        subscription.callback
+      );
+      continue;
+    }
+    let form = WebsubSubscriptionForm {
+      topic: subscription.topic,
+      feed: subscription.feed,
+      callback: subscription.callback,
+      secret: subscription.secret,
+      lease_expires_at: Utc::now() + TimeDelta::seconds(DEFAULT_LEASE_SECONDS),
+    };
+    WebsubSubscription::upsert(&mut context.pool(), &form).await?;
+  }
+  WebsubSubscription::delete_expired(&mut context.pool()).await?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_signature() -> LemmyResult<()> {
+    // Computed with `echo -n "hello" | openssl dgst -sha256 -hmac "secret"`
+    assert_eq!(
+      "sha256=88aab3ede8d3adf94d26ab90d3bafd4a2083070c3bcce9c014ee04a443847c0b",
+      signature("secret", "hello")?
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_hub_request() -> LemmyResult<()> {
+    let form = web::Query::<HubRequest>::from_query(
+      "hub.mode=subscribe&hub.topic=https%3A%2F%2Flemmy.tld%2Ffeeds%2Fall.xml&hub.callback=https%3A%2F%2Freader.tld%2Fcb",
+    )?;
+    assert_eq!(HubMode::Subscribe, form.mode);
+    assert_eq!("https://lemmy.tld/feeds/all.xml", form.topic);
+    assert_eq!(None, form.lease_seconds);
+    Ok(())
+  }
+
+  #[test]
+  fn test_public_feed() -> LemmyResult<()> {
+    let settings = Settings::default();
+    let feeds = format!("{}/feeds", settings.get_protocol_and_hostname());
+
+    let feed = PublicFeed::parse(&format!("{feeds}/c/News.atom?sort=New"), &settings)?;
+    assert_eq!(FeedFormat::Atom, feed.format);
+    assert_eq!("c/news", feed.key);
+    let feed = PublicFeed::parse(&format!("{feeds}/search.xml?q=lemmy"), &settings)?;
+    assert_eq!("search", feed.key);
+
+    // Private feeds, feeds of other instances and other urls can't be subscribed
+    assert!(PublicFeed::parse(&format!("{feeds}/inbox/token.xml"), &settings).is_err());
+    assert!(PublicFeed::parse("https://other.tld/feeds/all.xml", &settings).is_err());
+    assert!(PublicFeed::parse(&format!("{feeds}/c/news/extra.xml"), &settings).is_err());
+    assert!(PublicFeed::parse(&format!("{feeds}/hub.xml"), &settings).is_err());
+    Ok(())
+  }
+}
@@ -1,5 +1,5 @@
 use actix_web::web::*;
-use lemmy_api_utils::context::LemmyContext;
+use lemmy_api_utils::{context::LemmyContext, media::MediaStorage};
 use lemmy_db_views_site::api::SuccessResponse;
 use lemmy_utils::error::LemmyResult;
 
@@ -9,15 +9,7 @@ pub mod upload;
 mod utils;
 
 pub async fn pictrs_health(context: Data<LemmyContext>) -> LemmyResult<Json<SuccessResponse>> {
-  let pictrs_config = context.settings().pictrs()?;
-  let url = format!("{}healthz", pictrs_config.url);
-
-  context
-    .pictrs_client()
-    .get(url)
-    .send()
-    .await?
-    .error_for_status()?;
+  context.media().health(&context).await?;
 
   Ok(Json(SuccessResponse::default()))
 }
@@ -1,11 +1,19 @@
-use super::utils::{adapt_request, delete_old_image, make_send};
+use super::utils::delete_old_image;
 use actix_web::{self, web::*, HttpRequest};
 use lemmy_api_utils::{
   context::LemmyContext,
-  request::PictrsResponse,
+  media::{
+    image_hash::{check_image_hash, image_hash},
+    quota::{check_media_quota, media_quota},
+    video::{probe_video, process_uploaded_video, VideoProbe},
+    MediaStorage,
+    UploadType::{self, *},
+  },
+  request::PictrsFile,
   utils::{is_admin, is_mod_or_admin},
 };
 use lemmy_db_schema::{
+  newtypes::ImageHash,
   source::{
     community::{Community, CommunityUpdateForm},
     images::{LocalImage, LocalImageForm},
@@ -18,15 +26,6 @@ use lemmy_db_views_community::api::CommunityIdQuery;
 use lemmy_db_views_local_image::api::UploadImageResponse;
 use lemmy_db_views_local_user::LocalUserView;
 use lemmy_utils::error::{LemmyErrorType, LemmyResult};
-use reqwest::Body;
-use std::time::Duration;
-use UploadType::*;
-
-pub enum UploadType {
-  Avatar,
-  Banner,
-  Other,
-}
 
 pub async fn upload_image(
   req: HttpRequest,
@@ -172,71 +171,71 @@ pub async fn do_upload_image(
   local_user_view: &LocalUserView,
   context: &Data<LemmyContext>,
 ) -> LemmyResult<UploadImageResponse> {
-  let pictrs = context.settings().pictrs()?;
-  let max_upload_size = pictrs.max_upload_size.map(|m| m.to_string());
-  let image_url = format!("{}image", pictrs.url);
-
-  let mut client_req = adapt_request(&req, image_url, context);
-
-  // Set pictrs parameters to downscale images and restrict file types.
-  // https://git.asonix.dog/asonix/pict-rs/#api
-  client_req = match upload_type {
-    Avatar => {
-      let max_size = pictrs.max_avatar_size.to_string();
-      client_req.query(&[
-        ("resize", max_size.as_ref()),
-        ("allow_animation", "false"),
-        ("allow_video", "false"),
-      ])
-    }
-    Banner => {
-      let max_size = pictrs.max_banner_size.to_string();
-      client_req.query(&[
-        ("resize", max_size.as_ref()),
-        ("allow_animation", "false"),
-        ("allow_video", "false"),
-      ])
+  // Reject early if the quota is already used up
+  check_media_quota(local_user_view, 1, context).await?;
+
+  let mut images = context
+    .media()
+    .upload(&req, body, upload_type, context)
+    .await?;
+
+  // Check all files first, so that nothing is stored if any of them is rejected
+  let checked = match check_uploads(&images, context).await {
+    Ok(checked) => checked,
+    Err(e) => {
+      delete_uploads(&images, context).await?;
+      return Err(e);
     }
-    Other => {
-      let mut query = vec![("allow_video", pictrs.allow_video_uploads.to_string())];
-      if let Some(max_upload_size) = max_upload_size {
-        query.push(("resize", max_upload_size));
-      }
-      client_req.query(&query)
-    }
-  };
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string())
   };
-  let res = client_req
-    .timeout(Duration::from_secs(pictrs.upload_timeout))
-    .body(Body::wrap_stream(make_send(body)))
-    .send()
-    .await?
-    .error_for_status()?;
-
-  let mut images = res.json::<PictrsResponse>().await?;
-  for image in &images.files {
-    // Pictrs allows uploading multiple images in a single request. Lemmy doesnt need this,
-    // but still a user may upload multiple and so we need to store all links in db for
-    // to allow deletion via web ui.
+
+  // Pictrs allows uploading multiple images in a single request. Lemmy doesnt need this,
+  // but still a user may upload multiple and so we need to store all links in db for
+  // to allow deletion via web ui.
+  let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+  let mut forms = vec![];
+  for (image, checked) in images.iter().zip(&checked) {
     let form = LocalImageForm {
       pictrs_alias: image.file.to_string(),
       person_id: local_user_view.person.id,
       thumbnail_for_post_id: None,
+      phash: checked.phash,
+      file_size: Some(image.file_size),
     };
 
-    let protocol_and_hostname = context.settings().get_protocol_and_hostname();
-    let thumbnail_url = image.image_url(&protocol_and_hostname)?;
-
     // Also store the details for the image
+    let thumbnail_url = image.image_url(&protocol_and_hostname)?;
     let details_form = image.details.build_image_details_form(&thumbnail_url);
-    LocalImage::create(&mut context.pool(), &form, &details_form).await?;
+    forms.push((form, details_form));
+  }
+
+  // The quota is checked again while storing, in case of parallel uploads
+  let quota = media_quota(local_user_view, context).await?;
+  let stored = LocalImage::create_within_quota(
+    &mut context.pool(),
+    &forms,
+    local_user_view.person.id,
+    quota,
+  )
+  .await;
+  if let Err(e) = stored {
+    delete_uploads(&images, context).await?;
+    return Err(e);
+  }
+
+  for (image, checked) in images.iter().zip(checked) {
+    if let Some(probe) = checked.video {
+      let thumbnail_url = image.image_url(&protocol_and_hostname)?;
+      process_uploaded_video(
+        &image.file,
+        &thumbnail_url,
+        probe,
+        local_user_view.person.id,
+        context,
+      )
+      .await?;
+    }
   }
-  let image = images
-    .files
-    .pop()
-    .ok_or(LemmyErrorType::InvalidImageUpload)?;
+  let image = images.pop().ok_or(LemmyErrorType::InvalidImageUpload)?;
 
   let url = image.image_url(&context.settings().get_protocol_and_hostname())?;
   Ok(UploadImageResponse {
@@ -244,3 +243,44 @@ pub async fn do_upload_image(
     filename: image.file,
   })
 }
+
+struct CheckedUpload {
+  phash: Option<ImageHash>,
+  video: Option<VideoProbe>,
+}
+
+/// Checks the uploaded files against the image hash blocklist. Videos can't be hashed, instead
+/// they are probed if video processing is enabled.
+async fn check_uploads(
+  images: &[PictrsFile],
+  context: &LemmyContext,
+) -> LemmyResult<Vec<CheckedUpload>> {
+  let video_config = context.settings().pictrs()?.video;
+  let mut checked = vec![];
+  for image in images {
+    let (phash, video) = if image.details.content_type.starts_with("image/") {
+      let data = context.media().read(&image.file, context).await?;
+      (image_hash(data).await.ok(), None)
+    } else if let (true, Some(config)) = (
+      image.details.content_type.starts_with("video/"),
+      &video_config,
+    ) {
+      let data = context.media().read(&image.file, context).await?;
+      (None, Some(probe_video(&data, config).await?))
+    } else {
+      (None, None)
+    };
+    if let Some(phash) = phash {
+      check_image_hash(phash, context).await?;
+    }
+    checked.push(CheckedUpload { phash, video });
+  }
+  Ok(checked)
+}
+
+async fn delete_uploads(images: &[PictrsFile], context: &LemmyContext) -> LemmyResult<()> {
+  for image in images {
+    context.media().delete(&image.file, context).await?;
+  }
+  Ok(())
+}
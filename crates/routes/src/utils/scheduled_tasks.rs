@@ -1,4 +1,7 @@
-use crate::nodeinfo::{NodeInfo, NodeInfoWellKnown};
+use crate::{
+  feeds::websub::renew_websub_subscriptions,
+  nodeinfo::{NodeInfo, NodeInfoWellKnown},
+};
 use activitypub_federation::config::Data;
 use chrono::{DateTime, TimeZone, Utc};
 use clokwerk::{AsyncScheduler, TimeUnits as CTimeUnits};
@@ -16,15 +19,20 @@ use diesel::{
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use diesel_uplete::uplete;
 use lemmy_api_utils::{
+  automod::automod_check_post,
   context::LemmyContext,
+  federation_blocklist::refresh_blocklist_subscriptions,
+  notify::notify_feed_subscribers,
   send_activity::{ActivityChannel, SendActivityData},
   utils::send_webmention,
 };
 use lemmy_db_schema::{
+  newtypes::CommunityId,
   source::{
     community::Community,
     instance::{Instance, InstanceForm},
     local_user::LocalUser,
+    mod_log::moderator::{ModFreezeCommunity, ModFreezeCommunityForm},
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
@@ -36,6 +44,8 @@ use lemmy_db_schema_file::schema::{
   community,
   community_actions,
   federation_blocklist,
+  federation_queue_command,
+  federation_send_error,
   instance,
   instance_actions,
   local_site,
@@ -61,7 +71,8 @@ pub async fn setup(context: Data<LemmyContext>) -> LemmyResult<()> {
   let mut scheduler = AsyncScheduler::with_tz(Utc);
 
   let context_1 = context.clone();
-  // Every 10 minutes update hot ranks, delete expired captchas and publish scheduled posts
+  // Every 10 minutes update hot ranks, delete expired captchas, publish scheduled posts and lift
+  // expired post locks and community freezes
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
     let context = context_1.clone();
 
@@ -78,11 +89,20 @@ pub async fn setup(context: Data<LemmyContext>) -> LemmyResult<()> {
         .await
         .inspect_err(|e| warn!("Failed to publish scheduled posts: {e}"))
         .ok();
+      unlock_expired_posts(&mut context.pool())
+        .await
+        .inspect_err(|e| warn!("Failed to unlock expired posts: {e}"))
+        .ok();
+      unfreeze_expired_communities(&mut context.pool())
+        .await
+        .inspect_err(|e| warn!("Failed to unfreeze expired communities: {e}"))
+        .ok();
     }
   });
 
   let context_1 = context.clone();
-  // Update active counts expired bans and unpublished posts every hour
+  // Update active counts expired bans and unpublished posts, refresh blocklist subscriptions and
+  // renew WebSub subscriptions every hour
   scheduler.every(CTimeUnits::hour(1)).run(move || {
     let context = context_1.clone();
 
@@ -99,6 +119,14 @@ pub async fn setup(context: Data<LemmyContext>) -> LemmyResult<()> {
         .await
         .inspect_err(|e| warn!("Failed to delete expired instance bans: {e}"))
         .ok();
+      refresh_blocklist_subscriptions(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to refresh blocklist subscriptions: {e}"))
+        .ok();
+      renew_websub_subscriptions(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to renew WebSub subscriptions: {e}"))
+        .ok();
     }
   });
 
@@ -293,6 +321,21 @@ async fn clear_old_activities(pool: &mut DbPool<'_>) -> LemmyResult<()> {
   )
   .execute(conn)
   .await?;
+
+  diesel::delete(
+    federation_send_error::table
+      .filter(federation_send_error::published_at.lt(now() - IntervalDsl::days(7))),
+  )
+  .execute(conn)
+  .await?;
+
+  // Commands for instances which are paused or dead are never handled
+  diesel::delete(
+    federation_queue_command::table
+      .filter(federation_queue_command::published_at.lt(now() - IntervalDsl::days(7))),
+  )
+  .execute(conn)
+  .await?;
   info!("Done.");
   Ok(())
 }
@@ -422,6 +465,54 @@ async fn delete_instance_block_when_expired(pool: &mut DbPool<'_>) -> LemmyResul
   Ok(())
 }
 
+/// Lift post locks which have expired. Remote instances receive the expiry time together with the
+/// lock, so this doesn't need to be federated.
+async fn unlock_expired_posts(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  info!("Unlocking posts with expired locks ...");
+  let conn = &mut get_conn(pool).await?;
+
+  update(post::table.filter(post::lock_expires_at.lt(now().nullable())))
+    .set((
+      post::locked.eq(false),
+      post::lock_expires_at.eq(None::<DateTime<Utc>>),
+      post::lock_max_account_age_days.eq(None::<i32>),
+    ))
+    .execute(conn)
+    .await?;
+  Ok(())
+}
+
+/// Lift community freezes which have expired, with a modlog entry for each. Like post locks, the
+/// expiry is federated together with the freeze, so remote instances lift it on their own.
+async fn unfreeze_expired_communities(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  info!("Unfreezing communities with expired freezes ...");
+  let community_ids: Vec<CommunityId> = {
+    let conn = &mut get_conn(pool).await?;
+    update(community::table.filter(community::freeze_expires_at.lt(now().nullable())))
+      .set((
+        community::posts_frozen.eq(false),
+        community::comments_frozen.eq(false),
+        community::freeze_expires_at.eq(None::<DateTime<Utc>>),
+      ))
+      .returning(community::id)
+      .get_results(conn)
+      .await?
+  };
+
+  for community_id in community_ids {
+    let form = ModFreezeCommunityForm {
+      mod_person_id: None,
+      community_id,
+      posts_frozen: false,
+      comments_frozen: false,
+      expires_at: None,
+      reason: None,
+    };
+    ModFreezeCommunity::create(pool, &form).await?;
+  }
+  Ok(())
+}
+
 /// Find all unpublished posts with scheduled date in the future, and publish them.
 async fn publish_scheduled_posts(context: &Data<LemmyContext>) -> LemmyResult<()> {
   let pool = &mut context.pool();
@@ -462,10 +553,23 @@ async fn publish_scheduled_posts(context: &Data<LemmyContext>) -> LemmyResult<()
     };
     Post::update(&mut context.pool(), post.id, &form).await?;
 
-    // send out post via federation and webmention
+    // Posts which are held for approval are sent out once a moderator approves them, and posts
+    // removed by automod aren't sent at all
+    let automod = automod_check_post(&post, context).await?;
+    if !automod.is_visible() {
+      continue;
+    }
+
+    // send out post via federation, WebSub and webmention
+    let post = Post {
+      nsfw: post.nsfw || automod.nsfw,
+      ..post
+    };
     let send_activity = SendActivityData::CreatePost(post.clone());
     ActivityChannel::submit_activity(send_activity, context)?;
+    notify_feed_subscribers(&post, false, context).await?;
     send_webmention(post, &community);
+    automod.send_replies(context)?;
   }
   Ok(())
 }
@@ -563,8 +667,16 @@ async fn build_update_instance_form(
 mod tests {
 
   use super::*;
+  use chrono::Days;
   use lemmy_api_utils::request::client_builder;
-  use lemmy_db_schema::test_data::TestData;
+  use lemmy_db_schema::{
+    source::{
+      community::CommunityInsertForm,
+      person::{Person, PersonInsertForm},
+      post::PostInsertForm,
+    },
+    test_data::TestData,
+  };
   use lemmy_utils::{
     error::{LemmyErrorType, LemmyResult},
     settings::structs::Settings,
@@ -609,7 +721,58 @@ mod tests {
     update_instance_software(&mut context.pool(), context.client()).await?;
     delete_expired_captcha_answers(&mut context.pool()).await?;
     publish_scheduled_posts(&context).await?;
+    unlock_expired_posts(&mut context.pool()).await?;
+    unfreeze_expired_communities(&mut context.pool()).await?;
     data.delete(&mut context.pool()).await?;
     Ok(())
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_unlock_expired_posts() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+    let data = TestData::create(pool).await?;
+
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(data.instance.id, "locker"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::new(
+      data.instance.id,
+      "test community unlock".to_string(),
+      "nada".to_owned(),
+      "pubkey".to_string(),
+    );
+    let community = Community::create(pool, &community_form).await?;
+
+    let lock_form = |expires_at| PostUpdateForm {
+      locked: Some(true),
+      lock_expires_at: Some(Some(expires_at)),
+      lock_max_account_age_days: Some(Some(7)),
+      ..Default::default()
+    };
+    let post_form = PostInsertForm::new("expired".into(), person.id, community.id);
+    let expired_post = Post::create(pool, &post_form).await?;
+    Post::update(pool, expired_post.id, &lock_form(Utc::now() - Days::new(1))).await?;
+    let post_form = PostInsertForm::new("active".into(), person.id, community.id);
+    let active_post = Post::create(pool, &post_form).await?;
+    Post::update(pool, active_post.id, &lock_form(Utc::now() + Days::new(1))).await?;
+
+    unlock_expired_posts(pool).await?;
+
+    // The expired lock is lifted together with its settings
+    let expired_post = Post::read(pool, expired_post.id).await?;
+    assert!(!expired_post.locked);
+    assert_eq!(None, expired_post.lock_expires_at);
+    assert_eq!(None, expired_post.lock_max_account_age_days);
+
+    let active_post = Post::read(pool, active_post.id).await?;
+    assert!(active_post.locked);
+    assert_eq!(Some(7), active_post.lock_max_account_age_days);
+
+    data.delete(pool).await?;
+    Ok(())
+  }
 }
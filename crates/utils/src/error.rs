@@ -20,6 +20,7 @@ pub enum LemmyErrorType {
   NotAnAdmin,
   CantBlockYourself,
   CantNoteYourself,
+  CantFollowYourself,
   CantBlockAdmin,
   PasswordsDoNotMatch,
   EmailNotVerified,
@@ -133,6 +134,22 @@ pub enum LemmyErrorType {
   MultiCommunityUpdateWrongUser,
   CannotCombineCommunityIdAndMultiCommunityId,
   MultiCommunityEntryLimitReached,
+  SlowModeActive,
+  CommunityFrozen,
+  InvalidActivityRange,
+  CommunityMoved,
+  CrossPostingRestricted,
+  ImageBlocked,
+  InvalidImageHash,
+  InvalidImageHashBlockThreshold,
+  InvalidGalleryImage,
+  GalleryPostCantHaveUrl,
+  MediaQuotaExceeded,
+  InvalidMediaQuota,
+  InvalidVideo,
+  VideoTooLong,
+  PostIsNotQuestion,
+  OnlyTopLevelCommentCanBeAnswer,
 }
 
 /// Federation related errors, these dont need to be translated.
@@ -162,6 +179,8 @@ pub enum FederationError {
   ObjectIsNotPrivate,
   InvalidFollow(String),
   Unreachable,
+  ActivitySignedByOtherActor,
+  InvalidCommunityMove,
 }
 
 cfg_if! {
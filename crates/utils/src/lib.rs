@@ -42,6 +42,16 @@ pub const CACHE_DURATION_API: Duration = Duration::from_secs(0);
 #[cfg(not(debug_assertions))]
 pub const CACHE_DURATION_API: Duration = Duration::from_secs(1);
 
+#[cfg(debug_assertions)]
+pub const CACHE_DURATION_FEED: Duration = Duration::from_secs(0);
+#[cfg(not(debug_assertions))]
+pub const CACHE_DURATION_FEED: Duration = Duration::from_secs(5 * 60);
+
+#[cfg(debug_assertions)]
+pub const CACHE_DURATION_SITEMAP: Duration = Duration::from_secs(0);
+#[cfg(not(debug_assertions))]
+pub const CACHE_DURATION_SITEMAP: Duration = Duration::from_secs(60 * 60);
+
 #[cfg(debug_assertions)]
 pub const CACHE_DURATION_LARGEST_COMMUNITY: Duration = Duration::from_secs(0);
 #[cfg(not(debug_assertions))]
@@ -52,6 +62,10 @@ pub const MAX_COMMENT_DEPTH_LIMIT: usize = 50;
 /// Doing DB transactions of bigger batches than this tend to cause seq scans.
 pub const DB_BATCH_SIZE: i64 = 1000;
 
+/// Activities which are signed with the previous key of an actor are accepted for this long after
+/// the key was rotated, as they may have been queued before the rotation.
+pub const KEY_ROTATION_GRACE_PERIOD: Duration = DAY;
+
 #[macro_export]
 macro_rules! location_info {
   () => {
@@ -116,6 +116,77 @@ pub struct PictrsConfig {
   /// banners can still be uploaded.
   #[default(false)]
   pub image_upload_disabled: bool,
+
+  /// Where uploads and thumbnails are stored. With `Pictrs` all media is handled by pict-rs at
+  /// `url`. Otherwise Lemmy stores media itself, and takes care of resizing images and stripping
+  /// their metadata. In that case videos can only be uploaded if `video` is configured.
+  #[default(StorageBackend::Pictrs)]
+  pub storage: StorageBackend,
+
+  /// Directory for media with the `Filesystem` storage.
+  #[doku(example = "/var/lib/lemmy/media")]
+  pub storage_path: Option<String>,
+
+  /// Bucket for media with the `S3` storage.
+  #[doku(example = "Some(Default::default())")]
+  pub s3: Option<S3Config>,
+
+  /// Process uploaded videos with ffmpeg. They are transcoded to H.264 in several resolutions,
+  /// and a poster frame is extracted for the post thumbnail.
+  #[doku(example = "Some(Default::default())")]
+  pub video: Option<VideoConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct VideoConfig {
+  #[default("ffmpeg")]
+  #[doku(example = "ffmpeg")]
+  pub ffmpeg_path: String,
+  #[default("ffprobe")]
+  #[doku(example = "ffprobe")]
+  pub ffprobe_path: String,
+  /// Videos are transcoded to each of these heights which is not larger than the original.
+  #[default(vec![1080, 720, 480])]
+  #[doku(example = "720")]
+  pub resolutions: Vec<u32>,
+  /// Longer videos are rejected (in seconds).
+  #[default(600)]
+  pub max_duration: u32,
+  /// How many ffmpeg processes may run at the same time. Each of them can use all CPU cores.
+  #[default(1)]
+  pub max_concurrent_jobs: u8,
+  /// Maximum run time of a single ffmpeg process (in seconds). It is killed afterwards.
+  #[default(600)]
+  pub timeout: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, Document, PartialEq)]
+pub enum StorageBackend {
+  /// Send all media to pict-rs
+  #[default]
+  Pictrs,
+  /// Store media in the local directory `storage_path`
+  Filesystem,
+  /// Store media in the S3-compatible bucket configured in `s3`
+  S3,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct S3Config {
+  /// Endpoint of a S3-compatible service like Garage or MinIO. Leave out for AWS.
+  #[doku(example = "http://localhost:3900")]
+  pub endpoint: Option<String>,
+  #[doku(example = "lemmy-media")]
+  pub bucket: String,
+  #[default("us-east-1")]
+  #[doku(example = "us-east-1")]
+  pub region: String,
+  #[doku(example = "GK31c2f218a2e44f485b94239e")]
+  pub access_key_id: String,
+  #[doku(example = "b892c0665f0ada8a4755dae98baa3b133590e11dae3bcc1f9d769d67f16c3835")]
+  pub secret_access_key: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default, Document, PartialEq)]
@@ -211,4 +282,8 @@ pub struct FederationWorkerConfig {
   /// per second) and if a receiving instance is not keeping up.
   #[default(1)]
   pub concurrent_sends_per_instance: i8,
+  /// Number of background workers in this process which handle incoming activities. Workers of
+  /// multiple processes can run at the same time. With 0, incoming activities are only queued.
+  #[default(4)]
+  pub incoming_workers: u8,
 }
@@ -0,0 +1,8 @@
+ALTER TABLE post_report
+    DROP COLUMN severity;
+
+ALTER TABLE comment_report
+    DROP COLUMN severity;
+
+DROP TYPE report_severity_enum;
+
@@ -0,0 +1,14 @@
+-- Reporters can mark how urgently a report needs to be handled, which is used to order report
+-- cases
+CREATE TYPE report_severity_enum AS enum (
+    'Low',
+    'Medium',
+    'High'
+);
+
+ALTER TABLE post_report
+    ADD COLUMN severity report_severity_enum NOT NULL DEFAULT 'Medium';
+
+ALTER TABLE comment_report
+    ADD COLUMN severity report_severity_enum NOT NULL DEFAULT 'Medium';
+
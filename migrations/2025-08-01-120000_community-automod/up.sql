@@ -0,0 +1,69 @@
+-- Declarative automod rules, configured by community moderators
+CREATE TYPE automod_action_enum AS enum (
+    'Remove',
+    'Hold',
+    'Report',
+    'MarkNsfw',
+    'AddTag',
+    'Reply'
+);
+
+CREATE TABLE automod_rule (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    creator_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name varchar(255) NOT NULL,
+    enabled boolean NOT NULL DEFAULT TRUE,
+    apply_to_posts boolean NOT NULL DEFAULT TRUE,
+    apply_to_comments boolean NOT NULL DEFAULT TRUE,
+    title_regex text,
+    body_regex text,
+    url_domain_regex text,
+    instance_domain_regex text,
+    max_account_age_days int,
+    max_karma int,
+    language_id int REFERENCES LANGUAGE ON UPDATE CASCADE ON DELETE CASCADE,
+    action automod_action_enum NOT NULL,
+    action_message text,
+    tag_id int REFERENCES tag ON UPDATE CASCADE ON DELETE CASCADE,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz
+);
+
+CREATE INDEX idx_automod_rule_community ON automod_rule (community_id);
+
+CREATE INDEX idx_automod_rule_creator ON automod_rule (creator_id);
+
+CREATE INDEX idx_automod_rule_language ON automod_rule (language_id);
+
+CREATE INDEX idx_automod_rule_tag ON automod_rule (tag_id);
+
+-- Modlog entries for actions taken by automod rules. The mod_person_id is the creator of the rule,
+-- and for comments post_id is the post of the comment.
+CREATE TABLE mod_automod_action (
+    id serial PRIMARY KEY,
+    mod_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    automod_rule_id int REFERENCES automod_rule ON UPDATE CASCADE ON DELETE SET NULL,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    comment_id int REFERENCES comment ON UPDATE CASCADE ON DELETE CASCADE,
+    action automod_action_enum NOT NULL,
+    reason text,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_mod_automod_action_mod ON mod_automod_action (mod_person_id);
+
+CREATE INDEX idx_mod_automod_action_rule ON mod_automod_action (automod_rule_id);
+
+CREATE INDEX idx_mod_automod_action_community ON mod_automod_action (community_id);
+
+CREATE INDEX idx_mod_automod_action_post ON mod_automod_action (post_id);
+
+CREATE INDEX idx_mod_automod_action_comment ON mod_automod_action (comment_id);
+
+ALTER TABLE modlog_combined
+    ADD COLUMN mod_automod_action_id int UNIQUE REFERENCES mod_automod_action ON UPDATE CASCADE ON DELETE CASCADE,
+    DROP CONSTRAINT modlog_combined_check,
+    ADD CONSTRAINT modlog_combined_check CHECK ((num_nonnulls (admin_allow_instance_id, admin_block_instance_id, admin_purge_comment_id, admin_purge_community_id, admin_purge_person_id, admin_purge_post_id, admin_add_id, mod_add_to_community_id, admin_ban_id, mod_ban_from_community_id, mod_feature_post_id, mod_change_community_visibility_id, mod_lock_post_id, mod_remove_comment_id, admin_remove_community_id, mod_remove_post_id, mod_transfer_community_id, mod_automod_action_id) = 1));
+
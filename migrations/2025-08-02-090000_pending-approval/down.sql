@@ -0,0 +1,11 @@
+ALTER TABLE community
+    DROP COLUMN posts_require_approval,
+    DROP COLUMN comments_require_approval,
+    DROP COLUMN approval_max_account_age_days;
+
+ALTER TABLE post
+    DROP COLUMN pending_approval;
+
+ALTER TABLE comment
+    DROP COLUMN pending_approval;
+
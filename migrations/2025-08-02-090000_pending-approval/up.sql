@@ -0,0 +1,21 @@
+-- Allows communities to hold new posts and comments for review by a moderator, before they
+-- become visible and get federated.
+ALTER TABLE community
+    ADD COLUMN posts_require_approval boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN comments_require_approval boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN approval_max_account_age_days integer;
+
+ALTER TABLE post
+    ADD COLUMN pending_approval boolean NOT NULL DEFAULT FALSE;
+
+ALTER TABLE comment
+    ADD COLUMN pending_approval boolean NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_post_pending_approval ON post (community_id)
+WHERE
+    pending_approval;
+
+CREATE INDEX idx_comment_pending_approval ON comment (post_id)
+WHERE
+    pending_approval;
+
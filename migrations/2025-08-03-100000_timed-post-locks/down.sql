@@ -0,0 +1,12 @@
+ALTER TABLE post
+    DROP COLUMN lock_expires_at,
+    DROP COLUMN lock_max_account_age_days,
+    DROP COLUMN comment_slow_mode_minutes;
+
+ALTER TABLE community
+    DROP COLUMN comment_slow_mode_minutes;
+
+ALTER TABLE mod_lock_post
+    DROP COLUMN expires_at,
+    DROP COLUMN max_account_age_days;
+
@@ -0,0 +1,17 @@
+-- Post locks which expire, which only apply to new accounts, and slow mode for comments.
+ALTER TABLE post
+    ADD COLUMN lock_expires_at timestamptz,
+    ADD COLUMN lock_max_account_age_days integer,
+    ADD COLUMN comment_slow_mode_minutes integer;
+
+ALTER TABLE community
+    ADD COLUMN comment_slow_mode_minutes integer;
+
+ALTER TABLE mod_lock_post
+    ADD COLUMN expires_at timestamptz,
+    ADD COLUMN max_account_age_days integer;
+
+CREATE INDEX idx_post_lock_expires_at ON post (lock_expires_at)
+WHERE
+    lock_expires_at IS NOT NULL;
+
@@ -0,0 +1,31 @@
+-- Temporarily freeze posting and/or commenting in a community, for example during raids.
+ALTER TABLE community
+    ADD COLUMN posts_frozen boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN comments_frozen boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN freeze_expires_at timestamptz;
+
+CREATE INDEX idx_community_freeze_expires_at ON community (freeze_expires_at)
+WHERE
+    freeze_expires_at IS NOT NULL;
+
+-- Modlog entries for applying and lifting freezes. Freezes which are lifted automatically once
+-- they expire have no mod_person_id.
+CREATE TABLE mod_freeze_community (
+    id serial PRIMARY KEY,
+    mod_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    posts_frozen boolean NOT NULL,
+    comments_frozen boolean NOT NULL,
+    expires_at timestamptz,
+    reason text,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_mod_freeze_community_mod ON mod_freeze_community (mod_person_id);
+
+CREATE INDEX idx_mod_freeze_community_community ON mod_freeze_community (community_id);
+
+ALTER TABLE modlog_combined
+    ADD COLUMN mod_freeze_community_id int UNIQUE REFERENCES mod_freeze_community ON UPDATE CASCADE ON DELETE CASCADE,
+    DROP CONSTRAINT modlog_combined_check,
+    ADD CONSTRAINT modlog_combined_check CHECK ((num_nonnulls (admin_allow_instance_id, admin_block_instance_id, admin_purge_comment_id, admin_purge_community_id, admin_purge_person_id, admin_purge_post_id, admin_add_id, mod_add_to_community_id, admin_ban_id, mod_ban_from_community_id, mod_feature_post_id, mod_change_community_visibility_id, mod_lock_post_id, mod_remove_comment_id, admin_remove_community_id, mod_remove_post_id, mod_transfer_community_id, mod_automod_action_id, mod_freeze_community_id) = 1));
@@ -0,0 +1,24 @@
+-- Incoming activities are stored here after their signature is verified, and processed by
+-- background workers. Entries which fail too often are kept as dead letters with failed_at set.
+-- While a worker processes an entry, claimed_until is set so that no other worker processes
+-- activities of the same actor at the same time.
+CREATE TABLE inbound_activity (
+    id serial PRIMARY KEY,
+    ap_id text UNIQUE NOT NULL,
+    actor_apub_id text NOT NULL,
+    data text NOT NULL,
+    attempts int NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    last_error text,
+    failed_at timestamptz,
+    claimed_until timestamptz,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_inbound_activity_next_attempt ON inbound_activity (next_attempt_at)
+WHERE
+    failed_at IS NULL;
+
+CREATE INDEX idx_inbound_activity_failed ON inbound_activity (failed_at)
+WHERE
+    failed_at IS NOT NULL;
@@ -0,0 +1,9 @@
+DROP TABLE federation_send_error;
+
+DROP TABLE federation_queue_command;
+
+DROP TYPE federation_queue_command_enum;
+
+ALTER TABLE federation_queue_state
+    DROP COLUMN paused;
+
@@ -0,0 +1,35 @@
+-- Admins can pause sending activities to an instance
+ALTER TABLE federation_queue_state
+    ADD COLUMN paused boolean NOT NULL DEFAULT FALSE;
+
+CREATE TYPE federation_queue_command_enum AS enum (
+    'RetryNow',
+    'Skip',
+    'Resend'
+);
+
+-- Admin commands for the federation worker of an instance. They are deleted once the worker
+-- handles them.
+CREATE TABLE federation_queue_command (
+    id serial PRIMARY KEY,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    command federation_queue_command_enum NOT NULL,
+    first_activity_id bigint,
+    last_activity_id bigint,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_federation_queue_command_instance ON federation_queue_command (instance_id);
+
+-- Recent errors when sending activities to other instances
+CREATE TABLE federation_send_error (
+    id serial PRIMARY KEY,
+    instance_id int REFERENCES instance ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    activity_id bigint NOT NULL,
+    http_status int,
+    error text NOT NULL,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_federation_send_error_instance_published ON federation_send_error (instance_id, published_at DESC);
+
@@ -0,0 +1,12 @@
+-- Notification settings for new posts and comments of followed persons
+CREATE TYPE person_notifications_mode_enum AS enum (
+    'AllPostsAndComments',
+    'AllPosts',
+    'Disabled'
+);
+
+ALTER TABLE person_actions
+    ADD COLUMN notifications person_notifications_mode_enum;
+
+ALTER TYPE listing_type_enum
+    ADD VALUE 'FollowedPersons';
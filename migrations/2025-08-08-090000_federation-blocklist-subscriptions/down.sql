@@ -0,0 +1,11 @@
+DROP TABLE federation_blocklist_pending;
+
+ALTER TABLE federation_blocklist
+    DROP COLUMN severity,
+    DROP COLUMN public_reason,
+    DROP COLUMN private_comment,
+    DROP COLUMN subscription_id;
+
+DROP TABLE federation_blocklist_subscription;
+
+DROP TYPE federation_block_severity_enum;
@@ -0,0 +1,34 @@
+-- Silenced instances still federate, but their content is hidden from the `All` listing.
+-- Suspended instances are blocked from federation entirely.
+CREATE TYPE federation_block_severity_enum AS enum (
+    'Silence',
+    'Suspend'
+);
+
+-- Remote blocklists in Mastodon's domain block CSV format, which are refreshed regularly
+CREATE TABLE federation_blocklist_subscription (
+    id serial PRIMARY KEY,
+    url text NOT NULL UNIQUE,
+    last_refreshed_at timestamptz,
+    last_error text,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+ALTER TABLE federation_blocklist
+    ADD COLUMN severity federation_block_severity_enum NOT NULL DEFAULT 'Suspend',
+    ADD COLUMN public_reason text,
+    ADD COLUMN private_comment text,
+    ADD COLUMN subscription_id int REFERENCES federation_blocklist_subscription ON UPDATE CASCADE ON DELETE SET NULL;
+
+-- Domains which were newly added to a subscribed blocklist, and are waiting for an admin to
+-- review and apply them. Discarded domains are kept so that they aren't suggested again.
+CREATE TABLE federation_blocklist_pending (
+    id serial PRIMARY KEY,
+    subscription_id int REFERENCES federation_blocklist_subscription ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    domain text NOT NULL,
+    severity federation_block_severity_enum NOT NULL,
+    public_reason text,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    discarded_at timestamptz,
+    UNIQUE (subscription_id, domain)
+);
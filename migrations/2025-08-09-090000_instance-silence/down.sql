@@ -0,0 +1,3 @@
+ALTER TABLE admin_block_instance
+    DROP COLUMN severity;
+
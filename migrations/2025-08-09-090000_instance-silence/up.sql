@@ -0,0 +1,4 @@
+-- Null if the instance was unblocked
+ALTER TABLE admin_block_instance
+    ADD COLUMN severity federation_block_severity_enum;
+
@@ -0,0 +1,23 @@
+-- ActivityPub relays which the local site actor follows, to discover content without users
+-- following communities first.
+CREATE TABLE federation_relay (
+    id serial PRIMARY KEY,
+    -- Either the relay actor (LitePub style) or the relay inbox (Mastodon style)
+    url text NOT NULL UNIQUE,
+    -- Only known once the relay actor was fetched, which for Mastodon style relays happens when
+    -- it accepts the follow.
+    ap_id text UNIQUE,
+    inbox_url text,
+    public_key text,
+    follow_pending boolean NOT NULL DEFAULT TRUE,
+    -- Send activities of local public communities to the relay
+    publish boolean NOT NULL DEFAULT FALSE,
+    -- Id of the last Follow which was sent. Mastodon style relays are subscribed by their inbox, so
+    -- the relay actor is only known from the Accept, which refers to the Follow.
+    follow_id text,
+    -- How many posts and comments forwarded by the relay are fetched and stored per hour
+    max_objects_per_hour integer NOT NULL DEFAULT 100 CHECK (max_objects_per_hour >= 0),
+    published_at timestamptz NOT NULL DEFAULT now(),
+    updated_at timestamptz
+);
+
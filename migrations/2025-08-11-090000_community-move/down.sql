@@ -0,0 +1,4 @@
+ALTER TABLE community
+    DROP COLUMN moved_to_url,
+    DROP COLUMN moved_from_url;
+
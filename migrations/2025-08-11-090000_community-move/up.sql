@@ -0,0 +1,6 @@
+-- Communities can move to another instance. The old community points to its successor, and the
+-- new community to its predecessor (`alsoKnownAs`), so that the move can be verified.
+ALTER TABLE community
+    ADD COLUMN moved_to_url varchar(255),
+    ADD COLUMN moved_from_url varchar(255);
+
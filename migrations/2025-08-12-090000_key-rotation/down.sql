@@ -0,0 +1,14 @@
+DROP TABLE actor_key_rotation;
+
+ALTER TABLE person
+    DROP COLUMN previous_public_key,
+    DROP COLUMN key_rotated_at;
+
+ALTER TABLE community
+    DROP COLUMN previous_public_key,
+    DROP COLUMN key_rotated_at;
+
+ALTER TABLE site
+    DROP COLUMN previous_public_key,
+    DROP COLUMN key_rotated_at;
+
@@ -0,0 +1,25 @@
+-- Local actors can rotate their signing keys. The previous public key is kept, so that activities
+-- which were signed shortly before the rotation can still be verified.
+ALTER TABLE person
+    ADD COLUMN previous_public_key text,
+    ADD COLUMN key_rotated_at timestamptz;
+
+ALTER TABLE community
+    ADD COLUMN previous_public_key text,
+    ADD COLUMN key_rotated_at timestamptz;
+
+ALTER TABLE site
+    ADD COLUMN previous_public_key text,
+    ADD COLUMN key_rotated_at timestamptz;
+
+-- Audit log of key rotations. The admin is null if the rotation was started from the command line.
+CREATE TABLE actor_key_rotation (
+    id serial PRIMARY KEY,
+    actor_type actor_type_enum NOT NULL,
+    actor_ap_id varchar(255) NOT NULL,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_actor_key_rotation_published ON actor_key_rotation (published_at DESC);
+
@@ -0,0 +1,6 @@
+ALTER TABLE post
+    DROP COLUMN cross_post_of_id;
+
+ALTER TABLE community
+    DROP COLUMN cross_posting_restricted_to_mods;
+
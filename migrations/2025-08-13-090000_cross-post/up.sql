@@ -0,0 +1,12 @@
+-- Posts can be cross-posted explicitly. Every cross-post points to the original post, so that all
+-- cross-posts of a post can be listed, even if they don't have the same url.
+ALTER TABLE post
+    ADD COLUMN cross_post_of_id int REFERENCES post ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_post_cross_post_of_id ON post (cross_post_of_id)
+WHERE
+    cross_post_of_id IS NOT NULL;
+
+ALTER TABLE community
+    ADD COLUMN cross_posting_restricted_to_mods boolean NOT NULL DEFAULT FALSE;
+
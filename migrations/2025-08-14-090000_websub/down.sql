@@ -0,0 +1,2 @@
+DROP TABLE websub_subscription;
+
@@ -0,0 +1,20 @@
+-- Subscriptions to public feeds with WebSub, see https://www.w3.org/TR/websub/
+CREATE TABLE websub_subscription (
+    id serial PRIMARY KEY,
+    topic text NOT NULL,
+    -- The feed of the topic, independent of its format and parameters, for example `c/news`
+    feed text NOT NULL,
+    callback text NOT NULL,
+    secret text,
+    lease_expires_at timestamptz NOT NULL,
+    -- Feed items up to this time were delivered to the subscriber
+    last_pushed_at timestamptz NOT NULL DEFAULT now(),
+    -- When content was last published in the feed
+    changed_at timestamptz,
+    published_at timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (topic, callback)
+);
+
+CREATE INDEX idx_websub_subscription_lease_expires_at ON websub_subscription (lease_expires_at);
+
+CREATE INDEX idx_websub_subscription_feed ON websub_subscription (feed);
@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    ADD COLUMN sitemap_exclude_nsfw boolean NOT NULL DEFAULT FALSE;
+
@@ -0,0 +1,8 @@
+DROP TABLE image_hash_block;
+
+ALTER TABLE local_site
+    DROP COLUMN image_hash_block_threshold;
+
+ALTER TABLE local_image
+    DROP COLUMN phash;
+
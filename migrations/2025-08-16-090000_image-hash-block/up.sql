@@ -0,0 +1,17 @@
+-- Perceptual hashes of uploads and stored thumbnails
+ALTER TABLE local_image
+    ADD COLUMN phash bigint;
+
+-- Uploads and remote thumbnails whose hash differs from a blocked hash in at most this many bits are
+-- rejected
+ALTER TABLE local_site
+    ADD COLUMN image_hash_block_threshold int NOT NULL DEFAULT 6;
+
+CREATE TABLE image_hash_block (
+    id serial PRIMARY KEY,
+    hash bigint NOT NULL UNIQUE,
+    reason text,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE SET NULL,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,11 @@
+-- Ordered images of gallery posts
+CREATE TABLE post_gallery_item (
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    position int NOT NULL,
+    url text NOT NULL,
+    url_content_type text,
+    alt_text text,
+    caption text,
+    PRIMARY KEY (post_id, position)
+);
+
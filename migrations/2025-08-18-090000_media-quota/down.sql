@@ -0,0 +1,10 @@
+ALTER TABLE local_user
+    DROP COLUMN media_quota;
+
+ALTER TABLE local_site
+    DROP COLUMN media_quota_user,
+    DROP COLUMN media_quota_moderator;
+
+ALTER TABLE local_image
+    DROP COLUMN file_size;
+
@@ -0,0 +1,13 @@
+-- Size of uploads in bytes, unknown for files which were uploaded before this was stored
+ALTER TABLE local_image
+    ADD COLUMN file_size bigint;
+
+-- Upload quotas in bytes, null means unlimited. Admins have no quota.
+ALTER TABLE local_site
+    ADD COLUMN media_quota_user bigint,
+    ADD COLUMN media_quota_moderator bigint;
+
+-- Overrides the quota of the user's class
+ALTER TABLE local_user
+    ADD COLUMN media_quota bigint;
+
@@ -0,0 +1,6 @@
+DROP TABLE video_transcode_job;
+
+DROP TABLE video_variant;
+
+DROP TABLE video_details;
+
@@ -0,0 +1,35 @@
+-- Metadata of local and remote videos, keyed by url like image_details. Dimensions and duration
+-- may be unknown for remote videos.
+CREATE TABLE video_details (
+    link text PRIMARY KEY,
+    width int,
+    height int,
+    duration_ms int,
+    poster_url text,
+    -- Set once all variants of a local upload were transcoded
+    transcoded_at timestamptz,
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+-- Transcoded versions of a video in different resolutions
+CREATE TABLE video_variant (
+    link text NOT NULL REFERENCES video_details ON UPDATE CASCADE ON DELETE CASCADE,
+    height int NOT NULL,
+    url text NOT NULL,
+    content_type text NOT NULL,
+    PRIMARY KEY (link, height)
+);
+
+-- Local uploads which are waiting to be transcoded. Workers claim a job by postponing
+-- next_attempt_at, so that it is picked up again if the server stops while transcoding.
+CREATE TABLE video_transcode_job (
+    link text PRIMARY KEY REFERENCES video_details ON UPDATE CASCADE ON DELETE CASCADE,
+    alias text NOT NULL,
+    person_id int NOT NULL REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE,
+    attempts int NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz NOT NULL DEFAULT now(),
+    published_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_video_transcode_job_next_attempt ON video_transcode_job (next_attempt_at);
+
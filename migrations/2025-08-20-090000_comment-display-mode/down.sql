@@ -0,0 +1,8 @@
+ALTER TABLE comment
+    DROP COLUMN accepted_answer;
+
+ALTER TABLE post
+    DROP COLUMN comment_display_mode;
+
+DROP TYPE comment_display_mode_enum;
+
@@ -0,0 +1,14 @@
+-- How the comments of a post are shown, chosen by the post creator or mods
+CREATE TYPE comment_display_mode_enum AS enum (
+    'Threaded',
+    'Flat',
+    'QuestionAnswer'
+);
+
+ALTER TABLE post
+    ADD COLUMN comment_display_mode comment_display_mode_enum DEFAULT 'Threaded' NOT NULL;
+
+-- Answers accepted by the creator of a question post
+ALTER TABLE comment
+    ADD COLUMN accepted_answer boolean DEFAULT FALSE NOT NULL;
+
@@ -0,0 +1,6 @@
+ALTER TABLE community
+    DROP COLUMN question_answer;
+
+ALTER TABLE post
+    DROP COLUMN solved;
+
@@ -0,0 +1,21 @@
+-- Communities for questions, where each post can be marked as solved
+ALTER TABLE community
+    ADD COLUMN question_answer boolean DEFAULT FALSE NOT NULL;
+
+-- Set when a comment is accepted as answer to the post
+ALTER TABLE post
+    ADD COLUMN solved boolean DEFAULT FALSE NOT NULL;
+
+UPDATE
+    post
+SET
+    solved = TRUE
+WHERE
+    EXISTS (
+        SELECT
+        FROM
+            comment
+        WHERE
+            comment.post_id = post.id
+            AND comment.accepted_answer);
+
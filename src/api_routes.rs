@@ -1,16 +1,21 @@
 use actix_web::{guard, web::*};
 use lemmy_api::{
   comment::{
+    accept_answer::accept_answer,
+    approve_pending::approve_pending_comment,
     distinguish::distinguish_comment,
     like::like_comment,
     list_comment_likes::list_comment_likes,
+    list_pending::list_pending_comments,
     save::save_comment,
   },
   community::{
     add_mod::add_mod_to_community,
+    automod::{create_automod_rule, delete_automod_rule, edit_automod_rule, list_automod_rules},
     ban::ban_from_community,
     block::user_block_community,
     follow::follow_community,
+    freeze::freeze_community,
     multi_community_follow::follow_multi_community,
     pending_follows::{
       approve::post_pending_follows_approve,
@@ -30,6 +35,7 @@ use lemmy_api::{
     change_password_after_reset::change_password_after_reset,
     donation_dialog_shown::donation_dialog_shown,
     export_data::export_data,
+    follow_person::follow_person,
     generate_totp_secret::generate_totp_secret,
     get_captcha::get_captcha,
     list_hidden::list_person_hidden,
@@ -51,16 +57,19 @@ use lemmy_api::{
     resend_verification_email::resend_verification_email,
     reset_password::reset_password,
     save_settings::save_user_settings,
+    update_person_notifications::update_person_notifications,
     update_totp::update_totp,
     user_block_instance::{user_block_instance_communities, user_block_instance_persons},
     validate_auth::validate_auth,
     verify_email::verify_email,
   },
   post::{
+    approve_pending::approve_pending_post,
     feature::feature_post,
     get_link_metadata::get_link_metadata,
     hide::hide_post,
     like::like_post,
+    list_pending::list_pending_posts,
     list_post_likes::list_post_likes,
     lock::lock_post,
     mark_many_read::mark_posts_as_read,
@@ -74,13 +83,43 @@ use lemmy_api::{
     community_report::{create::create_community_report, resolve::resolve_community_report},
     post_report::{create::create_post_report, resolve::resolve_post_report},
     private_message_report::{create::create_pm_report, resolve::resolve_pm_report},
-    report_combined::list::list_reports,
+    report_combined::{
+      list::list_reports,
+      list_cases::list_report_cases,
+      resolve_case::resolve_report_case,
+    },
   },
   site::{
     admin_allow_instance::admin_allow_instance,
     admin_block_instance::admin_block_instance,
     admin_list_users::admin_list_users,
+    admin_set_media_quota::admin_set_media_quota,
     federated_instances::get_federated_instances,
+    federation_blocklist::{
+      add_subscription::add_federation_blocklist_subscription,
+      export::export_federation_blocklist,
+      import::import_federation_blocklist,
+      list::list_federation_blocklist,
+      list_subscriptions::list_federation_blocklist_subscriptions,
+      remove_subscription::remove_federation_blocklist_subscription,
+      review_subscription::review_federation_blocklist_subscription,
+    },
+    federation_queue::{
+      list_errors::list_federation_send_errors,
+      pause::admin_pause_federation,
+      resend::admin_resend_federation,
+      retry::admin_retry_federation,
+      skip::admin_skip_federation,
+    },
+    image_hash_block::{
+      create::create_image_hash_block,
+      delete::delete_image_hash_block,
+      export::export_image_hash_blocklist,
+      import::import_image_hash_blocklist,
+      list::list_image_hash_blocks,
+    },
+    inbound_activity::{list::list_inbound_activities, replay::replay_inbound_activity},
+    key_rotation::{list::list_key_rotations, rotate::admin_rotate_keys},
     leave_admin::leave_admin,
     list_all_media::list_all_media,
     mod_log::get_mod_log,
@@ -96,6 +135,7 @@ use lemmy_api::{
       list::list_registration_applications,
       unread_count::get_unread_registration_application_count,
     },
+    relay::{add::add_relay, edit::edit_relay, list::list_relays, remove::remove_relay},
   },
 };
 use lemmy_api_crud::{
@@ -158,9 +198,13 @@ use lemmy_api_crud::{
   },
 };
 use lemmy_apub::api::{
+  backfill_community::backfill_community,
+  community_archive::{export_community, import_community},
+  list_comment_tree::list_comment_tree,
   list_comments::{list_comments, list_comments_slim},
   list_person_content::list_person_content,
   list_posts::list_posts,
+  move_community::move_community,
   read_community::get_community,
   read_person::read_person,
   resolve_object::resolve_object,
@@ -238,6 +282,10 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           // Mod Actions
           .route("/remove", post().to(remove_community))
           .route("/transfer", post().to(transfer_community))
+          .route("/export", get().to(export_community))
+          .route("/import", post().to(import_community))
+          .route("/move", post().to(move_community))
+          .route("/freeze", post().to(freeze_community))
           .route("/ban_user", post().to(ban_from_community))
           .route("/mod", post().to(add_mod_to_community))
           .route("/icon", post().to(upload_community_icon))
@@ -248,6 +296,13 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("/tag", put().to(update_community_tag))
           .route("/tag", delete().to(delete_community_tag))
           .route("/notifications", post().to(update_community_notifications))
+          .service(
+            scope("/automod")
+              .route("", post().to(create_automod_rule))
+              .route("", put().to(edit_automod_rule))
+              .route("", delete().to(delete_automod_rule))
+              .route("/list", get().to(list_automod_rules)),
+          )
           .service(
             scope("/pending_follows")
               .route("/count", get().to(get_pending_follows_count))
@@ -297,7 +352,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("/report", post().to(create_post_report))
           .route("/report/resolve", put().to(resolve_post_report))
           .route("/notifications", post().to(update_post_notifications))
-          .route("/mod_update", put().to(mod_update_post)),
+          .route("/mod_update", put().to(mod_update_post))
+          .route("/pending/list", get().to(list_pending_posts))
+          .route("/pending/approve", post().to(approve_pending_post)),
       )
       // Comment
       .service(
@@ -314,13 +371,17 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
           .route("/delete", post().to(delete_comment))
           .route("/remove", post().to(remove_comment))
           .route("/distinguish", post().to(distinguish_comment))
+          .route("/accept_answer", post().to(accept_answer))
           .route("/like", post().to(like_comment))
           .route("/like/list", get().to(list_comment_likes))
           .route("/save", put().to(save_comment))
           .route("/list", get().to(list_comments))
           .route("/list/slim", get().to(list_comments_slim))
+          .route("/tree", get().to(list_comment_tree))
           .route("/report", post().to(create_comment_report))
-          .route("/report/resolve", put().to(resolve_comment_report)),
+          .route("/report/resolve", put().to(resolve_comment_report))
+          .route("/pending/list", get().to(list_pending_comments))
+          .route("/pending/approve", post().to(approve_pending_comment)),
       )
       // Private Message
       .service(
@@ -335,7 +396,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
       .service(
         scope("/report")
           .wrap(rate_limit.message())
-          .route("/list", get().to(list_reports)),
+          .route("/list", get().to(list_reports))
+          .route("/case/list", get().to(list_report_cases))
+          .route("/case/resolve", put().to(resolve_report_case)),
       )
       // User
       .service(
@@ -411,7 +474,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
         scope("/person")
           .route("", get().to(read_person))
           .route("/content", get().to(list_person_content))
-          .route("/note", post().to(user_note_person)),
+          .route("/note", post().to(user_note_person))
+          .route("/follow", post().to(follow_person))
+          .route("/notifications", post().to(update_person_notifications)),
       )
       // Admin Actions
       .service(
@@ -447,13 +512,64 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
               .route("/delete", post().to(delete_tagline))
               .route("/list", get().to(list_taglines)),
           )
+          .service(
+            scope("/image_hash_block")
+              .route("", post().to(create_image_hash_block))
+              .route("/delete", post().to(delete_image_hash_block))
+              .route("/list", get().to(list_image_hash_blocks))
+              .route("/import", post().to(import_image_hash_blocklist))
+              .route("/export", get().to(export_image_hash_blocklist)),
+          )
           .route("/ban", post().to(ban_from_site))
+          .route("/community/backfill", post().to(backfill_community))
           .route("/users", get().to(admin_list_users))
+          .route("/media_quota", post().to(admin_set_media_quota))
           .route("/leave", post().to(leave_admin))
           .service(
             scope("/instance")
               .route("/block", post().to(admin_block_instance))
-              .route("/allow", post().to(admin_allow_instance)),
+              .route("/allow", post().to(admin_allow_instance))
+              .service(
+                scope("/blocklist")
+                  .route("/list", get().to(list_federation_blocklist))
+                  .route("/import", post().to(import_federation_blocklist))
+                  .route("/export", get().to(export_federation_blocklist))
+                  .service(
+                    scope("/subscription")
+                      .route("", post().to(add_federation_blocklist_subscription))
+                      .route("", delete().to(remove_federation_blocklist_subscription))
+                      .route("/list", get().to(list_federation_blocklist_subscriptions))
+                      .route(
+                        "/review",
+                        post().to(review_federation_blocklist_subscription),
+                      ),
+                  ),
+              ),
+          )
+          .service(
+            scope("/federation")
+              .route("/pause", post().to(admin_pause_federation))
+              .route("/retry", post().to(admin_retry_federation))
+              .route("/skip", post().to(admin_skip_federation))
+              .route("/resend", post().to(admin_resend_federation))
+              .route("/errors", get().to(list_federation_send_errors)),
+          )
+          .service(
+            scope("/key_rotation")
+              .route("/rotate", post().to(admin_rotate_keys))
+              .route("/list", get().to(list_key_rotations)),
+          )
+          .service(
+            scope("/inbound_activity")
+              .route("/list", get().to(list_inbound_activities))
+              .route("/replay", post().to(replay_inbound_activity)),
+          )
+          .service(
+            scope("/relay")
+              .route("", post().to(add_relay))
+              .route("", put().to(edit_relay))
+              .route("", delete().to(remove_relay))
+              .route("/list", get().to(list_relays)),
           ),
       )
       .service(
@@ -10,9 +10,16 @@ use actix_web::{
   HttpServer,
 };
 use clap::{Parser, Subcommand};
-use lemmy_api::sitemap::get_sitemap;
+use lemmy_api::sitemap::{
+  get_communities_sitemap,
+  get_posts_sitemap,
+  get_sitemap,
+  get_users_sitemap,
+};
 use lemmy_api_utils::{
   context::LemmyContext,
+  key_rotation::rotate_actor_key,
+  media::{video::start_video_workers, MediaBackend},
   request::client_builder,
   send_activity::{ActivityChannel, MATCH_OUTGOING_ACTIVITIES},
   utils::local_site_rate_limit_to_rate_limit_config,
@@ -20,13 +27,18 @@ use lemmy_api_utils::{
 use lemmy_apub::{
   activities::{handle_outgoing_activities, match_outgoing_activities},
   collections::fetch_community_collections,
+  inbox_queue::start_inbox_workers,
   VerifyUrlData,
   FEDERATION_HTTP_FETCH_LIMIT,
 };
 use lemmy_apub_objects::objects::{community::FETCH_COMMUNITY_COLLECTIONS, instance::ApubSite};
-use lemmy_db_schema::{source::secret::Secret, utils::build_db_pool};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{actor_key_rotation::ActorKeyRotation, secret::Secret},
+  utils::build_db_pool,
+};
 use lemmy_db_views_site::SiteView;
-use lemmy_federate::{Opts, SendManager};
+use lemmy_federate::{Opts, ResponseStatusMiddleware, SendManager};
 use lemmy_routes::{
   feeds,
   middleware::{
@@ -55,6 +67,7 @@ use serde_json::json;
 use std::{ops::Deref, time::Duration};
 use tokio::signal::unix::SignalKind;
 use tracing_actix_web::{DefaultRootSpanBuilder, TracingLogger};
+use url::Url;
 
 #[cfg_attr(target_arch = "x86_64", global_allocator)]
 #[cfg(target_arch = "x86_64")]
@@ -127,6 +140,18 @@ enum CmdSubcommand {
     #[arg(long, default_value_t = 1)]
     number: u64,
   },
+  /// Replace the signing keys of local actors, then exit.
+  ///
+  /// Unlike rotation through the API, this doesn't send an `Update` activity. Other instances
+  /// fetch the new key when a signature doesn't match the key they know.
+  RotateKeys {
+    /// The ap_id of the local site, person or community whose key is replaced.
+    #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+    actor: Option<Url>,
+    /// Replace the keys of all local actors.
+    #[arg(long, default_value_t = false)]
+    all: bool,
+  },
 }
 
 #[derive(Subcommand, Debug, PartialEq, Eq)]
@@ -139,6 +164,10 @@ enum MigrationSubcommand {
 
 /// Placing the main function in lib.rs allows other crates to import it and embed Lemmy
 pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
+  if let Some(CmdSubcommand::RotateKeys { actor, all }) = &args.subcommand {
+    return rotate_keys(actor.clone(), *all).await;
+  }
+
   if let Some(CmdSubcommand::Migration {
     subcommand,
     all,
@@ -200,6 +229,7 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
 
   let client = ClientBuilder::new(client_builder(&SETTINGS).build()?)
     .with(TracingMiddleware::default())
+    .with(ResponseStatusMiddleware)
     .build();
   let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
     .with(TracingMiddleware::default())
@@ -208,6 +238,7 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     pool.clone(),
     client.clone(),
     pictrs_client,
+    MediaBackend::new(&SETTINGS)?,
     secret.clone(),
     rate_limit_cell,
   );
@@ -244,6 +275,15 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   let outgoing_activities_task =
     tokio::task::spawn(handle_outgoing_activities(request_data.clone()));
 
+  // Process incoming activities which were queued by the inbox
+  start_inbox_workers(request_data.clone(), SETTINGS.federation.incoming_workers);
+
+  // Transcode uploaded videos which were queued by the upload handler
+  start_video_workers(context.clone());
+
+  // Push changed feeds to their WebSub subscribers
+  feeds::websub::start_websub_pushes(context.clone());
+
   if !args.disable_scheduled_tasks {
     // Schedules various cleanup tasks for the DB
     let _scheduled_tasks = tokio::task::spawn(scheduled_tasks::setup(request_data.clone()));
@@ -309,6 +349,21 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   Ok(())
 }
 
+async fn rotate_keys(actor: Option<Url>, all: bool) -> LemmyResult<()> {
+  let pool = build_db_pool()?;
+  let pool = &mut (&pool).into();
+  let actors = if all {
+    ActorKeyRotation::list_local_actors(pool).await?
+  } else {
+    actor.map(Into::into).into_iter().collect::<Vec<DbUrl>>()
+  };
+  for actor in actors {
+    rotate_actor_key(pool, &actor, None).await?;
+    println!("Rotated key of {actor}");
+  }
+  Ok(())
+}
+
 /// Creates temporary HTTP server which returns status 503 for all requests.
 fn create_startup_server() -> LemmyResult<ServerHandle> {
   let startup_server = HttpServer::new(move || {
@@ -374,13 +429,20 @@ fn create_http_server(
           webfinger::config(cfg);
         }
       })
-      .configure(feeds::config)
+      .configure(|cfg| feeds::config(cfg, &rate_limit))
       .configure(nodeinfo::config)
       .service(
         scope("/sitemap.xml")
           .wrap(rate_limit.message())
           .route("", get().to(get_sitemap)),
       )
+      .service(
+        scope("/sitemap")
+          .wrap(rate_limit.message())
+          .route("/posts/{month}/{page}.xml", get().to(get_posts_sitemap))
+          .route("/communities/{page}.xml", get().to(get_communities_sitemap))
+          .route("/users/{page}.xml", get().to(get_users_sitemap)),
+      )
   })
   .disable_signals()
   .bind(bind)?